@@ -36,14 +36,24 @@ pub enum TypeExpr {
 pub enum PrimitiveType {
     String,
     Number,
+    Integer,
     Boolean,
 }
 
+impl PrimitiveType {
+    /// True if a value of this type can be used where `other` is expected;
+    /// `Integer` is a subtype of `Number`
+    pub fn is_subtype_of(&self, other: &PrimitiveType) -> bool {
+        self == other || (*self == PrimitiveType::Integer && *other == PrimitiveType::Number)
+    }
+}
+
 impl fmt::Display for PrimitiveType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             PrimitiveType::String => write!(f, "String"),
             PrimitiveType::Number => write!(f, "Number"),
+            PrimitiveType::Integer => write!(f, "Integer"),
             PrimitiveType::Boolean => write!(f, "Boolean"),
         }
     }
@@ -102,10 +112,33 @@ pub enum Expression {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Literal {
     String(String),
-    Number(f64),
+    Integer(i64),
+    Float(f64),
     Boolean(bool),
 }
 
+impl Literal {
+    /// The primitive type of this literal; floats are plain `Number`s
+    pub fn primitive_type(&self) -> PrimitiveType {
+        match self {
+            Literal::String(_) => PrimitiveType::String,
+            Literal::Integer(_) => PrimitiveType::Integer,
+            Literal::Float(_) => PrimitiveType::Number,
+            Literal::Boolean(_) => PrimitiveType::Boolean,
+        }
+    }
+}
+
+/// Render a float so it reads back as a float: whole values keep a `.0`
+/// (`42.0`, not `42`), which CLIPS also needs to treat them as FLOAT
+pub fn format_float(n: f64) -> String {
+    if n.is_finite() && n.fract() == 0.0 {
+        format!("{:.1}", n)
+    } else {
+        n.to_string()
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FunctionCall {
     pub name: String,
@@ -141,7 +174,8 @@ impl fmt::Display for Literal {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Literal::String(s) => write!(f, "\"{}\"", s),
-            Literal::Number(n) => write!(f, "{}", n),
+            Literal::Integer(n) => write!(f, "{}", n),
+            Literal::Float(n) => write!(f, "{}", format_float(*n)),
             Literal::Boolean(b) => write!(f, "{}", b),
         }
     }
@@ -17,8 +17,8 @@ mod tests;
 // Re-export main types
 pub use ast::*;
 pub use parser::CawParser;
-pub use runtime::Runtime;
-pub use transpiler::ClipsTranspiler;
+pub use runtime::{Runtime, RuntimeSnapshot};
+pub use transpiler::{ClipsTranspiler, Diagnostic, DiagnosticSeverity};
 pub use repl::{ReplSession, ReplCommand};
 
 #[derive(Error, Debug)]
@@ -58,6 +58,17 @@ impl CawParser {
         Ok(Program { statements })
     }
 
+    /// Parse a complete CAW program and serialize its AST to JSON
+    ///
+    /// Statements are externally tagged by kind, e.g.
+    /// `{"statements": [{"FeatherDecl": {...}}]}`, so editor tooling can
+    /// walk the structure without linking this crate.
+    pub fn parse_to_json(input: &str) -> CawResult<serde_json::Value> {
+        let program = Self::parse_program(input)?;
+        serde_json::to_value(&program)
+            .map_err(|e| crate::CawError::Unknown(format!("Failed to serialize AST: {}", e)))
+    }
+
     fn parse_statement(pair: pest::iterators::Pair<Rule>) -> CawResult<Statement> {
         let inner = pair.into_inner().next().ok_or_else(|| {
             ParseError("Empty statement".to_string())
@@ -113,6 +124,7 @@ impl CawParser {
                 let prim = match inner.as_str() {
                     "String" => PrimitiveType::String,
                     "Number" => PrimitiveType::Number,
+                    "Integer" => PrimitiveType::Integer,
                     "Boolean" => PrimitiveType::Boolean,
                     _ => return Err(ParseError(format!("Unknown primitive type: {}", inner.as_str()))),
                 };
@@ -245,9 +257,7 @@ impl CawParser {
 
     fn parse_agent_call(pair: pest::iterators::Pair<Rule>) -> CawResult<Expression> {
         let mut parts = pair.into_inner();
-        let func_call = Self::parse_function_call(parts.next().ok_or_else(|| ParseError("Missing function call".to_string()))?)?;
-
-        let mut expr = Expression::FunctionCall(func_call);
+        let mut expr = Self::parse_call_or_primary(parts.next().ok_or_else(|| ParseError("Missing function call".to_string()))?)?;
 
         for method_pair in parts {
             if method_pair.as_rule() == Rule::function_call {
@@ -269,6 +279,51 @@ impl CawParser {
         Ok(expr)
     }
 
+    /// Parse a `function_call` pair, which is just its primary (identifier,
+    /// literal, record or parenthesized expression) when nothing is called
+    fn parse_call_or_primary(pair: pest::iterators::Pair<Rule>) -> CawResult<Expression> {
+        let mut inner = pair.clone().into_inner();
+        let primary = inner.next().ok_or_else(|| ParseError("Missing primary".to_string()))?;
+        let primary_inner = primary.into_inner().next().ok_or_else(|| ParseError("Missing primary".to_string()))?;
+        let called = inner.next().is_some();
+
+        match primary_inner.as_rule() {
+            Rule::identifier if !called => Ok(Expression::Identifier(primary_inner.as_str().to_string())),
+            Rule::identifier => Self::parse_function_call(pair).map(Expression::FunctionCall),
+            _ if !called => Self::parse_primary(primary_inner),
+            _ => Err(ParseError("Expected identifier as function name".to_string())),
+        }
+    }
+
+    fn parse_primary(pair: pest::iterators::Pair<Rule>) -> CawResult<Expression> {
+        match pair.as_rule() {
+            Rule::literal => Self::parse_literal(pair).map(Expression::Literal),
+            Rule::record_literal => Self::parse_record(pair).map(Expression::Record),
+            Rule::expression => Self::parse_expression(pair),
+            _ => Err(ParseError(format!("Unexpected primary: {:?}", pair.as_rule()))),
+        }
+    }
+
+    fn parse_literal(pair: pest::iterators::Pair<Rule>) -> CawResult<Literal> {
+        let inner = pair.into_inner().next().ok_or_else(|| ParseError("Empty literal".to_string()))?;
+        let text = inner.as_str();
+
+        match inner.as_rule() {
+            Rule::string_literal => Ok(Literal::String(text.trim_matches('"').to_string())),
+            // A decimal point makes a float; `42` stays an integer
+            Rule::number_literal if text.contains('.') => text
+                .parse()
+                .map(Literal::Float)
+                .map_err(|_| ParseError(format!("Invalid number: {}", text))),
+            Rule::number_literal => text
+                .parse()
+                .map(Literal::Integer)
+                .map_err(|_| ParseError(format!("Invalid number: {}", text))),
+            Rule::boolean_literal => Ok(Literal::Boolean(text == "true")),
+            _ => Err(ParseError(format!("Unexpected literal: {:?}", inner.as_rule()))),
+        }
+    }
+
     fn parse_function_call(pair: pest::iterators::Pair<Rule>) -> CawResult<FunctionCall> {
         let mut inner = pair.into_inner();
         let primary = inner.next().ok_or_else(|| ParseError("Missing function name".to_string()))?;
@@ -7,7 +7,8 @@ use crate::{Literal, Expression};
 pub fn print_literal(lit: &Literal) -> String {
     match lit {
         Literal::String(s) => format!("\"{}\"", s).green().to_string(),
-        Literal::Number(n) => n.to_string().cyan().to_string(),
+        Literal::Integer(n) => n.to_string().cyan().to_string(),
+        Literal::Float(n) => crate::ast::format_float(*n).cyan().to_string(),
         Literal::Boolean(b) => b.to_string().yellow().to_string(),
     }
 }
@@ -97,7 +97,8 @@ use colored::*;
 /// REPL command type
 pub enum ReplCommand {
     Help,
-    Facts,
+    /// List facts, optionally filtered by type or name (`:facts person`)
+    Facts(Option<String>),
     Rules,
     Agents,
     Clear,
@@ -117,12 +118,16 @@ impl ReplCommand {
 
         match trimmed {
             ":help" => Some(ReplCommand::Help),
-            ":facts" => Some(ReplCommand::Facts),
+            ":facts" => Some(ReplCommand::Facts(None)),
             ":rules" => Some(ReplCommand::Rules),
             ":agents" => Some(ReplCommand::Agents),
             ":clear" => Some(ReplCommand::Clear),
             ":export" => Some(ReplCommand::Export),
             ":exit" => Some(ReplCommand::Exit),
+            _ if trimmed.starts_with(":facts ") => {
+                let filter = trimmed[":facts ".len()..].trim();
+                Some(ReplCommand::Facts(Some(filter.to_string())))
+            }
             _ if trimmed.starts_with(':') => {
                 eprintln!("{} Unknown command: {}", "✗".red(), trimmed);
                 None
@@ -139,8 +144,8 @@ pub fn execute_command(session: &mut ReplSession, cmd: ReplCommand) -> Result<bo
             print_help();
             Ok(true)
         }
-        ReplCommand::Facts => {
-            print_facts(session);
+        ReplCommand::Facts(filter) => {
+            print_facts(session, filter.as_deref());
             Ok(true)
         }
         ReplCommand::Rules => {
@@ -184,6 +189,7 @@ fn print_help() {
     println!("{}", "Built-in Commands:".bold().underline());
     println!("  {}  - Show this help message", ":help".cyan());
     println!("  {}  - List all facts in the session", ":facts".cyan());
+    println!("  {} - List facts of a type, e.g. :facts person", ":facts <t>".cyan());
     println!("  {}  - List all rules in the session", ":rules".cyan());
     println!("  {} - List all agents in the session", ":agents".cyan());
     println!("  {} - Clear the session state", ":clear".cyan());
@@ -204,8 +210,11 @@ fn print_help() {
     println!();
 }
 
-fn print_facts(session: &ReplSession) {
-    let facts = session.runtime().facts();
+fn print_facts(session: &ReplSession, filter: Option<&str>) {
+    let facts: Vec<_> = match filter {
+        Some(predicate) => session.runtime().find_facts(predicate),
+        None => session.runtime().facts().iter().collect(),
+    };
     if facts.is_empty() {
         println!("{}", "No facts defined".dimmed());
     } else {
@@ -2,13 +2,19 @@
 /// Executes parsed CAW programs with rule evaluation and agent messaging
 
 use crate::ast::*;
+use crate::transpiler::ClipsTranspiler;
 use crate::types::TypeChecker;
-use crate::CawResult;
+use crate::{CawError, CawResult};
+use clara_clips::backend::ffi::split_clips_constructs;
+use clara_clips::ClipsEnvironment;
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::collections::HashMap;
 
 /// Runtime engine for executing CAW programs
 pub struct Runtime {
+    /// Every statement executed so far, in order, for [`Runtime::run_in_clips`]
+    statements: Vec<Statement>,
     facts: Vec<Fact>,
     rules: Vec<Rule>,
     agents: HashMap<String, Agent>,
@@ -16,12 +22,19 @@ pub struct Runtime {
 }
 
 /// A fact in the knowledge base
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Fact {
     pub name: String,
     pub data: Value,
 }
 
+impl Fact {
+    /// The declared type of the fact (the `Type` in `feather name: Type = ...`)
+    pub fn type_name(&self) -> Option<&str> {
+        self.data.get("type").and_then(Value::as_str)
+    }
+}
+
 /// A rule in the system
 #[derive(Debug, Clone)]
 pub struct Rule {
@@ -39,9 +52,35 @@ pub struct Agent {
     pub rules: Vec<Rule>,
 }
 
+/// Serializable summary of the runtime state
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuntimeSnapshot {
+    pub facts: Vec<Fact>,
+    pub rules: Vec<RuleSummary>,
+    pub agents: Vec<AgentSummary>,
+}
+
+/// Summary of a registered rule
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleSummary {
+    pub name: String,
+    pub conditions: usize,
+    pub actions: usize,
+}
+
+/// Summary of a declared agent
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentSummary {
+    pub name: String,
+    pub domain: String,
+    pub facts: usize,
+    pub rules: usize,
+}
+
 impl Runtime {
     pub fn new() -> Self {
         Self {
+            statements: Vec::new(),
             facts: Vec::new(),
             rules: Vec::new(),
             agents: HashMap::new(),
@@ -54,6 +93,7 @@ impl Runtime {
         let mut results = Vec::new();
 
         for statement in &program.statements {
+            self.statements.push(statement.clone());
             match statement {
                 Statement::TypeDecl(td) => {
                     // Register type in type checker
@@ -106,6 +146,33 @@ impl Runtime {
         }
     }
 
+    /// Run everything executed so far in a fresh CLIPS environment
+    ///
+    /// The accumulated program is transpiled with [`ClipsTranspiler`]; its
+    /// constructs are built and its top-level expressions (fact assertions)
+    /// evaluated in order, then `(run)` fires the rules. Returns the text the
+    /// rules printed, or CLIPS' count of fired rules if they printed nothing.
+    pub fn run_in_clips(&self) -> CawResult<String> {
+        let program = Program {
+            statements: self.statements.clone(),
+        };
+        let clips = ClipsTranspiler::new().transpile_program(&program);
+
+        let mut env = ClipsEnvironment::new()
+            .map_err(|e| CawError::RuntimeError(format!("Failed to create CLIPS environment: {}", e)))?;
+        for construct in split_clips_constructs(&clips) {
+            let loaded = if construct.starts_with("(def") {
+                env.build(&construct)
+            } else {
+                env.eval(&construct).map(|_| ())
+            };
+            loaded.map_err(|e| CawError::RuntimeError(format!("Failed to load into CLIPS: {}", e)))?;
+        }
+
+        env.eval("(run)")
+            .map_err(|e| CawError::RuntimeError(format!("CLIPS run failed: {}", e)))
+    }
+
     /// Evaluate an expression
     pub fn eval_expression(&self, expr: &Expression) -> CawResult<Value> {
         match expr {
@@ -134,7 +201,8 @@ impl Runtime {
     fn eval_literal(&self, lit: &Literal) -> Value {
         match lit {
             Literal::String(s) => json!(s),
-            Literal::Number(n) => json!(n),
+            Literal::Integer(n) => json!(n),
+            Literal::Float(n) => json!(n),
             Literal::Boolean(b) => json!(b),
         }
     }
@@ -179,6 +247,63 @@ impl Runtime {
     pub fn agents(&self) -> &HashMap<String, Agent> {
         &self.agents
     }
+
+    /// Find facts by predicate
+    ///
+    /// A fact matches when either its declared type or its name equals
+    /// `predicate` (case-insensitive), so `person` finds every `Person` fact.
+    pub fn find_facts(&self, predicate: &str) -> Vec<&Fact> {
+        self.facts
+            .iter()
+            .filter(|f| {
+                f.name.eq_ignore_ascii_case(predicate)
+                    || f.type_name().is_some_and(|t| t.eq_ignore_ascii_case(predicate))
+            })
+            .collect()
+    }
+
+    /// Check whether a rule with the given name is registered
+    pub fn has_rule(&self, name: &str) -> bool {
+        self.rules.iter().any(|r| r.name == name)
+    }
+
+    /// Look up an agent by name
+    pub fn agent(&self, name: &str) -> Option<&Agent> {
+        self.agents.get(name)
+    }
+
+    /// Produce a serializable summary of facts, rules and agents
+    ///
+    /// Agents are sorted by name so snapshots are stable across runs.
+    pub fn snapshot(&self) -> RuntimeSnapshot {
+        let rules = self
+            .rules
+            .iter()
+            .map(|r| RuleSummary {
+                name: r.name.clone(),
+                conditions: r.conditions.len(),
+                actions: r.actions.len(),
+            })
+            .collect();
+
+        let mut agents: Vec<AgentSummary> = self
+            .agents
+            .values()
+            .map(|a| AgentSummary {
+                name: a.name.clone(),
+                domain: a.domain.to_string(),
+                facts: a.facts.len(),
+                rules: a.rules.len(),
+            })
+            .collect();
+        agents.sort_by(|a, b| a.name.cmp(&b.name));
+
+        RuntimeSnapshot {
+            facts: self.facts.clone(),
+            rules,
+            agents,
+        }
+    }
 }
 
 impl Default for Runtime {
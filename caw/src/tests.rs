@@ -13,6 +13,20 @@ mod parser_tests {
         assert_eq!(program.statements.len(), 1);
     }
 
+    #[test]
+    fn test_parse_integer_and_float_literals() {
+        use crate::{Expression, Literal};
+
+        let literal = |input: &str| match CawParser::parse_program(input).expect("Parse failed").statements.remove(0) {
+            Statement::Expression(Expression::Literal(lit)) => lit,
+            other => panic!("Expected literal, got {:?}", other),
+        };
+        assert_eq!(literal("42"), Literal::Integer(42));
+        assert_eq!(literal("42.0"), Literal::Float(42.0));
+        assert_eq!(literal("42.5"), Literal::Float(42.5));
+        assert!(CawParser::parse_program("99999999999999999999").is_err());
+    }
+
     #[test]
     fn test_parse_type_declaration_record() {
         let input = "type Particle = { type: String, state: String }";
@@ -186,6 +200,24 @@ then
         let result = CawParser::parse_program(input);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_parse_to_json() {
+        let input = r#"
+type Particle = { type: String, state: String }
+let albert = Expert(Physics.Nuclear._)
+        "#;
+        let json = CawParser::parse_to_json(input).unwrap();
+        let statements = json["statements"].as_array().unwrap();
+        assert_eq!(statements.len(), 2);
+        assert_eq!(statements[0]["TypeDecl"]["name"], "Particle");
+        assert_eq!(statements[1]["AgentDecl"]["name"], "albert");
+    }
+
+    #[test]
+    fn test_parse_to_json_error() {
+        assert!(CawParser::parse_to_json("type = invalid").is_err());
+    }
 }
 
 #[cfg(test)]
@@ -242,6 +274,97 @@ mod runtime_tests {
         assert_eq!(runtime.agents().len(), 1);
         assert!(runtime.agents().contains_key("test_agent"));
     }
+
+    fn populated_runtime() -> Runtime {
+        use crate::{AgentDecl, DomainPath, Expression, FeatherDecl, Literal, Record, RuneDecl};
+
+        let feather = |name: &str, type_name: &str, value: &str| {
+            Statement::FeatherDecl(FeatherDecl {
+                name: name.to_string(),
+                type_name: type_name.to_string(),
+                value: Record {
+                    fields: vec![(
+                        "name".to_string(),
+                        Expression::Literal(Literal::String(value.to_string())),
+                    )],
+                },
+            })
+        };
+
+        let program = Program {
+            statements: vec![
+                feather("alice", "Person", "Alice"),
+                feather("bob", "Person", "Bob"),
+                feather("rex", "Pet", "Rex"),
+                Statement::AgentDecl(AgentDecl {
+                    name: "albert".to_string(),
+                    domain: DomainPath {
+                        segments: vec!["Physics".to_string(), "Nuclear".to_string()],
+                        wildcard: true,
+                    },
+                }),
+                Statement::RuneDecl(RuneDecl {
+                    name: "Greet".to_string(),
+                    conditions: vec![Expression::Literal(Literal::Boolean(true))],
+                    actions: vec![],
+                }),
+            ],
+        };
+        let mut runtime = Runtime::new();
+        runtime.execute_program(&program).expect("Execution failed");
+        runtime
+    }
+
+    #[test]
+    fn test_find_facts_by_type() {
+        let runtime = populated_runtime();
+
+        let people = runtime.find_facts("person");
+        let names: Vec<&str> = people.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["alice", "bob"]);
+
+        let pets = runtime.find_facts("Pet");
+        assert_eq!(pets.len(), 1);
+        assert_eq!(pets[0].type_name(), Some("Pet"));
+
+        assert!(runtime.find_facts("robot").is_empty());
+    }
+
+    #[test]
+    fn test_find_facts_by_name() {
+        let runtime = populated_runtime();
+        let facts = runtime.find_facts("rex");
+        assert_eq!(facts.len(), 1);
+        assert_eq!(facts[0].name, "rex");
+    }
+
+    #[test]
+    fn test_has_rule_and_agent_lookup() {
+        let runtime = populated_runtime();
+
+        assert!(runtime.has_rule("Greet"));
+        assert!(!runtime.has_rule("Missing"));
+
+        let agent = runtime.agent("albert").expect("agent should exist");
+        assert_eq!(agent.domain.to_string(), "Physics.Nuclear._");
+        assert!(runtime.agent("marie").is_none());
+    }
+
+    #[test]
+    fn test_snapshot_is_serializable() {
+        let runtime = populated_runtime();
+        let snapshot = runtime.snapshot();
+
+        assert_eq!(snapshot.facts.len(), 3);
+        assert_eq!(snapshot.rules.len(), 1);
+        assert_eq!(snapshot.rules[0].name, "Greet");
+        assert_eq!(snapshot.agents.len(), 1);
+        assert_eq!(snapshot.agents[0].domain, "Physics.Nuclear._");
+
+        let json = serde_json::to_value(&snapshot).expect("snapshot should serialize");
+        assert_eq!(json["agents"][0]["name"], "albert");
+        assert_eq!(json["facts"][0]["data"]["type"], "Person");
+    }
 }
 
 #[cfg(test)]
@@ -330,11 +453,95 @@ mod transpiler_tests {
         assert!(output.contains("Generated from CAW language"));
         assert!(output.contains("CAW v0.1.0"));
     }
+
+    #[test]
+    fn test_transpile_rune_program() {
+        let input = r#"
+type Particle = { kind: String, state: String }
+feather radium: Particle = { kind: "radium", state: "unstable" }
+rune "Decay" when
+  Particle({ state: "unstable" })
+then
+  printout(t, "decaying", crlf)
+        "#;
+        let program = crate::CawParser::parse_program(input).expect("Parse failed");
+        assert_eq!(program.statements.len(), 3);
+
+        let output = ClipsTranspiler::new().transpile_program(&program);
+        assert!(output.contains("(deftemplate Particle\n  (slot kind)\n  (slot state))"));
+        assert!(output.contains(r#"(assert (Particle (kind "radium") (state "unstable")))"#));
+        assert!(output.contains(r#"(Particle (state "unstable"))"#));
+        assert!(output.contains(r#"=>
+  (printout t "decaying" crlf))"#));
+    }
+
+    #[test]
+    fn test_transpile_with_diagnostics_primitive_type() {
+        let program = crate::CawParser::parse_program("type Age = Number").expect("Parse failed");
+
+        let transpiler = ClipsTranspiler::new();
+        let (output, diagnostics) = transpiler.transpile_with_diagnostics(&program);
+        assert_eq!(output, transpiler.transpile_program(&program));
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, crate::DiagnosticSeverity::Warning);
+        assert_eq!(diagnostics[0].statement, 0);
+        assert!(diagnostics[0].message.contains("no output generated"));
+        assert!(diagnostics[0].message.contains("Age"));
+    }
+
+    #[test]
+    fn test_transpile_numeric_slots_and_literals() {
+        let input = r#"
+type Sample = { count: Integer, weight: Number, label: String }
+feather s1: Sample = { count: 42, weight: 42.0, label: "a" }
+        "#;
+        let program = crate::CawParser::parse_program(input).expect("Parse failed");
+        let output = ClipsTranspiler::new().transpile_program(&program);
+
+        assert!(output.contains(
+            "(deftemplate Sample\n  (slot count (type INTEGER))\n  (slot weight (type NUMBER))\n  (slot label))"
+        ));
+        assert!(output.contains(r#"(assert (Sample (count 42) (weight 42.0) (label "a")))"#));
+    }
+
+    #[test]
+    fn test_transpile_with_diagnostics_clean_program() {
+        let input = r#"
+type Particle = { type: String, state: String }
+feather radium: Particle = { type: "radium", state: "unstable" }
+        "#;
+        let program = crate::CawParser::parse_program(input).expect("Parse failed");
+
+        let (_, diagnostics) = ClipsTranspiler::new().transpile_with_diagnostics(&program);
+        assert!(diagnostics.is_empty(), "Unexpected diagnostics: {:?}", diagnostics);
+    }
+
+    #[test]
+    fn test_transpile_with_diagnostics_function_slot() {
+        let td = TypeDecl {
+            name: "Handler".to_string(),
+            type_expr: TypeExpr::Record(vec![(
+                "callback".to_string(),
+                Box::new(TypeExpr::Function(
+                    vec![TypeExpr::Primitive(PrimitiveType::String)],
+                    Box::new(TypeExpr::Primitive(PrimitiveType::Boolean)),
+                )),
+            )]),
+        };
+        let program = crate::Program {
+            statements: vec![crate::Statement::TypeDecl(td)],
+        };
+
+        let (output, diagnostics) = ClipsTranspiler::new().transpile_with_diagnostics(&program);
+        assert!(output.contains("deftemplate Handler"));
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("function type for slot 'callback'"));
+    }
 }
 
 #[cfg(test)]
 mod ast_tests {
-    use crate::{Program, DomainPath, Literal, Expression};
+    use crate::{Program, DomainPath, Literal, Expression, PrimitiveType};
 
     #[test]
     fn test_program_creation() {
@@ -373,10 +580,35 @@ mod ast_tests {
 
     #[test]
     fn test_literal_number_display() {
-        let lit = Literal::Number(42.5);
+        let lit = Literal::Float(42.5);
         assert!(lit.to_string().contains("42.5"));
     }
 
+    #[test]
+    fn test_literal_integer_and_float_display_round_trip() {
+        assert_eq!(Literal::Integer(42).to_string(), "42");
+        assert_eq!(Literal::Float(42.0).to_string(), "42.0");
+        assert_eq!(Literal::Float(0.25).to_string(), "0.25");
+
+        for lit in [Literal::Integer(42), Literal::Float(42.0), Literal::Float(42.5)] {
+            let program = crate::CawParser::parse_program(&lit.to_string()).expect("Parse failed");
+            match &program.statements[0] {
+                crate::Statement::Expression(Expression::Literal(parsed)) => assert_eq!(parsed, &lit),
+                other => panic!("Expected literal, got {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_integer_is_subtype_of_number() {
+        assert!(PrimitiveType::Integer.is_subtype_of(&PrimitiveType::Number));
+        assert!(PrimitiveType::Integer.is_subtype_of(&PrimitiveType::Integer));
+        assert!(!PrimitiveType::Number.is_subtype_of(&PrimitiveType::Integer));
+        assert!(!PrimitiveType::Integer.is_subtype_of(&PrimitiveType::String));
+        assert_eq!(Literal::Integer(1).primitive_type(), PrimitiveType::Integer);
+        assert_eq!(Literal::Float(1.0).primitive_type(), PrimitiveType::Number);
+    }
+
     #[test]
     fn test_literal_boolean_display() {
         let lit_true = Literal::Boolean(true);
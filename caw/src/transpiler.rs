@@ -2,6 +2,33 @@
 /// Converts CAW AST to CLIPS-compatible syntax
 
 use crate::ast::*;
+use serde::{Deserialize, Serialize};
+
+/// Severity of a transpiler diagnostic
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticSeverity {
+    Warning,
+}
+
+/// A non-fatal issue found while transpiling a program
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub severity: DiagnosticSeverity,
+    /// Index of the top-level statement the diagnostic refers to
+    pub statement: usize,
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn warning(statement: usize, message: String) -> Self {
+        Self {
+            severity: DiagnosticSeverity::Warning,
+            statement,
+            message,
+        }
+    }
+}
 
 /// Transpiles CAW to CLIPS
 pub struct ClipsTranspiler;
@@ -13,18 +40,42 @@ impl ClipsTranspiler {
 
     /// Transpile a program to CLIPS
     pub fn transpile_program(&self, program: &Program) -> String {
+        self.transpile_with_diagnostics(program).0
+    }
+
+    /// Transpile a program to CLIPS, reporting statements that were dropped
+    /// or only partially translated.
+    ///
+    /// A warning is emitted for every statement that produced no CLIPS output
+    /// and for every construct CLIPS has no equivalent for (function and union
+    /// slot types, agent calls, message sends).
+    pub fn transpile_with_diagnostics(&self, program: &Program) -> (String, Vec<Diagnostic>) {
         let mut output = String::new();
+        let mut diagnostics = Vec::new();
 
         // Add CLIPS header comment
         output.push_str("; Generated from CAW language\n");
         output.push_str("; CAW v0.1.0\n\n");
 
-        for statement in &program.statements {
-            output.push_str(&self.transpile_statement(statement));
+        for (index, statement) in program.statements.iter().enumerate() {
+            let clips = self.transpile_statement(statement);
+            if clips.trim().is_empty() {
+                diagnostics.push(Diagnostic::warning(
+                    index,
+                    format!("no output generated for {}", describe_statement(statement)),
+                ));
+            }
+            for feature in unsupported_features(statement) {
+                diagnostics.push(Diagnostic::warning(
+                    index,
+                    format!("unsupported feature: {}", feature),
+                ));
+            }
+            output.push_str(&clips);
             output.push_str("\n");
         }
 
-        output
+        (output, diagnostics)
     }
 
     fn transpile_statement(&self, stmt: &Statement) -> String {
@@ -44,9 +95,19 @@ impl ClipsTranspiler {
         // CLIPS uses deftemplate for structured facts
         match &td.type_expr {
             TypeExpr::Record(fields) => {
-                let mut output = format!("(deftemplate {} (\n", td.name);
-                for (name, _type_expr) in fields {
-                    output.push_str(&format!("  (slot {})\n", name));
+                let mut output = format!("(deftemplate {}", td.name);
+                for (name, type_expr) in fields {
+                    // Numeric slots are constrained so CLIPS keeps INTEGER
+                    // and FLOAT apart; other slots accept any value
+                    match type_expr.as_ref() {
+                        TypeExpr::Primitive(PrimitiveType::Integer) => {
+                            output.push_str(&format!("\n  (slot {} (type INTEGER))", name))
+                        }
+                        TypeExpr::Primitive(PrimitiveType::Number) => {
+                            output.push_str(&format!("\n  (slot {} (type NUMBER))", name))
+                        }
+                        _ => output.push_str(&format!("\n  (slot {})", name)),
+                    }
                 }
                 output.push_str(")\n");
                 output
@@ -72,30 +133,46 @@ impl ClipsTranspiler {
         output.push_str("  \"CAW-generated rule\"\n");
 
         // Conditions
-        output.push_str("  (");
-        for (i, cond) in rd.conditions.iter().enumerate() {
-            if i > 0 {
-                output.push_str("\n  (");
-            }
-            output.push_str(&self.transpile_expression(cond));
-            output.push_str(")");
+        for cond in &rd.conditions {
+            output.push_str(&format!("  {}\n", self.transpile_pattern(cond)));
         }
-        output.push_str("\n");
 
         // Arrow separator
-        output.push_str("  =>\n");
+        output.push_str("  =>");
 
         // Actions
-        output.push_str("  (");
         for action in &rd.actions {
-            output.push_str(&self.transpile_statement(action));
+            let clips = self.transpile_statement(action);
+            if !clips.trim().is_empty() {
+                output.push_str(&format!("\n  {}", clips.trim_end()));
+            }
         }
-        output.push_str(")\n");
 
         output.push_str(")\n");
         output
     }
 
+    /// Transpile a rune condition to a CLIPS pattern
+    ///
+    /// `Type({slot: value})` matches facts of template `Type` on the given
+    /// slots; anything else becomes an ordered pattern.
+    fn transpile_pattern(&self, cond: &Expression) -> String {
+        match cond {
+            Expression::FunctionCall(fc) => match fc.args.as_slice() {
+                [Expression::Record(rec)] => {
+                    let mut output = format!("({}", fc.name);
+                    for (key, expr) in &rec.fields {
+                        output.push_str(&format!(" ({} {})", key, self.transpile_expression(expr)));
+                    }
+                    output.push_str(")");
+                    output
+                }
+                _ => self.transpile_expression(cond),
+            },
+            _ => format!("({})", self.transpile_expression(cond)),
+        }
+    }
+
     fn transpile_expression(&self, expr: &Expression) -> String {
         match expr {
             Expression::Literal(lit) => self.transpile_literal(lit),
@@ -135,7 +212,8 @@ impl ClipsTranspiler {
     fn transpile_literal(&self, lit: &Literal) -> String {
         match lit {
             Literal::String(s) => format!("\"{}\"", s),
-            Literal::Number(n) => n.to_string(),
+            Literal::Integer(n) => n.to_string(),
+            Literal::Float(n) => format_float(*n),
             Literal::Boolean(b) => {
                 if *b {
                     "TRUE".to_string()
@@ -147,6 +225,89 @@ impl ClipsTranspiler {
     }
 }
 
+/// Short human-readable description of a statement for diagnostics
+fn describe_statement(stmt: &Statement) -> String {
+    match stmt {
+        Statement::TypeDecl(td) => match &td.type_expr {
+            TypeExpr::Primitive(p) => format!("type '{}' (primitive {})", td.name, p),
+            TypeExpr::Union(_, _) => format!("type '{}' (union)", td.name),
+            TypeExpr::Vector(_) => format!("type '{}' (vector)", td.name),
+            TypeExpr::Function(_, _) => format!("type '{}' (function)", td.name),
+            TypeExpr::Record(_) => format!("type '{}'", td.name),
+        },
+        Statement::AgentDecl(ad) => format!("agent '{}'", ad.name),
+        Statement::FeatherDecl(fd) => format!("feather '{}'", fd.name),
+        Statement::RuneDecl(rd) => format!("rune '{}'", rd.name),
+        Statement::Expression(expr) => format!("expression '{}'", expr),
+    }
+}
+
+/// Collect constructs inside a statement that have no CLIPS equivalent
+fn unsupported_features(stmt: &Statement) -> Vec<String> {
+    let mut features = Vec::new();
+    match stmt {
+        Statement::TypeDecl(td) => {
+            if let TypeExpr::Record(fields) = &td.type_expr {
+                for (name, type_expr) in fields {
+                    match type_expr.as_ref() {
+                        TypeExpr::Function(_, _) => features.push(format!(
+                            "function type for slot '{}' of '{}'", name, td.name
+                        )),
+                        TypeExpr::Union(_, _) => features.push(format!(
+                            "union type for slot '{}' of '{}'", name, td.name
+                        )),
+                        _ => {}
+                    }
+                }
+            }
+        }
+        Statement::AgentDecl(_) => {}
+        Statement::FeatherDecl(fd) => {
+            for (_, expr) in &fd.value.fields {
+                collect_unsupported_expression(expr, &mut features);
+            }
+        }
+        Statement::RuneDecl(rd) => {
+            for cond in &rd.conditions {
+                collect_unsupported_expression(cond, &mut features);
+            }
+            for action in &rd.actions {
+                if let Statement::AgentDecl(ad) = action {
+                    features.push(format!(
+                        "agent declaration '{}' in actions of rune '{}'", ad.name, rd.name
+                    ));
+                } else {
+                    features.extend(unsupported_features(action));
+                }
+            }
+        }
+        Statement::Expression(expr) => collect_unsupported_expression(expr, &mut features),
+    }
+    features
+}
+
+fn collect_unsupported_expression(expr: &Expression, features: &mut Vec<String>) {
+    match expr {
+        Expression::AgentCall(ac) => {
+            features.push(format!("agent call '{}.{}'", ac.agent, ac.method));
+        }
+        Expression::MessageSend(lhs, rhs) => {
+            features.push(format!("message send '{} ! {}'", lhs, rhs));
+        }
+        Expression::FunctionCall(fc) => {
+            for arg in &fc.args {
+                collect_unsupported_expression(arg, features);
+            }
+        }
+        Expression::Record(rec) => {
+            for (_, value) in &rec.fields {
+                collect_unsupported_expression(value, features);
+            }
+        }
+        Expression::Literal(_) | Expression::Identifier(_) => {}
+    }
+}
+
 impl Default for ClipsTranspiler {
     fn default() -> Self {
         Self::new()
@@ -49,9 +49,12 @@ impl TypeChecker {
     }
 
     /// Check the type of an expression
-    pub fn check_expression(&self, _expr: &Expression) -> CawResult<TypeExpr> {
-        // TODO: Implement full type checking
-        Ok(TypeExpr::Primitive(PrimitiveType::String))
+    pub fn check_expression(&self, expr: &Expression) -> CawResult<TypeExpr> {
+        match expr {
+            Expression::Literal(lit) => Ok(TypeExpr::Primitive(lit.primitive_type())),
+            // TODO: Implement full type checking
+            _ => Ok(TypeExpr::Primitive(PrimitiveType::String)),
+        }
     }
 
     /// Get the current type environment
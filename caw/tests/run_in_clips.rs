@@ -0,0 +1,53 @@
+//! End-to-end: CAW source → Runtime → CLIPS
+
+use caw::{CawParser, Runtime};
+
+#[test]
+fn test_rune_fires_in_clips() {
+    let input = r#"
+type Particle = { kind: String, state: String }
+feather radium: Particle = { kind: "radium", state: "unstable" }
+feather lead: Particle = { kind: "lead", state: "stable" }
+rune "Decay" when
+  Particle({ state: "unstable" })
+then
+  printout(t, "decaying", crlf)
+    "#;
+    let program = CawParser::parse_program(input).expect("Parse failed");
+    let mut runtime = Runtime::new();
+    runtime.execute_program(&program).expect("Execution failed");
+
+    // Only the unstable particle matches
+    let output = runtime.run_in_clips().expect("CLIPS run failed");
+    assert_eq!(output, "decaying\n");
+}
+
+#[test]
+fn test_run_in_clips_accumulates_programs() {
+    let mut runtime = Runtime::new();
+    for input in [
+        "type Particle = { kind: String, state: String }",
+        r#"feather radium: Particle = { kind: "radium", state: "unstable" }"#,
+        "rune \"Decay\" when\n  Particle({ state: \"unstable\" })\nthen\n  printout(t, \"decaying\", crlf)",
+    ] {
+        let program = CawParser::parse_program(input).expect("Parse failed");
+        runtime.execute_program(&program).expect("Execution failed");
+    }
+
+    assert_eq!(runtime.run_in_clips().expect("CLIPS run failed"), "decaying\n");
+    // Each run starts from a fresh environment
+    assert_eq!(runtime.run_in_clips().expect("CLIPS run failed"), "decaying\n");
+}
+
+#[test]
+fn test_run_in_clips_reports_invalid_clips() {
+    // Records have no CLIPS expression form, so the rule fails to build
+    let program = CawParser::parse_program(
+        "rune \"Broken\" when\n  ready\nthen\n  printout(t, { a: 1 })",
+    )
+    .expect("Parse failed");
+    let mut runtime = Runtime::new();
+    runtime.execute_program(&program).expect("Execution failed");
+
+    assert!(runtime.run_in_clips().is_err());
+}
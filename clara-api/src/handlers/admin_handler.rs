@@ -0,0 +1,46 @@
+//! Admin handlers for backing up and restoring sessions
+
+use actix_web::{web, HttpResponse};
+use clara_session::Snapshot;
+
+use crate::handlers::common::session_to_response;
+use crate::handlers::session_handler::AppState;
+use crate::models::{ApiError, RestoreResponse, SnapshotQuery};
+
+/// GET /admin/snapshot - Serialize every live session as one JSON document
+///
+/// Dynamic Prolog clauses are included unless `clauses=false`.
+pub async fn snapshot(
+    state: web::Data<AppState>,
+    query: web::Query<SnapshotQuery>,
+) -> Result<HttpResponse, ApiError> {
+    log::info!("Taking session snapshot (clauses: {})", query.clauses);
+
+    let snapshot = state
+        .session_manager
+        .snapshot(query.clauses)
+        .map_err(ApiError::from)?;
+
+    Ok(HttpResponse::Ok().json(snapshot))
+}
+
+/// POST /admin/restore - Recreate the sessions in a snapshot
+pub async fn restore(
+    state: web::Data<AppState>,
+    req: web::Json<Snapshot>,
+) -> Result<HttpResponse, ApiError> {
+    log::info!("Restoring {} sessions from snapshot", req.sessions.len());
+
+    let restored = state
+        .session_manager
+        .restore(&req)
+        .map_err(ApiError::from)?;
+
+    let response = RestoreResponse {
+        status: "restored".to_string(),
+        skipped: req.sessions.len() - restored.len(),
+        sessions: restored.iter().map(session_to_response).collect(),
+        clauses: req.prolog_clauses.as_ref().map_or(0, Vec::len),
+    };
+    Ok(HttpResponse::Ok().json(response))
+}
@@ -0,0 +1,87 @@
+//! CAW language tooling handlers.
+//!
+//! Exposes the CAW parser and CAW → CLIPS transpiler over HTTP so editor
+//! tooling can inspect the AST and preview generated CLIPS without linking
+//! the `caw` crate.
+//!
+//! # Endpoints
+//!
+//! | Method | Path | Description |
+//! |--------|------|-------------|
+//! | `POST` | `/caw/parse`     | Parse a CAW program and return its AST as JSON |
+//! | `POST` | `/caw/transpile` | Transpile a CAW program to CLIPS with diagnostics |
+
+use actix_web::{web, HttpResponse};
+use caw::{CawParser, ClipsTranspiler, Diagnostic};
+use clara_core::ClaraError;
+use serde::{Deserialize, Serialize};
+
+use crate::models::ApiError;
+
+/// Request body: CAW program source text.
+#[derive(Debug, Deserialize)]
+pub struct CawTranspileRequest {
+    pub source: String,
+}
+
+/// Response body: the parsed AST plus any transpiler warnings.
+#[derive(Debug, Serialize)]
+pub struct CawParseResponse {
+    pub ast: serde_json::Value,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Response body: generated CLIPS plus any transpiler warnings.
+#[derive(Debug, Serialize)]
+pub struct CawTranspileResponse {
+    pub clips: String,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+// ── POST /caw/parse ───────────────────────────────────────────────────────────
+
+/// Parse a CAW program and return its AST as JSON.
+///
+/// Responses:
+/// - `200 OK` — `{ "ast": { "statements": [...] }, "diagnostics": [...] }`
+/// - `400 Bad Request` — the CAW source could not be parsed.
+pub async fn parse_caw(
+    req: web::Json<CawTranspileRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let program = CawParser::parse_program(&req.source)
+        .map_err(|e| ApiError::new(ClaraError::SyntaxError(e.to_string())))?;
+
+    let ast = serde_json::to_value(&program)
+        .map_err(|e| ApiError::new(ClaraError::Internal(e.to_string())))?;
+    let (_, diagnostics) = ClipsTranspiler::new().transpile_with_diagnostics(&program);
+    log::info!(
+        "Parsed CAW program ({} statements, {} diagnostics)",
+        program.statements.len(),
+        diagnostics.len()
+    );
+
+    Ok(HttpResponse::Ok().json(CawParseResponse { ast, diagnostics }))
+}
+
+// ── POST /caw/transpile ───────────────────────────────────────────────────────
+
+/// Transpile a CAW program to CLIPS.
+///
+/// Responses:
+/// - `200 OK` — `{ "clips": "...", "diagnostics": [...] }`
+/// - `400 Bad Request` — the CAW source could not be parsed.
+pub async fn transpile_caw(
+    req: web::Json<CawTranspileRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let program = CawParser::parse_program(&req.source)
+        .map_err(|e| ApiError::new(ClaraError::SyntaxError(e.to_string())))?;
+
+    let (clips, diagnostics) = ClipsTranspiler::new().transpile_with_diagnostics(&program);
+    log::info!(
+        "Transpiled CAW program ({} statements, {} diagnostics)",
+        program.statements.len(),
+        diagnostics.len()
+    );
+
+    Ok(HttpResponse::Ok().json(CawTranspileResponse { clips, diagnostics }))
+}
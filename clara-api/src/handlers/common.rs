@@ -0,0 +1,105 @@
+//! Helpers shared by the CLIPS and Prolog session handlers
+
+use clara_core::ClaraError;
+use clara_prolog::BuiltinProfile;
+use clara_session::{SessionManager, SessionType};
+
+use crate::models::{ApiError, ResourceInfo, SessionConfig, SessionResponse};
+
+/// Convert a clara-session::Session to API SessionResponse
+pub fn session_to_response(session: &clara_session::Session) -> SessionResponse {
+    SessionResponse {
+        session_id: session.session_id.to_string(),
+        user_id: session.user_id.clone(),
+        session_type: session.session_type,
+        started: format_timestamp(session.created_at),
+        touched: format_timestamp(session.touched_at),
+        status: session.status.to_string(),
+        resources: ResourceInfo {
+            facts: session.resources.facts,
+            rules: session.resources.rules,
+            objects: session.resources.objects,
+            memory_mb: None,
+        },
+        limits: Some(ResourceInfo {
+            facts: session.limits.max_facts,
+            rules: session.limits.max_rules,
+            objects: 0,
+            memory_mb: Some(session.limits.max_memory_mb),
+        }),
+    }
+}
+
+/// Load a requested Prolog profile into a newly created session
+///
+/// A profile is only valid for Prolog sessions. If it is given for a CLIPS
+/// session, or its libraries can't be loaded, the session is terminated
+/// again and the error returned.
+pub fn apply_profile(
+    manager: &SessionManager,
+    session: &clara_session::Session,
+    profile: Option<BuiltinProfile>,
+) -> Result<(), ApiError> {
+    let Some(profile) = profile else {
+        return Ok(());
+    };
+
+    let result = if session.session_type == SessionType::Prolog {
+        manager
+            .with_prolog_env(&session.session_id, |env| env.load_builtins_profile(profile))
+            .map_err(ApiError::from)
+    } else {
+        Err(ApiError::new(ClaraError::ValidationError(format!(
+            "profile is only supported for Prolog sessions, not {}",
+            session.session_type
+        ))))
+    };
+    if result.is_err() {
+        log::warn!("Dropping session {}: {:?} profile not applied", session.session_id, profile);
+        let _ = manager.terminate_session(&session.session_id);
+    }
+    result
+}
+
+/// Restrict a newly created session to the tools allowed by its config
+///
+/// If the allowlist can't be stored the session is terminated again, so it
+/// never runs unrestricted.
+pub fn apply_allowed_tools(
+    manager: &SessionManager,
+    session: &clara_session::Session,
+    config: Option<&SessionConfig>,
+) -> Result<(), ApiError> {
+    let Some(tools) = config.and_then(|cfg| cfg.allowed_tools.clone()) else {
+        return Ok(());
+    };
+
+    let result = manager
+        .set_allowed_tools(&session.session_id, Some(tools))
+        .map(|_| ())
+        .map_err(ApiError::from);
+    if result.is_err() {
+        log::warn!("Dropping session {}: tool allowlist not applied", session.session_id);
+        let _ = manager.terminate_session(&session.session_id);
+    }
+    result
+}
+
+/// Convert a Unix timestamp to an ISO8601 string
+pub fn format_timestamp(secs: u64) -> String {
+    chrono::DateTime::from_timestamp(secs as i64, 0)
+        .map(|dt| dt.to_rfc3339())
+        .unwrap_or_else(|| "1970-01-01T00:00:00Z".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_timestamp() {
+        let ts = 1729700580; // 2024-10-23 17:03:00 UTC
+        let formatted = format_timestamp(ts);
+        assert!(formatted.contains("2024-10-23"));
+    }
+}
@@ -2,45 +2,44 @@
 //!
 //! REST API handlers for Prolog session management and query execution.
 
+use std::sync::OnceLock;
+use std::time::Duration;
+
 use actix_web::{web, HttpResponse};
-use clara_core::ClaraError;
+use clara_core::{truncate_str, ClaraError};
 use clara_session::SessionType;
 
 use crate::models::{
-    ApiError, CreateSessionRequest, SessionResponse, ResourceInfo, TerminateResponse,
-    PrologQueryRequest, PrologQueryResponse, PrologConsultRequest,
+    ApiError, CreateSessionRequest, SessionResponse, TerminateResponse,
+    PrologQueryFormat, PrologQueryRequest, PrologQueryResponse, PrologConsultRequest, PrologConsultFileRequest,
 };
+use crate::handlers::common::{apply_allowed_tools, apply_profile, session_to_response};
+use crate::handlers::ndjson::for_each_value;
+use crate::middleware::audit::audit_log;
+use crate::middleware::tracing::{slow_query_log, MAX_LOGGED_INPUT_CHARS};
+use crate::validation::consult_path::consult_root;
+use crate::validation::directives::directive_whitelist;
+use crate::validation::input::input_limits;
 
 /// Application state (shared with session_handler)
 pub use crate::handlers::session_handler::AppState;
 
-/// Convert a clara-session::Session to API SessionResponse
-fn session_to_response(session: &clara_session::Session) -> SessionResponse {
-    SessionResponse {
-        session_id: session.session_id.to_string(),
-        user_id: session.user_id.clone(),
-        started: format_timestamp(session.created_at),
-        touched: format_timestamp(session.touched_at),
-        status: session.status.to_string(),
-        resources: ResourceInfo {
-            facts: session.resources.facts,
-            rules: session.resources.rules,
-            objects: session.resources.objects,
-            memory_mb: None,
-        },
-        limits: Some(ResourceInfo {
-            facts: session.limits.max_facts,
-            rules: session.limits.max_rules,
-            objects: 0,
-            memory_mb: Some(session.limits.max_memory_mb),
-        }),
+const DEFAULT_QUERY_TIMEOUT_MS: u64 = 30000;
+
+static QUERY_TIMEOUT_MS: OnceLock<u64> = OnceLock::new();
+
+/// Install the process-wide time limit for `POST /devils/sessions/{id}/query`.
+/// The first call wins; later calls are ignored with a warning.
+pub fn set_query_timeout_ms(timeout_ms: u64) {
+    if QUERY_TIMEOUT_MS.set(timeout_ms).is_err() {
+        log::warn!("set_query_timeout_ms: timeout already set, ignoring {}", timeout_ms);
     }
 }
 
-fn format_timestamp(secs: u64) -> String {
-    chrono::DateTime::from_timestamp(secs as i64, 0)
-        .map(|dt| dt.to_rfc3339())
-        .unwrap_or_else(|| "1970-01-01T00:00:00Z".to_string())
+/// The configured Prolog query time limit, or the default if none was set.
+/// 0 means no limit.
+pub fn query_timeout_ms() -> u64 {
+    *QUERY_TIMEOUT_MS.get_or_init(|| DEFAULT_QUERY_TIMEOUT_MS)
 }
 
 /// POST /devils/sessions - Create a new Prolog session
@@ -63,6 +62,8 @@ pub async fn create_prolog_session(
         .session_manager
         .create_prolog_session(req.user_id.clone(), limits)
         .map_err(ApiError::from)?;
+    apply_allowed_tools(&state.session_manager, &session, req.config.as_ref())?;
+    apply_profile(&state.session_manager, &session, req.profile)?;
 
     let response = session_to_response(&session);
     Ok(HttpResponse::Created().json(response))
@@ -148,7 +149,20 @@ pub async fn query_prolog(
     req: web::Json<PrologQueryRequest>,
 ) -> Result<HttpResponse, ApiError> {
     let session_id_str = path.into_inner();
-    log::info!("Executing Prolog query in session {}: {}", session_id_str, req.goal);
+    log::info!(
+        "Executing Prolog query in session {}: {}",
+        session_id_str,
+        truncate_str(&req.goal, MAX_LOGGED_INPUT_CHARS)
+    );
+
+    if clara_prolog::is_blank_goal(&req.goal) {
+        return Err(ApiError::new(ClaraError::ValidationError("goal must not be empty".to_string())));
+    }
+    input_limits().check_prolog(&req.goal).map_err(ApiError::new)?;
+    if let Some(bindings) = &req.bindings {
+        let bindings_text = serde_json::to_string(bindings).unwrap_or_default();
+        input_limits().check_prolog(&bindings_text).map_err(ApiError::new)?;
+    }
 
     let session_id = clara_session::SessionId(session_id_str);
 
@@ -168,19 +182,53 @@ pub async fn query_prolog(
     let start = std::time::Instant::now();
 
     // Execute query via Prolog environment
-    let result = if req.all_solutions.unwrap_or(false) {
-        state
-            .session_manager
-            .with_prolog_env(&session_id, |env| env.query(&req.goal))
-            .map_err(ApiError::from)?
-    } else {
-        state
-            .session_manager
-            .with_prolog_env(&session_id, |env| env.query_once(&req.goal))
-            .map_err(ApiError::from)?
-    };
+    let all_solutions = req.all_solutions.unwrap_or(false);
+    let timeout = Some(req.timeout_ms.unwrap_or_else(query_timeout_ms))
+        .filter(|ms| *ms > 0)
+        .map(Duration::from_millis);
+    let no_params = serde_json::Map::new();
+    let params = req.bindings.as_ref().unwrap_or(&no_params);
+    let bindings = req.bindings.as_ref().filter(|b| !b.is_empty());
+    let max_solutions = req.max_solutions.filter(|_| req.format != PrologQueryFormat::Check);
+    if max_solutions == Some(0) {
+        return Err(ApiError::new(ClaraError::ValidationError(
+            "max_solutions must be at least 1".to_string(),
+        )));
+    }
+
+    let mut truncated = None;
+    let result = state
+        .session_manager
+        .with_prolog_env(&session_id, |env| {
+            if let Some(limit) = max_solutions {
+                let named = req.format == PrologQueryFormat::Bindings;
+                let (solutions, cut_off) = env.query_n_with(&req.goal, params, named, limit, timeout)?;
+                truncated = Some(cut_off);
+                return Ok(solutions);
+            }
+            match req.format {
+                PrologQueryFormat::Bindings => {
+                    env.query_bindings(&req.goal, params, all_solutions, timeout)
+                }
+                PrologQueryFormat::Check => env
+                    .check_with(&req.goal, params, timeout)
+                    .map(|succeeded| succeeded.to_string()),
+                PrologQueryFormat::Terms => match (bindings, timeout) {
+                    (Some(bindings), Some(timeout)) => {
+                        env.query_with_params_timeout(&req.goal, bindings, all_solutions, timeout)
+                    }
+                    (Some(bindings), None) => env.query_with_params(&req.goal, bindings, all_solutions),
+                    (None, Some(timeout)) if all_solutions => env.query_limited(&req.goal, timeout),
+                    (None, Some(timeout)) => env.query_once_timeout(&req.goal, timeout),
+                    (None, None) if all_solutions => env.query(&req.goal),
+                    (None, None) => env.query_once(&req.goal),
+                },
+            }
+        })
+        .map_err(ApiError::from)?;
 
     let elapsed_ms = start.elapsed().as_millis() as u64;
+    slow_query_log().check_goal(&session_id.0, &req.goal, elapsed_ms);
 
     // Touch session to update last activity
     state
@@ -192,26 +240,31 @@ pub async fn query_prolog(
         result,
         success: true,
         runtime_ms: elapsed_ms,
+        truncated,
     };
 
     Ok(HttpResponse::Ok().json(response))
 }
 
-/// POST /devils/sessions/{session_id}/consult - Load Prolog clauses into session
-pub async fn consult_prolog(
-    state: web::Data<AppState>,
-    path: web::Path<String>,
-    req: web::Json<PrologConsultRequest>,
-) -> Result<HttpResponse, ApiError> {
-    let session_id_str = path.into_inner();
-    log::info!("Loading {} clauses into Prolog session: {}", req.clauses.len(), session_id_str);
-
-    let session_id = clara_session::SessionId(session_id_str);
-
-    // Verify session exists and is a Prolog session
+/// Known directive predicates that must be executed (called) rather than asserted.
+/// These are top-level Prolog directives that have side effects when loaded.
+const DIRECTIVE_PREFIXES: &[&str] = &[
+    "use_module(",
+    "ensure_loaded(",
+    "consult(",
+    "module(",
+    "reexport(",
+    "load_files(",
+];
+
+/// Look up `session_id` and check that it is a Prolog session
+fn prolog_session(
+    state: &AppState,
+    session_id: &clara_session::SessionId,
+) -> Result<clara_session::Session, ApiError> {
     let session = state
         .session_manager
-        .get_session(&session_id)
+        .get_session(session_id)
         .map_err(ApiError::from)?;
 
     if session.session_type != SessionType::Prolog {
@@ -220,47 +273,90 @@ pub async fn consult_prolog(
             session.session_id
         ))));
     }
+    Ok(session)
+}
+
+/// Classify one consulted clause, vetting directives against the whitelist
+///
+/// Returns whether the clause is a directive, and the goal or term to run
+/// without its trailing dot.
+fn classify_clause<'a>(
+    clause: &'a str,
+    session: &clara_session::Session,
+) -> Result<(bool, &'a str), ApiError> {
+    let trimmed = clause.trim_start();
+    let directive = if let Some(goal) = trimmed.strip_prefix(":-") {
+        Some(goal)
+    } else if DIRECTIVE_PREFIXES.iter().any(|p| trimmed.starts_with(p)) {
+        Some(trimmed)
+    } else {
+        None
+    };
+
+    match directive {
+        Some(goal) => {
+            let goal = goal.trim().trim_end_matches('.').trim();
+            directive_whitelist().check(goal).map_err(|e| {
+                log::warn!("Rejected directive in session {}: {}", session.session_id.0, clause);
+                audit_log().record(&e, &session.session_id.0, Some(&session.user_id), clause);
+                ApiError::new(e)
+            })?;
+            Ok((true, goal))
+        }
+        // remove trailing dot if present, since assertz expects a term
+        None => Ok((false, trimmed.trim_end_matches('.').trim())),
+    }
+}
+
+/// Execute a directive via call/1, or assert a clause
+fn load_clause(
+    state: &AppState,
+    session_id: &clara_session::SessionId,
+    is_directive: bool,
+    term: &str,
+) -> Result<(), ApiError> {
+    state
+        .session_manager
+        .with_prolog_env(session_id, |env| {
+            if is_directive {
+                log::debug!("Executing directive in session {}: {}", session_id.0, term);
+                env.query_once(&format!("call(({}))", term)).map(|_| ())
+            } else {
+                log::debug!("Asserting clause into session {}: {}", session_id.0, term);
+                env.assertz(term)
+            }
+        })
+        .map_err(ApiError::from)
+}
+
+/// POST /devils/sessions/{session_id}/consult - Load Prolog clauses into session
+pub async fn consult_prolog(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    req: web::Json<PrologConsultRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let session_id_str = path.into_inner();
+    log::info!("Loading {} clauses into Prolog session: {}", req.clauses.len(), session_id_str);
 
-    // Known directive predicates that must be executed (called) rather than asserted.
-    // These are top-level Prolog directives that have side effects when loaded.
-    const DIRECTIVE_PREFIXES: &[&str] = &[
-        "use_module(",
-        "ensure_loaded(",
-        "consult(",
-        "module(",
-        "reexport(",
-        "load_files(",
-    ];
-
-    // Load each clause via assertz, or execute if it's a directive
+    let limits = input_limits();
+    limits.check_clause_count(req.clauses.len()).map_err(ApiError::new)?;
     for clause in &req.clauses {
-        state
-            .session_manager
-            .with_prolog_env(&session_id, |env| {
-                let trimmed = clause.trim_start();
-
-                // Check if this is a :- directive
-                if trimmed.starts_with(":-") {
-                    log::debug!("Executing :- directive in session {}: {}", session_id.0, clause);
-                    // Strip the :- prefix and execute as a goal
-                    let goal = trimmed.trim_start_matches(":-").trim().trim_end_matches('.').trim();
-                    env.query_once(goal).map(|_| ())
-                }
-                // Check if this is a bare directive (e.g. use_module(...))
-                else if DIRECTIVE_PREFIXES.iter().any(|p| trimmed.starts_with(p)) {
-                    log::debug!("Executing directive in session {}: {}", session_id.0, clause);
-                    let goal = trimmed.trim_end_matches('.').trim();
-                    env.query_once(goal).map(|_| ())
-                }
-                // Otherwise, assert as a regular clause/rule
-                else {
-                    log::debug!("Asserting clause into session {}: {}", session_id.0, clause);
-                    // remove trailing dot if present, since assertz expects a term
-                    let clause = trimmed.trim_end_matches('.').trim();
-                    env.assertz(&clause)
-                }
-            })
-            .map_err(ApiError::from)?;
+        limits.check_prolog(clause).map_err(ApiError::new)?;
+    }
+
+    let session_id = clara_session::SessionId(session_id_str);
+    let session = prolog_session(&state, &session_id)?;
+
+    // Classify every clause and vet directives against the whitelist before
+    // loading anything, so a rejected directive leaves the session untouched.
+    let items = req
+        .clauses
+        .iter()
+        .map(|clause| classify_clause(clause, &session))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for (is_directive, term) in items {
+        load_clause(&state, &session_id, is_directive, term)?;
     }
 
     // Touch session to update last activity
@@ -275,14 +371,93 @@ pub async fn consult_prolog(
     })))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// POST /devils/sessions/{session_id}/consult/file - Consult a Prolog file
+///
+/// The path must resolve to a file under `security.prolog_consult_root`;
+/// anything else is rejected with `InvalidFilePath`. The file is loaded with
+/// `consult/1`, so its directives are not checked against the whitelist —
+/// only trusted files belong under the root.
+pub async fn consult_prolog_file(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    req: web::Json<PrologConsultFileRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let session_id = clara_session::SessionId(path.into_inner());
+    log::info!("Consulting file {} into Prolog session: {}", req.path, session_id);
+
+    let session = prolog_session(&state, &session_id)?;
+    let root = consult_root().ok_or_else(|| {
+        ApiError::new(ClaraError::FileAccessDenied(
+            "Prolog file consult is disabled; set security.prolog_consult_root".to_string(),
+        ))
+    })?;
+    let file = root.resolve(&req.path).map_err(|e| {
+        log::warn!("Rejected consult path in session {}: {}", session_id.0, req.path);
+        audit_log().record(&e, &session_id.0, Some(&session.user_id), &req.path);
+        ApiError::new(e)
+    })?;
 
-    #[test]
-    fn test_format_timestamp() {
-        let ts = 1729700580; // 2024-10-23 17:03:00 UTC
-        let formatted = format_timestamp(ts);
-        assert!(formatted.contains("2024-10-23"));
-    }
+    state
+        .session_manager
+        .with_prolog_env(&session_id, |env| env.consult_file(&file.to_string_lossy()))
+        .map_err(ApiError::from)?;
+
+    state
+        .session_manager
+        .touch_session(&session_id)
+        .map_err(ApiError::from)?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "status": "file_consulted",
+        "path": req.path
+    })))
+}
+
+/// POST /devils/sessions/{session_id}/consult/stream - Load clauses from an NDJSON body
+///
+/// Each line is a JSON string holding one clause. Clauses are checked and
+/// loaded as their lines arrive, so the body is never held in memory as a
+/// whole. The clause-count limit is enforced as the count grows; on any
+/// error the clauses loaded before it stay loaded.
+pub async fn consult_prolog_stream(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    payload: web::Payload,
+) -> Result<HttpResponse, ApiError> {
+    let session_id = clara_session::SessionId(path.into_inner());
+    log::info!("Streaming clauses into Prolog session: {}", session_id);
+
+    let session = prolog_session(&state, &session_id)?;
+    let limits = input_limits();
+
+    // Leave room for the quotes and escapes of the JSON encoding; the
+    // decoded clause is checked against the exact limit
+    let max_line_bytes = limits.max_prolog_input_bytes.saturating_mul(2).saturating_add(2);
+    let mut loaded = 0;
+    let count = for_each_value(payload, max_line_bytes, |value| {
+        let serde_json::Value::String(clause) = value else {
+            return Err(ApiError::new(ClaraError::ValidationError(format!(
+                "Clause {} is not a JSON string",
+                loaded + 1
+            ))));
+        };
+        limits.check_clause_count(loaded + 1).map_err(ApiError::new)?;
+        limits.check_prolog(&clause).map_err(ApiError::new)?;
+        let (is_directive, term) = classify_clause(&clause, &session)?;
+        load_clause(&state, &session_id, is_directive, term)?;
+        loaded += 1;
+        Ok(())
+    })
+    .await?;
+
+    // Touch session to update last activity
+    state
+        .session_manager
+        .touch_session(&session_id)
+        .map_err(ApiError::from)?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "status": "clauses_loaded",
+        "count": count
+    })))
 }
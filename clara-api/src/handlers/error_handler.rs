@@ -1,4 +1,4 @@
-use actix_web::HttpResponse;
+use actix_web::{HttpRequest, HttpResponse};
 use crate::models::ApiErrorResponse;
 
 /// Generic error handler
@@ -11,3 +11,27 @@ pub fn handle_error(error: &str) -> HttpResponse {
     };
     HttpResponse::InternalServerError().json(response)
 }
+
+/// Default service for requests no route matched
+///
+/// Returns `MethodNotAllowed` (405) when the path exists under another
+/// method, `NotFound` (404) otherwise, in the usual JSON error envelope.
+pub async fn route_not_found(req: HttpRequest) -> HttpResponse {
+    if req.resource_map().has_resource(req.path()) {
+        let error = format!("Method {} not allowed for {}", req.method(), req.path());
+        HttpResponse::MethodNotAllowed().json(ApiErrorResponse {
+            error: error.clone(),
+            error_type: "MethodNotAllowed".to_string(),
+            details: error,
+            code: 405,
+        })
+    } else {
+        let error = format!("No route for {}", req.path());
+        HttpResponse::NotFound().json(ApiErrorResponse {
+            error: error.clone(),
+            error_type: "NotFound".to_string(),
+            details: error,
+            code: 404,
+        })
+    }
+}
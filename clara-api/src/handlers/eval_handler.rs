@@ -1,18 +1,136 @@
+use std::time::Duration;
+
 use actix_web::{web, HttpResponse};
 use crate::handlers::AppState;
-use crate::models::{ApiError, EvalRequest, EvalResponse, EvalMetrics};
+use clara_clips::clips_conversion::split_clips_error;
+use clara_core::{truncate_str, ClaraError};
+use clara_prolog::PrologError;
+use clara_session::{ManagerError, SessionType};
+use crate::middleware::tracing::{slow_query_log, MAX_LOGGED_INPUT_CHARS};
+use crate::models::{ApiError, EngineEvalResponse, EvalMetrics, EvalRequest, EvalResponse};
+use crate::validation::input::input_limits;
 
-/// POST /sessions/{session_id}/eval - Evaluate CLIPS code in a session
+/// POST /sessions/{session_id}/evaluate - Evaluate CLIPS code in a session
 pub async fn eval_session(
     state: web::Data<AppState>,
     path: web::Path<String>,
     req: web::Json<EvalRequest>,
 ) -> Result<HttpResponse, ApiError> {
     let session_id = path.into_inner();
+    let req = req.into_inner();
+    let response = run_blocking(move || eval_clips(&state, &session_id, &req)).await?;
+
+    if !response.success {
+        log::debug!("Returning HTTP 400 response for CLIPS error");
+        return Ok(HttpResponse::BadRequest().json(response));
+    }
+
+    log::debug!("Returning HTTP 200 response");
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// POST /sessions/{session_id}/eval - Evaluate in a CLIPS or Prolog session
+///
+/// CLIPS sessions run `script` as `/evaluate` does. Prolog sessions run it
+/// as a goal and return the first solution's bindings in `value`. Either
+/// way the response carries the session's `session_type`.
+pub async fn eval_any_session(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    req: web::Json<EvalRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let session_id = clara_session::SessionId(path.into_inner());
+    let session = state
+        .session_manager
+        .get_session(&session_id)
+        .map_err(ApiError::from)?;
+
+    let req = req.into_inner();
+    let response = run_blocking(move || match session.session_type {
+        SessionType::Clips => Ok(EngineEvalResponse::from(eval_clips(&state, &session_id.0, &req)?)),
+        SessionType::Prolog => eval_prolog(&state, &session_id, &req),
+    })
+    .await?;
+
+    if !response.success {
+        return Ok(HttpResponse::BadRequest().json(response));
+    }
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Run an evaluation on the blocking thread pool
+///
+/// Waiting for an evaluation queue slot and the engine call itself both
+/// block. Kept off the actix workers, a full queue can't stop the server
+/// answering other requests such as `/health`.
+async fn run_blocking<F, R>(f: F) -> Result<R, ApiError>
+where
+    F: FnOnce() -> Result<R, ApiError> + Send + 'static,
+    R: Send + 'static,
+{
+    web::block(f).await.map_err(|e| {
+        log::error!("Evaluation task panicked: {}", e);
+        ApiError::new(ClaraError::Internal("Evaluation task failed".to_string()))
+    })?
+}
+
+/// Run a Prolog goal for the first solution's bindings
+///
+/// A goal with no solution is an unsuccessful response rather than an
+/// `Err`, matching how CLIPS errors are reported.
+fn eval_prolog(
+    state: &AppState,
+    session_id: &clara_session::SessionId,
+    req: &EvalRequest,
+) -> Result<EngineEvalResponse, ApiError> {
+    log::info!("Evaluating goal in Prolog session: {}", session_id);
+    log::debug!("Goal: {}", truncate_str(&req.script, MAX_LOGGED_INPUT_CHARS));
+
+    if clara_prolog::is_blank_goal(&req.script) {
+        return Err(ApiError::new(ClaraError::ValidationError("script must not be empty".to_string())));
+    }
+    input_limits().check_prolog(&req.script).map_err(ApiError::new)?;
+
+    let timeout = Some(req.timeout_ms).filter(|ms| *ms > 0).map(Duration::from_millis);
+    let no_params = serde_json::Map::new();
+    let start = std::time::Instant::now();
+
+    let result = state
+        .session_manager
+        .run_queued(|| {
+            state.session_manager.with_prolog_env_typed(session_id, |env| {
+                env.query_bindings(&req.script, &no_params, false, timeout)
+            })
+        })
+        .map_err(ApiError::from)?;
+
+    let elapsed_ms = start.elapsed().as_millis() as u64;
+    slow_query_log().check_goal(&session_id.0, &req.script, elapsed_ms);
+
+    let result = match result {
+        Ok(bindings) => Ok(bindings),
+        Err(PrologError::QueryFailed(msg)) => Err(msg),
+        Err(e) => return Err(ApiError::from(ManagerError::PrologError(e))),
+    };
+
+    state
+        .session_manager
+        .touch_session(session_id)
+        .map_err(ApiError::from)?;
+
+    let metrics = EvalMetrics { elapsed_ms, ..EvalMetrics::default() };
+    Ok(EngineEvalResponse::prolog(result, metrics))
+}
+
+/// Run a CLIPS script through the evaluation queue, tracking session status
+fn eval_clips(state: &AppState, session_id: &str, req: &EvalRequest) -> Result<EvalResponse, ApiError> {
+    let session_id = session_id.to_string();
     log::info!("Evaluating script in session: {}", session_id);
-    log::debug!("Script content: {}", req.script);
+    log::debug!("Script content: {}", truncate_str(&req.script, MAX_LOGGED_INPUT_CHARS));
     log::debug!("Timeout: {:?}ms", req.timeout_ms);
 
+    input_limits().check_clips(&req.script).map_err(ApiError::new)?;
+
     // Verify session exists
     let session_id_obj = clara_session::SessionId(session_id.clone());
     log::debug!("Looking up session: {}", session_id);
@@ -37,14 +155,18 @@ pub async fn eval_session(
             ApiError::from(e)
         })?;
 
-    // Execute the script using FFI
+    // Execute the script using FFI once the evaluation queue admits it; a
+    // full queue is a 429. CLIPS errors come back as the inner `Err` so they
+    // can be reported in the response body.
     log::debug!("Executing script via CLIPS FFI");
     let start = std::time::Instant::now();
 
     let result = state
         .session_manager
-        .with_clips_env(&session_id_obj, |env| {
-            env.eval(&req.script)
+        .run_queued(|| {
+            state.session_manager.with_clips_env(&session_id_obj, |env| {
+                Ok(env.eval(&req.script))
+            })
         })
         .map_err(|e| {
             log::error!("FFI execution failed for session {}: {:?}", session_id, e);
@@ -55,6 +177,7 @@ pub async fn eval_session(
         })?;
 
     let elapsed_ms = start.elapsed().as_millis() as u64;
+    slow_query_log().check("CLIPS eval", &session_id, &req.script, elapsed_ms);
 
     // Complete evaluation and update session stats
     session.complete_evaluation(None); // TODO: extract rules_fired from result
@@ -66,10 +189,24 @@ pub async fn eval_session(
             ApiError::from(e)
         })?;
 
-    let eval_result = clara_core::EvalResult::success(
-        result,
-        clara_core::EvalMetrics::with_elapsed(elapsed_ms)
-    );
+    // Errors printed during an otherwise successful eval (e.g. from rule
+    // actions) count as failures too
+    let (output, error) = match &result {
+        Ok(output) => {
+            let (stdout, error) = split_clips_error(output);
+            (stdout.to_string(), error.map(str::to_string))
+        }
+        Err(e) => (String::new(), Some(e.clone())),
+    };
+
+    let metrics = clara_core::EvalMetrics::with_elapsed(elapsed_ms);
+    let eval_result = match &error {
+        None => clara_core::EvalResult::success(output, metrics),
+        Some(e) => clara_core::EvalResult {
+            stdout: output,
+            ..clara_core::EvalResult::failure(e.clone(), metrics)
+        },
+    };
 
     log::info!(
         "Evaluation complete: exit_code={}, elapsed={}ms",
@@ -79,21 +216,7 @@ pub async fn eval_session(
     log::debug!("stdout length: {} bytes", eval_result.stdout.len());
     log::debug!("stderr length: {} bytes", eval_result.stderr.len());
 
-    // Convert to API response
-    let response = EvalResponse {
-        stdout: eval_result.stdout,
-        stderr: eval_result.stderr,
-        exit_code: eval_result.exit_code,
-        metrics: EvalMetrics {
-            elapsed_ms: eval_result.metrics.elapsed_ms,
-            facts_added: eval_result.metrics.facts_added,
-            rules_fired: eval_result.metrics.rules_fired,
-        },
-        session: None,
-    };
-
-    log::debug!("Returning HTTP 200 response");
-    Ok(HttpResponse::Ok().json(response))
+    Ok(EvalResponse::from(eval_result))
 }
 
 #[cfg(test)]
@@ -103,6 +226,7 @@ mod tests {
     #[test]
     fn test_eval_response_structure() {
         let resp = EvalResponse {
+            success: true,
             stdout: "test output".to_string(),
             stderr: String::new(),
             exit_code: 0,
@@ -111,6 +235,8 @@ mod tests {
                 facts_added: None,
                 rules_fired: None,
             },
+            value: None,
+            error: None,
             session: None,
         };
         assert_eq!(resp.exit_code, 0);
@@ -8,12 +8,16 @@ pub mod trace_handler;
 pub mod source_handler;
 pub mod ritual_handler;
 pub mod transduce_handler;
+pub mod caw_handler;
+pub mod admin_handler;
+pub mod ndjson;
+pub mod common;
 
 pub use session_handler::{create_session, get_session, list_user_sessions,
                           terminate_session, save_session, AppState};
-pub use eval_handler::eval_session;
-pub use error_handler::handle_error;
+pub use eval_handler::{eval_any_session, eval_session};
+pub use error_handler::{handle_error, route_not_found};
 pub use devils_handler::{
     create_prolog_session, get_prolog_session, list_prolog_sessions,
-    terminate_prolog_session, query_prolog, consult_prolog,
+    terminate_prolog_session, query_prolog, consult_prolog, consult_prolog_stream,
 };
@@ -0,0 +1,165 @@
+//! Incremental NDJSON request bodies for bulk uploads
+//!
+//! `web::Json` buffers the whole body before parsing it. The streaming
+//! consult and facts endpoints read the body chunk by chunk instead and hand
+//! each line on as soon as it is complete, so memory use is bounded by the
+//! longest line rather than the size of the upload. Each line holds one JSON
+//! value; blank lines are skipped.
+
+use actix_web::web;
+use clara_core::ClaraError;
+use futures_util::StreamExt;
+
+use crate::models::ApiError;
+
+/// Splits a byte stream into JSON values, one per line
+#[derive(Debug)]
+pub struct NdjsonReader {
+    /// Bytes of the line still being received
+    partial: Vec<u8>,
+    max_line_bytes: usize,
+    /// Number of the line in `partial`, counting from 1
+    line: usize,
+}
+
+impl NdjsonReader {
+    /// Reader that rejects lines longer than `max_line_bytes`
+    pub fn new(max_line_bytes: usize) -> Self {
+        Self {
+            partial: Vec::new(),
+            max_line_bytes,
+            line: 1,
+        }
+    }
+
+    /// Bytes held for the incomplete last line
+    pub fn buffered(&self) -> usize {
+        self.partial.len()
+    }
+
+    /// Feed the next chunk of the body and return the values of the lines
+    /// it completed
+    pub fn push(&mut self, chunk: &[u8]) -> Result<Vec<serde_json::Value>, ClaraError> {
+        let mut values = Vec::new();
+        let mut rest = chunk;
+        while let Some(end) = rest.iter().position(|b| *b == b'\n') {
+            self.append(&rest[..end])?;
+            rest = &rest[end + 1..];
+            let line = std::mem::take(&mut self.partial);
+            if let Some(value) = self.parse_line(&line)? {
+                values.push(value);
+            }
+        }
+        self.append(rest)?;
+        Ok(values)
+    }
+
+    /// Parse the last line when the body doesn't end with a newline
+    pub fn finish(mut self) -> Result<Option<serde_json::Value>, ClaraError> {
+        let line = std::mem::take(&mut self.partial);
+        self.parse_line(&line)
+    }
+
+    /// Add bytes to the current line, refusing to grow it past the limit
+    fn append(&mut self, bytes: &[u8]) -> Result<(), ClaraError> {
+        if self.partial.len() + bytes.len() > self.max_line_bytes {
+            return Err(ClaraError::ValidationError(format!(
+                "Line {} exceeds the {} byte limit",
+                self.line, self.max_line_bytes
+            )));
+        }
+        self.partial.extend_from_slice(bytes);
+        Ok(())
+    }
+
+    fn parse_line(&mut self, line: &[u8]) -> Result<Option<serde_json::Value>, ClaraError> {
+        let number = self.line;
+        self.line += 1;
+
+        let line = line.trim_ascii();
+        if line.is_empty() {
+            return Ok(None);
+        }
+        serde_json::from_slice(line)
+            .map(Some)
+            .map_err(|e| ClaraError::InvalidRequestBody(format!("Line {}: {}", number, e)))
+    }
+}
+
+/// Read an NDJSON body, calling `handle` with each value as soon as its line
+/// has arrived
+///
+/// Stops at the first error from the body, the parser or `handle`; values
+/// handled before that are not undone. Returns the number of values handled.
+pub async fn for_each_value<F>(
+    mut payload: web::Payload,
+    max_line_bytes: usize,
+    mut handle: F,
+) -> Result<usize, ApiError>
+where
+    F: FnMut(serde_json::Value) -> Result<(), ApiError>,
+{
+    let mut reader = NdjsonReader::new(max_line_bytes);
+    let mut count = 0;
+
+    while let Some(chunk) = payload.next().await {
+        let chunk = chunk
+            .map_err(|e| ApiError::new(ClaraError::InvalidRequestBody(e.to_string())))?;
+        for value in reader.push(&chunk).map_err(ApiError::new)? {
+            handle(value)?;
+            count += 1;
+        }
+    }
+    if let Some(value) = reader.finish().map_err(ApiError::new)? {
+        handle(value)?;
+        count += 1;
+    }
+
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_large_body_parsed_incrementally() {
+        let body: String = (0..10_000)
+            .map(|i| format!("\"fact_{}(value).\"\n", i))
+            .collect();
+        let longest = body.lines().map(str::len).max().unwrap();
+
+        let mut reader = NdjsonReader::new(64);
+        let mut count = 0;
+        for chunk in body.as_bytes().chunks(7) {
+            count += reader.push(chunk).unwrap().len();
+            // Only the line in progress is ever held
+            assert!(reader.buffered() <= longest);
+        }
+        assert!(reader.finish().unwrap().is_none());
+        assert_eq!(count, 10_000);
+    }
+
+    #[test]
+    fn test_final_line_without_newline_and_blank_lines() {
+        let mut reader = NdjsonReader::new(64);
+        let values = reader.push(b"\"a.\"\r\n\n  \n{\"b\": 1}\n\"c").unwrap();
+        assert_eq!(values, vec![serde_json::json!("a."), serde_json::json!({"b": 1})]);
+        assert_eq!(reader.push(b".\"").unwrap(), Vec::<serde_json::Value>::new());
+        assert_eq!(reader.finish().unwrap(), Some(serde_json::json!("c.")));
+    }
+
+    #[test]
+    fn test_overlong_and_invalid_lines_rejected() {
+        // The limit applies before the newline arrives
+        let mut reader = NdjsonReader::new(8);
+        assert!(matches!(reader.push(b"\"0123456789"), Err(ClaraError::ValidationError(_))));
+        assert_eq!(reader.buffered(), 0);
+
+        let mut reader = NdjsonReader::new(64);
+        match reader.push(b"\"ok\"\nnot json\n") {
+            Err(ClaraError::InvalidRequestBody(msg)) => assert!(msg.starts_with("Line 2"), "{}", msg),
+            other => panic!("Expected InvalidRequestBody, got {:?}", other),
+        }
+    }
+}
@@ -46,7 +46,13 @@ fn acquire_and_cache(
     secret: &str,
     cache: &Arc<Mutex<Option<CachedToken>>>,
 ) -> Option<String> {
-    let client = FieryPitClient::new(url);
+    let client = match FieryPitClient::try_new(url) {
+        Ok(client) => client,
+        Err(e) => {
+            log::warn!("Cannot acquire service token from {}: {}", url, e);
+            return None;
+        }
+    };
     match client.auth_service_token("dis-bootstrap", secret) {
         Ok(resp) => {
             let margin_s: u64 = std::env::var("FIERYPIT_TOKEN_MARGIN_S")
@@ -106,7 +112,7 @@ fn get_bootstrap_token(
 
 /// Returns `true` when `result` is a `401 Unauthorized` FieryPit error.
 fn is_unauthorized(result: &Result<serde_json::Value, FieryPitError>) -> bool {
-    matches!(result, Err(FieryPitError::Status(s, _)) if s.as_u16() == 401)
+    matches!(result, Err(FieryPitError::Status(s, _, _)) if s.as_u16() == 401)
 }
 
 // ---------------------------------------------------------------------------
@@ -154,9 +160,17 @@ pub async fn create_ritual(
             let bootstrap   = kafka_bootstrap.as_deref().unwrap_or("localhost:9092");
             let token_cache = token_cache_arc.clone();
             for url in &participants {
+                let client = match FieryPitClient::try_new(url.as_str()) {
+                    Ok(client) => client,
+                    Err(e) => {
+                        log::warn!("create_ritual: failed to bootstrap participant {}: {}", url, e);
+                        continue;
+                    }
+                };
+
                 // Attempt 1 — use cached / static token.
                 let token1 = get_bootstrap_token(url.as_str(), &token_cache);
-                let mut c = FieryPitClient::new(url.as_str());
+                let mut c = client.clone();
                 if let Some(ref t) = token1 { c = c.with_service_key(t.as_str()); }
                 let r = c.ritual_join(ritual_id, &topic, bootstrap, &dis_domain, None, false, 30.0);
 
@@ -170,7 +184,7 @@ pub async fn create_ritual(
                         .filter(|s| !s.is_empty());
                     let token2 = secret_opt
                         .and_then(|sec| acquire_and_cache(url.as_str(), &sec, &token_cache));
-                    let mut c2 = FieryPitClient::new(url.as_str());
+                    let mut c2 = client;
                     if let Some(ref t) = token2 { c2 = c2.with_service_key(t.as_str()); }
                     c2.ritual_join(ritual_id, &topic, bootstrap, &dis_domain, None, false, 30.0)
                 } else {
@@ -330,7 +344,7 @@ mod tests {
     #[test]
     fn test_is_unauthorized_true_for_401() {
         let err: Result<serde_json::Value, FieryPitError> =
-            Err(FieryPitError::Status(StatusCode::UNAUTHORIZED, serde_json::json!({})));
+            Err(FieryPitError::Status(StatusCode::UNAUTHORIZED, serde_json::json!({}), None));
         assert!(is_unauthorized(&err));
     }
 
@@ -339,6 +353,7 @@ mod tests {
         let err: Result<serde_json::Value, FieryPitError> = Err(FieryPitError::Status(
             StatusCode::INTERNAL_SERVER_ERROR,
             serde_json::json!({}),
+            None,
         ));
         assert!(!is_unauthorized(&err));
     }
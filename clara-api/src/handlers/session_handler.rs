@@ -1,19 +1,50 @@
-use actix_web::{web, HttpResponse};
+use actix_web::{web, HttpRequest, HttpResponse};
+use clara_clips::clips_conversion::{
+    clips_fact_template, clips_fact_to_json_with_multislots, json_to_clips_fact,
+    json_to_clips_slots, split_clips_error,
+};
+use clara_clips::RunOutcome;
 use clara_session::SessionManager;
 use clara_ritual::RitualRegistry;
+use crate::handlers::common::{apply_allowed_tools, apply_profile, session_to_response};
+use crate::handlers::ndjson::for_each_value;
+use crate::middleware::audit::audit_log;
+use crate::middleware::tracing::slow_query_log;
 use crate::subprocess::SubprocessPool;
-use std::collections::{HashMap, HashSet};
-use std::sync::{Arc, Mutex, RwLock};
+use crate::validation::consult_path::clips_load_root;
+use crate::validation::input::input_limits;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
 use std::sync::atomic::AtomicBool;
 use std::time::Instant;
 use uuid::Uuid;
 use clara_cycle::{CycleStatus, DeductionResult};
 
 use crate::models::{
-    ApiError, CreateSessionRequest, SaveSessionRequest, ResourceInfo, SessionResponse,
-    TerminateResponse, LoadRulesRequest, LoadFactsRequest, RunRequest, RunResponse, QueryFactsResponse
+    ApiError, CreateSessionRequest, SaveSessionRequest, SessionResponse,
+    TerminateResponse, LoadRequest, LoadRulesRequest, LoadFactsRequest, ModifyFactRequest, RunRequest, RunResponse, QueryFactsResponse,
+    ResetMode, ResetQuery, SessionListQuery, LoadRulesResponse, RuleLoadFailure,
 };
 
+/// Loop detection window used until [`set_loop_detection_window`] is called.
+const DEFAULT_LOOP_DETECTION_WINDOW: usize = 100;
+
+static LOOP_DETECTION_WINDOW: OnceLock<usize> = OnceLock::new();
+
+/// Install the process-wide loop detection window for `POST /sessions/{id}/run`.
+/// The first call wins; later calls are ignored with a warning.
+pub fn set_loop_detection_window(window: usize) {
+    if LOOP_DETECTION_WINDOW.set(window).is_err() {
+        log::warn!("set_loop_detection_window: window already set, ignoring {}", window);
+    }
+}
+
+/// The configured loop detection window, or the default if none was set.
+/// 0 means loop detection is off.
+pub fn loop_detection_window() -> usize {
+    *LOOP_DETECTION_WINDOW.get_or_init(|| DEFAULT_LOOP_DETECTION_WINDOW)
+}
+
 /// A cached FieryPit service JWT with its expiry `Instant`.
 ///
 /// Stored in `AppState::fiery_pit_token_cache` and refreshed lazily when the
@@ -69,36 +100,6 @@ pub struct AppState {
     pub fiery_pit_token_cache: Arc<Mutex<Option<CachedToken>>>,
 }
 
-/// Convert a clara-session::Session to API SessionResponse
-fn session_to_response(session: &clara_session::Session) -> SessionResponse {
-    SessionResponse {
-        session_id: session.session_id.to_string(),
-        user_id: session.user_id.clone(),
-        started: format_timestamp(session.created_at),
-        touched: format_timestamp(session.touched_at),
-        status: session.status.to_string(),
-        resources: ResourceInfo {
-            facts: session.resources.facts,
-            rules: session.resources.rules,
-            objects: session.resources.objects,
-            memory_mb: None,
-        },
-        limits: Some(ResourceInfo {
-            facts: session.limits.max_facts,
-            rules: session.limits.max_rules,
-            objects: 0,
-            memory_mb: Some(session.limits.max_memory_mb),
-        }),
-    }
-}
-
-fn format_timestamp(secs: u64) -> String {
-    // Convert Unix timestamp to ISO8601 string
-    chrono::DateTime::from_timestamp(secs as i64, 0)
-        .map(|dt| dt.to_rfc3339())
-        .unwrap_or_else(|| "1970-01-01T00:00:00Z".to_string())
-}
-
 /// POST /sessions - Create a new session
 pub async fn create_session(
     state: web::Data<AppState>,
@@ -117,8 +118,10 @@ pub async fn create_session(
 
     let session = state
         .session_manager
-        .create_session_with_name(req.user_id.clone(), req.name.clone(), limits)
+        .create_typed_session(req.user_id.clone(), req.session_type, req.name.clone(), limits)
         .map_err(ApiError::from)?;
+    apply_allowed_tools(&state.session_manager, &session, req.config.as_ref())?;
+    apply_profile(&state.session_manager, &session, req.profile)?;
 
     let response = session_to_response(&session);
     Ok(HttpResponse::Created().json(response))
@@ -206,6 +209,11 @@ pub async fn terminate_session(
 }
 
 /// POST /sessions/{session_id}/rules - Load rules into a session
+///
+/// Each rule is compiled on its own, so one bad rule doesn't stop the rest
+/// from loading; failures are reported by index in the response. That is a
+/// 200 while any rule loaded and a 400 when every rule was rejected. A
+/// runtime failure in CLIPS aborts the request with a 500 instead.
 pub async fn load_rules(
     state: web::Data<AppState>,
     path: web::Path<String>,
@@ -220,13 +228,85 @@ pub async fn load_rules(
         .get_session(&session_id)
         .map_err(ApiError::from)?;
 
-    // Load each rule via CLIPS environment
     for rule in &req.rules {
-        state
+        input_limits().check_clips(rule).map_err(ApiError::new)?;
+    }
+
+    // Build each construct via CLIPS environment, continuing past failures
+    let mut failed = Vec::new();
+    for (index, rule) in req.rules.iter().enumerate() {
+        let result = state
             .session_manager
-            .with_clips_env(&session_id, |env| {
-                env.eval(rule)
+            .with_clips_env(&session_id, |env| env.build(rule));
+        match result {
+            Ok(()) => {}
+            Err(clara_session::ManagerError::ClipsSyntaxError(error)) => {
+                log::warn!("Rule {} failed to load into session {}: {}", index, session_id, error);
+                failed.push(RuleLoadFailure { index, error });
+            }
+            Err(e) => return Err(ApiError::from(e)),
+        }
+    }
+
+    // Touch session to update last activity
+    state
+        .session_manager
+        .touch_session(&session_id)
+        .map_err(ApiError::from)?;
+
+    let response = LoadRulesResponse {
+        status: "rules_loaded".to_string(),
+        count: req.rules.len(),
+        loaded: req.rules.len() - failed.len(),
+        failed,
+    };
+
+    if response.loaded == 0 && !response.failed.is_empty() {
+        Ok(HttpResponse::BadRequest().json(response))
+    } else {
+        Ok(HttpResponse::Ok().json(response))
+    }
+}
+
+/// POST /sessions/{session_id}/load - Load CLIPS files into a session
+///
+/// Files are resolved against `security.clips_load_root`; the request is
+/// refused if no root is configured, and every path is checked before any
+/// file is loaded, so a path outside the root loads nothing.
+pub async fn load_files(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    req: web::Json<LoadRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let session_id = clara_session::SessionId(path.into_inner());
+    log::info!("Loading {} files into session: {}", req.files.len(), session_id);
+
+    let session = state
+        .session_manager
+        .get_session(&session_id)
+        .map_err(ApiError::from)?;
+
+    let root = clips_load_root().ok_or_else(|| {
+        ApiError::new(clara_core::ClaraError::FileAccessDenied(
+            "CLIPS file load is disabled; set security.clips_load_root".to_string(),
+        ))
+    })?;
+    let files = req
+        .files
+        .iter()
+        .map(|file| {
+            root.resolve(file).map_err(|e| {
+                log::warn!("Rejected load path in session {}: {}", session_id.0, file);
+                audit_log().record(&e, &session_id.0, Some(&session.user_id), file);
+                ApiError::new(e)
             })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for file in &files {
+        state
+            .session_manager
+            .with_clips_env(&session_id, |env| env.load(&file.to_string_lossy()))
             .map_err(ApiError::from)?;
     }
 
@@ -237,8 +317,8 @@ pub async fn load_rules(
         .map_err(ApiError::from)?;
 
     Ok(HttpResponse::Ok().json(serde_json::json!({
-        "status": "rules_loaded",
-        "count": req.rules.len()
+        "status": "files_loaded",
+        "count": files.len()
     })))
 }
 
@@ -259,13 +339,7 @@ pub async fn load_facts(
 
     // Load each fact via CLIPS environment
     for fact in &req.facts {
-        let assert_cmd = format!("(assert {})", fact);
-        state
-            .session_manager
-            .with_clips_env(&session_id, |env| {
-                env.eval(&assert_cmd)
-            })
-            .map_err(ApiError::from)?;
+        assert_fact(&state, &session_id, fact)?;
     }
 
     // Touch session to update last activity
@@ -280,6 +354,162 @@ pub async fn load_facts(
     })))
 }
 
+/// POST /sessions/{session_id}/facts/stream - Load facts from an NDJSON body
+///
+/// Each line holds one fact in the same forms `POST /sessions/{id}/facts`
+/// accepts: a raw CLIPS string or a JSON object. Facts are asserted as their
+/// lines arrive, so the body is never held in memory as a whole; on any
+/// error the facts asserted before it stay asserted.
+pub async fn load_facts_stream(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    payload: web::Payload,
+) -> Result<HttpResponse, ApiError> {
+    let session_id = clara_session::SessionId(path.into_inner());
+    log::info!("Streaming facts into session: {}", session_id);
+
+    // Verify session exists
+    let _session = state
+        .session_manager
+        .get_session(&session_id)
+        .map_err(ApiError::from)?;
+
+    // Leave room for the quotes and escapes of the JSON encoding; the
+    // converted fact is checked against the exact limit
+    let max_line_bytes = input_limits().max_clips_input_bytes.saturating_mul(2).saturating_add(2);
+    let count = for_each_value(payload, max_line_bytes, |fact| {
+        assert_fact(&state, &session_id, &fact)
+    })
+    .await?;
+
+    // Touch session to update last activity
+    state
+        .session_manager
+        .touch_session(&session_id)
+        .map_err(ApiError::from)?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "status": "facts_loaded",
+        "count": count
+    })))
+}
+
+/// Assert one fact given as a raw CLIPS string or a JSON object
+fn assert_fact(
+    state: &AppState,
+    session_id: &clara_session::SessionId,
+    fact: &serde_json::Value,
+) -> Result<(), ApiError> {
+    let fact = match fact {
+        serde_json::Value::String(raw) => raw.clone(),
+        other => json_to_clips_fact(other)
+            .map_err(|e| ApiError::new(clara_core::ClaraError::ValidationError(e)))?,
+    };
+    input_limits().check_clips(&fact).map_err(ApiError::new)?;
+    let assert_cmd = format!("(assert {})", fact);
+    state
+        .session_manager
+        .with_clips_env(session_id, |env| {
+            env.eval(&assert_cmd)
+        })
+        .map_err(ApiError::from)?;
+    Ok(())
+}
+
+/// POST /sessions/{session_id}/facts/modify - Change slot values of one fact
+///
+/// CLIPS replaces a modified fact, so the response carries its new index.
+pub async fn modify_fact(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    req: web::Json<ModifyFactRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let session_id = clara_session::SessionId(path.into_inner());
+    log::info!("Modifying fact f-{} in session: {}", req.index, session_id);
+
+    if req.slots.is_empty() {
+        return Err(ApiError::new(clara_core::ClaraError::ValidationError(
+            "No slots to modify".to_string(),
+        )));
+    }
+    let slots = json_to_clips_slots(&req.slots)
+        .map_err(|e| ApiError::new(clara_core::ClaraError::ValidationError(e)))?;
+    input_limits().check_clips(&slots).map_err(ApiError::new)?;
+
+    let mut session = state
+        .session_manager
+        .get_session(&session_id)
+        .map_err(ApiError::from)?;
+
+    let modify_cmd = format!("(modify {} {})", req.index, slots);
+    let (output, facts) = change_fact(&state, &session_id, req.index, &modify_cmd)?;
+
+    // On failure `(modify)` prints an error and returns FALSE
+    let new_index = match split_clips_error(&output) {
+        (_, Some(_)) => None,
+        (value, None) => value
+            .trim()
+            .strip_prefix("<Fact-")
+            .and_then(|rest| rest.strip_suffix('>'))
+            .and_then(|index| index.parse::<u64>().ok()),
+    }
+    .ok_or_else(|| {
+        ApiError::new(clara_core::ClaraError::ValidationError(format!(
+            "Failed to modify fact f-{}: {}",
+            req.index,
+            output.trim()
+        )))
+    })?;
+
+    session.resources.facts = facts;
+    session.touch();
+    state
+        .session_manager
+        .update_session(session)
+        .map_err(ApiError::from)?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "status": "fact_modified",
+        "index": new_index
+    })))
+}
+
+/// DELETE /sessions/{session_id}/facts/{index} - Retract one fact
+pub async fn retract_fact(
+    state: web::Data<AppState>,
+    path: web::Path<(String, u64)>,
+) -> Result<HttpResponse, ApiError> {
+    let (session_id, index) = path.into_inner();
+    let session_id = clara_session::SessionId(session_id);
+    log::info!("Retracting fact f-{} from session: {}", index, session_id);
+
+    let mut session = state
+        .session_manager
+        .get_session(&session_id)
+        .map_err(ApiError::from)?;
+
+    let retract_cmd = format!("(retract {})", index);
+    let (output, facts) = change_fact(&state, &session_id, index, &retract_cmd)?;
+    if let (_, Some(error)) = split_clips_error(&output) {
+        return Err(ApiError::new(clara_core::ClaraError::ValidationError(format!(
+            "Failed to retract fact f-{}: {}",
+            index, error
+        ))));
+    }
+
+    session.resources.facts = facts;
+    session.touch();
+    state
+        .session_manager
+        .update_session(session)
+        .map_err(ApiError::from)?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "status": "fact_retracted",
+        "index": index
+    })))
+}
+
 /// POST /sessions/{session_id}/run - Run rules in a session
 pub async fn run_rules(
     state: web::Data<AppState>,
@@ -295,7 +525,19 @@ pub async fn run_rules(
         .get_session(&session_id)
         .map_err(ApiError::from)?;
 
-    let start = std::time::Instant::now();
+    // Catch rules written against templates that were never defined
+    if req.validate_templates {
+        let missing = state
+            .session_manager
+            .with_clips_env(&session_id, |env| env.undefined_rule_templates())
+            .map_err(ApiError::from)?;
+        if !missing.is_empty() {
+            return Err(ApiError::new(clara_core::ClaraError::ValidationError(format!(
+                "Rules reference undefined templates: {}",
+                missing.join(", ")
+            ))));
+        }
+    }
 
     // Run rules via CLIPS environment
     let run_cmd = if req.max_iterations < 0 {
@@ -304,17 +546,49 @@ pub async fn run_rules(
         format!("(run {})", req.max_iterations)
     };
 
-    let result = state
+    let window = req.loop_detection_window.unwrap_or_else(loop_detection_window);
+    // Both fact listings happen under the same lock as the run, so a
+    // concurrent change to the session isn't reported as the run's doing
+    let (outcome, activations_remaining, elapsed_ms, fact_changes) = state
         .session_manager
         .with_clips_env(&session_id, |env| {
-            env.eval(&run_cmd)
+            let before = if req.return_facts { Some(env_facts(env)?) } else { None };
+
+            let start = std::time::Instant::now();
+            let outcome = if window == 0 {
+                // Parse result to get rules fired count
+                let result = env.eval(&run_cmd)?;
+                RunOutcome::Completed { rules_fired: result.trim().parse::<u64>().unwrap_or(0) }
+            } else {
+                env.run_with_loop_detection(req.max_iterations, window)?
+            };
+            let elapsed_ms = start.elapsed().as_millis() as u64;
+            // Tells a client running in bounded steps whether to run again
+            let activations_remaining = env.agenda_size()?;
+
+            let fact_changes = match before {
+                Some(before) => Some((before, env_facts(env)?)),
+                None => None,
+            };
+            Ok((outcome, activations_remaining, elapsed_ms, fact_changes))
         })
         .map_err(ApiError::from)?;
 
-    let elapsed_ms = start.elapsed().as_millis() as u64;
-
-    // Parse result to get rules fired count
-    let rules_fired = result.trim().parse::<u64>().unwrap_or(0);
+    slow_query_log().check("CLIPS run", &session_id.0, &run_cmd, elapsed_ms);
+
+    let rules_fired = match outcome {
+        RunOutcome::Completed { rules_fired } => rules_fired,
+        RunOutcome::LoopDetected { rules_fired, activation } => {
+            log::warn!(
+                "Stopped run in session {} after {} firings: rule loop on {}",
+                session_id, rules_fired, activation
+            );
+            return Err(ApiError::new(clara_core::ClaraError::RuntimeError(format!(
+                "rule loop detected: {} fired repeatedly (stopped after {} firings)",
+                activation, rules_fired
+            ))));
+        }
+    };
 
     // Touch session to update last activity
     state
@@ -322,15 +596,86 @@ pub async fn run_rules(
         .touch_session(&session_id)
         .map_err(ApiError::from)?;
 
+    // Fact indices are never reused, so a new index is a new assertion
+    // and a missing one a retraction
+    let (asserted, retracted) = match fact_changes {
+        Some((before, after)) => {
+            let asserted = after
+                .iter()
+                .filter(|(index, _)| !before.contains_key(index))
+                .map(|(_, fact)| fact.clone())
+                .collect();
+            let retracted = before
+                .into_iter()
+                .filter(|(index, _)| !after.contains_key(index))
+                .map(|(_, fact)| fact)
+                .collect();
+            (Some(asserted), Some(retracted))
+        }
+        None => (None, None),
+    };
+
     let response = RunResponse {
         rules_fired,
         status: "completed".to_string(),
         runtime_ms: elapsed_ms,
+        activations_remaining,
+        asserted,
+        retracted,
     };
 
     Ok(HttpResponse::Ok().json(response))
 }
 
+/// POST /sessions/{session_id}/reset - Clear facts (`mode=facts`) or everything (`mode=all`)
+pub async fn reset_session(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    query: web::Query<ResetQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let session_id = clara_session::SessionId(path.into_inner());
+    log::info!("Resetting session {} (mode: {:?})", session_id, query.mode);
+
+    let mut session = state
+        .session_manager
+        .get_session(&session_id)
+        .map_err(ApiError::from)?;
+
+    // `(reset)` reasserts `initial-fact` and every deffacts, so recount
+    let facts = state
+        .session_manager
+        .with_clips_env(&session_id, |env| {
+            match query.mode {
+                ResetMode::Facts => env.reset()?,
+                ResetMode::All => {
+                    // `(clear)` also removes the Coire library; restore it so
+                    // publish functions keep working
+                    env.clear()?;
+                    env.load_coire_library()?;
+                    env.reset()?
+                }
+            }
+            Ok(env_facts(env)?.len() as u32)
+        })
+        .map_err(ApiError::from)?;
+
+    session.resources.facts = facts;
+    if query.mode == ResetMode::All {
+        session.resources.rules = 0;
+        session.resources.objects = 0;
+    }
+    session.touch();
+    state
+        .session_manager
+        .update_session(session)
+        .map_err(ApiError::from)?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "status": "reset",
+        "mode": query.mode
+    })))
+}
+
 /// GET /sessions/{session_id}/facts - Query facts in a session
 pub async fn query_facts(
     state: web::Data<AppState>,
@@ -348,21 +693,29 @@ pub async fn query_facts(
         .get_session(&session_id)
         .map_err(ApiError::from)?;
 
-    // Query facts via CLIPS environment
-    let query_cmd = format!("(find-all-facts ((?f)) TRUE)");
-    let result = state
-        .session_manager
-        .with_clips_env(&session_id, |env| {
-            env.eval(&query_cmd)
-        })
-        .map_err(ApiError::from)?;
+    let matches: Vec<String> = list_facts(&state, &session_id)?.into_values().collect();
+
+    // Look up each template's multislots once so they always read back as arrays
+    let mut multislots: HashMap<String, Vec<String>> = HashMap::new();
+    for template in matches.iter().filter_map(|m| clips_fact_template(m)) {
+        if !multislots.contains_key(template) {
+            let names = state
+                .session_manager
+                .with_clips_env(&session_id, |env| env.multislot_names(template))
+                .map_err(ApiError::from)?;
+            multislots.insert(template.to_string(), names);
+        }
+    }
 
-    // Parse result into list of facts
-    // For now, just split by lines and filter empty
-    let matches: Vec<String> = result
-        .lines()
-        .filter(|l| !l.trim().is_empty())
-        .map(|s| s.to_string())
+    let facts = matches
+        .iter()
+        .filter_map(|m| {
+            let slots = clips_fact_template(m)
+                .and_then(|t| multislots.get(t))
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            clips_fact_to_json_with_multislots(m, slots).ok()
+        })
         .collect();
 
     let count = matches.len();
@@ -370,16 +723,77 @@ pub async fn query_facts(
     let response = QueryFactsResponse {
         matches,
         count,
+        facts,
     };
 
     Ok(HttpResponse::Ok().json(response))
 }
 
-/// GET /sessions - List all sessions
+/// List the session's facts keyed by fact index
+fn list_facts(
+    state: &AppState,
+    session_id: &clara_session::SessionId,
+) -> Result<BTreeMap<u64, String>, ApiError> {
+    state
+        .session_manager
+        .with_clips_env(session_id, env_facts)
+        .map_err(ApiError::from)
+}
+
+/// Facts in `env` keyed by index; for use inside a `with_clips_env` closure
+fn env_facts(env: &mut clara_clips::ClipsEnvironment) -> Result<BTreeMap<u64, String>, String> {
+    let result = env.eval("(facts)")?;
+
+    // `(facts)` prints one `f-<index>   (fact ...)` line per fact, followed by
+    // a "For a total of N facts." summary
+    Ok(result
+        .lines()
+        .filter_map(|l| l.trim().strip_prefix("f-"))
+        .filter_map(|l| l.split_once(char::is_whitespace))
+        .filter_map(|(index, fact)| Some((index.parse().ok()?, fact.trim().to_string())))
+        .collect())
+}
+
+/// Eval `command` against fact `index`, failing with `FactNotFound` unless
+/// it names a live fact
+///
+/// The check, the change and the recount share one env lock, so a
+/// concurrent retract can't land in between. Returns the command's output
+/// and the session's fact count afterwards.
+fn change_fact(
+    state: &AppState,
+    session_id: &clara_session::SessionId,
+    index: u64,
+    command: &str,
+) -> Result<(String, u32), ApiError> {
+    state
+        .session_manager
+        .with_clips_env(session_id, |env| {
+            if !env_facts(env)?.contains_key(&index) {
+                return Ok(None);
+            }
+            let output = env.eval(command)?;
+            Ok(Some((output, env_facts(env)?.len() as u32)))
+        })
+        .map_err(ApiError::from)?
+        .ok_or_else(|| {
+            ApiError::new(clara_core::ClaraError::FactNotFound(format!(
+                "f-{} in session {}",
+                index, session_id
+            )))
+        })
+}
+
+/// GET /sessions - List all sessions, optionally only those of one type
+/// (`?type=clips` or `?type=prolog`)
 pub async fn list_all_sessions(
     state: web::Data<AppState>,
+    req: HttpRequest,
 ) -> Result<HttpResponse, ApiError> {
-    log::info!("Listing all sessions");
+    let query = web::Query::<SessionListQuery>::from_query(req.query_string()).map_err(|e| {
+        ApiError::new(clara_core::ClaraError::ValidationError(format!("Invalid query: {}", e)))
+    })?;
+    log::info!("Listing all sessions (type: {:?})", query.session_type);
 
     let sessions = state
         .session_manager
@@ -388,6 +802,7 @@ pub async fn list_all_sessions(
 
     let responses: Vec<SessionResponse> = sessions
         .iter()
+        .filter(|session| query.session_type.is_none_or(|t| session.session_type == t))
         .map(session_to_response)
         .collect();
 
@@ -396,15 +811,3 @@ pub async fn list_all_sessions(
         "total": responses.len()
     })))
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_format_timestamp() {
-        let ts = 1729700580; // 2024-10-23 17:03:00 UTC
-        let formatted = format_timestamp(ts);
-        assert!(formatted.contains("2024-10-23"));
-    }
-}
@@ -9,5 +9,8 @@ pub mod server;
 pub mod middleware;
 pub mod validation;
 pub mod subprocess;
+pub mod selftest;
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
 
 pub use server::start_server;
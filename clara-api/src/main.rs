@@ -12,6 +12,16 @@ fn main() -> std::io::Result<()> {
         .format_timestamp_millis()
         .init();
 
+    // `--selftest`: run the initialization sequence, report, and exit
+    if std::env::args().skip(1).any(|arg| arg == "--selftest") {
+        let config = ConfigLoader::from_env(None)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other,
+                format!("Failed to load config: {}", e)))?;
+        let report = clara_api::selftest::run_selftest(&config.clips.binary_path);
+        println!("{}", report);
+        std::process::exit(if report.passed() { 0 } else { 1 });
+    }
+
     log::info!("Starting Clara Cerebrum API Server");
 
     // Initialize global Coire (shared event mailbox)
@@ -0,0 +1,169 @@
+//! Append-only audit log of security violations.
+//!
+//! Rejections that are security relevant (`SecurityViolation` from the
+//! consult directive whitelist, `FileAccessDenied` and `InvalidFilePath` from
+//! path checks) are written here as one JSON object per line, in addition to
+//! the usual `warn` log entry. Each record is flushed and synced before the
+//! request fails, so it survives a crash.
+//! The destination comes from `config.security.audit_log_path` via
+//! [`set_audit_log`] at startup; until then records go to stdout.
+
+use clara_core::{truncate_str, ClaraError};
+use serde::Serialize;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+
+use crate::middleware::tracing::MAX_LOGGED_INPUT_CHARS;
+
+static AUDIT_LOG: OnceLock<AuditLog> = OnceLock::new();
+
+/// Install the process-wide audit log. The first call wins; later calls are
+/// ignored with a warning.
+pub fn set_audit_log(log: AuditLog) {
+    if AUDIT_LOG.set(log).is_err() {
+        log::warn!("set_audit_log: audit log already set, ignoring");
+    }
+}
+
+/// The configured audit log, or stdout if none was set.
+pub fn audit_log() -> &'static AuditLog {
+    AUDIT_LOG.get_or_init(AuditLog::stdout)
+}
+
+/// One audited rejection
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditRecord {
+    /// RFC 3339 time of the rejection
+    pub timestamp: String,
+    pub session_id: String,
+    pub user_id: Option<String>,
+    /// `ClaraError` variant name, e.g. `"SecurityViolation"`
+    pub kind: String,
+    pub message: String,
+    /// Offending input, truncated to `MAX_LOGGED_INPUT_CHARS`
+    pub input: String,
+}
+
+enum Sink {
+    Stdout,
+    File(Mutex<File>),
+}
+
+/// Writes [`AuditRecord`]s as JSON lines
+pub struct AuditLog {
+    sink: Sink,
+}
+
+impl AuditLog {
+    /// Audit log that writes to stdout
+    pub fn stdout() -> Self {
+        Self { sink: Sink::Stdout }
+    }
+
+    /// Audit log that appends to `path`, creating it if needed
+    pub fn file(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self { sink: Sink::File(Mutex::new(file)) })
+    }
+
+    /// Append `record` as one line and flush it durably
+    pub fn append(&self, record: &AuditRecord) -> io::Result<()> {
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+        match &self.sink {
+            Sink::Stdout => {
+                let mut out = io::stdout().lock();
+                out.write_all(line.as_bytes())?;
+                out.flush()
+            }
+            Sink::File(file) => {
+                let mut file = file
+                    .lock()
+                    .map_err(|_| io::Error::other("audit log lock poisoned"))?;
+                file.write_all(line.as_bytes())?;
+                file.sync_data()
+            }
+        }
+    }
+
+    /// Record `error` if it is a security rejection; other errors are ignored.
+    ///
+    /// Write failures are logged rather than returned so auditing never
+    /// changes the response the caller gets.
+    pub fn record(&self, error: &ClaraError, session_id: &str, user_id: Option<&str>, input: &str) {
+        if !matches!(
+            error,
+            ClaraError::SecurityViolation(_)
+                | ClaraError::CommandBlocked(_)
+                | ClaraError::FileAccessDenied(_)
+                | ClaraError::InvalidFilePath(_)
+        ) {
+            return;
+        }
+
+        let record = AuditRecord {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            session_id: session_id.to_string(),
+            user_id: user_id.map(str::to_string),
+            kind: error.error_type(),
+            message: error.to_string(),
+            input: truncate_str(input.trim(), MAX_LOGGED_INPUT_CHARS),
+        };
+        if let Err(e) = self.append(&record) {
+            log::error!("Failed to write audit record for session {}: {}", session_id, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validation::directives::DirectiveWhitelist;
+
+    fn temp_log_path(name: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("clara-audit-{}-{}.jsonl", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn test_blocked_directive_is_audited() {
+        let path = temp_log_path("blocked");
+        let log = AuditLog::file(&path).unwrap();
+
+        let input = "shell('rm -rf /')";
+        let error = DirectiveWhitelist::default().check(input).unwrap_err();
+        log.record(&error, "sess-1", Some("user-1"), input);
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 1);
+
+        let record: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(record["session_id"], "sess-1");
+        assert_eq!(record["user_id"], "user-1");
+        assert_eq!(record["kind"], "SecurityViolation");
+        assert_eq!(record["input"], input);
+        assert!(record["message"].as_str().unwrap().contains("shell"));
+        assert!(chrono::DateTime::parse_from_rfc3339(record["timestamp"].as_str().unwrap()).is_ok());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_non_security_errors_not_audited() {
+        let path = temp_log_path("ignored");
+        let log = AuditLog::file(&path).unwrap();
+
+        log.record(&ClaraError::ValidationError("bad".to_string()), "sess-1", None, "x");
+        log.record(&ClaraError::CommandBlocked("system".to_string()), "sess-1", None, "(system \"ls\")");
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), 1);
+        assert!(contents.contains("CommandBlocked"));
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
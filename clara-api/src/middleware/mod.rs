@@ -1,3 +1,4 @@
+pub mod audit;
 pub mod auth;
 pub mod cors;
 pub mod rate_limit;
@@ -1 +1,157 @@
-// Tracing middleware stub
+//! Slow-query logging for evaluation handlers.
+//!
+//! Handlers time each evaluation and pass the result to [`SlowQueryLog::check`],
+//! which emits a `warn` entry when the configured threshold is exceeded.
+//! The threshold comes from `config.observability.slow_query_ms` via
+//! [`set_slow_query_threshold`] at startup. Slow Prolog goals go through
+//! [`SlowQueryLog::check_goal`], which logs a goal that keeps being slow
+//! only once per [`SLOW_GOAL_REPEAT_WINDOW`].
+
+use clara_core::truncate_str;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock, PoisonError};
+use std::time::{Duration, Instant};
+
+/// Maximum number of input characters included in a log entry.
+pub const MAX_LOGGED_INPUT_CHARS: usize = 200;
+
+/// Threshold used until [`set_slow_query_threshold`] is called.
+const DEFAULT_SLOW_QUERY_MS: u64 = 1000;
+
+/// How long a logged slow goal is remembered, so repeats aren't logged again.
+pub const SLOW_GOAL_REPEAT_WINDOW: Duration = Duration::from_secs(60);
+
+static SLOW_QUERY_LOG: OnceLock<SlowQueryLog> = OnceLock::new();
+
+/// Install the process-wide threshold. The first call wins; later calls are
+/// ignored with a warning.
+pub fn set_slow_query_threshold(threshold_ms: u64) {
+    if SLOW_QUERY_LOG.set(SlowQueryLog::new(threshold_ms)).is_err() {
+        log::warn!("set_slow_query_threshold: threshold already set, ignoring {}ms", threshold_ms);
+    }
+}
+
+/// The configured slow-query log, or the default threshold if none was set.
+pub fn slow_query_log() -> &'static SlowQueryLog {
+    SLOW_QUERY_LOG.get_or_init(|| SlowQueryLog::new(DEFAULT_SLOW_QUERY_MS))
+}
+
+/// Logs evaluations slower than a threshold
+#[derive(Debug)]
+pub struct SlowQueryLog {
+    /// Threshold in milliseconds; 0 disables logging
+    threshold_ms: u64,
+    /// When each `(session_id, normalized goal)` was last logged
+    logged_goals: Mutex<HashMap<(String, String), Instant>>,
+}
+
+impl SlowQueryLog {
+    pub fn new(threshold_ms: u64) -> Self {
+        Self { threshold_ms, logged_goals: Mutex::new(HashMap::new()) }
+    }
+
+    /// Log a `warn` entry if `elapsed_ms` exceeds the threshold.
+    ///
+    /// Returns the logged message, or `None` when the evaluation was fast
+    /// enough (or logging is disabled).
+    pub fn check(&self, kind: &str, session_id: &str, input: &str, elapsed_ms: u64) -> Option<String> {
+        if self.threshold_ms == 0 || elapsed_ms <= self.threshold_ms {
+            return None;
+        }
+
+        let message = format!(
+            "Slow {} in session {}: {}ms (threshold {}ms): {}",
+            kind,
+            session_id,
+            elapsed_ms,
+            self.threshold_ms,
+            truncate_str(input.trim(), MAX_LOGGED_INPUT_CHARS)
+        );
+        log::warn!("{}", message);
+        Some(message)
+    }
+
+    /// Like [`check`](Self::check) for a Prolog goal, but a goal already
+    /// logged for the session within [`SLOW_GOAL_REPEAT_WINDOW`] isn't
+    /// logged again.
+    ///
+    /// Goals are keyed by [`clara_prolog::normalize_goal`], so the same goal
+    /// written with other spacing, operator notation or variable names is a
+    /// repeat. A goal that doesn't parse is keyed by its trimmed text.
+    pub fn check_goal(&self, session_id: &str, goal: &str, elapsed_ms: u64) -> Option<String> {
+        if self.threshold_ms == 0 || elapsed_ms <= self.threshold_ms {
+            return None;
+        }
+
+        let goal_key = clara_prolog::normalize_goal(goal).unwrap_or_else(|_| goal.trim().to_string());
+        {
+            let mut logged = self.logged_goals.lock().unwrap_or_else(PoisonError::into_inner);
+            let now = Instant::now();
+            logged.retain(|_, at| now.duration_since(*at) < SLOW_GOAL_REPEAT_WINDOW);
+            if logged.insert((session_id.to_string(), goal_key), now).is_some() {
+                return None;
+            }
+        }
+        self.check("Prolog query", session_id, goal, elapsed_ms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    /// Stand-in for an engine eval that takes `ms` milliseconds
+    fn mock_eval(ms: u64) -> u64 {
+        let start = Instant::now();
+        std::thread::sleep(Duration::from_millis(ms));
+        start.elapsed().as_millis() as u64
+    }
+
+    #[test]
+    fn test_slow_eval_logged_above_threshold() {
+        let log = SlowQueryLog::new(20);
+        let elapsed = mock_eval(40);
+
+        let message = log.check("eval", "sess-1", "(run)", elapsed).expect("should log");
+        assert!(message.contains("sess-1"));
+        assert!(message.contains("(run)"));
+        assert!(message.contains(&format!("{}ms", elapsed)));
+    }
+
+    #[test]
+    fn test_fast_eval_not_logged_below_threshold() {
+        let log = SlowQueryLog::new(10_000);
+        let elapsed = mock_eval(1);
+        assert!(log.check("eval", "sess-1", "(run)", elapsed).is_none());
+    }
+
+    #[test]
+    fn test_zero_threshold_disables_logging() {
+        let log = SlowQueryLog::new(0);
+        assert!(log.check("eval", "sess-1", "(run)", 60_000).is_none());
+    }
+
+    #[test]
+    fn test_slow_goal_logged_once_per_normalized_form() {
+        let log = SlowQueryLog::new(1);
+        assert!(log.check_goal("sess-3", "member(X,[1,2])", 5).is_some());
+
+        // Same goal with other spacing and variable names: the same entry
+        assert!(log.check_goal("sess-3", "member( Y , [1, 2] )", 5).is_none());
+        assert_eq!(log.logged_goals.lock().unwrap().len(), 1);
+
+        // A different goal, or the same goal in another session, is logged
+        assert!(log.check_goal("sess-3", "member(X,[1,3])", 5).is_some());
+        assert!(log.check_goal("sess-4", "member(X,[1,2])", 5).is_some());
+    }
+
+    #[test]
+    fn test_input_truncated() {
+        let log = SlowQueryLog::new(1);
+        let input = "x".repeat(500);
+        let message = log.check("query", "sess-2", &input, 5).unwrap();
+        assert!(message.ends_with('…'));
+        assert!(message.len() < 400);
+    }
+}
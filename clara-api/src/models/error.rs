@@ -1,9 +1,27 @@
-use actix_web::{http::StatusCode, HttpResponse, ResponseError};
+use actix_web::{http::header, http::StatusCode, HttpResponse, ResponseError};
 use clara_core::ClaraError;
 use clara_prolog::PrologError;
 use clara_session::ManagerError;
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::sync::OnceLock;
+
+const DEFAULT_RETRY_AFTER_SECS: u64 = 1;
+
+static RETRY_AFTER_SECS: OnceLock<u64> = OnceLock::new();
+
+/// Install the process-wide `Retry-After` backoff. The first call wins;
+/// later calls are ignored with a warning.
+pub fn set_retry_after_secs(secs: u64) {
+    if RETRY_AFTER_SECS.set(secs).is_err() {
+        log::warn!("set_retry_after_secs: backoff already set, ignoring {}", secs);
+    }
+}
+
+/// The configured `Retry-After` backoff, or the default if none was set.
+pub fn retry_after_secs() -> u64 {
+    *RETRY_AFTER_SECS.get_or_init(|| DEFAULT_RETRY_AFTER_SECS)
+}
 
 /// API error response
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -33,11 +51,26 @@ impl ApiError {
             409 => StatusCode::CONFLICT,
             429 => StatusCode::TOO_MANY_REQUESTS,
             500 => StatusCode::INTERNAL_SERVER_ERROR,
+            503 => StatusCode::SERVICE_UNAVAILABLE,
             504 => StatusCode::GATEWAY_TIMEOUT,
             _ => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
 
+    /// Seconds to send in `Retry-After`, for errors that clear up by
+    /// themselves once load drops
+    ///
+    /// Session limits aren't included: they only lift when sessions are
+    /// terminated or evicted, which waiting a moment won't bring about.
+    pub fn retry_after(&self) -> Option<u64> {
+        match self.inner {
+            ClaraError::QueueFull
+            | ClaraError::ConcurrencyLimitExceeded
+            | ClaraError::ServiceUnavailable(_) => Some(retry_after_secs()),
+            _ => None,
+        }
+    }
+
     pub fn response(&self) -> ApiErrorResponse {
         ApiErrorResponse {
             error: self.inner.to_string(),
@@ -61,7 +94,11 @@ impl ResponseError for ApiError {
 
     fn error_response(&self) -> HttpResponse {
         let error_response = self.response();
-        HttpResponse::build(self.status_code()).json(error_response)
+        let mut builder = HttpResponse::build(self.status_code());
+        if let Some(secs) = self.retry_after() {
+            builder.insert_header((header::RETRY_AFTER, secs.to_string()));
+        }
+        builder.json(error_response)
     }
 }
 
@@ -88,15 +125,27 @@ impl From<ManagerError> for ApiError {
             ManagerError::GlobalSessionLimitExceeded => ClaraError::GlobalSessionLimitExceeded,
             ManagerError::SessionTerminated => ClaraError::SessionTerminated,
             ManagerError::SessionNotFound => ClaraError::SessionNotFound("Session not found".to_string()),
+            ManagerError::InvalidTransition { from, to } => {
+                ClaraError::ValidationError(format!("Cannot change session status from {} to {}", from, to))
+            }
             ManagerError::WrongSessionType { expected, actual } => {
                 ClaraError::ValidationError(format!("Expected {} session, got {}", expected, actual))
             }
             ManagerError::EnvironmentError(msg) => {
                 ClaraError::Internal(format!("Environment execution error: {}", msg))
             }
+            ManagerError::ClipsSyntaxError(msg) => ClaraError::SyntaxError(msg),
             ManagerError::PrologError(prolog_err) => {
                 clara_error_from_prolog(prolog_err)
             }
+            ManagerError::QueueFull => ClaraError::QueueFull,
+            ManagerError::Persistence(msg) => ClaraError::Internal(format!("Persistence error: {}", msg)),
+            ManagerError::UnsupportedSessionType(session_type) => {
+                ClaraError::ValidationError(format!("Unsupported session type: {}", session_type))
+            }
+            ManagerError::InvalidSessionId(id) => {
+                ClaraError::ValidationError(format!("Invalid session id: {:?}", id))
+            }
         };
         Self { inner: clara_error }
     }
@@ -115,6 +164,12 @@ fn clara_error_from_prolog(err: PrologError) -> ClaraError {
             }
         }
         PrologError::QueryFailed(msg) => ClaraError::EvalFailed(msg.clone()),
+        PrologError::InvalidArgument(msg) => ClaraError::ValidationError(msg.clone()),
+        PrologError::Timeout { timeout_ms } => ClaraError::EvalTimeout { timeout_ms: *timeout_ms },
+        PrologError::EngineInUse { .. } => ClaraError::ConcurrencyLimitExceeded,
+        PrologError::InitializationFailed(_) | PrologError::EngineCreationFailed(_) => {
+            ClaraError::ServiceUnavailable(err.to_string())
+        }
         _ => ClaraError::Internal(err.to_string()),
     }
 }
@@ -131,6 +186,33 @@ mod tests {
         assert_eq!(api_err.status_code(), StatusCode::NOT_FOUND);
     }
 
+    #[test]
+    fn test_queue_full_is_too_many_requests() {
+        let api_err = ApiError::from(ManagerError::QueueFull);
+        assert_eq!(api_err.status_code(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(api_err.response().error_type, "QueueFull");
+    }
+
+    #[test]
+    fn test_retry_after_on_429_and_503() {
+        let full = ApiError::from(ManagerError::QueueFull).error_response();
+        assert_eq!(full.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(full.headers().get(header::RETRY_AFTER).unwrap(), &retry_after_secs().to_string());
+
+        let no_engine = ApiError::from(ManagerError::PrologError(PrologError::EngineCreationFailed(
+            "PL_create_engine returned null".to_string(),
+        )))
+        .error_response();
+        assert_eq!(no_engine.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(no_engine.headers().get(header::RETRY_AFTER).unwrap(), &retry_after_secs().to_string());
+
+        // Waiting won't lift a session limit or fix a bad request
+        let limited = ApiError::from(ManagerError::UserSessionLimitExceeded).error_response();
+        assert!(limited.headers().get(header::RETRY_AFTER).is_none());
+        let invalid = ApiError::from(ClaraError::ValidationError("bad".to_string())).error_response();
+        assert!(invalid.headers().get(header::RETRY_AFTER).is_none());
+    }
+
     #[test]
     fn test_api_error_response() {
         let clara_err = ClaraError::ValidationError("bad input".to_string());
@@ -140,4 +222,13 @@ mod tests {
         assert_eq!(response.code, 400);
         assert_eq!(response.error_type, "ValidationError");
     }
+
+    #[test]
+    fn test_api_error_from_clips_errors() {
+        let syntax = ApiError::from(ManagerError::ClipsSyntaxError("CLIPS Build failed (code 3)".to_string()));
+        assert_eq!(syntax.status_code(), StatusCode::BAD_REQUEST);
+
+        let runtime = ApiError::from(ManagerError::EnvironmentError("engine fell over".to_string()));
+        assert_eq!(runtime.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
 }
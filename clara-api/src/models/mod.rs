@@ -2,15 +2,19 @@ pub mod error;
 pub mod request;
 pub mod response;
 
-pub use error::{ApiError, ApiErrorResponse};
+pub use error::{retry_after_secs, set_retry_after_secs, ApiError, ApiErrorResponse};
+/// Engine backing a session; serialized as `"clips"` or `"prolog"`
+pub use clara_session::SessionType;
 pub use request::{
     CreateSessionRequest, EvalRequest, LoadRequest, SaveSessionRequest, ReloadRequest,
-    LoadRulesRequest, LoadFactsRequest, RunRequest, PrologQueryRequest, PrologConsultRequest,
-    DeduceRequest, DeduceResumeRequest, CoirePushRequest, RegisterSourceRequest,
+    LoadRulesRequest, LoadFactsRequest, ModifyFactRequest, RunRequest, ResetMode, ResetQuery, SessionListQuery, SnapshotQuery, PrologQueryRequest,
+    PrologQueryFormat,
+    PrologConsultRequest, PrologConsultFileRequest, DeduceRequest, DeduceResumeRequest, CoirePushRequest,
+    RegisterSourceRequest, SessionConfig,
 };
 pub use response::{
-    SessionResponse, EvalResponse, LoadResponse, SaveResponse, ReloadResponse, StatusResponse,
-    TerminateResponse, HealthResponse, ResourceInfo, EvalMetrics, RunResponse, QueryFactsResponse,
+    SessionResponse, EvalResponse, EngineEvalResponse, LoadResponse, SaveResponse, ReloadResponse, StatusResponse,
+    TerminateResponse, RestoreResponse, HealthResponse, ResourceInfo, EvalMetrics, RunResponse, QueryFactsResponse,
     PrologQueryResponse, DeduceStartResponse, DeduceStatusResponse, DeduceInterruptResponse,
-    DeduceDeleteSnapshotResponse,
+    DeduceDeleteSnapshotResponse, LoadRulesResponse, RuleLoadFailure,
 };
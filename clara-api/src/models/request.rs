@@ -1,3 +1,5 @@
+use clara_prolog::BuiltinProfile;
+use clara_session::SessionType;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
@@ -11,6 +13,10 @@ pub struct SessionConfig {
     pub max_rules: Option<u32>,
     #[serde(default)]
     pub max_memory_mb: Option<u32>,
+    /// Tools `clara_evaluate` may call from the session; all registered
+    /// tools if omitted
+    #[serde(default)]
+    pub allowed_tools: Option<Vec<String>>,
 }
 
 /// Create session request
@@ -19,6 +25,14 @@ pub struct CreateSessionRequest {
     pub user_id: String,
     #[serde(default)]
     pub name: Option<String>,
+    /// Engine for `POST /sessions`; the server's default session type if omitted
+    #[serde(default, rename = "type")]
+    pub session_type: Option<SessionType>,
+    /// Prolog libraries to load: `minimal`, `standard` or `data_processing`.
+    /// Only valid for Prolog sessions. Profiles add libraries but don't
+    /// hide any, so `minimal` is not a sandbox.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<BuiltinProfile>,
     #[serde(default)]
     pub config: Option<SessionConfig>,
     #[serde(default)]
@@ -65,7 +79,18 @@ pub struct LoadRulesRequest {
 /// Load facts request
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LoadFactsRequest {
-    pub facts: Vec<String>,
+    /// Each fact is either raw CLIPS text (`"(person (name \"Al\"))"`) or a
+    /// JSON object with a `"template"` key, converted via `clips_conversion`.
+    pub facts: Vec<serde_json::Value>,
+}
+
+/// Request to change slot values of one fact
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModifyFactRequest {
+    /// Fact index, as in `f-<index>`
+    pub index: u64,
+    /// New slot values, converted via `clips_conversion`
+    pub slots: serde_json::Map<String, serde_json::Value>,
 }
 
 /// Run rules request
@@ -73,6 +98,56 @@ pub struct LoadFactsRequest {
 pub struct RunRequest {
     #[serde(default = "default_max_iterations")]
     pub max_iterations: i64,
+    /// Reject the run if any rule pattern references a template that was
+    /// never `deftemplate`d. Off by default since ordered facts rely on
+    /// implied templates.
+    #[serde(default)]
+    pub validate_templates: bool,
+    /// Report the facts asserted and retracted by the run
+    #[serde(default)]
+    pub return_facts: bool,
+    /// Recent firings checked for rule loops, overriding
+    /// `clips.loop_detection_window`; 0 turns loop detection off
+    #[serde(default)]
+    pub loop_detection_window: Option<usize>,
+}
+
+/// What `POST /sessions/{id}/reset` clears
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResetMode {
+    /// `(reset)`: drop facts, keep templates and rules
+    #[default]
+    Facts,
+    /// `(clear)`: drop everything the session has loaded
+    All,
+}
+
+/// Query parameters for `POST /sessions/{id}/reset`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResetQuery {
+    #[serde(default)]
+    pub mode: ResetMode,
+}
+
+/// Query parameters for `GET /sessions`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionListQuery {
+    /// Only list sessions of this type (`?type=clips` or `?type=prolog`)
+    #[serde(rename = "type", default)]
+    pub session_type: Option<SessionType>,
+}
+
+/// Query parameters for `GET /admin/snapshot`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotQuery {
+    /// Include the dynamic Prolog clauses (default `true`)
+    #[serde(default = "default_include_clauses")]
+    pub clauses: bool,
+}
+
+fn default_include_clauses() -> bool {
+    true
 }
 
 fn default_timeout() -> u64 {
@@ -83,6 +158,19 @@ fn default_max_iterations() -> i64 {
     -1 // -1 means run until completion
 }
 
+/// Shape of `POST /devils/sessions/{id}/query` results
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PrologQueryFormat {
+    /// Each solution as an object of the goal's named variables, e.g. `{"X": 1}`
+    #[default]
+    Bindings,
+    /// Each solution as the instantiated goal term
+    Terms,
+    /// Only whether the goal succeeds: `"true"` or `"false"`
+    Check,
+}
+
 /// Prolog query request
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PrologQueryRequest {
@@ -91,6 +179,21 @@ pub struct PrologQueryRequest {
     /// If true, return all solutions; if false, return first solution only
     #[serde(default)]
     pub all_solutions: Option<bool>,
+    /// Values for named variables in `goal`, e.g. `{"Low": 1, "High": 5}`.
+    /// Bound as terms before the goal runs, never spliced into its text.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bindings: Option<serde_json::Map<String, serde_json::Value>>,
+    /// Abort the query after this many milliseconds, overriding
+    /// `resources.prolog_query_timeout_ms`; 0 means no limit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    /// Result shape; `all_solutions` is ignored for `check`
+    #[serde(default)]
+    pub format: PrologQueryFormat,
+    /// Stop after this many solutions and report whether more were left in
+    /// `truncated`; implies `all_solutions`. Ignored for `check`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_solutions: Option<usize>,
 }
 
 /// Prolog consult request - load clauses into the knowledge base
@@ -100,6 +203,13 @@ pub struct PrologConsultRequest {
     pub clauses: Vec<String>,
 }
 
+/// Prolog file consult request - load a file from under the consult root
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrologConsultFileRequest {
+    /// File to consult, relative to `security.prolog_consult_root`
+    pub path: String,
+}
+
 /// Request to start a new deduction cycle.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeduceRequest {
@@ -243,9 +353,11 @@ mod tests {
         let req = CreateSessionRequest {
             user_id: "user-123".to_string(),
             name: Some("Test Session".to_string()),
+            session_type: None,
             config: None,
             preload: vec![],
             metadata: HashMap::new(),
+            profile: None,
         };
         assert_eq!(req.user_id, "user-123");
     }
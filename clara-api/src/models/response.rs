@@ -1,3 +1,5 @@
+use clara_clips::clips_conversion::{clips_value_to_json, clips_warnings, split_clips_error};
+use clara_session::SessionType;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -6,6 +8,8 @@ use uuid::Uuid;
 pub struct SessionResponse {
     pub session_id: String,
     pub user_id: String,
+    /// Engine backing the session: "clips" or "prolog"
+    pub session_type: SessionType,
     pub started: String,
     pub touched: String,
     pub status: String,
@@ -40,14 +44,113 @@ pub struct StatusResponse {
 /// Eval response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EvalResponse {
+    /// False when CLIPS reported an error; the message is in `error`
+    pub success: bool,
     pub stdout: String,
     pub stderr: String,
     pub exit_code: i32,
     pub metrics: EvalMetrics,
+    /// `stdout` as structured JSON when it is a single CLIPS value;
+    /// multifields become arrays
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<serde_json::Value>,
+    /// CLIPS error text, split out of the captured output
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub session: Option<SessionResponse>,
 }
 
+impl From<clara_core::EvalResult> for EvalResponse {
+    /// Carry an eval result over to the API, splitting any CLIPS error
+    /// message out of `stdout`
+    ///
+    /// The exit code, stderr and metrics are kept as they are. The result is
+    /// successful only with exit code 0 and no error, either given or found
+    /// in the output; `value` is parsed from the remaining stdout only then.
+    fn from(result: clara_core::EvalResult) -> Self {
+        let (stdout, marked_error) = split_clips_error(&result.stdout);
+        let mut error = result.error.clone().or_else(|| marked_error.map(str::to_string));
+        if result.exit_code != 0 && error.is_none() {
+            error = Some(if result.stderr.trim().is_empty() {
+                format!("CLIPS exited with code {}", result.exit_code)
+            } else {
+                result.stderr.trim().to_string()
+            });
+        }
+
+        let success = error.is_none();
+        let value = if success { clips_value_to_json(stdout).ok() } else { None };
+        Self {
+            success,
+            stdout: stdout.to_string(),
+            stderr: result.stderr,
+            exit_code: result.exit_code,
+            metrics: result.metrics.into(),
+            value,
+            error,
+            session: None,
+        }
+    }
+}
+
+/// Response for POST /sessions/{session_id}/eval, for either engine
+///
+/// `session_type` says which engine produced the result. For CLIPS, `value`
+/// is the output parsed as a CLIPS value; for Prolog it is the bindings of
+/// the first solution.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EngineEvalResponse {
+    pub session_type: SessionType,
+    pub success: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<serde_json::Value>,
+    pub stdout: String,
+    /// CLIPS `WARNING:` messages; always empty for Prolog
+    #[serde(default)]
+    pub warnings: Vec<String>,
+    pub metrics: EvalMetrics,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl EngineEvalResponse {
+    /// A Prolog query result: the bindings JSON on success, or the failure
+    /// message
+    pub fn prolog(result: Result<String, String>, metrics: EvalMetrics) -> Self {
+        let (success, stdout, value, error) = match result {
+            Ok(bindings) => {
+                let value = serde_json::from_str(&bindings).ok();
+                (true, bindings, value, None)
+            }
+            Err(e) => (false, String::new(), None, Some(e)),
+        };
+        Self {
+            session_type: SessionType::Prolog,
+            success,
+            value,
+            stdout,
+            warnings: Vec::new(),
+            metrics,
+            error,
+        }
+    }
+}
+
+impl From<EvalResponse> for EngineEvalResponse {
+    fn from(resp: EvalResponse) -> Self {
+        Self {
+            session_type: SessionType::Clips,
+            success: resp.success,
+            value: resp.value,
+            warnings: clips_warnings(&resp.stdout).into_iter().map(str::to_string).collect(),
+            stdout: resp.stdout,
+            metrics: resp.metrics,
+            error: resp.error,
+        }
+    }
+}
+
 /// Eval metrics
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct EvalMetrics {
@@ -58,6 +161,16 @@ pub struct EvalMetrics {
     pub rules_fired: Option<u32>,
 }
 
+impl From<clara_core::EvalMetrics> for EvalMetrics {
+    fn from(metrics: clara_core::EvalMetrics) -> Self {
+        Self {
+            elapsed_ms: metrics.elapsed_ms,
+            facts_added: metrics.facts_added,
+            rules_fired: metrics.rules_fired,
+        }
+    }
+}
+
 /// Load response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LoadResponse {
@@ -90,6 +203,18 @@ pub struct TerminateResponse {
     pub saved: bool,
 }
 
+/// Restore response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestoreResponse {
+    pub status: String,
+    /// Sessions recreated from the snapshot
+    pub sessions: Vec<SessionResponse>,
+    /// Sessions in the snapshot whose id already existed
+    pub skipped: usize,
+    /// Prolog clauses replayed
+    pub clauses: usize,
+}
+
 /// Health check response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HealthResponse {
@@ -97,12 +222,41 @@ pub struct HealthResponse {
     pub uptime_seconds: u64,
 }
 
+/// A rule that failed to compile in `POST /sessions/{id}/rules`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleLoadFailure {
+    /// Position of the rule in the request's `rules` array
+    pub index: usize,
+    pub error: String,
+}
+
+/// Load rules response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoadRulesResponse {
+    pub status: String,
+    /// Number of rules in the request
+    pub count: usize,
+    /// Number of rules that compiled
+    pub loaded: usize,
+    pub failed: Vec<RuleLoadFailure>,
+}
+
 /// Run rules response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RunResponse {
     pub rules_fired: u64,
     pub status: String,
     pub runtime_ms: u64,
+    /// Activations still on the agenda when the run stopped; non-zero after
+    /// a run cut short by `max_iterations`
+    #[serde(default)]
+    pub activations_remaining: u64,
+    /// Facts asserted by the run; present only when `return_facts` was set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asserted: Option<Vec<String>>,
+    /// Facts retracted by the run; present only when `return_facts` was set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retracted: Option<Vec<String>>,
 }
 
 /// Query facts response
@@ -110,6 +264,9 @@ pub struct RunResponse {
 pub struct QueryFactsResponse {
     pub matches: Vec<String>,
     pub count: usize,
+    /// `matches` converted to JSON objects via `clips_conversion`
+    #[serde(default)]
+    pub facts: Vec<serde_json::Value>,
 }
 
 /// Prolog query response
@@ -121,6 +278,10 @@ pub struct PrologQueryResponse {
     pub success: bool,
     /// Execution time in milliseconds
     pub runtime_ms: u64,
+    /// With `max_solutions`, whether the query was stopped while more
+    /// solutions may have been left
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub truncated: Option<bool>,
 }
 
 /// Response for POST /deduce — deduction accepted and running asynchronously.
@@ -163,6 +324,7 @@ mod tests {
         let resp = SessionResponse {
             session_id: "sess-123".to_string(),
             user_id: "user-123".to_string(),
+            session_type: SessionType::Clips,
             started: "2025-10-23T17:03:00Z".to_string(),
             touched: "2025-10-23T17:03:00Z".to_string(),
             status: "active".to_string(),
@@ -176,4 +338,100 @@ mod tests {
         };
         assert_eq!(resp.session_id, "sess-123");
     }
+
+    #[test]
+    fn test_eval_response_from_success() {
+        let mut metrics = clara_core::EvalMetrics::with_elapsed(12);
+        metrics.rules_fired = Some(3);
+        let resp = EvalResponse::from(clara_core::EvalResult::success("42".to_string(), metrics));
+
+        assert!(resp.success);
+        assert_eq!(resp.exit_code, 0);
+        assert_eq!(resp.stdout, "42");
+        assert_eq!(resp.value, Some(serde_json::json!(42)));
+        assert_eq!(resp.error, None);
+        assert_eq!(resp.metrics.elapsed_ms, 12);
+        assert_eq!(resp.metrics.rules_fired, Some(3));
+    }
+
+    #[test]
+    fn test_eval_response_from_nonzero_exit() {
+        let result = clara_core::EvalResult {
+            stdout: "partial".to_string(),
+            stderr: "segfault".to_string(),
+            exit_code: 139,
+            metrics: clara_core::EvalMetrics::with_elapsed(5),
+            error: None,
+        };
+        let resp = EvalResponse::from(result);
+
+        assert!(!resp.success);
+        assert_eq!(resp.exit_code, 139);
+        assert_eq!(resp.stdout, "partial");
+        assert_eq!(resp.stderr, "segfault");
+        assert_eq!(resp.error.as_deref(), Some("segfault"));
+        assert_eq!(resp.value, None);
+
+        // With nothing on stderr the exit code is reported instead
+        let resp = EvalResponse::from(result_with_exit(2));
+        assert_eq!(resp.error.as_deref(), Some("CLIPS exited with code 2"));
+    }
+
+    #[test]
+    fn test_eval_response_splits_error_marker() {
+        let output = "before\n[EXPRNPSR3] Missing function declaration for foo.\n";
+        let resp = EvalResponse::from(clara_core::EvalResult::success(
+            output.to_string(),
+            clara_core::EvalMetrics::default(),
+        ));
+
+        assert!(!resp.success);
+        assert_eq!(resp.exit_code, 0);
+        assert_eq!(resp.stdout, "before\n");
+        assert_eq!(resp.error.as_deref(), Some("[EXPRNPSR3] Missing function declaration for foo."));
+        assert_eq!(resp.value, None);
+    }
+
+    #[test]
+    fn test_engine_eval_response_clips_serialization() {
+        let output = "[CSTRCPSR1] WARNING: Redefining defrule: r1 +j+\n";
+        let resp = EngineEvalResponse::from(EvalResponse::from(clara_core::EvalResult::success(
+            output.to_string(),
+            clara_core::EvalMetrics::with_elapsed(4),
+        )));
+
+        let json = serde_json::to_value(&resp).unwrap();
+        assert_eq!(json["session_type"], "clips");
+        assert_eq!(json["success"], true);
+        assert_eq!(json["warnings"], serde_json::json!(["[CSTRCPSR1] WARNING: Redefining defrule: r1 +j+"]));
+        assert_eq!(json["metrics"]["elapsed_ms"], 4);
+        assert!(json.get("error").is_none());
+
+        let back: EngineEvalResponse = serde_json::from_value(json).unwrap();
+        assert_eq!(back.session_type, SessionType::Clips);
+        assert_eq!(back.stdout, output);
+    }
+
+    #[test]
+    fn test_engine_eval_response_prolog_serialization() {
+        let resp = EngineEvalResponse::prolog(Ok(r#"{"X":1}"#.to_string()), EvalMetrics::default());
+        let json = serde_json::to_value(&resp).unwrap();
+        assert_eq!(json["session_type"], "prolog");
+        assert_eq!(json["value"], serde_json::json!({"X": 1}));
+        assert_eq!(json["stdout"], r#"{"X":1}"#);
+        assert_eq!(json["warnings"], serde_json::json!([]));
+
+        let failed = EngineEvalResponse::prolog(Err("Query failed: fail".to_string()), EvalMetrics::default());
+        let json = serde_json::to_value(&failed).unwrap();
+        assert_eq!(json["success"], false);
+        assert_eq!(json["error"], "Query failed: fail");
+        assert!(json.get("value").is_none());
+    }
+
+    fn result_with_exit(exit_code: i32) -> clara_core::EvalResult {
+        clara_core::EvalResult {
+            exit_code,
+            ..clara_core::EvalResult::success(String::new(), clara_core::EvalMetrics::default())
+        }
+    }
 }
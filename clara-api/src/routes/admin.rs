@@ -0,0 +1 @@
+pub use crate::handlers::admin_handler::{snapshot, restore};
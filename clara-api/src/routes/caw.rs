@@ -0,0 +1 @@
+pub use crate::handlers::caw_handler::{parse_caw, transpile_caw};
@@ -1,7 +1,9 @@
 use actix_web::HttpResponse;
 
+use crate::validation::input::render_rejection_metrics;
+
 pub async fn metrics() -> HttpResponse {
     HttpResponse::Ok()
         .content_type("text/plain; version=0.0.4")
-        .body("# No metrics yet")
+        .body(render_rejection_metrics())
 }
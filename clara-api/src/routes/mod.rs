@@ -9,6 +9,8 @@ pub mod trace;
 pub mod source;
 pub mod ritual;
 pub mod transduce;
+pub mod caw;
+pub mod admin;
 
 use actix_web::web;
 
@@ -22,6 +24,9 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
             .route("/livez", web::get().to(health::live))
             // Metrics route
             .route("/metrics", web::get().to(metrics::metrics))
+            // Admin backup routes
+            .route("/admin/snapshot", web::get().to(admin::snapshot))
+            .route("/admin/restore", web::post().to(admin::restore))
             // Session routes (CLIPS/LilDaemon)
             .route("/sessions", web::post().to(sessions::create_session))
             .route("/sessions", web::get().to(sessions::list_all_sessions))
@@ -29,11 +34,17 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
             .route("/sessions/{session_id}", web::get().to(sessions::get_session))
             .route("/sessions/{session_id}", web::delete().to(sessions::terminate_session))
             .route("/sessions/{session_id}/evaluate", web::post().to(sessions::eval_session))
+            .route("/sessions/{session_id}/eval", web::post().to(sessions::eval_any_session))
             .route("/sessions/{session_id}/save", web::post().to(sessions::save_session))
             .route("/sessions/{session_id}/rules", web::post().to(sessions::load_rules))
+            .route("/sessions/{session_id}/load", web::post().to(sessions::load_files))
             .route("/sessions/{session_id}/facts", web::post().to(sessions::load_facts))
             .route("/sessions/{session_id}/facts", web::get().to(sessions::query_facts))
+            .route("/sessions/{session_id}/facts/stream", web::post().to(sessions::load_facts_stream))
+            .route("/sessions/{session_id}/facts/modify", web::post().to(sessions::modify_fact))
+            .route("/sessions/{session_id}/facts/{index}", web::delete().to(sessions::retract_fact))
             .route("/sessions/{session_id}/run", web::post().to(sessions::run_rules))
+            .route("/sessions/{session_id}/reset", web::post().to(sessions::reset_session))
             // Devils routes (Prolog/LilDevils)
             .route("/devils/sessions", web::post().to(devils::create_prolog_session))
             .route("/devils/sessions", web::get().to(devils::list_prolog_sessions))
@@ -41,6 +52,8 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
             .route("/devils/sessions/{session_id}", web::delete().to(devils::terminate_prolog_session))
             .route("/devils/sessions/{session_id}/query", web::post().to(devils::query_prolog))
             .route("/devils/sessions/{session_id}/consult", web::post().to(devils::consult_prolog))
+            .route("/devils/sessions/{session_id}/consult/stream", web::post().to(devils::consult_prolog_stream))
+            .route("/devils/sessions/{session_id}/consult/file", web::post().to(devils::consult_prolog_file))
             // Deduction cycle routes — literal paths before parameterised ones
             .route("/deduce",                      web::get().to(deduce::list_deductions))
             .route("/deduce",                      web::post().to(deduce::start_deduce))
@@ -56,6 +69,9 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
             .route("/deduce/{id}/trace/{change_id}/entries",          web::get().to(trace::trace_entries))
             // Graph (edge) transduction
             .route("/transduce/graph",                                web::post().to(transduce::transduce_graph))
+            // CAW language tooling
+            .route("/caw/parse",                                      web::post().to(caw::parse_caw))
+            .route("/caw/transpile",                                  web::post().to(caw::transpile_caw))
             // Source registry
             .route("/source",                                         web::post().to(source::register_source))
             .route("/source/{id}",                                    web::get().to(source::get_source))
@@ -72,4 +88,6 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
             .route("/ritual/{id}/status",   web::get().to(ritual::ritual_status))
             .route("/ritual/{id}",          web::delete().to(ritual::terminate_ritual))
     );
+    // Unmatched paths and methods get the JSON error envelope
+    cfg.default_service(web::to(crate::handlers::route_not_found));
 }
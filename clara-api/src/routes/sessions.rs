@@ -1,6 +1,7 @@
 // Re-export handlers
 pub use crate::handlers::session_handler::{
     create_session, get_session, list_user_sessions, list_all_sessions, terminate_session,
-    save_session, load_rules, load_facts, run_rules, query_facts,
+    save_session, load_rules, load_files, load_facts, load_facts_stream, modify_fact, retract_fact, run_rules, query_facts,
+    reset_session,
 };
-pub use crate::handlers::eval_handler::eval_session;
+pub use crate::handlers::eval_handler::{eval_any_session, eval_session};
@@ -0,0 +1,146 @@
+//! Startup self-test (`clara-api --selftest`)
+//!
+//! Runs the server's initialization sequence — Coire, the toolbox, Prolog
+//! with `clara_evaluate/2`, a CLIPS subprocess and a sample Prolog query
+//! through the toolbox — without starting the HTTP server, and reports the
+//! status of each subsystem. A failing or panicking step is recorded and the
+//! remaining steps still run, so one report shows everything that is broken.
+
+use std::fmt;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use crate::subprocess::repl::ReplHandler;
+
+/// Subsystems checked, in the order they are initialized
+pub const SUBSYSTEMS: [&str; 5] = ["coire", "toolbox", "prolog", "clips", "query"];
+
+/// Outcome of one self-test step
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    pub subsystem: &'static str,
+    pub ok: bool,
+    /// What was found, or why the step failed
+    pub detail: String,
+}
+
+/// Outcome of a whole self-test run
+#[derive(Debug, Clone)]
+pub struct SelfTestReport {
+    pub checks: Vec<CheckResult>,
+}
+
+impl SelfTestReport {
+    /// True if every subsystem passed
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|c| c.ok)
+    }
+
+    /// The result for `subsystem`, if it was checked
+    pub fn check(&self, subsystem: &str) -> Option<&CheckResult> {
+        self.checks.iter().find(|c| c.subsystem == subsystem)
+    }
+}
+
+impl fmt::Display for SelfTestReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Clara self-test")?;
+        for check in &self.checks {
+            let mark = if check.ok { " OK " } else { "FAIL" };
+            writeln!(f, "  [{}] {:<8} {}", mark, check.subsystem, check.detail)?;
+        }
+        let failed = self.checks.iter().filter(|c| !c.ok).count();
+        if failed == 0 {
+            write!(f, "All {} checks passed", self.checks.len())
+        } else {
+            write!(f, "{} of {} checks failed", failed, self.checks.len())
+        }
+    }
+}
+
+/// Run every self-test step, spawning CLIPS from `clips_binary`
+pub fn run_selftest(clips_binary: &str) -> SelfTestReport {
+    let checks = vec![
+        run_check("coire", check_coire),
+        run_check("toolbox", check_toolbox),
+        run_check("prolog", check_prolog),
+        run_check("clips", || check_clips(clips_binary)),
+        run_check("query", check_query),
+    ];
+    SelfTestReport { checks }
+}
+
+fn run_check(subsystem: &'static str, check: impl FnOnce() -> Result<String, String>) -> CheckResult {
+    log::info!("Self-test: checking {}", subsystem);
+    let result = catch_unwind(AssertUnwindSafe(check)).unwrap_or_else(|panic| {
+        let message = panic
+            .downcast_ref::<String>()
+            .map(String::as_str)
+            .or_else(|| panic.downcast_ref::<&str>().copied())
+            .unwrap_or("unknown panic");
+        Err(format!("panicked: {}", message))
+    });
+
+    match result {
+        Ok(detail) => CheckResult { subsystem, ok: true, detail },
+        Err(detail) => {
+            log::error!("Self-test: {} failed: {}", subsystem, detail);
+            CheckResult { subsystem, ok: false, detail }
+        }
+    }
+}
+
+fn check_coire() -> Result<String, String> {
+    match clara_coire::init_global() {
+        Ok(()) => Ok("initialized".to_string()),
+        Err(clara_coire::CoireError::AlreadyInitialized) => Ok("already initialized".to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+fn check_toolbox() -> Result<String, String> {
+    clara_toolbox::ToolboxManager::init_global();
+    let mut tools = clara_toolbox::ToolboxManager::global()
+        .lock()
+        .map_err(|e| e.to_string())?
+        .list_tools();
+    tools.sort();
+
+    if !tools.iter().any(|t| t == "echo") {
+        return Err(format!("echo tool missing; registered: {}", tools.join(", ")));
+    }
+    Ok(format!("{} tools ({})", tools.len(), tools.join(", ")))
+}
+
+fn check_prolog() -> Result<String, String> {
+    clara_prolog::init_global();
+    let env = clara_prolog::PrologEnvironment::new().map_err(|e| e.to_string())?;
+    if !env
+        .check("current_predicate(the_rabbit:clara_evaluate/2)")
+        .map_err(|e| e.to_string())?
+    {
+        return Err("the_rabbit:clara_evaluate/2 is not registered".to_string());
+    }
+    Ok("initialized with the_rabbit:clara_evaluate/2".to_string())
+}
+
+fn check_clips(clips_binary: &str) -> Result<String, String> {
+    let mut handler = ReplHandler::new(clips_binary).map_err(|e| e.to_string())?;
+    let result = handler
+        .execute("(printout t \"clara-selftest\" crlf)", 5000)
+        .map_err(|e| e.to_string())?;
+    if !result.stdout.contains("clara-selftest") {
+        return Err(format!("unexpected output from {}: {:?}", clips_binary, result.stdout));
+    }
+    Ok(format!("spawned {}", clips_binary))
+}
+
+fn check_query() -> Result<String, String> {
+    let env = clara_prolog::PrologEnvironment::new().map_err(|e| e.to_string())?;
+    let result = env
+        .query_once(r#"the_rabbit:clara_evaluate('{"tool":"echo","arguments":{"message":"clara-selftest"}}', R)"#)
+        .map_err(|e| e.to_string())?;
+    if !result.contains("clara-selftest") {
+        return Err(format!("unexpected echo result: {}", result));
+    }
+    Ok("clara_evaluate echo round trip".to_string())
+}
@@ -1,9 +1,9 @@
 use actix_web::{web, App, HttpServer};
 use clara_coire::CarrionPicker;
 use clara_cycle::CoireStore;
-use clara_session::{SessionManager, ManagerConfig};
+use clara_session::{SessionManager, ManagerConfig, SessionType};
 use clara_config::ConfigLoader;
-use clara_toolbox::{set_domain_id, ToolboxCacheEviction};
+use clara_toolbox::{set_domain_id, set_max_evaluate_response_bytes, ToolboxCacheEviction};
 use clara_ritual::{KafkaBridge, RitualRegistry};
 #[cfg(test)]
 use clara_ritual::InMemoryBroker;
@@ -13,8 +13,16 @@ use std::sync::{Arc, Mutex, RwLock};
 use std::time::Duration;
 
 use crate::handlers::AppState;
+use crate::handlers::devils_handler::set_query_timeout_ms;
+use crate::handlers::session_handler::set_loop_detection_window;
+use crate::middleware::audit::{set_audit_log, AuditLog};
+use crate::middleware::tracing::set_slow_query_threshold;
+use crate::models::set_retry_after_secs;
 use crate::routes;
 use crate::subprocess::SubprocessPool;
+use crate::validation::consult_path::{set_clips_load_root, set_consult_root, ConsultRoot};
+use crate::validation::directives::{set_directive_whitelist, DirectiveWhitelist};
+use crate::validation::input::{set_input_limits, InputLimits};
 
 /// Start the Actix-web server.
 ///
@@ -43,22 +51,170 @@ pub async fn start_server(
         info!("Dis domain ID not configured — cache entries will have domain_id=None");
     }
 
+    set_max_evaluate_response_bytes(config.security.max_tool_response_bytes);
+
+    // Warn about evaluations slower than the configured threshold
+    set_slow_query_threshold(config.observability.slow_query_ms);
+
+    // Stop CLIPS runs whose rules keep refiring on the same facts
+    set_loop_detection_window(config.clips.loop_detection_window);
+
+    // Abort Prolog queries that run past the configured limit
+    set_query_timeout_ms(config.resources.prolog_query_timeout_ms);
+
+    // Retry Prolog engines briefly held by another thread
+    clara_prolog::set_engine_acquire_attempts(config.resources.prolog_engine_acquire_attempts);
+
+    // Tell clients turned away by a full queue when to try again
+    set_retry_after_secs(config.resources.retry_after_seconds);
+
+    // Restrict which directives Prolog consult may execute
+    set_directive_whitelist(DirectiveWhitelist::new(
+        config.security.prolog_allowed_directives.clone(),
+        config.security.prolog_allowed_modules.clone(),
+    ));
+
+    // Record security violations in an append-only audit log
+    if let Some(path) = &config.security.audit_log_path {
+        let audit = AuditLog::file(path).map_err(|e| {
+            std::io::Error::new(e.kind(), format!("Cannot open audit log '{}': {}", path, e))
+        })?;
+        set_audit_log(audit);
+    }
+
+    // Only consult Prolog files from under the configured root
+    if let Some(dir) = &config.security.prolog_consult_root {
+        let root = ConsultRoot::new(dir).map_err(|e| {
+            std::io::Error::new(std::io::ErrorKind::NotFound, e.to_string())
+        })?;
+        info!("Prolog file consults allowed under {}", root.path().display());
+        set_consult_root(root);
+    }
+
+    // Only load CLIPS files from under the configured root
+    if let Some(dir) = &config.security.clips_load_root {
+        let root = ConsultRoot::new(dir).map_err(|e| {
+            std::io::Error::new(std::io::ErrorKind::NotFound, e.to_string())
+        })?;
+        info!("CLIPS file loads allowed under {}", root.path().display());
+        set_clips_load_root(root);
+    }
+
+    // Reject oversized CLIPS and Prolog input before it reaches an engine
+    set_input_limits(InputLimits {
+        max_clips_input_bytes: config.security.max_clips_input_bytes,
+        max_prolog_input_bytes: config.security.max_prolog_input_bytes,
+        max_prolog_clauses: config.security.max_prolog_clauses,
+    });
+
     // Create session manager with config from file
-    let session_config = ManagerConfig {
-        max_concurrent_sessions: config.sessions.max_concurrent,
-        max_sessions_per_user: config.sessions.max_per_user,
-    };
+    let mut session_config = ManagerConfig::builder()
+        .max_concurrent_sessions(config.sessions.max_concurrent)
+        .max_sessions_per_user(config.sessions.max_per_user)
+        .max_in_flight_evals(config.resources.max_concurrent_evals)
+        .max_queued_evals(config.resources.max_eval_queue_depth as usize)
+        .default_session_type(match config.sessions.default_session_type.as_str() {
+            "prolog" => SessionType::Prolog,
+            _ => SessionType::Clips,
+        });
+    // "lru" reclaims sessions idle past the TTL once the global cap is hit
+    if config.sessions.eviction_policy == "lru" {
+        session_config = session_config.idle_eviction_ttl_seconds(config.sessions.default_ttl_seconds);
+    }
+    if let Some(timeout) = config.sessions.idle_timeout_seconds {
+        session_config = session_config.idle_timeout_seconds(timeout);
+    }
+    let session_config = session_config.build().map_err(|e| {
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("Invalid session config: {}", e))
+    })?;
     let session_manager = SessionManager::new(session_config);
+    if config.prewarm.prolog > 0 || config.prewarm.clips > 0 {
+        session_manager
+            .prewarm(config.prewarm.prolog, config.prewarm.clips)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, format!("Failed to prewarm engines: {}", e)))?;
+    }
+
+    // Periodically ping idle Prolog engines, replacing any a bad query broke
+    let health_interval = config.prewarm.health_check_interval_seconds;
+    if config.prewarm.prolog > 0 && health_interval > 0 {
+        let min_idle = config.prewarm.prolog;
+        let checked_manager = session_manager.clone();
+        actix_rt::spawn(async move {
+            let mut interval = actix_rt::time::interval(Duration::from_secs(health_interval));
+            loop {
+                interval.tick().await;
+                let manager = checked_manager.clone();
+                match web::block(move || manager.check_idle_prolog_engines(min_idle)).await {
+                    Ok(Ok(_)) => {}
+                    Ok(Err(e)) => log::warn!("Idle Prolog engine check failed: {}", e),
+                    Err(e) => log::warn!("Idle Prolog engine check did not run: {}", e),
+                }
+            }
+        });
+    }
+
+    // Periodically terminate sessions idle past the idle timeout, and purge
+    // terminated sessions past the tombstone retention window
+    let evicting_manager = session_manager.clone();
+    let tombstone_retention = Duration::from_secs(config.sessions.tombstone_retention_seconds);
+    actix_rt::spawn(async move {
+        let mut interval = actix_rt::time::interval(Duration::from_secs(60));
+        loop {
+            interval.tick().await;
+            let manager = evicting_manager.clone();
+            // Each step runs even if the other fails, so a failing eviction
+            // can't keep tombstones from being purged
+            let swept = web::block(move || {
+                (manager.evict_idle(), manager.cleanup_terminated(tombstone_retention))
+            })
+            .await;
+            match swept {
+                Ok((evicted, purged)) => {
+                    match evicted {
+                        Ok(evicted) if !evicted.is_empty() => {
+                            info!("Session evictor terminated {} idle session(s)", evicted.len());
+                        }
+                        Ok(_) => {}
+                        Err(e) => log::warn!("Session eviction failed: {}", e),
+                    }
+                    match purged {
+                        Ok(purged) if !purged.is_empty() => {
+                            info!("Session evictor purged {} terminated session(s)", purged.len());
+                        }
+                        Ok(_) => {}
+                        Err(e) => log::warn!("Terminated session purge failed: {}", e),
+                    }
+                }
+                Err(e) => log::warn!("Session eviction did not run: {}", e),
+            }
+        }
+    });
 
     // Create subprocess pool with configured paths
-    let subprocess_pool = SubprocessPool::new(
+    let subprocess_pool = SubprocessPool::with_max(
         config.clips.binary_path.clone(),
         config.clips.sentinel_marker.clone(),
+        config.clips.max_processes,
     );
 
     // Subprocesses are created lazily on first session request, not during startup
     info!("Subprocess pool initialized (lazy creation enabled).");
 
+    // Periodically drop handlers for sessions idle past the session TTL;
+    // they are recreated lazily on the next eval.
+    let idle_threshold = Duration::from_secs(config.sessions.default_ttl_seconds);
+    let reaper_pool = subprocess_pool.clone();
+    actix_rt::spawn(async move {
+        let mut interval = actix_rt::time::interval(Duration::from_secs(60));
+        loop {
+            interval.tick().await;
+            let reaped = reaper_pool.reap_idle(idle_threshold);
+            if reaped > 0 {
+                info!("Subprocess reaper dropped {} idle handler(s)", reaped);
+            }
+        }
+    });
+
     // Optionally open the Coire persistent store.
     let coire_store = if let Some(ref path) = config.persistence.coire_store_path {
         match CoireStore::open(path) {
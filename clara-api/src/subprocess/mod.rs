@@ -7,30 +7,156 @@ pub mod repl;
 
 pub use repl::ReplHandler;
 
-use clara_core::{ClaraResult, EvalResult};
+use crate::middleware::tracing::MAX_LOGGED_INPUT_CHARS;
+use clara_core::{truncate_str, ClaraError, ClaraResult, EvalResult};
 use log::debug;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Per-session handler plus the last time it was used
+struct SessionHandler {
+    handler: Arc<Mutex<ReplHandler>>,
+    last_used: Instant,
+}
 
 /// Transactional CLIPS subprocess manager
 /// Each execute() call spawns a fresh CLIPS process
 pub struct SubprocessPool {
     clips_binary: String,
+    /// Base of the per-command marker that ends a command's output in the
+    /// REPL protocol; commands containing it are rejected so it can't be forged
+    sentinel_marker: String,
+    /// Maximum number of handlers held at once; 0 means unbounded
+    max_processes: usize,
+    /// Handlers keyed by session ID, created lazily and dropped by `reap_idle`
+    handlers: Arc<Mutex<HashMap<String, SessionHandler>>>,
 }
 
 impl SubprocessPool {
     /// Create a new subprocess manager
-    pub fn new(clips_binary: String, _sentinel_marker: String) -> Self {
-        Self { clips_binary }
+    pub fn new(clips_binary: String, sentinel_marker: String) -> Self {
+        Self::with_max(clips_binary, sentinel_marker, 0)
+    }
+
+    /// Create a subprocess manager holding at most `max_processes` handlers
+    ///
+    /// When the cap is reached, `get_or_create` evicts the least-recently-used
+    /// handler that isn't mid-execution. A cap of 0 means unbounded.
+    pub fn with_max(clips_binary: String, sentinel_marker: String, max_processes: usize) -> Self {
+        Self {
+            clips_binary,
+            sentinel_marker,
+            max_processes,
+            handlers: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Get the handler for a session, creating it if it doesn't exist
+    /// (or was reaped) and marking it as used now
+    pub fn get_or_create(&self, session_id: &str) -> ClaraResult<Arc<Mutex<ReplHandler>>> {
+        let mut handlers = self.handlers.lock()
+            .map_err(|_| ClaraError::Internal("Subprocess pool lock poisoned".to_string()))?;
+
+        if let Some(entry) = handlers.get_mut(session_id) {
+            entry.last_used = Instant::now();
+            return Ok(Arc::clone(&entry.handler));
+        }
+
+        if self.max_processes > 0 && handlers.len() >= self.max_processes {
+            Self::evict_lru(&mut handlers)?;
+        }
+
+        debug!("SubprocessPool creating handler for session {}", session_id);
+        let handler = Arc::new(Mutex::new(ReplHandler::with_sentinel(&self.clips_binary, &self.sentinel_marker)?));
+        handlers.insert(session_id.to_string(), SessionHandler {
+            handler: Arc::clone(&handler),
+            last_used: Instant::now(),
+        });
+        Ok(handler)
     }
 
     /// Execute a command in a fresh CLIPS subprocess (transactional model)
     /// Sessions are used for resource management and login tracking only
-    pub fn execute(&self, _session_id: &str, command: &str, timeout_ms: u64) -> ClaraResult<EvalResult> {
+    ///
+    /// Commands containing the sentinel marker are rejected with
+    /// `ValidationError` before anything is written to the subprocess, so
+    /// input can't fake the end of its own output. A command still running
+    /// after `timeout_ms` (0 for no limit) fails with `EvalTimeout`.
+    pub fn execute(&self, session_id: &str, command: &str, timeout_ms: u64) -> ClaraResult<EvalResult> {
+        if !self.sentinel_marker.is_empty() && command.contains(&self.sentinel_marker) {
+            return Err(ClaraError::ValidationError(
+                "Command contains the reserved CLIPS output sentinel".to_string(),
+            ));
+        }
+
         debug!("SubprocessPool::execute spawning fresh CLIPS process");
-        debug!("Command length: {} bytes, timeout: {}ms", command.len(), timeout_ms);
+        debug!(
+            "Command ({} bytes, timeout {}ms): {}",
+            command.len(),
+            timeout_ms,
+            truncate_str(command, MAX_LOGGED_INPUT_CHARS)
+        );
+
+        // The handler spawns and cleans up its own process per call
+        let handler = self.get_or_create(session_id)?;
+        let result = handler.lock()
+            .map_err(|_| ClaraError::Internal("Subprocess handler lock poisoned".to_string()))?
+            .execute(command, timeout_ms);
+
+        // The timed-out process is already killed; drop the handler too so
+        // the session's next command starts from a fresh one
+        if let Err(ClaraError::EvalTimeout { .. }) = &result {
+            debug!("Discarding subprocess handler for session {} after timeout", session_id);
+            if let Ok(mut handlers) = self.handlers.lock() {
+                handlers.remove(session_id);
+            }
+        }
+        result
+    }
+
+    /// Drop handlers for sessions idle longer than `threshold`
+    ///
+    /// Session metadata is untouched; the next `execute` for a reaped session
+    /// recreates its handler via `get_or_create`. Returns the number reaped.
+    pub fn reap_idle(&self, threshold: Duration) -> usize {
+        let Ok(mut handlers) = self.handlers.lock() else {
+            return 0;
+        };
+
+        let before = handlers.len();
+        handlers.retain(|session_id, entry| {
+            let keep = entry.last_used.elapsed() <= threshold;
+            if !keep {
+                debug!("Reaping idle subprocess handler for session {}", session_id);
+            }
+            keep
+        });
+        before - handlers.len()
+    }
+
+    /// Drop the least-recently-used handler not currently executing
+    ///
+    /// A handler is busy while `execute` (or another caller of
+    /// `get_or_create`) still holds a clone of its `Arc`.
+    fn evict_lru(handlers: &mut HashMap<String, SessionHandler>) -> ClaraResult<()> {
+        let victim = handlers
+            .iter()
+            .filter(|(_, entry)| Arc::strong_count(&entry.handler) == 1)
+            .min_by_key(|(_, entry)| entry.last_used)
+            .map(|(session_id, _)| session_id.clone())
+            .ok_or_else(|| ClaraError::ResourceLimitExceeded {
+                resource: format!("CLIPS subprocesses (max {})", handlers.len()),
+            })?;
+
+        debug!("Evicting least-recently-used subprocess handler for session {}", victim);
+        handlers.remove(&victim);
+        Ok(())
+    }
 
-        // Create a fresh handler and execute (it spawns and cleans up its own process)
-        let mut handler = ReplHandler::new(&self.clips_binary)?;
-        handler.execute(command, timeout_ms)
+    /// Number of sessions currently holding a handler
+    pub fn handler_count(&self) -> usize {
+        self.handlers.lock().map(|h| h.len()).unwrap_or(0)
     }
 }
 
@@ -38,6 +164,9 @@ impl Clone for SubprocessPool {
     fn clone(&self) -> Self {
         Self {
             clips_binary: self.clips_binary.clone(),
+            sentinel_marker: self.sentinel_marker.clone(),
+            max_processes: self.max_processes,
+            handlers: Arc::clone(&self.handlers),
         }
     }
 }
@@ -54,5 +183,84 @@ mod tests {
         );
         // Pool is now created with just the binary path
         assert!(pool.clips_binary.contains("clips"));
+        assert_eq!(pool.handler_count(), 0);
+    }
+
+    #[test]
+    fn test_reap_idle_recreates_handler() {
+        // `cat` stands in for CLIPS: it echoes the command and exits on EOF
+        let pool = SubprocessPool::new("cat".to_string(), "__END__".to_string());
+
+        let first = pool.execute("sess-1", "(+ 1 2)", 1000).unwrap();
+        assert!(first.stdout.contains("(+ 1 2)"));
+        assert_eq!(pool.handler_count(), 1);
+
+        // A generous threshold keeps the fresh handler
+        assert_eq!(pool.reap_idle(Duration::from_secs(60)), 0);
+        assert_eq!(pool.handler_count(), 1);
+
+        std::thread::sleep(Duration::from_millis(20));
+        assert_eq!(pool.reap_idle(Duration::from_millis(5)), 1);
+        assert_eq!(pool.handler_count(), 0);
+
+        // The next execute transparently recreates the handler
+        let second = pool.execute("sess-1", "(+ 3 4)", 1000).unwrap();
+        assert!(second.stdout.contains("(+ 3 4)"));
+        assert_eq!(pool.handler_count(), 1);
+    }
+
+    #[test]
+    fn test_max_processes_evicts_lru_handler() {
+        let pool = SubprocessPool::with_max("cat".to_string(), "__END__".to_string(), 2);
+
+        pool.execute("sess-1", "(+ 1 1)", 1000).unwrap();
+        std::thread::sleep(Duration::from_millis(5));
+        pool.execute("sess-2", "(+ 2 2)", 1000).unwrap();
+        std::thread::sleep(Duration::from_millis(5));
+        // Touch sess-1 so sess-2 becomes the least recently used
+        pool.execute("sess-1", "(+ 1 1)", 1000).unwrap();
+
+        pool.execute("sess-3", "(+ 3 3)", 1000).unwrap();
+        assert_eq!(pool.handler_count(), 2);
+
+        let handlers = pool.handlers.lock().unwrap();
+        assert!(handlers.contains_key("sess-1"));
+        assert!(!handlers.contains_key("sess-2"));
+        assert!(handlers.contains_key("sess-3"));
+    }
+
+    #[test]
+    fn test_command_with_sentinel_rejected() {
+        let pool = SubprocessPool::new("cat".to_string(), "__END__".to_string());
+
+        for command in ["(printout t \"__END__\" crlf)", "__END__", "(+ 1 2)\n__END__\n(+ 3 4)"] {
+            let result = pool.execute("sess-1", command, 1000);
+            assert!(
+                matches!(result, Err(ClaraError::ValidationError(_))),
+                "{:?} was not rejected: {:?}",
+                command,
+                result
+            );
+        }
+
+        // The session keeps working and sees only its own output
+        let next = pool.execute("sess-1", "(+ 5 6)", 1000).unwrap();
+        assert!(next.stdout.contains("(+ 5 6)"));
+        assert!(!next.stdout.contains("__END__"));
+
+        // An empty marker disables the check rather than rejecting everything
+        let unguarded = SubprocessPool::new("cat".to_string(), String::new());
+        assert!(unguarded.execute("sess-1", "(+ 1 2)", 1000).is_ok());
+    }
+
+    #[test]
+    fn test_max_processes_rejects_when_all_busy() {
+        let pool = SubprocessPool::with_max("cat".to_string(), "__END__".to_string(), 1);
+
+        // Holding the handler marks it as in use, so it can't be evicted
+        let _busy = pool.get_or_create("sess-1").unwrap();
+        let result = pool.get_or_create("sess-2");
+        assert!(matches!(result, Err(ClaraError::ResourceLimitExceeded { .. })));
+        assert_eq!(pool.handler_count(), 1);
     }
 }
@@ -1,32 +1,75 @@
-use clara_core::{ClaraError, ClaraResult, EvalResult, EvalMetrics};
-use std::io::Write;
-use std::process::{Command, Stdio};
-use std::time::Instant;
+use clara_core::{truncate_str, ClaraError, ClaraResult, EvalResult, EvalMetrics};
+use std::io::{Read, Write};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
 use log::debug;
 
+use crate::middleware::tracing::MAX_LOGGED_INPUT_CHARS;
+
+/// How often a subprocess with a deadline is checked for exit
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// Source of the per-command nonce appended to the sentinel; shared by all
+/// handlers so no two commands in this process use the same marker
+static NEXT_SENTINEL_NONCE: AtomicU64 = AtomicU64::new(1);
+
 /// REPL Protocol handler for CLIPS subprocess communication
 /// Uses transactional interaction - spawns a fresh process for each eval
 pub struct ReplHandler {
     clips_binary: String,
+    /// Base of the end-of-output marker; empty returns the whole transcript
+    sentinel_marker: String,
 }
 
 impl ReplHandler {
     /// Create a new REPL handler (doesn't spawn a process until eval)
+    ///
+    /// Output isn't delimited: `execute` returns the full transcript.
     pub fn new(clips_binary: &str) -> ClaraResult<Self> {
+        Self::with_sentinel(clips_binary, "")
+    }
+
+    /// Create a REPL handler that ends each command's output with a unique
+    /// marker built from `sentinel_marker` and a nonce
+    ///
+    /// `execute` returns only what was printed before that marker, so output
+    /// that happens to contain `sentinel_marker` itself is still delimited
+    /// correctly.
+    pub fn with_sentinel(clips_binary: &str, sentinel_marker: &str) -> ClaraResult<Self> {
         debug!("Initializing REPL handler for CLIPS binary: {}", clips_binary);
 
         Ok(Self {
             clips_binary: clips_binary.to_owned(),
+            sentinel_marker: sentinel_marker.to_owned(),
         })
     }
 
+    /// The marker for the next command, or `None` when output isn't delimited
+    fn next_marker(&self) -> Option<String> {
+        if self.sentinel_marker.is_empty() {
+            return None;
+        }
+        let nonce = NEXT_SENTINEL_NONCE.fetch_add(1, Ordering::Relaxed);
+        // Fixed width, so no marker is a prefix of a later one
+        Some(format!("{}_{:016x}", self.sentinel_marker, nonce))
+    }
+
     /// Execute a command in a fresh CLIPS subprocess (transactional)
     /// Spawns a new process, sends command + (exit), and waits for completion
-    /// (( todo timeout handling )))
-    pub fn execute(&mut self, command: &str, _timeout_ms: u64) -> ClaraResult<EvalResult> {
+    ///
+    /// `timeout_ms` is a wall-clock deadline for the whole exchange; 0 means
+    /// no limit. A process still running at the deadline is killed and
+    /// `EvalTimeout` returned, so the next call starts from a fresh process.
+    pub fn execute(&mut self, command: &str, timeout_ms: u64) -> ClaraResult<EvalResult> {
         let start = Instant::now();
+        let deadline = (timeout_ms > 0).then(|| start + Duration::from_millis(timeout_ms));
 
-        debug!("Spawning fresh CLIPS subprocess for command: {}", command);
+        debug!(
+            "Spawning fresh CLIPS subprocess for command: {}",
+            truncate_str(command, MAX_LOGGED_INPUT_CHARS)
+        );
 
         // Create a child process with piped stdin/stdout
         let mut child = Command::new(&self.clips_binary)
@@ -36,17 +79,29 @@ impl ReplHandler {
             .spawn()
             .map_err(|e| ClaraError::ProcessSpawnError(format!("Failed to spawn CLIPS: {}", e)))?;
 
+        // Drain both pipes on their own threads so the process never blocks
+        // on a full pipe while we wait for it
+        let stdout_reader = spawn_reader(child.stdout.take());
+        let stderr_reader = spawn_reader(child.stderr.take());
+
         // Get stdin handle
         let mut stdin = child
             .stdin
             .take()
             .ok_or_else(|| ClaraError::ProcessCommunicationError("Cannot capture stdin".to_string()))?;
 
-        // Write command and exit marker
+        // Write command, end-of-output marker and exit
         writeln!(stdin, "{}", command).map_err(|e| {
             ClaraError::ProcessCommunicationError(format!("Failed to write command: {}", e))
         })?;
 
+        let marker = self.next_marker();
+        if let Some(marker) = &marker {
+            writeln!(stdin, "(printout t \"{}\" crlf)", marker).map_err(|e| {
+                ClaraError::ProcessCommunicationError(format!("Failed to write sentinel: {}", e))
+            })?;
+        }
+
         writeln!(stdin, "(exit)").map_err(|e| {
             ClaraError::ProcessCommunicationError(format!("Failed to write exit: {}", e))
         })?;
@@ -56,17 +111,24 @@ impl ReplHandler {
 
         debug!("Command and exit sent, waiting for subprocess completion...");
 
-        // Wait for the process to complete (this will block until it exits)
-        let output = child
-            .wait_with_output()
-            .map_err(|e| ClaraError::ProcessCommunicationError(format!("Failed to wait for subprocess: {}", e)))?;
+        let Some(status) = wait_until(&mut child, deadline)? else {
+            // The reader threads are left to finish on their own: anything
+            // the process spawned may still hold the pipes open
+            debug!("CLIPS subprocess exceeded {}ms, killed", timeout_ms);
+            return Err(ClaraError::EvalTimeout { timeout_ms });
+        };
+        let stdout = stdout_reader.join().unwrap_or_default();
+        let stderr = stderr_reader.join().unwrap_or_default();
 
         let elapsed = start.elapsed().as_millis() as u64;
         let metrics = EvalMetrics::with_elapsed(elapsed);
 
-        // Parse stdout as the output transcript
-        let stdout_str = String::from_utf8_lossy(&output.stdout).to_string();
-        let stderr_str = String::from_utf8_lossy(&output.stderr).to_string();
+        // Parse stdout as the output transcript, up to this command's marker
+        let mut stdout_str = String::from_utf8_lossy(&stdout).to_string();
+        if let Some(marker) = &marker {
+            stdout_str = strip_from_marker(stdout_str, marker);
+        }
+        let stderr_str = String::from_utf8_lossy(&stderr).to_string();
 
         debug!("Subprocess completed in {}ms", elapsed);
         debug!("STDOUT:\n{}", stdout_str);
@@ -75,8 +137,8 @@ impl ReplHandler {
         }
 
         // Check exit status
-        if !output.status.success() {
-            debug!("CLIPS process exited with non-zero status: {:?}", output.status);
+        if !status.success() {
+            debug!("CLIPS process exited with non-zero status: {:?}", status);
         }
 
         // Return the full transcript as output
@@ -90,6 +152,52 @@ impl ReplHandler {
     }
 }
 
+/// Read `pipe` to the end on a new thread
+fn spawn_reader<R: Read + Send + 'static>(pipe: Option<R>) -> JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buf);
+        }
+        buf
+    })
+}
+
+/// Wait for `child` to exit, killing it once `deadline` passes
+///
+/// Returns `None` if it was killed.
+fn wait_until(child: &mut Child, deadline: Option<Instant>) -> ClaraResult<Option<ExitStatus>> {
+    let wait_error =
+        |e: std::io::Error| ClaraError::ProcessCommunicationError(format!("Failed to wait for subprocess: {}", e));
+
+    let Some(deadline) = deadline else {
+        return child.wait().map(Some).map_err(wait_error);
+    };
+    loop {
+        if let Some(status) = child.try_wait().map_err(wait_error)? {
+            return Ok(Some(status));
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            child.wait().map_err(wait_error)?;
+            return Ok(None);
+        }
+        std::thread::sleep(WAIT_POLL_INTERVAL);
+    }
+}
+
+/// Truncate `transcript` at the start of the line holding `marker`
+///
+/// A transcript without the marker (e.g. the process died early) is kept
+/// whole.
+fn strip_from_marker(mut transcript: String, marker: &str) -> String {
+    if let Some(pos) = transcript.find(marker) {
+        let line_start = transcript[..pos].rfind('\n').map_or(0, |i| i + 1);
+        transcript.truncate(line_start);
+    }
+    transcript
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -99,4 +207,31 @@ mod tests {
         let handler = ReplHandler::new("/bin/ls");
         assert!(handler.is_ok());
     }
+
+    #[test]
+    fn test_markers_are_unique_per_command() {
+        let handler = ReplHandler::with_sentinel("cat", "__END__").unwrap();
+        let first = handler.next_marker().unwrap();
+        let second = handler.next_marker().unwrap();
+        assert!(first.starts_with("__END___"));
+        assert_eq!(first.len(), second.len());
+        assert_ne!(first, second);
+
+        assert!(ReplHandler::new("cat").unwrap().next_marker().is_none());
+    }
+
+    #[test]
+    fn test_output_containing_base_sentinel_still_delimited() {
+        // `cat` stands in for CLIPS: it echoes the command, then the marker
+        // line, then (exit)
+        let mut handler = ReplHandler::with_sentinel("cat", "__END__").unwrap();
+
+        let command = "before\n__END__\nafter __END___0000000000000000";
+        let result = handler.execute(command, 1000).unwrap();
+        assert_eq!(result.stdout, format!("{}\n", command));
+
+        // The next command is delimited the same way
+        let result = handler.execute("(+ 1 2)", 1000).unwrap();
+        assert_eq!(result.stdout, "(+ 1 2)\n");
+    }
 }
@@ -0,0 +1,128 @@
+//! In-process API for integration tests
+//!
+//! Enabled with the `test-support` feature. [`TestServer`] holds an
+//! [`AppState`] with a fresh in-memory session manager and a subprocess pool
+//! pointing at a CLIPS binary that doesn't exist, and serves every route from
+//! [`routes::configure`] without binding a port.
+//!
+//! ```ignore
+//! let server = TestServer::spawn();
+//! let resp = server.post("/devils/sessions", json!({"user_id": "test-user"})).await;
+//! assert!(resp.status.is_success());
+//! assert_eq!(resp.body["session_type"], "prolog");
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, RwLock};
+
+use actix_web::dev::{Request, ServiceResponse};
+use actix_web::http::StatusCode;
+use actix_web::{test, web, App};
+use clara_ritual::{InMemoryBroker, RitualRegistry};
+use clara_session::{ManagerConfig, Session, SessionManager};
+
+use crate::handlers::AppState;
+use crate::routes;
+use crate::subprocess::SubprocessPool;
+
+/// User id the session helpers create sessions for
+pub const TEST_USER: &str = "test-user";
+
+/// App state for tests, with the default manager config
+pub fn test_state() -> web::Data<AppState> {
+    test_state_with(ManagerConfig::default())
+}
+
+/// App state for tests, with its session manager built from `config`
+pub fn test_state_with(config: ManagerConfig) -> web::Data<AppState> {
+    web::Data::new(AppState {
+        session_manager: SessionManager::new(config),
+        subprocess_pool: SubprocessPool::new("./clips".to_string(), "__END__".to_string()),
+        deductions: Arc::new(RwLock::new(HashMap::new())),
+        coire_store: None,
+        active_coire_sessions: Arc::new(RwLock::new(HashSet::new())),
+        snapshot_ttl_ms: 604_800_000,
+        ritual_registry: Arc::new(RitualRegistry::new("dis.test", Arc::new(InMemoryBroker::new()))),
+        dis_domain: "dis.test".to_string(),
+        kafka_bootstrap: None,
+        fiery_pit_token_cache: Arc::new(Mutex::new(None)),
+    })
+}
+
+/// Status and JSON body of a response; the body is `Null` when empty
+#[derive(Debug)]
+pub struct JsonResponse {
+    pub status: StatusCode,
+    pub body: serde_json::Value,
+}
+
+/// The full API served in-process over a test [`AppState`]
+pub struct TestServer {
+    pub state: web::Data<AppState>,
+}
+
+impl TestServer {
+    /// A server over [`test_state`]
+    pub fn spawn() -> Self {
+        Self { state: test_state() }
+    }
+
+    /// A server whose session manager is built from `config`
+    pub fn with_config(config: ManagerConfig) -> Self {
+        Self { state: test_state_with(config) }
+    }
+
+    /// Send a request through every configured route
+    pub async fn call(&self, req: Request) -> ServiceResponse {
+        let app = test::init_service(
+            App::new()
+                .app_data(self.state.clone())
+                .configure(routes::configure),
+        )
+        .await;
+        test::call_service(&app, req).await
+    }
+
+    pub async fn get(&self, uri: &str) -> JsonResponse {
+        self.call_json(test::TestRequest::get().uri(uri).to_request()).await
+    }
+
+    pub async fn post(&self, uri: &str, body: serde_json::Value) -> JsonResponse {
+        self.call_json(test::TestRequest::post().uri(uri).set_json(body).to_request())
+            .await
+    }
+
+    pub async fn delete(&self, uri: &str) -> JsonResponse {
+        self.call_json(test::TestRequest::delete().uri(uri).to_request()).await
+    }
+
+    /// Create a CLIPS session for [`TEST_USER`] directly in the manager
+    pub fn create_clips_session(&self) -> Session {
+        self.state
+            .session_manager
+            .create_session(TEST_USER.to_string(), None)
+            .expect("Failed to create CLIPS session")
+    }
+
+    /// Create a Prolog session for [`TEST_USER`] directly in the manager
+    pub fn create_prolog_session(&self) -> Session {
+        self.state
+            .session_manager
+            .create_prolog_session(TEST_USER.to_string(), None)
+            .expect("Failed to create Prolog session")
+    }
+
+    async fn call_json(&self, req: Request) -> JsonResponse {
+        let resp = self.call(req).await;
+        let status = resp.status();
+        let bytes = test::read_body(resp).await;
+        let body = if bytes.is_empty() {
+            serde_json::Value::Null
+        } else {
+            serde_json::from_slice(&bytes).unwrap_or_else(|e| {
+                panic!("{} response is not JSON ({}): {}", status, e, String::from_utf8_lossy(&bytes))
+            })
+        };
+        JsonResponse { status, body }
+    }
+}
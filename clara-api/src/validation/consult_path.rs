@@ -0,0 +1,151 @@
+//! Path sandbox for `POST /devils/sessions/{id}/consult/file` and
+//! `POST /sessions/{id}/load`.
+//!
+//! Prolog and CLIPS files may only be loaded from below a configured root
+//! directory. Requested paths are resolved against the root and
+//! canonicalized before the check, so `..` segments and symlinks cannot
+//! reach files outside it.
+//!
+//! The roots are process-wide: call [`set_consult_root`] and
+//! [`set_clips_load_root`] once at startup with
+//! `config.security.prolog_consult_root` and
+//! `config.security.clips_load_root`; until then file loads are refused.
+
+use clara_core::ClaraError;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+static ROOT: OnceLock<ConsultRoot> = OnceLock::new();
+static CLIPS_ROOT: OnceLock<ConsultRoot> = OnceLock::new();
+
+/// Install the process-wide consult root. The first call wins; later calls
+/// are ignored with a warning.
+pub fn set_consult_root(root: ConsultRoot) {
+    if ROOT.set(root).is_err() {
+        log::warn!("set_consult_root: root already set, ignoring");
+    }
+}
+
+/// The configured consult root, or `None` if file consults are disabled.
+pub fn consult_root() -> Option<&'static ConsultRoot> {
+    ROOT.get()
+}
+
+/// Install the process-wide root for CLIPS file loads. The first call wins;
+/// later calls are ignored with a warning.
+pub fn set_clips_load_root(root: ConsultRoot) {
+    if CLIPS_ROOT.set(root).is_err() {
+        log::warn!("set_clips_load_root: root already set, ignoring");
+    }
+}
+
+/// The configured CLIPS load root, or `None` if file loads are disabled.
+pub fn clips_load_root() -> Option<&'static ConsultRoot> {
+    CLIPS_ROOT.get()
+}
+
+/// Directory that loaded Prolog or CLIPS files must live under
+#[derive(Debug, Clone)]
+pub struct ConsultRoot {
+    root: PathBuf,
+}
+
+impl ConsultRoot {
+    /// Use `dir` as the root. Fails if it does not exist or is not a
+    /// directory.
+    pub fn new(dir: impl AsRef<Path>) -> Result<Self, ClaraError> {
+        let dir = dir.as_ref();
+        let root = dir
+            .canonicalize()
+            .ok()
+            .filter(|p| p.is_dir())
+            .ok_or_else(|| {
+                ClaraError::InvalidFilePath(format!("Consult root {} is not a directory", dir.display()))
+            })?;
+        Ok(Self { root })
+    }
+
+    /// The canonical root directory
+    pub fn path(&self) -> &Path {
+        &self.root
+    }
+
+    /// Resolve `requested` (relative to the root, or absolute) to the
+    /// canonical path of an existing file under the root.
+    ///
+    /// Missing files and files outside the root are rejected with the same
+    /// message, so the response does not reveal what exists elsewhere.
+    pub fn resolve(&self, requested: &str) -> Result<PathBuf, ClaraError> {
+        let rejected =
+            || ClaraError::InvalidFilePath(format!("{} is not a file under the consult root", requested));
+
+        if requested.trim().is_empty() || requested.contains('\0') {
+            return Err(rejected());
+        }
+
+        let resolved = self.root.join(requested).canonicalize().map_err(|_| rejected())?;
+        if !resolved.starts_with(&self.root) || !resolved.is_file() {
+            return Err(rejected());
+        }
+        Ok(resolved)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// `<tmp>/<name>/root/rules.pl` plus `<tmp>/<name>/secret.pl` outside the root
+    fn layout(name: &str) -> (PathBuf, ConsultRoot) {
+        let base = std::env::temp_dir().join(format!("{}-{}", name, std::process::id()));
+        let root = base.join("root");
+        fs::create_dir_all(root.join("nested")).unwrap();
+        fs::write(root.join("rules.pl"), "fact(1).\n").unwrap();
+        fs::write(root.join("nested/more.pl"), "fact(2).\n").unwrap();
+        fs::write(base.join("secret.pl"), "secret(1).\n").unwrap();
+        (base, ConsultRoot::new(&root).unwrap())
+    }
+
+    #[test]
+    fn test_in_root_files_resolved() {
+        let (base, root) = layout("clara-consult-ok");
+        assert_eq!(root.resolve("rules.pl").unwrap(), root.path().join("rules.pl"));
+        assert_eq!(root.resolve("nested/../rules.pl").unwrap(), root.path().join("rules.pl"));
+        assert_eq!(root.resolve("nested/more.pl").unwrap(), root.path().join("nested/more.pl"));
+
+        let absolute = root.path().join("rules.pl");
+        assert!(root.resolve(absolute.to_str().unwrap()).is_ok());
+        fs::remove_dir_all(base).ok();
+    }
+
+    #[test]
+    fn test_paths_outside_root_rejected() {
+        let (base, root) = layout("clara-consult-escape");
+        let secret = base.join("secret.pl").canonicalize().unwrap();
+
+        for requested in [
+            "../secret.pl",
+            "nested/../../secret.pl",
+            secret.to_str().unwrap(),
+            "missing.pl",
+            "nested",
+            "",
+        ] {
+            let result = root.resolve(requested);
+            assert!(
+                matches!(result, Err(ClaraError::InvalidFilePath(_))),
+                "{:?} resolved to {:?}",
+                requested,
+                result
+            );
+        }
+        fs::remove_dir_all(base).ok();
+    }
+
+    #[test]
+    fn test_missing_root_rejected() {
+        let missing = std::env::temp_dir().join("clara-consult-no-such-root");
+        assert!(matches!(ConsultRoot::new(missing), Err(ClaraError::InvalidFilePath(_))));
+    }
+}
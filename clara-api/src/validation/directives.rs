@@ -0,0 +1,225 @@
+//! Prolog directive whitelist for `POST /devils/sessions/{id}/consult`.
+//!
+//! Consulted clauses come from API callers, so directives (`:- Goal`) are only
+//! executed when every goal they would run has a whitelisted functor.
+//! `use_module/1,2` is further restricted to approved modules.
+//!
+//! The whitelist is process-wide: call [`set_directive_whitelist`] once at
+//! startup with values from `config.security`; until then the defaults apply.
+
+use clara_core::ClaraError;
+use std::sync::OnceLock;
+
+/// Directive functors allowed when nothing is configured.
+pub const DEFAULT_ALLOWED_DIRECTIVES: &[&str] = &["dynamic", "discontiguous", "table", "use_module"];
+
+/// Modules `use_module` may load when nothing is configured.
+pub const DEFAULT_ALLOWED_MODULES: &[&str] = &[
+    "library(lists)",
+    "library(apply)",
+    "library(aggregate)",
+    "library(pairs)",
+    "library(assoc)",
+    "library(ordsets)",
+    "library(yall)",
+    "library(tabling)",
+    "library(clpfd)",
+];
+
+/// Declarations Prolog also accepts in prefix-operator form (`:- dynamic foo/1, bar/2.`).
+/// Everything after the operator is a declaration argument, never a goal.
+const PREFIX_OPERATOR_DIRECTIVES: &[&str] = &["dynamic", "discontiguous", "table", "multifile"];
+
+static WHITELIST: OnceLock<DirectiveWhitelist> = OnceLock::new();
+
+/// Install the process-wide whitelist. The first call wins; later calls are
+/// ignored with a warning.
+pub fn set_directive_whitelist(whitelist: DirectiveWhitelist) {
+    if WHITELIST.set(whitelist).is_err() {
+        log::warn!("set_directive_whitelist: whitelist already set, ignoring");
+    }
+}
+
+/// The configured whitelist, or the defaults if none was installed.
+pub fn directive_whitelist() -> &'static DirectiveWhitelist {
+    WHITELIST.get_or_init(DirectiveWhitelist::default)
+}
+
+/// Allowed directive functors and `use_module` targets
+#[derive(Debug, Clone)]
+pub struct DirectiveWhitelist {
+    functors: Vec<String>,
+    modules: Vec<String>,
+}
+
+impl Default for DirectiveWhitelist {
+    fn default() -> Self {
+        Self::new(
+            DEFAULT_ALLOWED_DIRECTIVES.iter().map(|s| s.to_string()).collect(),
+            DEFAULT_ALLOWED_MODULES.iter().map(|s| s.to_string()).collect(),
+        )
+    }
+}
+
+impl DirectiveWhitelist {
+    pub fn new(functors: Vec<String>, modules: Vec<String>) -> Self {
+        Self {
+            functors,
+            modules: modules.iter().map(|m| strip_whitespace(m)).collect(),
+        }
+    }
+
+    /// Check a directive goal (without the leading `:-` or trailing `.`).
+    ///
+    /// Conjunctions and disjunctions are checked goal by goal, so an allowed
+    /// directive can't smuggle in a blocked one.
+    pub fn check(&self, goal: &str) -> Result<(), ClaraError> {
+        let goal = goal.trim();
+        let (functor, rest) = split_functor(goal);
+
+        // `dynamic foo/1, bar/2` — the whole tail is the operator's argument
+        if PREFIX_OPERATOR_DIRECTIVES.contains(&functor) && rest.starts_with(char::is_whitespace) {
+            return self.check_functor(functor, goal);
+        }
+
+        let goals = split_top_level(goal);
+        if goals.len() > 1 {
+            return goals.iter().try_for_each(|g| self.check(g));
+        }
+
+        self.check_functor(functor, goal)?;
+        if functor == "use_module" {
+            let module = strip_whitespace(first_argument(rest));
+            if !self.modules.contains(&module) {
+                return Err(ClaraError::SecurityViolation(format!(
+                    "use_module of '{}' is not allowed",
+                    module
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn check_functor(&self, functor: &str, goal: &str) -> Result<(), ClaraError> {
+        if self.functors.iter().any(|f| f == functor) {
+            Ok(())
+        } else {
+            Err(ClaraError::SecurityViolation(format!(
+                "Directive not allowed: {}",
+                goal
+            )))
+        }
+    }
+}
+
+fn strip_whitespace(s: &str) -> String {
+    s.chars().filter(|c| !c.is_whitespace()).collect()
+}
+
+/// Split `name(args)` / `name args` into the functor name and the remainder.
+fn split_functor(goal: &str) -> (&str, &str) {
+    let end = goal
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(goal.len());
+    goal.split_at(end)
+}
+
+/// First argument of a parenthesised argument list, e.g. `(library(lists), [x])`.
+fn first_argument(rest: &str) -> &str {
+    let Some(inner) = rest.trim().strip_prefix('(') else {
+        return "";
+    };
+    let mut depth = 0;
+    for (i, c) in inner.char_indices() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' if depth == 0 => return &inner[..i],
+            ')' | ']' => depth -= 1,
+            ',' if depth == 0 => return &inner[..i],
+            _ => {}
+        }
+    }
+    inner
+}
+
+/// Split a goal on top-level `,`, `;`, `|` and `->`, ignoring quoted text.
+fn split_top_level(goal: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    let mut start = 0;
+    let mut chars = goal.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        if let Some(q) = quote {
+            if c == '\\' {
+                chars.next();
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '\'' | '"' | '`' => quote = Some(c),
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            ',' | ';' | '|' if depth == 0 => {
+                parts.push(goal[start..i].trim());
+                start = i + 1;
+            }
+            '-' if depth == 0 && matches!(chars.peek(), Some((_, '>'))) => {
+                parts.push(goal[start..i].trim());
+                chars.next();
+                start = i + 2;
+            }
+            _ => {}
+        }
+    }
+    parts.push(goal[start..].trim());
+    parts.retain(|p| !p.is_empty());
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dynamic_allowed() {
+        let whitelist = DirectiveWhitelist::default();
+        assert!(whitelist.check("dynamic(foo/1)").is_ok());
+        assert!(whitelist.check("dynamic foo/1, bar/2").is_ok());
+        assert!(whitelist.check("discontiguous(baz/3)").is_ok());
+    }
+
+    #[test]
+    fn test_shell_blocked() {
+        let whitelist = DirectiveWhitelist::default();
+        let result = whitelist.check("shell('ls')");
+        assert!(matches!(result, Err(ClaraError::SecurityViolation(_))));
+    }
+
+    #[test]
+    fn test_conjunction_cannot_smuggle_blocked_goal() {
+        let whitelist = DirectiveWhitelist::default();
+        assert!(whitelist.check("dynamic(foo/1), shell('rm -rf /')").is_err());
+        assert!(whitelist.check("dynamic(foo/1) -> halt ; true").is_err());
+        assert!(whitelist.check("dynamic(foo/1), discontiguous(foo/1)").is_ok());
+    }
+
+    #[test]
+    fn test_use_module_restricted_to_approved_modules() {
+        let whitelist = DirectiveWhitelist::default();
+        assert!(whitelist.check("use_module(library(lists))").is_ok());
+        assert!(whitelist.check("use_module(library( apply ), [maplist/3])").is_ok());
+        assert!(whitelist.check("use_module(library(process))").is_err());
+        assert!(whitelist.check("use_module('/tmp/evil.pl')").is_err());
+    }
+
+    #[test]
+    fn test_custom_whitelist() {
+        let whitelist = DirectiveWhitelist::new(vec!["ensure_loaded".to_string()], vec![]);
+        assert!(whitelist.check("ensure_loaded(library(lists))").is_ok());
+        assert!(whitelist.check("dynamic(foo/1)").is_err());
+    }
+}
@@ -1 +1,193 @@
-// Input validation stub
+//! Size limits for CLIPS and Prolog input, with rejection counters.
+//!
+//! Oversized scripts, goals and consult requests are rejected with a
+//! validation error before they reach an engine. Each rejection is counted
+//! by limit type and exported on `/metrics`, so operators can see which
+//! limits are being hit when tuning them.
+//!
+//! The limits are process-wide: call [`set_input_limits`] once at startup
+//! with values from `config.security`; until then the defaults apply.
+
+use clara_core::ClaraError;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+
+/// Largest CLIPS script, rule or fact accepted when nothing is configured.
+pub const DEFAULT_MAX_CLIPS_INPUT_BYTES: usize = 64 * 1024;
+/// Largest Prolog goal or clause accepted when nothing is configured.
+pub const DEFAULT_MAX_PROLOG_INPUT_BYTES: usize = 64 * 1024;
+/// Most clauses a single consult may load when nothing is configured.
+pub const DEFAULT_MAX_PROLOG_CLAUSES: usize = 1000;
+
+static LIMITS: OnceLock<InputLimits> = OnceLock::new();
+
+static REJECTIONS: [AtomicU64; LimitKind::ALL.len()] =
+    [AtomicU64::new(0), AtomicU64::new(0), AtomicU64::new(0)];
+
+/// Install the process-wide limits. The first call wins; later calls are
+/// ignored with a warning.
+pub fn set_input_limits(limits: InputLimits) {
+    if LIMITS.set(limits).is_err() {
+        log::warn!("set_input_limits: limits already set, ignoring");
+    }
+}
+
+/// The configured limits, or the defaults if none were installed.
+pub fn input_limits() -> &'static InputLimits {
+    LIMITS.get_or_init(InputLimits::default)
+}
+
+/// Which limit an input was rejected for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitKind {
+    ClipsInputSize,
+    PrologInputSize,
+    PrologClauseCount,
+}
+
+impl LimitKind {
+    pub const ALL: [LimitKind; 3] = [
+        LimitKind::ClipsInputSize,
+        LimitKind::PrologInputSize,
+        LimitKind::PrologClauseCount,
+    ];
+
+    /// Label used for the `limit` metric dimension
+    pub fn label(self) -> &'static str {
+        match self {
+            LimitKind::ClipsInputSize => "clips_input_size",
+            LimitKind::PrologInputSize => "prolog_input_size",
+            LimitKind::PrologClauseCount => "prolog_clause_count",
+        }
+    }
+
+    fn counter(self) -> &'static AtomicU64 {
+        &REJECTIONS[self as usize]
+    }
+}
+
+/// Number of inputs rejected for `kind` since startup
+pub fn rejection_count(kind: LimitKind) -> u64 {
+    kind.counter().load(Ordering::Relaxed)
+}
+
+/// Rejection counters in Prometheus text format
+pub fn render_rejection_metrics() -> String {
+    let mut out = String::from(
+        "# HELP clara_input_rejections_total Requests rejected for exceeding an input limit\n\
+         # TYPE clara_input_rejections_total counter\n",
+    );
+    for kind in LimitKind::ALL {
+        out.push_str(&format!(
+            "clara_input_rejections_total{{limit=\"{}\"}} {}\n",
+            kind.label(),
+            rejection_count(kind)
+        ));
+    }
+    out
+}
+
+/// Maximum input sizes for CLIPS and Prolog requests
+#[derive(Debug, Clone)]
+pub struct InputLimits {
+    pub max_clips_input_bytes: usize,
+    pub max_prolog_input_bytes: usize,
+    pub max_prolog_clauses: usize,
+}
+
+impl Default for InputLimits {
+    fn default() -> Self {
+        Self {
+            max_clips_input_bytes: DEFAULT_MAX_CLIPS_INPUT_BYTES,
+            max_prolog_input_bytes: DEFAULT_MAX_PROLOG_INPUT_BYTES,
+            max_prolog_clauses: DEFAULT_MAX_PROLOG_CLAUSES,
+        }
+    }
+}
+
+impl InputLimits {
+    /// Check a CLIPS script, rule or fact
+    pub fn check_clips(&self, input: &str) -> Result<(), ClaraError> {
+        if input.len() > self.max_clips_input_bytes {
+            return Err(reject(LimitKind::ClipsInputSize, format!(
+                "CLIPS input of {} bytes exceeds the {} byte limit",
+                input.len(),
+                self.max_clips_input_bytes
+            )));
+        }
+        Ok(())
+    }
+
+    /// Check a Prolog goal or clause
+    pub fn check_prolog(&self, input: &str) -> Result<(), ClaraError> {
+        if input.len() > self.max_prolog_input_bytes {
+            return Err(reject(LimitKind::PrologInputSize, format!(
+                "Prolog input of {} bytes exceeds the {} byte limit",
+                input.len(),
+                self.max_prolog_input_bytes
+            )));
+        }
+        Ok(())
+    }
+
+    /// Check the number of clauses in one consult request
+    pub fn check_clause_count(&self, count: usize) -> Result<(), ClaraError> {
+        if count > self.max_prolog_clauses {
+            return Err(reject(LimitKind::PrologClauseCount, format!(
+                "{} clauses exceeds the limit of {} per consult",
+                count,
+                self.max_prolog_clauses
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Count a rejection and build its error
+fn reject(kind: LimitKind, message: String) -> ClaraError {
+    kind.counter().fetch_add(1, Ordering::Relaxed);
+    log::warn!("Rejected input ({}): {}", kind.label(), message);
+    ClaraError::ValidationError(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn small_limits() -> InputLimits {
+        InputLimits {
+            max_clips_input_bytes: 8,
+            max_prolog_input_bytes: 8,
+            max_prolog_clauses: 2,
+        }
+    }
+
+    #[test]
+    fn test_within_limits_accepted() {
+        let limits = small_limits();
+        assert!(limits.check_clips("(+ 1 2)").is_ok());
+        assert!(limits.check_prolog("true").is_ok());
+        assert!(limits.check_clause_count(2).is_ok());
+    }
+
+    #[test]
+    fn test_oversized_inputs_counted_by_kind() {
+        let limits = small_limits();
+        let before: Vec<u64> = LimitKind::ALL.iter().map(|k| rejection_count(*k)).collect();
+
+        let result = limits.check_clips("(printout t \"far too long\" crlf)");
+        assert!(matches!(result, Err(ClaraError::ValidationError(_))));
+        assert!(limits.check_prolog("member(X, [1,2,3])").is_err());
+        assert!(limits.check_prolog("length(L, 100)").is_err());
+        assert!(limits.check_clause_count(3).is_err());
+
+        // Counters are process-wide, so other tests may bump them concurrently
+        let after: Vec<u64> = LimitKind::ALL.iter().map(|k| rejection_count(*k)).collect();
+        assert!(after[0] > before[0]);
+        assert!(after[1] >= before[1] + 2);
+        assert!(after[2] > before[2]);
+
+        let metrics = render_rejection_metrics();
+        assert!(metrics.contains("clara_input_rejections_total{limit=\"prolog_clause_count\"}"));
+    }
+}
@@ -1 +1,3 @@
+pub mod consult_path;
+pub mod directives;
 pub mod input;
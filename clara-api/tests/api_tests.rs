@@ -0,0 +1,711 @@
+//! Integration tests for the /sessions/* (CLIPS) REST API endpoints
+
+use actix_web::{test, web, App};
+use clara_api::handlers::session_handler::{self, AppState};
+use clara_api::subprocess::SubprocessPool;
+use clara_session::{SessionManager, ManagerConfig};
+use serde_json::json;
+
+/// Create test app state
+fn create_test_state() -> web::Data<AppState> {
+    use std::collections::{HashMap, HashSet};
+    use std::sync::{Arc, Mutex, RwLock};
+    use clara_ritual::{InMemoryBroker, RitualRegistry};
+    web::Data::new(AppState {
+        session_manager: SessionManager::new(ManagerConfig::default()),
+        subprocess_pool: SubprocessPool::new(
+            "./clips".to_string(),
+            "__END__".to_string(),
+        ),
+        deductions: Arc::new(RwLock::new(HashMap::new())),
+        coire_store: None,
+        active_coire_sessions: Arc::new(RwLock::new(HashSet::new())),
+        snapshot_ttl_ms: 604_800_000,
+        ritual_registry: Arc::new(RitualRegistry::new(
+            "dis.test",
+            Arc::new(InMemoryBroker::new()),
+        )),
+        dis_domain: "dis.test".to_string(),
+        kafka_bootstrap: None,
+        fiery_pit_token_cache: Arc::new(Mutex::new(None)),
+    })
+}
+
+/// Test that POST /sessions/{id}/run reports facts asserted and retracted by the run
+#[actix_web::test]
+async fn test_run_returns_derived_facts() {
+    let state = create_test_state();
+
+    let session = state.session_manager
+        .create_session("test-user".to_string(), None)
+        .expect("Failed to create session");
+    let session_id = session.session_id.to_string();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(state.clone())
+            .route("/sessions/{session_id}/facts", web::post().to(session_handler::load_facts))
+            .route("/sessions/{session_id}/run", web::post().to(session_handler::run_rules))
+    ).await;
+
+    state.session_manager
+        .with_clips_env(&session.session_id, |env| {
+            env.build("(defrule greet ?f <- (person ?name) => (retract ?f) (assert (greeted ?name)))")
+        })
+        .expect("Failed to build rule");
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/sessions/{}/facts", session_id))
+        .set_json(&json!({ "facts": ["(person alice)"] }))
+        .to_request();
+    assert!(test::call_service(&app, req).await.status().is_success());
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/sessions/{}/run", session_id))
+        .set_json(&json!({ "return_facts": true }))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+
+    assert_eq!(body["rules_fired"], 1);
+    assert_eq!(body["asserted"], json!(["(greeted alice)"]));
+    assert_eq!(body["retracted"], json!(["(person alice)"]));
+}
+
+/// Test that the fact diff is omitted unless requested
+#[actix_web::test]
+async fn test_run_omits_facts_by_default() {
+    let state = create_test_state();
+
+    let session = state.session_manager
+        .create_session("test-user".to_string(), None)
+        .expect("Failed to create session");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(state.clone())
+            .route("/sessions/{session_id}/run", web::post().to(session_handler::run_rules))
+    ).await;
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/sessions/{}/run", session.session_id))
+        .set_json(&json!({}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+
+    assert!(body.get("asserted").is_none());
+    assert!(body.get("retracted").is_none());
+}
+
+/// Test that a bounded run reports the activations it left on the agenda
+#[actix_web::test]
+async fn test_bounded_run_reports_remaining_activations() {
+    let state = create_test_state();
+
+    let session = state.session_manager
+        .create_session("test-user".to_string(), None)
+        .expect("Failed to create session");
+    let session_id = session.session_id.to_string();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(state.clone())
+            .route("/sessions/{session_id}/run", web::post().to(session_handler::run_rules))
+    ).await;
+
+    state.session_manager
+        .with_clips_env(&session.session_id, |env| {
+            env.build("(defrule process ?f <- (item ?n) => (retract ?f) (assert (done ?n)))")?;
+            env.eval("(assert (item 1) (item 2) (item 3) (item 4) (item 5))")
+        })
+        .expect("Failed to set up rule");
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/sessions/{}/run", session_id))
+        .set_json(&json!({ "max_iterations": 2 }))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["rules_fired"], 2);
+    assert_eq!(body["activations_remaining"], 3);
+
+    // Running again to completion drains the agenda
+    let req = test::TestRequest::post()
+        .uri(&format!("/sessions/{}/run", session_id))
+        .set_json(&json!({ "max_iterations": -1 }))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["rules_fired"], 3);
+    assert_eq!(body["activations_remaining"], 0);
+}
+
+/// Test that POST /sessions/{id}/reset?mode=facts keeps templates but drops facts,
+/// while mode=all drops the templates too
+#[actix_web::test]
+async fn test_reset_modes() {
+    let state = create_test_state();
+
+    let session = state.session_manager
+        .create_session("test-user".to_string(), None)
+        .expect("Failed to create session");
+    let session_id = session.session_id.clone();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(state.clone())
+            .route("/sessions/{session_id}/reset", web::post().to(session_handler::reset_session))
+    ).await;
+
+    let load = || {
+        state.session_manager
+            .with_clips_env(&session_id, |env| {
+                env.build("(deftemplate person (slot name))")?;
+                env.eval("(assert (person (name alice)))").map(|_| ())
+            })
+            .expect("Failed to load template and fact");
+    };
+    let snapshot = || {
+        state.session_manager
+            .with_clips_env(&session_id, |env| {
+                Ok((env.eval("(get-deftemplate-list)")?, env.eval("(facts)")?))
+            })
+            .expect("Failed to inspect environment")
+    };
+
+    load();
+    let req = test::TestRequest::post()
+        .uri(&format!("/sessions/{}/reset?mode=facts", session_id))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["mode"], "facts");
+
+    let (templates, facts) = snapshot();
+    assert!(templates.contains("person"), "template should survive: {}", templates);
+    assert!(!facts.contains("alice"), "fact should be gone: {}", facts);
+
+    load();
+    let req = test::TestRequest::post()
+        .uri(&format!("/sessions/{}/reset?mode=all", session_id))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["mode"], "all");
+
+    let (templates, facts) = snapshot();
+    assert!(!templates.contains("person"), "template should be cleared: {}", templates);
+    assert!(!facts.contains("alice"), "fact should be gone: {}", facts);
+}
+
+/// Test that the session's fact count after a reset includes deffacts
+#[actix_web::test]
+async fn test_reset_recounts_deffacts() {
+    let state = create_test_state();
+
+    let session = state.session_manager
+        .create_session("test-user".to_string(), None)
+        .expect("Failed to create session");
+    let session_id = session.session_id.clone();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(state.clone())
+            .route("/sessions/{session_id}/reset", web::post().to(session_handler::reset_session))
+    ).await;
+
+    state.session_manager
+        .with_clips_env(&session_id, |env| {
+            env.build("(deffacts staff (person alice) (person bob))")
+        })
+        .expect("Failed to load deffacts");
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/sessions/{}/reset?mode=facts", session_id))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+
+    let facts = state.session_manager
+        .with_clips_env(&session_id, |env| env.eval("(facts)"))
+        .expect("Failed to list facts");
+    let count = state.session_manager
+        .get_session(&session_id)
+        .expect("Failed to get session")
+        .resources
+        .facts;
+    assert!(facts.contains("alice") && facts.contains("bob"), "{}", facts);
+    assert_eq!(count as usize, facts.lines().filter(|l| l.trim().starts_with("f-")).count());
+    assert!(count >= 2);
+}
+
+/// Test modifying a fact's slots and retracting a fact by index
+#[actix_web::test]
+async fn test_modify_and_retract_fact() {
+    let state = create_test_state();
+
+    let session = state.session_manager
+        .create_session("test-user".to_string(), None)
+        .expect("Failed to create session");
+    let session_id = session.session_id.to_string();
+
+    let fact_index = |printed: String| -> u64 {
+        printed.trim().trim_start_matches("<Fact-").trim_end_matches('>').parse().unwrap()
+    };
+    let (alice, bob) = state.session_manager
+        .with_clips_env(&session.session_id, |env| {
+            env.build("(deftemplate person (slot name) (slot age))")?;
+            Ok((
+                env.eval("(assert (person (name alice) (age 30)))")?,
+                env.eval("(assert (person (name bob) (age 40)))")?,
+            ))
+        })
+        .expect("Failed to load facts");
+    let (alice, bob) = (fact_index(alice), fact_index(bob));
+
+    let app = test::init_service(
+        App::new()
+            .app_data(state.clone())
+            .route("/sessions/{session_id}/facts", web::get().to(session_handler::query_facts))
+            .route("/sessions/{session_id}/facts/modify", web::post().to(session_handler::modify_fact))
+            .route("/sessions/{session_id}/facts/{index}", web::delete().to(session_handler::retract_fact))
+    ).await;
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/sessions/{}/facts/modify", session_id))
+        .set_json(&json!({ "index": alice, "slots": { "age": 31 } }))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["status"], "fact_modified");
+    assert!(body["index"].as_u64().is_some());
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/sessions/{}/facts/{}", session_id, bob))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["status"], "fact_retracted");
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/sessions/{}/facts", session_id))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let people: Vec<&serde_json::Value> = body["facts"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|f| f["template"] == "person")
+        .collect();
+    assert_eq!(people.len(), 1, "bob should be gone: {}", body);
+    assert_eq!(people[0]["name"], "alice");
+    assert_eq!(people[0]["age"], 31);
+
+    // The retracted index no longer exists
+    let req = test::TestRequest::delete()
+        .uri(&format!("/sessions/{}/facts/{}", session_id, bob))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), actix_web::http::StatusCode::NOT_FOUND);
+}
+
+/// Test that multislot values come back as JSON arrays from GET /facts and /evaluate
+#[actix_web::test]
+async fn test_multislots_returned_as_arrays() {
+    let state = create_test_state();
+
+    let session = state.session_manager
+        .create_session("test-user".to_string(), None)
+        .expect("Failed to create session");
+    let session_id = session.session_id.to_string();
+
+    state.session_manager
+        .with_clips_env(&session.session_id, |env| {
+            env.build("(deftemplate order (slot id) (multislot items))")?;
+            env.eval("(assert (order (id 1) (items widget gadget)))")?;
+            env.eval("(assert (order (id 2) (items widget)))").map(|_| ())
+        })
+        .expect("Failed to load facts");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(state.clone())
+            .route("/sessions/{session_id}/facts", web::get().to(session_handler::query_facts))
+            .route("/sessions/{session_id}/evaluate", web::post().to(clara_api::handlers::eval_session))
+    ).await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/sessions/{}/facts", session_id))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+
+    let items_of = |id: i64| {
+        body["facts"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|f| f["id"] == id)
+            .map(|f| f["items"].clone())
+    };
+    assert_eq!(items_of(1), Some(json!(["widget", "gadget"])));
+    assert_eq!(items_of(2), Some(json!(["widget"])));
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/sessions/{}/evaluate", session_id))
+        .set_json(&json!({ "script": "(create$ a b c)" }))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["value"], json!(["a", "b", "c"]));
+}
+
+/// Test that a CLIPS error is reported as `success: false` with the error text
+#[actix_web::test]
+async fn test_eval_reports_clips_error() {
+    let state = create_test_state();
+
+    let session = state.session_manager
+        .create_session("test-user".to_string(), None)
+        .expect("Failed to create session");
+    let session_id = session.session_id.to_string();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(state.clone())
+            .route("/sessions/{session_id}/evaluate", web::post().to(clara_api::handlers::eval_session))
+    ).await;
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/sessions/{}/evaluate", session_id))
+        .set_json(&json!({ "script": "(no-such-function 1 2)" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), actix_web::http::StatusCode::BAD_REQUEST);
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["success"], false);
+    assert!(body["error"].as_str().unwrap().contains("no-such-function"), "error: {}", body);
+    assert!(body.get("value").is_none());
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/sessions/{}/evaluate", session_id))
+        .set_json(&json!({ "script": "(+ 1 2)" }))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["success"], true);
+    assert_eq!(body["value"], 3);
+}
+
+/// Test that POST /sessions uses the default type unless `type` is given
+#[actix_web::test]
+async fn test_create_session_type_defaults_and_overrides() {
+    let state = create_test_state();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(state.clone())
+            .route("/sessions", web::post().to(session_handler::create_session))
+    ).await;
+
+    let req = test::TestRequest::post()
+        .uri("/sessions")
+        .set_json(&json!({ "user_id": "test-user" }))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["session_type"], "clips");
+
+    let req = test::TestRequest::post()
+        .uri("/sessions")
+        .set_json(&json!({ "user_id": "test-user", "type": "prolog" }))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["session_type"], "prolog");
+}
+
+/// Test that a bad rule is reported by index without blocking the others
+#[actix_web::test]
+async fn test_load_rules_reports_failures() {
+    let state = create_test_state();
+    let session = state.session_manager
+        .create_session("test-user".to_string(), None)
+        .expect("Failed to create session");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(state.clone())
+            .route("/sessions/{session_id}/rules", web::post().to(session_handler::load_rules))
+    ).await;
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/sessions/{}/rules", session.session_id))
+        .set_json(&json!({ "rules": [
+            "(defrule first (a) => (assert (b)))",
+            "(defrule broken (a) =>",
+            "(defrule second (b) => (assert (c)))",
+            "(not-a-construct)"
+        ] }))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+
+    assert_eq!(body["count"], 4);
+    assert_eq!(body["loaded"], 2);
+    let failed: Vec<u64> = body["failed"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| f["index"].as_u64().unwrap())
+        .collect();
+    assert_eq!(failed, vec![1, 3]);
+
+    let rules = state.session_manager
+        .with_clips_env(&session.session_id, |env| env.eval("(rules)"))
+        .unwrap();
+    assert!(rules.contains("first") && rules.contains("second"));
+}
+
+/// Test that a batch whose rules CLIPS all rejects is a 400, including rules
+/// that parse but fail its build-time checks, while a partly loaded batch
+/// stays a 200
+#[actix_web::test]
+async fn test_load_rules_rejected_rule_is_bad_request() {
+    let state = create_test_state();
+    let session = state.session_manager
+        .create_session("test-user".to_string(), None)
+        .expect("Failed to create session");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(state.clone())
+            .route("/sessions/{session_id}/rules", web::post().to(session_handler::load_rules))
+    ).await;
+    let load = |rules: serde_json::Value| {
+        test::TestRequest::post()
+            .uri(&format!("/sessions/{}/rules", session.session_id))
+            .set_json(&json!({ "rules": rules }))
+            .to_request()
+    };
+
+    let resp = test::call_service(&app, load(json!(["(defrule ok (a) => (assert (b)))"]))).await;
+    assert_eq!(resp.status().as_u16(), 200);
+
+    // Unbalanced parentheses
+    let resp = test::call_service(&app, load(json!(["(defrule broken (a) =>"]))).await;
+    assert_eq!(resp.status().as_u16(), 400, "Syntax error should be a client error");
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["loaded"], 0);
+    assert_eq!(body["failed"][0]["index"], 0);
+
+    // Well-formed, but the RHS calls a function that doesn't exist
+    let resp = test::call_service(
+        &app,
+        load(json!(["(defrule uses-undefined (a) => (no-such-function 1))"])),
+    ).await;
+    assert_eq!(resp.status().as_u16(), 400, "Build-time semantic error should be a client error");
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["loaded"], 0);
+    assert_eq!(body["failed"][0]["index"], 0);
+
+    // One good rule next to a semantically invalid one: the batch is a 200
+    // and the rejected rule still comes back in `failed`
+    let resp = test::call_service(
+        &app,
+        load(json!([
+            "(defrule fine (a) => (assert (c)))",
+            "(defrule uses-undefined (a) => (no-such-function 1))"
+        ])),
+    ).await;
+    assert_eq!(resp.status().as_u16(), 200, "Partly loaded batch should succeed");
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["loaded"], 1);
+    assert_eq!(body["failed"][0]["index"], 1);
+
+    // Both go through with_clips_env as syntax errors, not environment failures
+    let result = state.session_manager
+        .with_clips_env(&session.session_id, |env| env.build("(defrule broken (a) =>"));
+    assert!(matches!(result, Err(clara_session::ManagerError::ClipsSyntaxError(_))));
+    let result = state.session_manager
+        .with_clips_env(&session.session_id, |_| Err::<(), _>("engine fell over".to_string()));
+    assert!(matches!(result, Err(clara_session::ManagerError::EnvironmentError(_))));
+}
+
+/// Test POST /sessions/{id}/load loads files under the CLIPS load root and
+/// rejects paths that escape it
+#[actix_web::test]
+async fn test_load_files_sandboxed() {
+    use clara_api::validation::consult_path::{set_clips_load_root, ConsultRoot};
+
+    let base = std::env::temp_dir().join(format!("clara-clips-load-api-{}", std::process::id()));
+    let root = base.join("root");
+    std::fs::create_dir_all(&root).unwrap();
+    std::fs::write(
+        root.join("greet.clp"),
+        "(deftemplate visitor (slot name))\n(defrule loaded-greet (visitor (name ?n)) => (assert (greeted ?n)))\n",
+    )
+    .unwrap();
+    std::fs::write(base.join("secret.clp"), "(defrule loaded-secret (a) => (assert (b)))\n").unwrap();
+    set_clips_load_root(ConsultRoot::new(&root).unwrap());
+
+    let state = create_test_state();
+    let session = state.session_manager
+        .create_session("test-user".to_string(), None)
+        .expect("Failed to create session");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(state.clone())
+            .route("/sessions/{session_id}/load", web::post().to(session_handler::load_files))
+    ).await;
+    let load = |files: serde_json::Value| {
+        test::TestRequest::post()
+            .uri(&format!("/sessions/{}/load", session.session_id))
+            .set_json(&json!({ "files": files }))
+            .to_request()
+    };
+
+    let resp = test::call_service(&app, load(json!(["greet.clp"]))).await;
+    assert!(resp.status().is_success(), "In-root load should succeed");
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["status"], "files_loaded");
+    assert_eq!(body["count"], 1);
+
+    // One bad path rejects the whole request
+    let resp = test::call_service(&app, load(json!(["greet.clp", "../secret.clp"]))).await;
+    assert_eq!(resp.status().as_u16(), 400, "Traversal should be rejected");
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["error_type"], "InvalidFilePath");
+
+    let rules = state.session_manager
+        .with_clips_env(&session.session_id, |env| env.eval("(rules)"))
+        .unwrap();
+    assert!(rules.contains("loaded-greet"), "In-root file should be loaded");
+    assert!(!rules.contains("loaded-secret"), "File outside the root must not be loaded");
+
+    std::fs::remove_dir_all(base).ok();
+}
+
+/// Test that unknown paths and wrong methods get the JSON error envelope
+#[actix_web::test]
+async fn test_unmatched_routes_return_json_errors() {
+    let app = test::init_service(
+        App::new().configure(clara_api::routes::configure)
+    ).await;
+
+    let req = test::TestRequest::get().uri("/no/such/path").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), actix_web::http::StatusCode::NOT_FOUND);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["error_type"], "NotFound");
+    assert_eq!(body["code"], 404);
+    assert!(body["error"].as_str().unwrap().contains("/no/such/path"));
+
+    let req = test::TestRequest::delete().uri("/healthz").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), actix_web::http::StatusCode::METHOD_NOT_ALLOWED);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["error_type"], "MethodNotAllowed");
+    assert_eq!(body["code"], 405);
+}
+
+/// Test that a rule reasserting its own trigger is reported as a loop
+#[actix_web::test]
+async fn test_run_reports_rule_loop() {
+    let state = create_test_state();
+
+    let session = state.session_manager
+        .create_session("test-user".to_string(), None)
+        .expect("Failed to create session");
+    let session_id = session.session_id.to_string();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(state.clone())
+            .route("/sessions/{session_id}/run", web::post().to(session_handler::run_rules))
+    ).await;
+
+    state.session_manager
+        .with_clips_env(&session.session_id, |env| {
+            env.build("(defrule ping ?f <- (ping) => (retract ?f) (assert (ping)))")?;
+            env.eval("(assert (ping))")
+        })
+        .expect("Failed to set up rule");
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/sessions/{}/run", session_id))
+        .set_json(&json!({ "max_iterations": 1000, "loop_detection_window": 10 }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 500);
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["error_type"], "RuntimeError");
+    let message = body["error"].as_str().unwrap();
+    assert!(message.contains("rule loop detected"), "{}", message);
+    assert!(message.contains("ping"), "{}", message);
+}
+
+/// Test that POST /sessions/{id}/eval tags the result with the session's engine
+#[actix_web::test]
+async fn test_unified_eval_reports_session_type() {
+    let state = create_test_state();
+
+    let clips = state.session_manager
+        .create_session("test-user".to_string(), None)
+        .expect("Failed to create CLIPS session");
+    let prolog = state.session_manager
+        .create_prolog_session("test-user".to_string(), None)
+        .expect("Failed to create Prolog session");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(state.clone())
+            .route("/sessions/{session_id}/eval", web::post().to(clara_api::handlers::eval_any_session))
+    ).await;
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/sessions/{}/eval", clips.session_id))
+        .set_json(&json!({ "script": "(+ 1 2)" }))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["session_type"], "clips");
+    assert_eq!(body["success"], true);
+    assert_eq!(body["value"], 3);
+    assert_eq!(body["warnings"], json!([]));
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/sessions/{}/eval", prolog.session_id))
+        .set_json(&json!({ "script": "X is 1 + 2" }))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["session_type"], "prolog");
+    assert_eq!(body["success"], true);
+    assert_eq!(body["value"], json!({"X": 3}));
+
+    // A goal without solutions is reported in the same envelope
+    let req = test::TestRequest::post()
+        .uri(&format!("/sessions/{}/eval", prolog.session_id))
+        .set_json(&json!({ "script": "fail" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), actix_web::http::StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["session_type"], "prolog");
+    assert_eq!(body["success"], false);
+    assert!(body["error"].is_string(), "body: {}", body);
+}
+
+/// Test that a subprocess command past its timeout fails with a 504 and the
+/// session's next command runs in a fresh process
+#[test]
+fn test_subprocess_timeout_is_gateway_timeout_and_recovers() {
+    use clara_api::models::ApiError;
+    use clara_core::ClaraError;
+    use std::time::{Duration, Instant};
+
+    // `sh` stands in for a CLIPS run that never finishes in time
+    let pool = SubprocessPool::new("sh".to_string(), String::new());
+
+    let start = Instant::now();
+    let result = pool.execute("sess-slow", "sleep 5", 200);
+    assert!(start.elapsed() < Duration::from_secs(2), "took {:?}", start.elapsed());
+    let err = match result {
+        Err(err @ ClaraError::EvalTimeout { timeout_ms: 200 }) => err,
+        other => panic!("Expected a timeout, got {:?}", other),
+    };
+    assert_eq!(ApiError::from(err).status_code(), 504);
+    assert_eq!(pool.handler_count(), 0, "The timed-out handler should be discarded");
+
+    let next = pool.execute("sess-slow", "echo recovered", 2000).unwrap();
+    assert!(next.stdout.contains("recovered"), "{:?}", next.stdout);
+}
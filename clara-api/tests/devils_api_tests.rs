@@ -6,60 +6,33 @@
 use actix_web::{test, web, App};
 use clara_api::handlers::devils_handler;
 use clara_api::handlers::session_handler::AppState;
-use clara_api::subprocess::SubprocessPool;
-use clara_session::{SessionManager, ManagerConfig};
+use clara_api::test_support::{self, TestServer};
+use clara_session::{SessionStatus, SessionType};
 use serde_json::json;
 
 /// Create test app state
 fn create_test_state() -> web::Data<AppState> {
-    use std::collections::{HashMap, HashSet};
-    use std::sync::{Arc, Mutex, RwLock};
-    use clara_ritual::{InMemoryBroker, RitualRegistry};
-    web::Data::new(AppState {
-        session_manager: SessionManager::new(ManagerConfig::default()),
-        subprocess_pool: SubprocessPool::new(
-            "./clips".to_string(),
-            "__END__".to_string(),
-        ),
-        deductions: Arc::new(RwLock::new(HashMap::new())),
-        coire_store: None,
-        active_coire_sessions: Arc::new(RwLock::new(HashSet::new())),
-        snapshot_ttl_ms: 604_800_000,
-        ritual_registry: Arc::new(RitualRegistry::new(
-            "dis.test",
-            Arc::new(InMemoryBroker::new()),
-        )),
-        dis_domain: "dis.test".to_string(),
-        kafka_bootstrap: None,
-        fiery_pit_token_cache: Arc::new(Mutex::new(None)),
-    })
+    test_support::test_state()
 }
 
 /// Test creating a Prolog session via POST /devils/sessions
 #[actix_web::test]
 async fn test_create_prolog_session() {
-    let state = create_test_state();
-
-    let app = test::init_service(
-        App::new()
-            .app_data(state.clone())
-            .route("/devils/sessions", web::post().to(devils_handler::create_prolog_session))
-    ).await;
-
-    let req = test::TestRequest::post()
-        .uri("/devils/sessions")
-        .set_json(&json!({
-            "user_id": "test-user"
-        }))
-        .to_request();
+    let server = TestServer::spawn();
 
-    let resp = test::call_service(&app, req).await;
-    assert!(resp.status().is_success(), "Create session should succeed");
+    let resp = server.post("/devils/sessions", json!({ "user_id": "test-user" })).await;
+    assert!(resp.status.is_success(), "Create session should succeed");
 
-    let body: serde_json::Value = test::read_body_json(resp).await;
+    let body = resp.body;
     assert!(body.get("session_id").is_some(), "Response should contain session_id");
     assert_eq!(body.get("user_id").and_then(|v| v.as_str()), Some("test-user"));
     assert_eq!(body.get("status").and_then(|v| v.as_str()), Some("active"));
+
+    // The session is visible through the rest of the API
+    let session_id = body["session_id"].as_str().unwrap();
+    let resp = server.get(&format!("/devils/sessions/{}", session_id)).await;
+    assert!(resp.status.is_success(), "Get session should succeed");
+    assert_eq!(resp.body["session_type"], "prolog");
 }
 
 /// Test listing Prolog sessions via GET /devils/sessions
@@ -91,6 +64,97 @@ async fn test_list_prolog_sessions() {
 
     let total = body.get("total").and_then(|v| v.as_u64()).unwrap_or(0);
     assert!(total >= 1, "Should have at least one session");
+
+    for session in body["sessions"].as_array().unwrap() {
+        assert_eq!(session.get("session_type").and_then(|v| v.as_str()), Some("prolog"));
+    }
+}
+
+/// Test that GET /sessions reports the engine type of each session in the mixed list
+#[actix_web::test]
+async fn test_list_all_sessions_reports_session_type() {
+    use clara_api::handlers::session_handler;
+
+    let state = create_test_state();
+
+    let clips = state.session_manager
+        .create_session("test-user".to_string(), None)
+        .expect("Failed to create CLIPS session");
+    let prolog = state.session_manager
+        .create_prolog_session("test-user".to_string(), None)
+        .expect("Failed to create Prolog session");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(state.clone())
+            .route("/sessions", web::get().to(session_handler::list_all_sessions))
+    ).await;
+
+    let req = test::TestRequest::get()
+        .uri("/sessions")
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success(), "List sessions should succeed");
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let sessions = body["sessions"].as_array().expect("sessions array");
+    assert_eq!(sessions.len(), 2);
+
+    let type_of = |id: &str| {
+        sessions
+            .iter()
+            .find(|s| s["session_id"] == id)
+            .and_then(|s| s["session_type"].as_str())
+            .map(str::to_string)
+    };
+    assert_eq!(type_of(&clips.session_id.to_string()).as_deref(), Some("clips"));
+    assert_eq!(type_of(&prolog.session_id.to_string()).as_deref(), Some("prolog"));
+}
+
+/// Test filtering GET /sessions by `?type=` and rejecting unknown types
+#[actix_web::test]
+async fn test_list_all_sessions_type_filter() {
+    use clara_api::handlers::session_handler;
+
+    let state = create_test_state();
+
+    let clips = state.session_manager
+        .create_session("test-user".to_string(), None)
+        .expect("Failed to create CLIPS session");
+    let prolog = state.session_manager
+        .create_prolog_session("test-user".to_string(), None)
+        .expect("Failed to create Prolog session");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(state.clone())
+            .route("/sessions", web::get().to(session_handler::list_all_sessions))
+    ).await;
+
+    for (filter, expected) in [("clips", &clips), ("prolog", &prolog)] {
+        let req = test::TestRequest::get()
+            .uri(&format!("/sessions?type={}", filter))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert!(resp.status().is_success(), "Filter {} should succeed", filter);
+
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        let sessions = body["sessions"].as_array().expect("sessions array");
+        assert_eq!(sessions.len(), 1, "{}", body);
+        assert_eq!(sessions[0]["session_id"], expected.session_id.to_string());
+        assert_eq!(sessions[0]["session_type"], filter);
+        assert_eq!(body["total"], 1);
+    }
+
+    let req = test::TestRequest::get()
+        .uri("/sessions?type=lisp")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status().as_u16(), 400, "Unknown type should be rejected");
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["error_type"], "ValidationError");
+    assert!(body["error"].as_str().unwrap_or_default().contains("lisp"), "{}", body);
 }
 
 /// Test getting a specific Prolog session via GET /devils/sessions/{id}
@@ -150,6 +214,26 @@ async fn test_terminate_prolog_session() {
     assert_eq!(body.get("status").and_then(|v| v.as_str()), Some("terminated"));
 }
 
+/// Test that terminating a session twice succeeds both times, through either route
+#[actix_web::test]
+async fn test_terminate_session_twice() {
+    let server = TestServer::spawn();
+
+    for uri in ["/devils/sessions", "/sessions"] {
+        let session = server.create_prolog_session();
+        for _ in 0..2 {
+            let resp = server.delete(&format!("{}/{}", uri, session.session_id)).await;
+            assert!(resp.status.is_success(), "DELETE {} failed: {:?}", uri, resp);
+            assert_eq!(resp.body["status"], "terminated");
+        }
+        assert!(server
+            .state
+            .session_manager
+            .with_prolog_env(&session.session_id, |_| Ok(()))
+            .is_err());
+    }
+}
+
 /// Test executing a Prolog query via POST /devils/sessions/{id}/query
 #[actix_web::test]
 async fn test_query_prolog() {
@@ -182,6 +266,278 @@ async fn test_query_prolog() {
     assert!(body.get("runtime_ms").is_some(), "Should have runtime_ms");
 }
 
+/// Test grounding named goal variables from `bindings`
+#[actix_web::test]
+async fn test_query_prolog_with_bindings() {
+    let state = create_test_state();
+
+    let session = state.session_manager
+        .create_prolog_session("test-user".to_string(), None)
+        .expect("Failed to create session");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(state.clone())
+            .route("/devils/sessions/{session_id}/query", web::post().to(devils_handler::query_prolog))
+    ).await;
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/devils/sessions/{}/query", session.session_id))
+        .set_json(&json!({
+            "goal": "between(Low, High, X)",
+            "bindings": { "Low": 1, "High": 5 },
+            "all_solutions": true,
+            "format": "terms"
+        }))
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success(), "Query should succeed");
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let solutions: Vec<serde_json::Value> =
+        serde_json::from_str(body["result"].as_str().unwrap()).unwrap();
+    let xs: Vec<i64> = solutions.iter().map(|s| s["args"][2].as_i64().unwrap()).collect();
+    assert_eq!(xs, vec![1, 2, 3, 4, 5]);
+
+    // A binding that names no variable in the goal is rejected
+    let req = test::TestRequest::post()
+        .uri(&format!("/devils/sessions/{}/query", session.session_id))
+        .set_json(&json!({
+            "goal": "between(Low, 5, X)",
+            "bindings": { "Low": 1, "High": 5 }
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), actix_web::http::StatusCode::BAD_REQUEST);
+}
+
+/// Test that binding values are data and can't change the goal
+#[actix_web::test]
+async fn test_query_prolog_bindings_not_injectable() {
+    let state = create_test_state();
+
+    let session = state.session_manager
+        .create_prolog_session("test-user".to_string(), None)
+        .expect("Failed to create session");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(state.clone())
+            .route("/devils/sessions/{session_id}/query", web::post().to(devils_handler::query_prolog))
+    ).await;
+
+    let payload = "x), assertz(pwned), atom_length(y";
+    let req = test::TestRequest::post()
+        .uri(&format!("/devils/sessions/{}/query", session.session_id))
+        .set_json(&json!({
+            "goal": "atom_length(A, N)",
+            "bindings": { "A": payload },
+            "format": "terms"
+        }))
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success(), "Query should succeed");
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let solution: serde_json::Value =
+        serde_json::from_str(body["result"].as_str().unwrap()).unwrap();
+    assert_eq!(solution["args"][0], payload);
+    assert_eq!(solution["args"][1], payload.len());
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/devils/sessions/{}/query", session.session_id))
+        .set_json(&json!({ "goal": "current_predicate(pwned/0)" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(!resp.status().is_success(), "Injected clause must not exist");
+}
+
+/// Test each result `format` over the same goal
+#[actix_web::test]
+async fn test_query_prolog_formats() {
+    let state = create_test_state();
+
+    let session = state.session_manager
+        .create_prolog_session("test-user".to_string(), None)
+        .expect("Failed to create session");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(state.clone())
+            .route("/devils/sessions/{session_id}/query", web::post().to(devils_handler::query_prolog))
+    ).await;
+
+    let query = |body: serde_json::Value| {
+        test::TestRequest::post()
+            .uri(&format!("/devils/sessions/{}/query", session.session_id))
+            .set_json(&body)
+            .to_request()
+    };
+    let result = |body: serde_json::Value| -> serde_json::Value {
+        serde_json::from_str(body["result"].as_str().unwrap()).unwrap()
+    };
+
+    // Bindings is the default
+    for body in [
+        json!({"goal": "member(X, [a, b])", "all_solutions": true}),
+        json!({"goal": "member(X, [a, b])", "all_solutions": true, "format": "bindings"}),
+    ] {
+        let resp = test::call_service(&app, query(body)).await;
+        assert!(resp.status().is_success());
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(result(body), json!([{"X": "a"}, {"X": "b"}]));
+    }
+
+    let resp = test::call_service(
+        &app,
+        query(json!({"goal": "member(X, [a, b])", "all_solutions": true, "format": "terms"})),
+    ).await;
+    assert!(resp.status().is_success());
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let terms = result(body);
+    assert_eq!(terms.as_array().unwrap().len(), 2);
+    assert_eq!(terms[0]["functor"], "member");
+    assert_eq!(terms[1]["args"][0], "b");
+
+    for (goal, expected) in [("member(X, [a, b])", json!(true)), ("member(c, [a, b])", json!(false))] {
+        let resp = test::call_service(&app, query(json!({"goal": goal, "format": "check"}))).await;
+        assert!(resp.status().is_success());
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(result(body), expected, "{}", goal);
+    }
+
+    // Request bindings and the first-solution form work with every format
+    let resp = test::call_service(
+        &app,
+        query(json!({"goal": "member(X, L)", "bindings": {"L": ["b", "c"]}})),
+    ).await;
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(result(body), json!({"X": "b", "L": ["b", "c"]}));
+
+    // Unknown formats are rejected
+    let resp = test::call_service(&app, query(json!({"goal": "true", "format": "raw"}))).await;
+    assert_eq!(resp.status().as_u16(), 400);
+}
+
+/// Test that max_solutions caps an unbounded query and reports the cut-off
+#[actix_web::test]
+async fn test_query_prolog_max_solutions() {
+    let server = TestServer::spawn();
+    let session = server.create_prolog_session();
+    let uri = format!("/devils/sessions/{}/query", session.session_id);
+
+    // Untimed queries step through solutions, timed ones use limit/2
+    for timeout_ms in [0, 5000] {
+        let resp = server.post(&uri, json!({
+            "goal": "between(1, inf, X)",
+            "max_solutions": 5,
+            "timeout_ms": timeout_ms,
+        })).await;
+        assert!(resp.status.is_success(), "{:?}", resp);
+        let solutions: serde_json::Value = serde_json::from_str(resp.body["result"].as_str().unwrap()).unwrap();
+        assert_eq!(solutions, json!([{"X": 1}, {"X": 2}, {"X": 3}, {"X": 4}, {"X": 5}]));
+        assert_eq!(resp.body["truncated"], true);
+
+        let resp = server.post(&uri, json!({
+            "goal": "member(X, [a, b])",
+            "max_solutions": 5,
+            "timeout_ms": timeout_ms,
+        })).await;
+        let solutions: serde_json::Value = serde_json::from_str(resp.body["result"].as_str().unwrap()).unwrap();
+        assert_eq!(solutions, json!([{"X": "a"}, {"X": "b"}]));
+        assert_eq!(resp.body["truncated"], false);
+    }
+
+    let resp = server.post(&uri, json!({"goal": "true", "max_solutions": 0})).await;
+    assert_eq!(resp.status.as_u16(), 400);
+
+    // Without max_solutions the flag is left out
+    let resp = server.post(&uri, json!({"goal": "true"})).await;
+    assert!(resp.body.get("truncated").is_none());
+}
+
+/// Test that blank goals are rejected with a validation error before parsing
+#[actix_web::test]
+async fn test_query_prolog_blank_goal() {
+    let state = create_test_state();
+
+    let session = state.session_manager
+        .create_prolog_session("test-user".to_string(), None)
+        .expect("Failed to create session");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(state.clone())
+            .route("/devils/sessions/{session_id}/query", web::post().to(devils_handler::query_prolog))
+    ).await;
+
+    for goal in ["", "   \n\t", "% just a comment"] {
+        let req = test::TestRequest::post()
+            .uri(&format!("/devils/sessions/{}/query", session.session_id))
+            .set_json(&json!({"goal": goal}))
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status().as_u16(), 400, "{:?} should be rejected", goal);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["error_type"], "ValidationError");
+        assert!(body["error"].as_str().unwrap_or_default().contains("goal must not be empty"), "{}", body);
+    }
+
+    // The environment rejects them the same way
+    let env = clara_prolog::PrologEnvironment::new().expect("Failed to create environment");
+    for goal in ["", "  ", "/* nothing */"] {
+        assert!(matches!(
+            env.query_once(goal),
+            Err(clara_prolog::PrologError::InvalidArgument(msg)) if msg == "goal must not be empty"
+        ));
+    }
+}
+
+/// Test that a query running past `timeout_ms` is aborted with a 504
+#[actix_web::test]
+async fn test_query_prolog_timeout() {
+    let state = create_test_state();
+
+    let session = state.session_manager
+        .create_prolog_session("test-user".to_string(), None)
+        .expect("Failed to create session");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(state.clone())
+            .route("/devils/sessions/{session_id}/query", web::post().to(devils_handler::query_prolog))
+    ).await;
+
+    let started = std::time::Instant::now();
+    let req = test::TestRequest::post()
+        .uri(&format!("/devils/sessions/{}/query", session.session_id))
+        .set_json(&json!({
+            "goal": "repeat, fail",
+            "timeout_ms": 200
+        }))
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status().as_u16(), 504, "Slow query should time out");
+    assert!(started.elapsed() < std::time::Duration::from_secs(10));
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["error_type"], "EvalTimeout");
+    assert_eq!(body["code"], 504);
+    assert!(body["error"].as_str().unwrap_or_default().contains("200ms"), "{}", body);
+
+    // The engine is still usable afterwards
+    let req = test::TestRequest::post()
+        .uri(&format!("/devils/sessions/{}/query", session.session_id))
+        .set_json(&json!({"goal": "X is 2 + 3", "timeout_ms": 1000}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success(), "Query after a timeout should succeed");
+}
+
 /// Test loading clauses via POST /devils/sessions/{id}/consult
 #[actix_web::test]
 async fn test_consult_prolog() {
@@ -217,6 +573,104 @@ async fn test_consult_prolog() {
     assert_eq!(body.get("count").and_then(|v| v.as_u64()), Some(3));
 }
 
+/// Test POST /devils/sessions/{id}/consult/file loads files under the
+/// consult root and rejects paths that escape it
+#[actix_web::test]
+async fn test_consult_prolog_file_sandboxed() {
+    use clara_api::validation::consult_path::{set_consult_root, ConsultRoot};
+
+    let base = std::env::temp_dir().join(format!("clara-consult-api-{}", std::process::id()));
+    let root = base.join("root");
+    std::fs::create_dir_all(&root).unwrap();
+    std::fs::write(root.join("family.pl"), "consulted_parent(tom, mary).\n").unwrap();
+    std::fs::write(base.join("secret.pl"), "consulted_secret(42).\n").unwrap();
+    set_consult_root(ConsultRoot::new(&root).unwrap());
+
+    let state = create_test_state();
+    let session = state.session_manager
+        .create_prolog_session("test-user".to_string(), None)
+        .expect("Failed to create session");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(state.clone())
+            .route("/devils/sessions/{session_id}/consult/file", web::post().to(devils_handler::consult_prolog_file))
+    ).await;
+    let consult = |path: &str| {
+        test::TestRequest::post()
+            .uri(&format!("/devils/sessions/{}/consult/file", session.session_id))
+            .set_json(&json!({"path": path}))
+            .to_request()
+    };
+
+    let resp = test::call_service(&app, consult("family.pl")).await;
+    assert!(resp.status().is_success(), "In-root consult should succeed");
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["status"], "file_consulted");
+
+    let resp = test::call_service(&app, consult("../secret.pl")).await;
+    assert_eq!(resp.status().as_u16(), 400, "Traversal should be rejected");
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["error_type"], "InvalidFilePath");
+
+    let (parent, secret) = state.session_manager
+        .with_prolog_env(&session.session_id, |env| {
+            Ok((env.check("consulted_parent(tom, mary)")?, env.check("current_predicate(consulted_secret/1)")?))
+        })
+        .unwrap();
+    assert!(parent, "In-root file should be loaded");
+    assert!(!secret, "File outside the root must not be loaded");
+
+    std::fs::remove_dir_all(base).ok();
+}
+
+/// Test that a streamed NDJSON consult loads every clause and counts them
+#[actix_web::test]
+async fn test_consult_prolog_stream() {
+    let state = create_test_state();
+
+    let session = state.session_manager
+        .create_prolog_session("test-user".to_string(), None)
+        .expect("Failed to create session");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(state.clone())
+            .route("/devils/sessions/{session_id}/consult/stream", web::post().to(devils_handler::consult_prolog_stream))
+    ).await;
+
+    // Below the default clause-count limit, in a body far larger than one line
+    let body: String = (0..900)
+        .map(|i| format!("\"streamed_item({}).\"\n", i))
+        .collect();
+    let req = test::TestRequest::post()
+        .uri(&format!("/devils/sessions/{}/consult/stream", session.session_id))
+        .insert_header(("content-type", "application/x-ndjson"))
+        .set_payload(body)
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success(), "Streamed consult should succeed");
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["status"], "clauses_loaded");
+    assert_eq!(body["count"], 900);
+
+    let counted = state.session_manager
+        .with_prolog_env(&session.session_id, |env| {
+            env.check("aggregate_all(count, streamed_item(_), 900)")
+        })
+        .unwrap();
+    assert!(counted);
+
+    // A line that isn't a JSON string is rejected
+    let req = test::TestRequest::post()
+        .uri(&format!("/devils/sessions/{}/consult/stream", session.session_id))
+        .set_payload("\"streamed_other(1).\"\n42\n")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+}
+
 /// Test full workflow: create session, consult, query, terminate
 #[actix_web::test]
 async fn test_full_prolog_workflow() {
@@ -302,6 +756,55 @@ async fn test_query_nonexistent_session() {
     assert!(!resp.status().is_success(), "Query to non-existent session should fail");
 }
 
+/// Test that oversized goals and consults are rejected and counted on /metrics
+#[actix_web::test]
+async fn test_oversized_input_rejected_and_counted() {
+    use clara_api::routes::metrics;
+    use clara_api::validation::input::{input_limits, rejection_count, LimitKind};
+
+    let state = create_test_state();
+    let session = state.session_manager
+        .create_prolog_session("test-user".to_string(), None)
+        .expect("Failed to create session");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(state.clone())
+            .route("/devils/sessions/{session_id}/query", web::post().to(devils_handler::query_prolog))
+            .route("/devils/sessions/{session_id}/consult", web::post().to(devils_handler::consult_prolog))
+            .route("/metrics", web::get().to(metrics::metrics))
+    ).await;
+
+    let size_before = rejection_count(LimitKind::PrologInputSize);
+    let count_before = rejection_count(LimitKind::PrologClauseCount);
+
+    let goal = format!("atom_length('{}', _)", "x".repeat(input_limits().max_prolog_input_bytes));
+    let req = test::TestRequest::post()
+        .uri(&format!("/devils/sessions/{}/query", session.session_id))
+        .set_json(&json!({"goal": goal}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+
+    let clauses: Vec<String> = (0..=input_limits().max_prolog_clauses)
+        .map(|i| format!("n({}).", i))
+        .collect();
+    let req = test::TestRequest::post()
+        .uri(&format!("/devils/sessions/{}/consult", session.session_id))
+        .set_json(&json!({"clauses": clauses}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+
+    assert!(rejection_count(LimitKind::PrologInputSize) > size_before);
+    assert!(rejection_count(LimitKind::PrologClauseCount) > count_before);
+
+    let req = test::TestRequest::get().uri("/metrics").to_request();
+    let body = test::call_and_read_body(&app, req).await;
+    let body = String::from_utf8(body.to_vec()).unwrap();
+    assert!(body.contains("clara_input_rejections_total{limit=\"prolog_input_size\"}"));
+}
+
 /// Test error handling: get non-existent session
 #[actix_web::test]
 async fn test_get_nonexistent_session() {
@@ -324,3 +827,109 @@ async fn test_get_nonexistent_session() {
 // Note: Server startup and splinteredmind integration tests are in startup_tests.rs
 // Those tests must run outside of an async runtime because the splinteredmind tool
 // uses reqwest::blocking which cannot be initialized inside a Tokio runtime.
+
+/// Collect the key paths of a JSON object, recursing into nested objects
+fn json_shape(value: &serde_json::Value, prefix: &str, out: &mut Vec<String>) {
+    if let Some(map) = value.as_object() {
+        for (key, child) in map {
+            let path = format!("{}{}", prefix, key);
+            json_shape(child, &format!("{}.", path), out);
+            out.push(path);
+        }
+    }
+    out.sort();
+}
+
+/// Test that CLIPS and Prolog session endpoints share one response shape
+#[actix_web::test]
+async fn test_session_responses_share_shape() {
+    use clara_api::handlers::session_handler;
+
+    let state = create_test_state();
+
+    let clips = state.session_manager
+        .create_session("test-user".to_string(), None)
+        .expect("Failed to create CLIPS session");
+    let prolog = state.session_manager
+        .create_prolog_session("test-user".to_string(), None)
+        .expect("Failed to create Prolog session");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(state.clone())
+            .route("/sessions/{session_id}", web::get().to(session_handler::get_session))
+            .route("/devils/sessions/{session_id}", web::get().to(devils_handler::get_prolog_session))
+    ).await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/sessions/{}", clips.session_id))
+        .to_request();
+    let clips_body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/devils/sessions/{}", prolog.session_id))
+        .to_request();
+    let prolog_body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+
+    let (mut clips_shape, mut prolog_shape) = (Vec::new(), Vec::new());
+    json_shape(&clips_body, "", &mut clips_shape);
+    json_shape(&prolog_body, "", &mut prolog_shape);
+    assert_eq!(clips_shape, prolog_shape);
+    assert_eq!(clips_body["session_type"], "clips");
+    assert_eq!(prolog_body["session_type"], "prolog");
+}
+
+/// Test loading a builtin profile when creating a session
+#[actix_web::test]
+async fn test_create_session_with_profile() {
+    let server = TestServer::spawn();
+
+    let resp = server.post("/devils/sessions", json!({
+        "user_id": "test-user",
+        "profile": "data_processing",
+    })).await;
+    assert!(resp.status.is_success(), "{:?}", resp);
+
+    let uri = format!("/devils/sessions/{}/query", resp.body["session_id"].as_str().unwrap());
+    let resp = server.post(&uri, json!({"goal": "atom_json_term('{\"a\": 1}', _, [])"})).await;
+    assert!(resp.status.is_success(), "{:?}", resp);
+
+    // CLIPS sessions have no profiles; the session isn't left behind
+    let resp = server.post("/sessions", json!({
+        "user_id": "test-user",
+        "session_type": "clips",
+        "profile": "standard",
+    })).await;
+    assert_eq!(resp.status.as_u16(), 400);
+    let sessions = server.state.session_manager.get_user_sessions("test-user").unwrap();
+    assert!(sessions.iter().all(|s| s.session_type != SessionType::Clips
+        || s.status == SessionStatus::Terminated));
+
+    let resp = server.post("/devils/sessions", json!({"user_id": "test-user", "profile": "everything"})).await;
+    assert!(resp.status.is_client_error());
+}
+
+/// Test that a full eval queue is a 429 with a Retry-After hint
+#[actix_web::test]
+async fn test_queue_full_sends_retry_after() {
+    let server = TestServer::with_config(clara_session::ManagerConfig {
+        max_in_flight_evals: 1,
+        max_queued_evals: 0,
+        ..Default::default()
+    });
+    let session = server.create_prolog_session();
+
+    // Hold the only slot so the next evaluation is turned away
+    let _running = server.state.session_manager.eval_queue().acquire().unwrap();
+    let resp = server
+        .call(
+            test::TestRequest::post()
+                .uri(&format!("/sessions/{}/eval", session.session_id))
+                .set_json(json!({ "script": "true" }))
+                .to_request(),
+        )
+        .await;
+    assert_eq!(resp.status().as_u16(), 429);
+    let retry_after = resp.headers().get("retry-after").expect("Retry-After should be set");
+    assert!(retry_after.to_str().unwrap().parse::<u64>().unwrap() >= 1);
+}
@@ -247,3 +247,30 @@ fn test_invoke_use_echo_predicate() {
 
     println!("=== use_echo predicate test completed ===");
 }
+
+/// Test the `--selftest` routine reports each subsystem's status
+#[test]
+fn test_selftest_reports_subsystems() {
+    let manifest_dir = env!("CARGO_MANIFEST_DIR");
+    let workspace_root = std::path::Path::new(manifest_dir).parent().unwrap();
+    let clips_binary = workspace_root.join("clips/binaries/clips");
+
+    let report = clara_api::selftest::run_selftest(&clips_binary.to_string_lossy());
+    println!("{}", report);
+
+    for subsystem in clara_api::selftest::SUBSYSTEMS {
+        assert!(report.check(subsystem).is_some(), "{} missing from report", subsystem);
+    }
+    for subsystem in ["coire", "toolbox", "prolog", "query"] {
+        let check = report.check(subsystem).unwrap();
+        assert!(check.ok, "{} failed: {}", subsystem, check.detail);
+    }
+
+    // A CLIPS binary that cannot be spawned fails only the clips step
+    let report = clara_api::selftest::run_selftest("/nonexistent/clips");
+    let clips = report.check("clips").unwrap();
+    assert!(!clips.ok, "clips should fail with a missing binary");
+    assert!(!report.passed());
+    assert!(report.check("toolbox").unwrap().ok);
+    assert!(report.to_string().contains("[FAIL] clips"));
+}
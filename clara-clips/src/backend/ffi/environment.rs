@@ -1,10 +1,30 @@
 // Safe Rust wrapper around CLIPS Environment
 
 use super::bindings::{self, CLIPSValue, Environment, EvalError};
+use crate::clips_conversion::{split_clips_error, BUILD_FAILED_PREFIX, INVALID_CONSTRUCT_PREFIX};
+use clara_core::truncate_str;
+use std::collections::VecDeque;
 use std::ffi::{CStr, CString};
 use libc::c_void;
 use uuid::Uuid;
 
+/// Firings of one activation fingerprint within the detection window that
+/// count as a loop in [`ClipsEnvironment::run_with_loop_detection`]
+pub const LOOP_REPEAT_LIMIT: usize = 3;
+
+/// Longest slice of evaluated code written to the debug log
+const MAX_LOGGED_CODE_CHARS: usize = 200;
+
+/// How a [`ClipsEnvironment::run_with_loop_detection`] run ended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RunOutcome {
+    /// The agenda emptied or the firing limit was reached
+    Completed { rules_fired: u64 },
+    /// A rule kept firing on the same facts; the run stopped before firing
+    /// it again. `activation` is the rule name followed by the matched facts.
+    LoopDetected { rules_fired: u64, activation: String },
+}
+
 /// Safe wrapper around a CLIPS Environment
 pub struct ClipsEnvironment {
     env: *mut Environment,
@@ -86,7 +106,11 @@ impl ClipsEnvironment {
 
     /// Evaluate a CLIPS expression and return the result as a string
     pub fn eval(&mut self, code: &str) -> Result<String, String> {
-        log::debug!("Evaluating CLIPS code in session {}: {}", self.session_id, code);
+        log::debug!(
+            "Evaluating CLIPS code in session {}: {}",
+            self.session_id,
+            truncate_str(code, MAX_LOGGED_CODE_CHARS)
+        );
         unsafe {
             let c_code = CString::new(code)
                 .map_err(|e| format!("Invalid code string: {}", e))?;
@@ -148,14 +172,15 @@ impl ClipsEnvironment {
     pub fn build(&mut self, construct: &str) -> Result<(), String> {
         unsafe {
             let c_str = CString::new(construct)
-                .map_err(|e| format!("Invalid construct string: {}", e))?;
+                .map_err(|e| format!("{}: {}", INVALID_CONSTRUCT_PREFIX, e))?;
             // Build returns BuildError: 0 = BE_NO_ERROR (success), non-zero = failure
             let result = bindings::Build(self.env, c_str.as_ptr());
             if result == 0 {
                 Ok(())
             } else {
-                let preview = &construct[..construct.len().min(80)];
-                Err(format!("CLIPS Build failed (code {}) for: {}", result, preview))
+                // Cut on a char boundary; byte slicing panics on multibyte input
+                let preview: String = construct.chars().take(80).collect();
+                Err(format!("{} (code {}) for: {}", BUILD_FAILED_PREFIX, result, preview))
             }
         }
     }
@@ -263,27 +288,35 @@ impl ClipsEnvironment {
         Ok(dispatched)
     }
 
-    /// Reset the CLIPS environment
+    /// Reset the CLIPS environment (`(reset)` semantics)
+    ///
+    /// Retracts all facts and reasserts `deffacts`, keeping templates, rules
+    /// and functions. Defglobals revert to their initial values, so the
+    /// `?*coire-session-id*` global is re-seeded afterwards.
     pub fn reset(&mut self) -> Result<(), String> {
         unsafe {
             bindings::Reset(self.env);
-            Ok(())
         }
+        self.eval(&format!("(bind ?*coire-session-id* \"{}\")", self.session_id))?;
+        Ok(())
     }
 
-    /// Load a CLIPS file
+    /// Load a CLIPS constructs file
+    ///
+    /// Issues `(load* "path")`, the quiet form of `(load)`, so the CLIPS
+    /// diagnostics for a file that can't be opened or has a bad construct
+    /// end up in the error. Constructs before a bad one stay loaded.
     pub fn load(&mut self, path: &str) -> Result<(), String> {
-        unsafe {
-            let c_path = CString::new(path)
-                .map_err(|e| format!("Invalid path string: {}", e))?;
+        let quoted = path.replace('\\', "\\\\").replace('"', "\\\"");
+        let output = self.eval(&format!("(load* \"{}\")", quoted))?;
 
-            let result = bindings::Load(self.env, c_path.as_ptr());
-            if result != 0 {
-                Err(format!("Failed to load file: {}", path))
-            } else {
-                Ok(())
-            }
+        if let (_, Some(error)) = split_clips_error(&output) {
+            return Err(format!("Failed to load file {}: {}", path, error));
+        }
+        if output.trim_end().ends_with("FALSE") {
+            return Err(format!("Failed to load file: {}", path));
         }
+        Ok(())
     }
 
     /// Clear the CLIPS environment
@@ -294,6 +327,147 @@ impl ClipsEnvironment {
         }
     }
 
+    /// List templates referenced by rule patterns that have no explicit
+    /// `deftemplate` in this environment.
+    ///
+    /// CLIPS silently creates an implied (ordered) template for any unknown
+    /// pattern head, so a rule written against a template that was never
+    /// defined loads fine and simply never fires. Run this before `(run)` to
+    /// catch that mistake. Environments that use ordered facts on purpose will
+    /// see those heads reported too.
+    pub fn undefined_rule_templates(&mut self) -> Result<Vec<String>, String> {
+        let mut referenced = Vec::new();
+        for rule in parse_symbol_list(&self.eval("(get-defrule-list)")?) {
+            let source = self.eval(&format!("(ppdefrule {})", rule))?;
+            for name in rule_pattern_templates(&source) {
+                if !referenced.contains(&name) {
+                    referenced.push(name);
+                }
+            }
+        }
+
+        let mut defined = Vec::new();
+        for template in parse_symbol_list(&self.eval("(get-deftemplate-list)")?) {
+            let slots = self.eval(&format!("(deftemplate-slot-names {})", template))?;
+            if slots.trim() != "(implied)" {
+                defined.push(template);
+            }
+        }
+
+        Ok(referenced
+            .into_iter()
+            .filter(|name| !defined.contains(name))
+            .collect())
+    }
+
+    /// Names of the multislots in `template`
+    ///
+    /// Implied (ordered) templates have no named slots and return an empty list.
+    pub fn multislot_names(&mut self, template: &str) -> Result<Vec<String>, String> {
+        let slots = self.eval(&format!("(deftemplate-slot-names {})", template))?;
+        if slots.trim() == "(implied)" {
+            return Ok(Vec::new());
+        }
+
+        let mut multislots = Vec::new();
+        for slot in parse_symbol_list(&slots) {
+            let multip = self.eval(&format!("(deftemplate-slot-multip {} {})", template, slot))?;
+            if multip.trim() == "TRUE" {
+                multislots.push(slot);
+            }
+        }
+        Ok(multislots)
+    }
+
+    /// Pretty-printed source of this environment's templates, functions and
+    /// rules, in an order they can be rebuilt with [`build`](Self::build)
+    ///
+    /// Implied (ordered) templates are left out since CLIPS recreates them on
+    /// demand, as are defglobals. Only the current module is listed.
+    pub fn constructs(&mut self) -> Result<Vec<String>, String> {
+        let mut constructs = Vec::new();
+        for template in parse_symbol_list(&self.eval("(get-deftemplate-list)")?) {
+            let slots = self.eval(&format!("(deftemplate-slot-names {})", template))?;
+            if slots.trim() != "(implied)" {
+                constructs.push(self.eval(&format!("(ppdeftemplate {} stdout)", template))?);
+            }
+        }
+        for (list, pretty_print) in [("get-deffunction-list", "ppdeffunction"), ("get-defrule-list", "ppdefrule")] {
+            for name in parse_symbol_list(&self.eval(&format!("({})", list))?) {
+                constructs.push(self.eval(&format!("({} {} stdout)", pretty_print, name))?);
+            }
+        }
+
+        Ok(constructs
+            .into_iter()
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty())
+            .collect())
+    }
+
+    /// Every fact in the fact list, oldest first, as text `(assert ...)`
+    /// accepts
+    pub fn facts(&mut self) -> Result<Vec<String>, String> {
+        let mut facts = Vec::new();
+        // Printed as (<Fact-1> <Fact-2> ...)
+        for fact in parse_symbol_list(&self.eval("(get-fact-list)")?) {
+            let index = fact.trim_start_matches("<Fact-").trim_end_matches('>');
+            facts.push(self.eval(&format!("(ppfact {} stdout)", index))?.trim().to_string());
+        }
+        Ok(facts)
+    }
+
+    /// Run the agenda one activation at a time, stopping if a rule loops
+    ///
+    /// Each activation is fingerprinted by its rule name and the contents of
+    /// the facts that matched it, so a rule that retracts and reasserts its
+    /// own trigger produces the same fingerprint every time even though the
+    /// fact indices change. The last `window` fingerprints are remembered; an
+    /// activation that would fire for the `LOOP_REPEAT_LIMIT`th time within
+    /// that window is not fired and the run ends with
+    /// [`RunOutcome::LoopDetected`]. A negative `limit` runs until the agenda
+    /// is empty, as `(run)` does. Only the current module's agenda is
+    /// inspected.
+    pub fn run_with_loop_detection(&mut self, limit: i64, window: usize) -> Result<RunOutcome, String> {
+        let mut recent: VecDeque<String> = VecDeque::with_capacity(window);
+        let mut rules_fired = 0u64;
+
+        while limit < 0 || (rules_fired as i64) < limit {
+            let Some((rule, basis)) = parse_agenda_top(&self.eval("(agenda)")?) else {
+                break;
+            };
+
+            let mut fingerprint = rule;
+            for index in basis {
+                fingerprint.push(' ');
+                fingerprint.push_str(self.eval(&format!("(ppfact {} stdout)", index))?.trim());
+            }
+
+            let repeats = recent.iter().filter(|f| **f == fingerprint).count();
+            if repeats + 1 >= LOOP_REPEAT_LIMIT {
+                return Ok(RunOutcome::LoopDetected { rules_fired, activation: fingerprint });
+            }
+
+            self.eval("(run 1)")?;
+            rules_fired += 1;
+
+            if window > 0 {
+                if recent.len() == window {
+                    recent.pop_front();
+                }
+                recent.push_back(fingerprint);
+            }
+        }
+
+        Ok(RunOutcome::Completed { rules_fired })
+    }
+
+    /// Number of activations on the current module's agenda, i.e. rules
+    /// that would still fire if the run continued
+    pub fn agenda_size(&mut self) -> Result<u64, String> {
+        Ok(count_agenda_activations(&self.eval("(agenda)")?))
+    }
+
     /// Get raw environment pointer (for advanced use cases)
     pub fn as_ptr(&self) -> *mut Environment {
         self.env
@@ -389,6 +563,149 @@ pub fn split_clips_constructs(source: &str) -> Vec<String> {
     constructs
 }
 
+/// Parse a printed CLIPS multifield such as `(foo bar baz)` into its symbols.
+fn parse_symbol_list(printed: &str) -> Vec<String> {
+    printed
+        .trim()
+        .trim_start_matches('(')
+        .trim_end_matches(')')
+        .split_whitespace()
+        .map(|s| s.to_string())
+        .collect()
+}
+
+/// Rule name and matched fact indices of the first activation in `(agenda)`
+/// output, e.g. `0      loop: f-1,f-2` or `10     check: f-3,*`
+///
+/// Returns `None` when the agenda is empty. Non-fact basis entries (`*` for
+/// negated patterns, instance names) are dropped.
+fn parse_agenda_top(printed: &str) -> Option<(String, Vec<u64>)> {
+    let line = printed.lines().map(str::trim).find(|l| !l.is_empty())?;
+    if line.starts_with("For a total of") {
+        return None;
+    }
+    let (_salience, rest) = line.split_once(char::is_whitespace)?;
+    let (rule, basis) = rest.trim().split_once(':')?;
+    let facts = basis
+        .split(',')
+        .filter_map(|entry| entry.trim().strip_prefix("f-")?.parse().ok())
+        .collect();
+    Some((rule.trim().to_string(), facts))
+}
+
+/// Number of activations listed in `(agenda)` output: one line per
+/// activation, followed by a `For a total of ...` summary
+fn count_agenda_activations(printed: &str) -> u64 {
+    printed
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with("For a total of"))
+        .count() as u64
+}
+
+/// Minimal s-expression tree used to walk defrule patterns.
+enum SExpr {
+    Atom(String),
+    List(Vec<SExpr>),
+}
+
+fn parse_sexprs(source: &str) -> Vec<SExpr> {
+    let mut stack: Vec<Vec<SExpr>> = vec![Vec::new()];
+    let mut chars = source.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            ';' => {
+                while let Some(&n) = chars.peek() {
+                    if n == '\n' {
+                        break;
+                    }
+                    chars.next();
+                }
+            }
+            '(' => stack.push(Vec::new()),
+            ')' => {
+                if stack.len() > 1 {
+                    let list = stack.pop().unwrap();
+                    stack.last_mut().unwrap().push(SExpr::List(list));
+                }
+            }
+            '"' => {
+                let mut text = String::from('"');
+                while let Some(n) = chars.next() {
+                    text.push(n);
+                    if n == '\\' {
+                        if let Some(escaped) = chars.next() {
+                            text.push(escaped);
+                        }
+                    } else if n == '"' {
+                        break;
+                    }
+                }
+                stack.last_mut().unwrap().push(SExpr::Atom(text));
+            }
+            c if c.is_whitespace() => {}
+            c => {
+                let mut atom = String::from(c);
+                while let Some(&n) = chars.peek() {
+                    if n.is_whitespace() || n == '(' || n == ')' || n == '"' || n == ';' {
+                        break;
+                    }
+                    atom.push(n);
+                    chars.next();
+                }
+                stack.last_mut().unwrap().push(SExpr::Atom(atom));
+            }
+        }
+    }
+
+    stack.into_iter().next().unwrap_or_default()
+}
+
+/// Collect template names used by the pattern CEs of a pretty-printed defrule.
+///
+/// Descends through `not`/`and`/`or`/`exists`/`forall`/`logical`; `test` and
+/// `object` CEs are ignored.
+pub fn rule_pattern_templates(defrule: &str) -> Vec<String> {
+    fn collect(ce: &SExpr, names: &mut Vec<String>) {
+        let SExpr::List(items) = ce else { return };
+        let Some(SExpr::Atom(head)) = items.first() else { return };
+        match head.as_str() {
+            "not" | "and" | "or" | "exists" | "forall" | "logical" => {
+                for child in &items[1..] {
+                    collect(child, names);
+                }
+            }
+            "test" | "object" => {}
+            name if !name.starts_with('"') && !names.iter().any(|n| n == name) => {
+                names.push(name.to_string());
+            }
+            _ => {}
+        }
+    }
+
+    let mut names = Vec::new();
+    for expr in parse_sexprs(defrule) {
+        let SExpr::List(items) = expr else { continue };
+        if !matches!(items.first(), Some(SExpr::Atom(h)) if h == "defrule") {
+            continue;
+        }
+        // Skip `defrule`, the rule name, and an optional comment string
+        let mut body = items.iter().skip(2).peekable();
+        if matches!(body.peek(), Some(SExpr::Atom(a)) if a.starts_with('"')) {
+            body.next();
+        }
+        for ce in body {
+            match ce {
+                SExpr::Atom(a) if a == "=>" => break,
+                SExpr::List(inner) if matches!(inner.first(), Some(SExpr::Atom(h)) if h == "declare") => {}
+                _ => collect(ce, &mut names),
+            }
+        }
+    }
+    names
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -414,6 +731,79 @@ mod tests {
         assert!(result.is_ok(), "Should reset environment successfully");
     }
 
+    #[test]
+    fn test_reset_keeps_templates_and_drops_facts() {
+        let mut env = ClipsEnvironment::new().expect("Failed to create environment");
+        env.build("(deftemplate person (slot name))").expect("Failed to build template");
+        env.eval("(assert (person (name alice)))").expect("Failed to assert fact");
+        assert!(env.eval("(facts)").unwrap().contains("alice"));
+
+        env.reset().expect("Failed to reset");
+
+        assert!(!env.eval("(facts)").unwrap().contains("alice"));
+        let templates = env.eval("(get-deftemplate-list)").unwrap();
+        assert!(templates.contains("person"), "template should survive reset: {}", templates);
+        let global = env.eval("?*coire-session-id*").unwrap();
+        assert!(global.contains(&env.session_id().to_string()));
+    }
+
+    #[test]
+    fn test_multislot_names() {
+        let mut env = ClipsEnvironment::new().expect("Failed to create environment");
+        env.build("(deftemplate order (slot id) (multislot items) (multislot tags))")
+            .expect("Failed to build template");
+        assert_eq!(env.multislot_names("order").unwrap(), vec!["items", "tags"]);
+
+        env.eval("(assert (point 1 2))").expect("Failed to assert ordered fact");
+        assert!(env.multislot_names("point").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_constructs_and_facts_rebuild_environment() {
+        let mut env = ClipsEnvironment::new().expect("Failed to create environment");
+        env.build("(deftemplate pet (slot name) (multislot tags))").unwrap();
+        env.build("(defrule greet (pet (name ?n)) => (assert (greeted ?n)))").unwrap();
+        env.eval("(assert (pet (name rex) (tags loud \"big dog\")))").unwrap();
+        env.eval("(assert (color red))").unwrap();
+
+        let constructs = env.constructs().unwrap();
+        assert!(constructs.iter().any(|c| c.starts_with("(deftemplate") && c.contains("pet")));
+        assert!(constructs.iter().any(|c| c.starts_with("(defrule") && c.contains("greet")));
+        assert!(!constructs.iter().any(|c| c.contains("deftemplate color")));
+        let facts = env.facts().unwrap();
+
+        // The Coire library constructs are already in a fresh environment
+        let mut copy = ClipsEnvironment::new().expect("Failed to create environment");
+        let library = copy.constructs().unwrap();
+        for construct in constructs.iter().filter(|c| !library.contains(c)) {
+            copy.build(construct).unwrap();
+        }
+        for fact in &facts {
+            copy.eval(&format!("(assert {})", fact)).unwrap();
+        }
+        assert_eq!(copy.facts().unwrap(), facts);
+        assert!(copy.eval("(agenda)").unwrap().contains("greet"));
+    }
+
+    #[test]
+    fn test_load_constructs_file() {
+        let dir = std::env::temp_dir().join(format!("clara-clips-load-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let good = dir.join("good.clp");
+        let bad = dir.join("bad.clp");
+        std::fs::write(&good, "(deftemplate visitor (slot name))\n(deffacts guests (visitor (name al)))\n").unwrap();
+        std::fs::write(&bad, "(defrule broken (a) =>\n").unwrap();
+
+        let mut env = ClipsEnvironment::new().expect("Failed to create environment");
+        env.load(&good.to_string_lossy()).expect("Failed to load constructs file");
+        env.reset().unwrap();
+        assert!(env.eval("(facts)").unwrap().contains("(visitor (name al))"));
+
+        assert!(env.load(&bad.to_string_lossy()).is_err(), "Bad construct should fail the load");
+        assert!(env.load(&dir.join("missing.clp").to_string_lossy()).is_err());
+        std::fs::remove_dir_all(dir).ok();
+    }
+
     #[test]
     fn test_clear() {
         let mut env = ClipsEnvironment::new().expect("Failed to create environment");
@@ -442,6 +832,81 @@ mod tests {
         assert!(constructs[1].starts_with("(deffunction"));
     }
 
+    #[test]
+    fn test_rule_pattern_templates() {
+        let rule = r#"
+(defrule classify "doc string"
+   (declare (salience 10))
+   ?p <- (person (name ?n))
+   (not (pet (owner ?n)))
+   (or (dog ?n) (cat ?n))
+   (test (> 1 0))
+   =>
+   (assert (lonely ?n)))
+"#;
+        assert_eq!(rule_pattern_templates(rule), vec!["person", "pet", "dog", "cat"]);
+    }
+
+    #[test]
+    fn test_parse_agenda_top() {
+        assert_eq!(
+            parse_agenda_top("0      loop: f-1,f-2\n0      other: f-3\nFor a total of 2 activations.\n"),
+            Some(("loop".to_string(), vec![1, 2]))
+        );
+        assert_eq!(
+            parse_agenda_top("-10    lonely: f-4,*\nFor a total of 1 activation.\n"),
+            Some(("lonely".to_string(), vec![4]))
+        );
+        assert_eq!(parse_agenda_top(""), None);
+    }
+
+    #[test]
+    fn test_count_agenda_activations() {
+        assert_eq!(
+            count_agenda_activations("0      loop: f-1,f-2\n0      other: f-3\nFor a total of 2 activations.\n"),
+            2
+        );
+        assert_eq!(count_agenda_activations("For a total of 0 activations.\n"), 0);
+        assert_eq!(count_agenda_activations(""), 0);
+    }
+
+    #[test]
+    fn test_run_detects_self_triggering_rule() {
+        let mut env = ClipsEnvironment::new().expect("Failed to create environment");
+        env.build("(defrule ping ?f <- (ping) => (retract ?f) (assert (ping)))").unwrap();
+        env.eval("(assert (ping))").unwrap();
+
+        match env.run_with_loop_detection(1000, 10).expect("Run should not fail") {
+            RunOutcome::LoopDetected { rules_fired, activation } => {
+                assert_eq!(rules_fired, (LOOP_REPEAT_LIMIT - 1) as u64);
+                assert!(activation.starts_with("ping"), "{}", activation);
+            }
+            other => panic!("Loop not detected: {:?}", other),
+        }
+
+        // A rule whose facts differ each time is left alone
+        env.clear().unwrap();
+        env.load_coire_library().unwrap();
+        env.build("(defrule count ?f <- (n ?x&:(< ?x 5)) => (retract ?f) (assert (n (+ ?x 1))))").unwrap();
+        env.eval("(assert (n 0))").unwrap();
+        assert_eq!(
+            env.run_with_loop_detection(-1, 10).unwrap(),
+            RunOutcome::Completed { rules_fired: 5 }
+        );
+    }
+
+    #[test]
+    fn test_undefined_rule_templates() {
+        let mut env = ClipsEnvironment::new().expect("Failed to create environment");
+        env.build("(deftemplate person (slot name))").unwrap();
+        // `pet` was never defined, so CLIPS quietly gives it an implied template
+        env.build("(defrule greet (person (name ?n)) (pet ?n) => (printout t ?n crlf))")
+            .unwrap();
+
+        let missing = env.undefined_rule_templates().expect("Validation should run");
+        assert_eq!(missing, vec!["pet".to_string()]);
+    }
+
     #[test]
     fn test_clara_evaluate_callback() {
         // Initialize the global ToolboxManager
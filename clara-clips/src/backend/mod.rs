@@ -3,4 +3,4 @@
 pub mod ffi;
 
 // Re-export FFI types for convenience
-pub use ffi::ClipsEnvironment;
+pub use ffi::{ClipsEnvironment, RunOutcome};
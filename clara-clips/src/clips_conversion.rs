@@ -0,0 +1,497 @@
+//! Canonical mapping between JSON values and CLIPS source text.
+//!
+//! | JSON                        | CLIPS                                        |
+//! |-----------------------------|----------------------------------------------|
+//! | `null`                      | symbol `nil`                                 |
+//! | `true` / `false`            | symbols `TRUE` / `FALSE`                     |
+//! | integer number              | INTEGER (`42`)                               |
+//! | other number                | FLOAT, always with a `.` or exponent (`1.0`) |
+//! | string                      | STRING (`"text"`, `\` and `"` escaped)       |
+//! | array of scalars            | multifield, printed `(a b c)`                |
+//! | object with `"template"`    | deftemplate fact `(person (name "Al"))`      |
+//!
+//! Going back, any other bare symbol (and fact/instance addresses) becomes a
+//! JSON string. Multifields can't nest in CLIPS, so arrays of arrays and
+//! objects inside slots are rejected.
+//!
+//! Facts are objects whose `"template"` key names the deftemplate and whose
+//! other keys are slots. Ordered facts use a `"values"` array instead:
+//! `{"template": "point", "values": [1, 2]}` ↔ `(point 1 2)`. A multislot
+//! holding exactly one value reads back as a scalar, since the printed fact
+//! doesn't distinguish it from a single-field slot; pass the template's
+//! multislot names to [`clips_fact_to_json_with_multislots`] to always get
+//! an array.
+
+use serde_json::{Map, Number, Value};
+
+/// Convert a JSON value to its CLIPS source representation.
+pub fn json_to_clips_value(value: &Value) -> Result<String, String> {
+    match value {
+        Value::Array(items) => Ok(format!("({})", multifield_fields(items)?)),
+        Value::Object(_) => json_to_clips_fact(value),
+        scalar => scalar_to_clips(scalar),
+    }
+}
+
+/// Convert a printed CLIPS value (as returned by `eval`) to JSON.
+///
+/// A parenthesised value is read as a multifield, or as a fact when its
+/// first field is a symbol followed by slot lists.
+pub fn clips_value_to_json(text: &str) -> Result<Value, String> {
+    let mut exprs = parse(text)?;
+    if exprs.len() != 1 {
+        return Err(format!("Expected a single CLIPS value, got: {}", text.trim()));
+    }
+    match exprs.remove(0) {
+        SExpr::List(items) if is_template_fact(&items) => list_to_fact(items, &[]),
+        SExpr::List(items) => items.into_iter().map(scalar_to_json).collect::<Result<_, _>>().map(Value::Array),
+        atom => scalar_to_json(atom),
+    }
+}
+
+/// Build an `(assert ...)`-ready fact from a JSON object with a `"template"` key.
+pub fn json_to_clips_fact(value: &Value) -> Result<String, String> {
+    let obj = value
+        .as_object()
+        .ok_or_else(|| format!("Fact must be a JSON object, got: {}", value))?;
+    let template = obj
+        .get("template")
+        .and_then(Value::as_str)
+        .ok_or_else(|| "Fact object requires a string \"template\" key".to_string())?;
+    if !is_symbol(template) {
+        return Err(format!("Invalid template name: {}", template));
+    }
+
+    // Ordered fact: {"template": "point", "values": [1, 2]}
+    if obj.len() == 2 {
+        if let Some(Value::Array(values)) = obj.get("values") {
+            if values.is_empty() {
+                return Ok(format!("({})", template));
+            }
+            return Ok(format!("({} {})", template, multifield_fields(values)?));
+        }
+    }
+
+    let slots = slot_list(obj.iter().filter(|(k, _)| k.as_str() != "template"))?;
+    Ok(format!("({}{})", template, slots))
+}
+
+/// Build the slot list for `(modify <fact> ...)` from a JSON object of
+/// slot values, e.g. `{"age": 43}` → `(age 43)`.
+pub fn json_to_clips_slots(slots: &Map<String, Value>) -> Result<String, String> {
+    Ok(slot_list(slots.iter())?.trim_start().to_string())
+}
+
+/// Parse a printed fact such as `(person (name "Al") (age 42))` into JSON.
+pub fn clips_fact_to_json(text: &str) -> Result<Value, String> {
+    clips_fact_to_json_with_multislots(text, &[])
+}
+
+/// Like [`clips_fact_to_json`], but slots named in `multislots` always read
+/// back as arrays, even when they hold zero or one value.
+pub fn clips_fact_to_json_with_multislots(text: &str, multislots: &[String]) -> Result<Value, String> {
+    let mut exprs = parse(text)?;
+    match (exprs.len(), exprs.pop()) {
+        (1, Some(SExpr::List(items))) => list_to_fact(items, multislots),
+        _ => Err(format!("Expected a single CLIPS fact, got: {}", text.trim())),
+    }
+}
+
+/// Template name of a printed fact, e.g. `person` for `(person (name "Al"))`.
+pub fn clips_fact_template(text: &str) -> Option<&str> {
+    let rest = text.trim().strip_prefix('(')?;
+    let end = rest.find(|c: char| c.is_whitespace() || c == '(' || c == ')').unwrap_or(rest.len());
+    Some(&rest[..end]).filter(|name| !name.is_empty())
+}
+
+/// Split captured `eval` output at the first CLIPS error message.
+///
+/// CLIPS prefixes errors with an ID such as `[EXPRNPSR3]`; everything from
+/// the first such line on is returned as the error text, the rest as
+/// ordinary output. `[ID] WARNING:` lines are not treated as errors.
+pub fn split_clips_error(output: &str) -> (&str, Option<&str>) {
+    let mut offset = 0;
+    for line in output.split_inclusive('\n') {
+        if is_error_line(line) {
+            return (&output[..offset], Some(output[offset..].trim()));
+        }
+        offset += line.len();
+    }
+    (output, None)
+}
+
+/// The `[ID] WARNING:` lines in captured `eval` output, trimmed
+pub fn clips_warnings(output: &str) -> Vec<&str> {
+    output
+        .lines()
+        .filter(|line| message_after_id(line).is_some_and(|m| m.trim_start().starts_with("WARNING")))
+        .map(str::trim)
+        .collect()
+}
+
+fn is_error_line(line: &str) -> bool {
+    message_after_id(line).is_some_and(|message| !message.trim_start().starts_with("WARNING"))
+}
+
+/// The text after a leading CLIPS message ID such as `[EXPRNPSR3]`
+fn message_after_id(line: &str) -> Option<&str> {
+    let rest = line.trim_start().strip_prefix('[')?;
+    let (id, message) = rest.split_once(']')?;
+    let id_ok = id.chars().next().is_some_and(|c| c.is_ascii_uppercase())
+        && id.chars().last().is_some_and(|c| c.is_ascii_digit())
+        && id.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit());
+    Some(message).filter(|_| id_ok)
+}
+
+/// Start of the error `ClipsEnvironment::build` returns when CLIPS rejects
+/// a construct; the CLIPS `BuildError` code follows as `(code N)`
+pub const BUILD_FAILED_PREFIX: &str = "CLIPS Build failed";
+
+/// Start of the error for a construct that can't be passed to CLIPS at all
+pub const INVALID_CONSTRUCT_PREFIX: &str = "Invalid construct string";
+
+/// Whether a CLIPS failure was caused by its input or by the engine
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClipsErrorKind {
+    /// The input was rejected: it didn't parse, wasn't a known construct, or
+    /// referred to something undefined
+    Syntax,
+    /// Anything else; the input may well be fine
+    Runtime,
+}
+
+/// Classify an error returned by a `ClipsEnvironment` method.
+///
+/// Build codes 2 (construct not found) and 3 (parsing error, which also
+/// covers semantic checks such as calls to undefined functions) are
+/// `Syntax`, as is a construct holding a NUL byte. Build code 1 (could not
+/// build, e.g. mid-`run`) and every other error are `Runtime`.
+pub fn classify_clips_error(error: &str) -> ClipsErrorKind {
+    if error.starts_with(INVALID_CONSTRUCT_PREFIX) {
+        return ClipsErrorKind::Syntax;
+    }
+    let code = error
+        .strip_prefix(BUILD_FAILED_PREFIX)
+        .and_then(|rest| rest.strip_prefix(" (code "))
+        .and_then(|rest| rest.split_once(')'))
+        .and_then(|(code, _)| code.parse::<i32>().ok());
+    match code {
+        Some(2) | Some(3) => ClipsErrorKind::Syntax,
+        _ => ClipsErrorKind::Runtime,
+    }
+}
+
+// ── Writing ──────────────────────────────────────────────────────────────────
+
+fn scalar_to_clips(value: &Value) -> Result<String, String> {
+    match value {
+        Value::Null => Ok("nil".to_string()),
+        Value::Bool(true) => Ok("TRUE".to_string()),
+        Value::Bool(false) => Ok("FALSE".to_string()),
+        Value::Number(n) if n.is_i64() || n.is_u64() => Ok(n.to_string()),
+        Value::Number(n) => {
+            let f = n.as_f64().ok_or_else(|| format!("Unrepresentable number: {}", n))?;
+            let mut text = f.to_string();
+            if !text.contains(['.', 'e', 'E']) {
+                text.push_str(".0");
+            }
+            Ok(text)
+        }
+        Value::String(s) => Ok(format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))),
+        Value::Array(_) | Value::Object(_) => Err(format!("Expected a scalar value, got: {}", value)),
+    }
+}
+
+/// ` (slot value)` for each slot, each with a leading space
+fn slot_list<'a>(slots: impl Iterator<Item = (&'a String, &'a Value)>) -> Result<String, String> {
+    let mut out = String::new();
+    for (slot, slot_value) in slots {
+        if !is_symbol(slot) {
+            return Err(format!("Invalid slot name: {}", slot));
+        }
+        let fields = match slot_value {
+            Value::Array(items) => multifield_fields(items)?,
+            Value::Object(_) => {
+                return Err(format!("Slot '{}' holds a nested object; CLIPS slots can't hold facts", slot))
+            }
+            scalar => scalar_to_clips(scalar)?,
+        };
+        if fields.is_empty() {
+            out.push_str(&format!(" ({})", slot));
+        } else {
+            out.push_str(&format!(" ({} {})", slot, fields));
+        }
+    }
+    Ok(out)
+}
+
+fn multifield_fields(items: &[Value]) -> Result<String, String> {
+    let fields = items
+        .iter()
+        .map(|item| match item {
+            Value::Array(_) | Value::Object(_) => {
+                Err(format!("Multifields can't nest; unsupported element: {}", item))
+            }
+            scalar => scalar_to_clips(scalar),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(fields.join(" "))
+}
+
+fn is_symbol(name: &str) -> bool {
+    !name.is_empty()
+        && !name.contains(|c: char| c.is_whitespace() || "()\"&|<~;?$".contains(c))
+        && !looks_numeric(name)
+}
+
+// ── Reading ──────────────────────────────────────────────────────────────────
+
+enum SExpr {
+    Str(String),
+    Atom(String),
+    List(Vec<SExpr>),
+}
+
+fn parse(text: &str) -> Result<Vec<SExpr>, String> {
+    let mut stack: Vec<Vec<SExpr>> = vec![Vec::new()];
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '(' => stack.push(Vec::new()),
+            ')' => {
+                if stack.len() < 2 {
+                    return Err(format!("Unbalanced ')' in: {}", text.trim()));
+                }
+                let list = stack.pop().unwrap();
+                stack.last_mut().unwrap().push(SExpr::List(list));
+            }
+            '"' => {
+                let mut s = String::new();
+                loop {
+                    match chars.next() {
+                        Some('\\') => s.extend(chars.next()),
+                        Some('"') => break,
+                        Some(ch) => s.push(ch),
+                        None => return Err(format!("Unterminated string in: {}", text.trim())),
+                    }
+                }
+                stack.last_mut().unwrap().push(SExpr::Str(s));
+            }
+            c if c.is_whitespace() => {}
+            c => {
+                let mut atom = String::from(c);
+                while let Some(&n) = chars.peek() {
+                    if n.is_whitespace() || n == '(' || n == ')' || n == '"' {
+                        break;
+                    }
+                    atom.push(n);
+                    chars.next();
+                }
+                stack.last_mut().unwrap().push(SExpr::Atom(atom));
+            }
+        }
+    }
+
+    if stack.len() != 1 {
+        return Err(format!("Unbalanced '(' in: {}", text.trim()));
+    }
+    Ok(stack.pop().unwrap())
+}
+
+fn looks_numeric(token: &str) -> bool {
+    let digits = token.trim_start_matches(['+', '-']);
+    let digits = digits.strip_prefix('.').unwrap_or(digits);
+    digits.starts_with(|c: char| c.is_ascii_digit())
+}
+
+fn scalar_to_json(expr: SExpr) -> Result<Value, String> {
+    match expr {
+        SExpr::Str(s) => Ok(Value::String(s)),
+        SExpr::Atom(atom) => Ok(match atom.as_str() {
+            "nil" => Value::Null,
+            "TRUE" => Value::Bool(true),
+            "FALSE" => Value::Bool(false),
+            _ if looks_numeric(&atom) => {
+                if let Ok(i) = atom.parse::<i64>() {
+                    Value::Number(i.into())
+                } else if let Some(n) = atom.parse::<f64>().ok().and_then(Number::from_f64) {
+                    Value::Number(n)
+                } else {
+                    Value::String(atom)
+                }
+            }
+            _ => Value::String(atom),
+        }),
+        SExpr::List(_) => Err("Multifields can't nest".to_string()),
+    }
+}
+
+/// `(name (slot ...) ...)` — a template fact rather than a plain multifield
+fn is_template_fact(items: &[SExpr]) -> bool {
+    matches!(items.first(), Some(SExpr::Atom(a)) if !looks_numeric(a))
+        && items.len() > 1
+        && items[1..].iter().all(|i| matches!(i, SExpr::List(slot) if matches!(slot.first(), Some(SExpr::Atom(_)))))
+}
+
+fn list_to_fact(items: Vec<SExpr>, multislots: &[String]) -> Result<Value, String> {
+    let mut items = items.into_iter();
+    let template = match items.next() {
+        Some(SExpr::Atom(name)) => name,
+        _ => return Err("Fact must start with a template name".to_string()),
+    };
+
+    let rest: Vec<SExpr> = items.collect();
+    let mut fact = Map::new();
+    fact.insert("template".to_string(), Value::String(template));
+
+    if !rest.is_empty() && !rest.iter().all(|i| matches!(i, SExpr::List(_))) {
+        // Ordered fact
+        let values = rest.into_iter().map(scalar_to_json).collect::<Result<_, _>>()?;
+        fact.insert("values".to_string(), Value::Array(values));
+        return Ok(Value::Object(fact));
+    }
+
+    for slot in rest {
+        let SExpr::List(parts) = slot else { unreachable!() };
+        let mut parts = parts.into_iter();
+        let name = match parts.next() {
+            Some(SExpr::Atom(name)) => name,
+            _ => return Err("Slot must start with a name".to_string()),
+        };
+        let mut values = parts.map(scalar_to_json).collect::<Result<Vec<_>, _>>()?;
+        let value = if values.len() == 1 && !multislots.contains(&name) {
+            values.remove(0)
+        } else {
+            Value::Array(values)
+        };
+        fact.insert(name, value);
+    }
+    Ok(Value::Object(fact))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn round_trip(value: Value) {
+        let clips = json_to_clips_value(&value).unwrap();
+        assert_eq!(clips_value_to_json(&clips).unwrap(), value, "via CLIPS text: {}", clips);
+    }
+
+    #[test]
+    fn test_scalar_mappings() {
+        assert_eq!(json_to_clips_value(&json!(null)).unwrap(), "nil");
+        assert_eq!(json_to_clips_value(&json!(true)).unwrap(), "TRUE");
+        assert_eq!(json_to_clips_value(&json!(false)).unwrap(), "FALSE");
+        assert_eq!(json_to_clips_value(&json!(42)).unwrap(), "42");
+        assert_eq!(json_to_clips_value(&json!(2.0)).unwrap(), "2.0");
+        assert_eq!(json_to_clips_value(&json!(-0.5)).unwrap(), "-0.5");
+        assert_eq!(json_to_clips_value(&json!("say \"hi\"")).unwrap(), r#""say \"hi\"""#);
+        assert_eq!(json_to_clips_value(&json!([1, "a", null])).unwrap(), r#"(1 "a" nil)"#);
+    }
+
+    #[test]
+    fn test_round_trip_each_json_type() {
+        round_trip(json!(null));
+        round_trip(json!(true));
+        round_trip(json!(false));
+        round_trip(json!(7));
+        round_trip(json!(-12));
+        round_trip(json!(3.25));
+        round_trip(json!(2.0));
+        round_trip(json!("plain"));
+        round_trip(json!("TRUE"));
+        round_trip(json!("back\\slash and \"quotes\""));
+        round_trip(json!([]));
+        round_trip(json!([1, 2.5, "x", false, null]));
+        round_trip(json!({"template": "person", "name": "Al", "age": 42, "tags": ["a", "b"]}));
+    }
+
+    #[test]
+    fn test_symbols_read_as_strings() {
+        assert_eq!(clips_value_to_json("red").unwrap(), json!("red"));
+        assert_eq!(clips_value_to_json("(red green)").unwrap(), json!(["red", "green"]));
+        assert_eq!(clips_value_to_json("<Fact-3>").unwrap(), json!("<Fact-3>"));
+    }
+
+    #[test]
+    fn test_fact_conversion() {
+        let fact = json!({"template": "person", "name": "Al", "alive": true, "pets": []});
+        let clips = json_to_clips_fact(&fact).unwrap();
+        assert!(clips.starts_with("(person "));
+        assert!(clips.contains(r#"(name "Al")"#));
+        assert!(clips.contains("(alive TRUE)"));
+        assert!(clips.contains("(pets)"));
+        assert_eq!(clips_fact_to_json(&clips).unwrap(), fact);
+
+        // Ordered facts
+        let ordered = json!({"template": "point", "values": [1, 2]});
+        assert_eq!(json_to_clips_fact(&ordered).unwrap(), "(point 1 2)");
+        assert_eq!(clips_fact_to_json("(point 1 2)").unwrap(), ordered);
+
+        // Slot lists for (modify ...)
+        let slots = json!({"age": 43, "tags": ["a", "b"]});
+        let slots = json_to_clips_slots(slots.as_object().unwrap()).unwrap();
+        assert_eq!(slots, r#"(age 43) (tags "a" "b")"#);
+    }
+
+    #[test]
+    fn test_multislots_read_as_arrays() {
+        let fact = r#"(order (id 7) (items "widget") (tags))"#;
+        assert_eq!(clips_fact_template(fact), Some("order"));
+
+        // Without slot info a one-value multislot is indistinguishable from a slot
+        assert_eq!(clips_fact_to_json(fact).unwrap()["items"], json!("widget"));
+
+        let multislots = vec!["items".to_string(), "tags".to_string()];
+        let value = clips_fact_to_json_with_multislots(fact, &multislots).unwrap();
+        assert_eq!(value, json!({"template": "order", "id": 7, "items": ["widget"], "tags": []}));
+    }
+
+    #[test]
+    fn test_unsupported_values_rejected() {
+        assert!(json_to_clips_value(&json!([[1]])).is_err());
+        assert!(json_to_clips_fact(&json!({"name": "no template"})).is_err());
+        assert!(json_to_clips_fact(&json!({"template": "p", "inner": {"a": 1}})).is_err());
+        assert!(json_to_clips_fact(&json!({"template": "bad name"})).is_err());
+        assert!(clips_value_to_json("(unterminated").is_err());
+    }
+
+    #[test]
+    fn test_split_clips_error() {
+        assert_eq!(split_clips_error("42"), ("42", None));
+
+        let output = "partial\n[EXPRNPSR3] Missing function declaration for foo.\n";
+        assert_eq!(
+            split_clips_error(output),
+            ("partial\n", Some("[EXPRNPSR3] Missing function declaration for foo."))
+        );
+
+        let warning = "[CSTRCPSR1] WARNING: Redefining defrule: r1 +j+\n";
+        assert_eq!(split_clips_error(warning), (warning, None));
+        assert_eq!(split_clips_error("[not an id] text"), ("[not an id] text", None));
+    }
+
+    #[test]
+    fn test_clips_warnings() {
+        let output = "TRUE\n[CSTRCPSR1] WARNING: Redefining defrule: r1 +j+\n[EXPRNPSR3] Missing function.\n";
+        assert_eq!(clips_warnings(output), vec!["[CSTRCPSR1] WARNING: Redefining defrule: r1 +j+"]);
+        assert!(clips_warnings("42").is_empty());
+    }
+
+    #[test]
+    fn test_classify_clips_error() {
+        let parse = format!("{} (code 3) for: (defrule broken (a) =>", BUILD_FAILED_PREFIX);
+        assert_eq!(classify_clips_error(&parse), ClipsErrorKind::Syntax);
+        let unknown = format!("{} (code 2) for: (not-a-construct)", BUILD_FAILED_PREFIX);
+        assert_eq!(classify_clips_error(&unknown), ClipsErrorKind::Syntax);
+        let nul = format!("{}: nul byte found", INVALID_CONSTRUCT_PREFIX);
+        assert_eq!(classify_clips_error(&nul), ClipsErrorKind::Syntax);
+
+        let busy = format!("{} (code 1) for: (defrule r (a) =>)", BUILD_FAILED_PREFIX);
+        assert_eq!(classify_clips_error(&busy), ClipsErrorKind::Runtime);
+        assert_eq!(classify_clips_error("Failed to create CLIPS environment"), ClipsErrorKind::Runtime);
+    }
+}
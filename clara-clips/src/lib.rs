@@ -1,10 +1,11 @@
 // Clara-CLIPS: CLIPS integration library
 
 pub mod backend;
+pub mod clips_conversion;
 
 // Re-export commonly used types
 pub use backend::ffi;
-pub use backend::ClipsEnvironment;
+pub use backend::{ClipsEnvironment, RunOutcome};
 
 // Force-link coire FFI symbols so the C linker can find them.
 // Without these re-exports, the linker strips the #[no_mangle] symbols
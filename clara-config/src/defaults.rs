@@ -1,5 +1,34 @@
 use crate::schema::*;
 
+/// Defaults suited to `env`, for values no config file or environment
+/// variable sets
+///
+/// `development` (or `dev`) gets debug logging, binds to localhost only and
+/// leaves the CLIPS function allow-list off. Every other environment is
+/// treated as production: `info` logging, all interfaces, allow-list mode on
+/// and encrypted persistence. `env.debug` turns on debug logging anywhere.
+pub fn for_environment(env: &ConfigEnvironment) -> AppConfig {
+    let mut config = crate::ConfigLoader::default_config();
+    let development = matches!(env.env_name.as_str(), "development" | "dev");
+
+    if development {
+        config.server.host = "127.0.0.1".to_string();
+        config.observability.log_level = "debug".to_string();
+        config.security.allow_list_mode = false;
+        config.persistence.encryption = false;
+    } else {
+        config.server.host = "0.0.0.0".to_string();
+        config.observability.log_level = "info".to_string();
+        config.security.allow_list_mode = true;
+        config.persistence.encryption = true;
+    }
+    if env.debug {
+        config.observability.log_level = "debug".to_string();
+    }
+
+    config
+}
+
 pub fn default_server_config() -> ServerConfig {
     ServerConfig {
         host: "0.0.0.0".to_string(),
@@ -17,6 +46,8 @@ pub fn default_clips_config() -> ClipsConfig {
         handshake_timeout_ms: 5000,
         default_eval_timeout_ms: 2000,
         sentinel_marker: "__END__".to_string(),
+        max_processes: 100,
+        loop_detection_window: crate::schema::default_loop_detection_window(),
     }
 }
 
@@ -26,6 +57,9 @@ pub fn default_sessions_config() -> SessionsConfig {
         max_per_user: 10,
         eviction_policy: "lru".to_string(),
         default_ttl_seconds: 3600,
+        idle_timeout_seconds: None,
+        default_session_type: crate::schema::default_session_type(),
+        tombstone_retention_seconds: crate::schema::default_tombstone_retention_seconds(),
     }
 }
 
@@ -35,6 +69,10 @@ pub fn default_resources_config() -> ResourcesConfig {
         max_rules_per_session: 500,
         max_memory_mb: 128,
         max_eval_queue_depth: 10,
+        max_concurrent_evals: crate::schema::default_max_concurrent_evals(),
+        prolog_query_timeout_ms: crate::schema::default_prolog_query_timeout_ms(),
+        prolog_engine_acquire_attempts: crate::schema::default_prolog_engine_acquire_attempts(),
+        retry_after_seconds: crate::schema::default_retry_after_seconds(),
     }
 }
 
@@ -49,6 +87,15 @@ pub fn default_security_config() -> SecurityConfig {
         ],
         allow_list_mode: false,
         allowed_file_paths: vec!["./clips/rules".to_string()],
+        prolog_allowed_directives: crate::schema::default_prolog_allowed_directives(),
+        prolog_allowed_modules: crate::schema::default_prolog_allowed_modules(),
+        max_clips_input_bytes: crate::schema::default_max_input_bytes(),
+        max_prolog_input_bytes: crate::schema::default_max_input_bytes(),
+        max_prolog_clauses: crate::schema::default_max_prolog_clauses(),
+        audit_log_path: None,
+        prolog_consult_root: None,
+        clips_load_root: None,
+        max_tool_response_bytes: crate::schema::default_max_tool_response_bytes(),
     }
 }
 
@@ -74,6 +121,7 @@ pub fn default_observability_config() -> ObservabilityConfig {
         metrics_port: 9090,
         tracing_enabled: true,
         tracing_endpoint: "http://localhost:4317".to_string(),
+        slow_query_ms: 1000,
     }
 }
 
@@ -83,3 +131,11 @@ pub fn default_auth_config() -> AuthConfig {
         token_expiry_seconds: 3600,
     }
 }
+
+pub fn default_prewarm_config() -> PrewarmConfig {
+    PrewarmConfig {
+        prolog: 0,
+        clips: 0,
+        health_check_interval_seconds: 60,
+    }
+}
@@ -1,4 +1,4 @@
-use crate::schema::AppConfig;
+use crate::schema::{AppConfig, ConfigEnvironment};
 use std::env;
 use std::path::Path;
 use thiserror::Error;
@@ -41,61 +41,66 @@ impl ConfigLoader {
         Ok(config)
     }
 
-    /// Load configuration from environment-specific file
+    /// Load configuration for an environment from the `config` directory
+    ///
+    /// Starts from [`defaults::for_environment`](crate::defaults::for_environment)
+    /// and applies `config/default.toml`, then `config/<env>.toml` if it
+    /// exists, on top. Each file overrides only the fields it sets.
     pub fn from_env(env_name: Option<&str>) -> Result<AppConfig, ConfigError> {
-        let env_name = if let Some(name) = env_name {
-            name.to_string()
-        } else {
-            env::var("ENVIRONMENT").unwrap_or_else(|_| "development".to_string())
-        };
+        Self::from_env_in("config", env_name)
+    }
 
-        // Try environment-specific config first, fall back to default
-        let config_path = format!("config/{}.toml", env_name);
-        let default_path = "config/default.toml";
+    /// Like [`from_env`](Self::from_env), reading the files from `dir`
+    pub fn from_env_in<P: AsRef<Path>>(dir: P, env_name: Option<&str>) -> Result<AppConfig, ConfigError> {
+        let dir = dir.as_ref();
+        let mut environment = ConfigEnvironment::default();
+        if let Some(name) = env_name {
+            environment.env_name = name.to_string();
+        }
 
-        let mut config = Self::from_file(default_path)?;
+        let defaults = toml::Value::try_from(crate::defaults::for_environment(&environment))
+            .map_err(|e| ConfigError::TomlParse(e.to_string()))?;
+        let toml::Value::Table(mut merged) = defaults else {
+            return Err(ConfigError::TomlParse("defaults are not a table".to_string()));
+        };
 
-        // Overlay environment-specific config if it exists and is not default
-        if env_name != "development" && Path::new(&config_path).exists() {
-            let env_config = Self::from_file(&config_path)?;
-            config = Self::merge_configs(config, env_config);
+        // Environment-specific values override default.toml
+        Self::merge_tables(&mut merged, Self::read_table(&dir.join("default.toml"))?);
+        let env_path = dir.join(format!("{}.toml", environment.env_name));
+        if env_path.exists() {
+            Self::merge_tables(&mut merged, Self::read_table(&env_path)?);
         }
 
+        let mut config: AppConfig = toml::Value::Table(merged)
+            .try_into()
+            .map_err(|e: toml::de::Error| ConfigError::TomlParse(e.to_string()))?;
+        Self::interpolate_env_vars(&mut config)?;
+        config.validate().map_err(ConfigError::Validation)?;
+
         Ok(config)
     }
 
-    /// Merge two configurations, with the second overriding the first
-    fn merge_configs(mut base: AppConfig, overlay: AppConfig) -> AppConfig {
-        // For MVP, simple replacement of sections that are explicitly set
-        // A more sophisticated merge would preserve unset fields
+    /// Read a TOML file as a table, which may set any subset of the fields
+    fn read_table(path: &Path) -> Result<toml::Table, ConfigError> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            ConfigError::NotFound(format!("{}: {}", path.display(), e))
+        })?;
+        content.parse().map_err(|e: toml::de::Error| ConfigError::TomlParse(e.to_string()))
+    }
 
-        // Only override if overlay values differ from defaults
-        if overlay.server.host != "0.0.0.0" || overlay.server.port != 8080 {
-            base.server = overlay.server;
-        }
-        if !overlay.clips.binary_path.is_empty() {
-            base.clips = overlay.clips;
-        }
-        if overlay.sessions.max_concurrent != 100 {
-            base.sessions = overlay.sessions;
-        }
-        if overlay.resources.max_facts_per_session != 1000 {
-            base.resources = overlay.resources;
-        }
-        if !overlay.security.deny_list.is_empty() {
-            base.security = overlay.security;
-        }
-        if overlay.persistence.enabled {
-            base.persistence = overlay.persistence;
-        }
-        if overlay.observability.log_level != "info" {
-            base.observability = overlay.observability;
-        }
-        if overlay.auth.jwt_secret != "${JWT_SECRET}" {
-            base.auth = overlay.auth;
+    /// Merge `overlay` into `base`, recursing into tables so only the
+    /// fields `overlay` sets are replaced
+    fn merge_tables(base: &mut toml::Table, overlay: toml::Table) {
+        for (key, value) in overlay {
+            match (base.get_mut(&key), value) {
+                (Some(toml::Value::Table(base_table)), toml::Value::Table(overlay_table)) => {
+                    Self::merge_tables(base_table, overlay_table);
+                }
+                (_, value) => {
+                    base.insert(key, value);
+                }
+            }
         }
-
-        base
     }
 
     /// Interpolate environment variables in configuration values (${VAR_NAME} syntax)
@@ -141,6 +146,7 @@ impl ConfigLoader {
             persistence: crate::defaults::default_persistence_config(),
             observability: crate::defaults::default_observability_config(),
             auth: crate::defaults::default_auth_config(),
+            prewarm: crate::defaults::default_prewarm_config(),
         }
     }
 }
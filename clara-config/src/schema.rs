@@ -11,6 +11,8 @@ pub struct AppConfig {
     pub persistence: PersistenceConfig,
     pub observability: ObservabilityConfig,
     pub auth: AuthConfig,
+    #[serde(default)]
+    pub prewarm: PrewarmConfig,
 }
 
 /// Server configuration
@@ -43,8 +45,19 @@ pub struct ClipsConfig {
     pub handshake_timeout_ms: u64,
     pub default_eval_timeout_ms: u64,
     pub sentinel_marker: String,
+    /// Maximum concurrent per-session subprocess handlers
+    #[serde(default = "default_max_processes")]
+    pub max_processes: usize,
+    /// Recent rule firings remembered when checking `run` for rule loops;
+    /// 0 disables loop detection
+    #[serde(default = "default_loop_detection_window")]
+    pub loop_detection_window: usize,
 }
 
+fn default_max_processes() -> usize { 100 }
+
+pub(crate) fn default_loop_detection_window() -> usize { 100 }
+
 /// Session management configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionsConfig {
@@ -52,23 +65,125 @@ pub struct SessionsConfig {
     pub max_per_user: usize,
     pub eviction_policy: String,
     pub default_ttl_seconds: u64,
+    /// Terminate sessions idle for longer than this many seconds, checked
+    /// once a minute whatever the eviction policy. Unset disables it.
+    #[serde(default)]
+    pub idle_timeout_seconds: Option<u64>,
+    /// Engine for sessions created without an explicit type: "clips" or "prolog"
+    #[serde(default = "default_session_type")]
+    pub default_session_type: String,
+    /// How long a terminated session's record is kept, so lookups report it
+    /// terminated rather than not found; purged once a minute
+    #[serde(default = "default_tombstone_retention_seconds")]
+    pub tombstone_retention_seconds: u64,
 }
 
+pub(crate) fn default_session_type() -> String { "clips".to_string() }
+
+pub(crate) fn default_tombstone_retention_seconds() -> u64 { 300 }
+
 /// Resource limits per session
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResourcesConfig {
     pub max_facts_per_session: u32,
     pub max_rules_per_session: u32,
     pub max_memory_mb: u32,
+    /// Evaluations that may wait for a slot once `max_concurrent_evals` are
+    /// running; more are rejected with 429
     pub max_eval_queue_depth: u32,
+    /// Evaluations run at once across all sessions
+    #[serde(default = "default_max_concurrent_evals")]
+    pub max_concurrent_evals: usize,
+    /// Time limit for a Prolog query unless the request sets its own
+    /// `timeout_ms`; 0 means no limit
+    #[serde(default = "default_prolog_query_timeout_ms")]
+    pub prolog_query_timeout_ms: u64,
+    /// Times a Prolog engine busy in another thread is tried, with backoff,
+    /// before the request fails
+    #[serde(default = "default_prolog_engine_acquire_attempts")]
+    pub prolog_engine_acquire_attempts: u32,
+    /// `Retry-After` sent with 429s for a full eval queue and 503s for an
+    /// unavailable engine
+    #[serde(default = "default_retry_after_seconds")]
+    pub retry_after_seconds: u64,
 }
 
+pub(crate) fn default_max_concurrent_evals() -> usize { 8 }
+
+pub(crate) fn default_prolog_query_timeout_ms() -> u64 { 30000 }
+
+pub(crate) fn default_prolog_engine_acquire_attempts() -> u32 { 10 }
+
+pub(crate) fn default_retry_after_seconds() -> u64 { 1 }
+
 /// Security configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SecurityConfig {
     pub deny_list: Vec<String>,
     pub allow_list_mode: bool,
     pub allowed_file_paths: Vec<String>,
+    /// Directive functors (`:- Goal`) that Prolog consult may execute.
+    /// Any other directive is rejected with a security violation.
+    #[serde(default = "default_prolog_allowed_directives")]
+    pub prolog_allowed_directives: Vec<String>,
+    /// Modules that a consulted `use_module` directive may load,
+    /// e.g. `"library(lists)"`.
+    #[serde(default = "default_prolog_allowed_modules")]
+    pub prolog_allowed_modules: Vec<String>,
+    /// Largest CLIPS script, rule or fact a request may carry, in bytes
+    #[serde(default = "default_max_input_bytes")]
+    pub max_clips_input_bytes: usize,
+    /// Largest Prolog goal or clause a request may carry, in bytes
+    #[serde(default = "default_max_input_bytes")]
+    pub max_prolog_input_bytes: usize,
+    /// Most clauses a single Prolog consult may load
+    #[serde(default = "default_max_prolog_clauses")]
+    pub max_prolog_clauses: usize,
+    /// JSONL file that security violations are appended to; stdout if unset
+    #[serde(default)]
+    pub audit_log_path: Option<String>,
+    /// Directory Prolog files may be consulted from by path; file consults
+    /// are refused if unset
+    #[serde(default)]
+    pub prolog_consult_root: Option<String>,
+    /// Directory CLIPS files may be loaded from by path; file loads are
+    /// refused if unset
+    #[serde(default)]
+    pub clips_load_root: Option<String>,
+    /// Largest serialized tool response `clara_evaluate` hands back to an
+    /// engine, in bytes; larger ones are replaced by an error
+    #[serde(default = "default_max_tool_response_bytes")]
+    pub max_tool_response_bytes: usize,
+}
+
+pub(crate) fn default_max_input_bytes() -> usize { 64 * 1024 }
+
+pub(crate) fn default_max_prolog_clauses() -> usize { 1000 }
+
+pub(crate) fn default_max_tool_response_bytes() -> usize { 1024 * 1024 }
+
+pub(crate) fn default_prolog_allowed_directives() -> Vec<String> {
+    ["dynamic", "discontiguous", "table", "use_module"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+
+pub(crate) fn default_prolog_allowed_modules() -> Vec<String> {
+    [
+        "library(lists)",
+        "library(apply)",
+        "library(aggregate)",
+        "library(pairs)",
+        "library(assoc)",
+        "library(ordsets)",
+        "library(yall)",
+        "library(tabling)",
+        "library(clpfd)",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect()
 }
 
 /// Persistence configuration
@@ -115,6 +230,37 @@ pub struct ObservabilityConfig {
     pub metrics_port: u16,
     pub tracing_enabled: bool,
     pub tracing_endpoint: String,
+    /// Evaluations (CLIPS eval/run, Prolog queries) taking longer than this
+    /// many milliseconds are logged at `warn` level. Default: 1000.
+    /// Set to 0 to disable slow-query logging.
+    #[serde(default = "default_slow_query_ms")]
+    pub slow_query_ms: u64,
+}
+
+fn default_slow_query_ms() -> u64 { 1000 }
+
+/// Engines created at server start so the first sessions skip engine
+/// startup; together they are capped at `sessions.max_concurrent`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrewarmConfig {
+    /// Prolog engines to create
+    #[serde(default)]
+    pub prolog: usize,
+    /// CLIPS engines to create
+    #[serde(default)]
+    pub clips: usize,
+    /// How often idle Prolog engines are pinged; engines that fail are
+    /// replaced to keep `prolog` waiting. 0 disables the check. Default: 60.
+    #[serde(default = "default_health_check_interval_seconds")]
+    pub health_check_interval_seconds: u64,
+}
+
+fn default_health_check_interval_seconds() -> u64 { 60 }
+
+impl Default for PrewarmConfig {
+    fn default() -> Self {
+        crate::defaults::default_prewarm_config()
+    }
 }
 
 /// Authentication configuration
@@ -150,11 +296,20 @@ impl AppConfig {
         if self.sessions.max_per_user == 0 {
             return Err("sessions.max_per_user must be non-zero".to_string());
         }
+        if !matches!(self.sessions.default_session_type.as_str(), "clips" | "prolog") {
+            return Err("sessions.default_session_type must be \"clips\" or \"prolog\"".to_string());
+        }
+        if self.sessions.idle_timeout_seconds == Some(0) {
+            return Err("sessions.idle_timeout_seconds must be non-zero; leave it unset to disable".to_string());
+        }
 
         // Resources validation
         if self.resources.max_facts_per_session == 0 {
             return Err("resources.max_facts_per_session must be non-zero".to_string());
         }
+        if self.resources.max_concurrent_evals == 0 {
+            return Err("resources.max_concurrent_evals must be non-zero".to_string());
+        }
 
         // Auth validation
         if self.auth.jwt_secret.is_empty() {
@@ -0,0 +1,92 @@
+use clara_config::{defaults, ConfigEnvironment, ConfigLoader};
+use std::fs;
+
+fn environment(name: &str, debug: bool) -> ConfigEnvironment {
+    ConfigEnvironment { env_name: name.to_string(), debug }
+}
+
+#[test]
+fn test_development_defaults_are_permissive() {
+    let dev = defaults::for_environment(&environment("development", false));
+    assert_eq!(dev.server.host, "127.0.0.1");
+    assert_eq!(dev.observability.log_level, "debug");
+    assert!(!dev.security.allow_list_mode);
+    assert!(!dev.persistence.encryption);
+}
+
+#[test]
+fn test_production_defaults_are_strict() {
+    let prod = defaults::for_environment(&environment("production", false));
+    let dev = defaults::for_environment(&environment("development", false));
+
+    assert_eq!(prod.observability.log_level, "info");
+    assert!(prod.security.allow_list_mode);
+    assert!(prod.persistence.encryption);
+    assert_ne!(prod.server.host, dev.server.host);
+    assert_ne!(prod.observability.log_level, dev.observability.log_level);
+    assert_ne!(prod.security.allow_list_mode, dev.security.allow_list_mode);
+
+    // Anything that isn't development gets the strict defaults
+    let staging = defaults::for_environment(&environment("staging", false));
+    assert!(staging.security.allow_list_mode);
+    assert_eq!(staging.observability.log_level, "info");
+}
+
+#[test]
+fn test_debug_flag_enables_debug_logging() {
+    let prod = defaults::for_environment(&environment("production", true));
+    assert_eq!(prod.observability.log_level, "debug");
+    assert!(prod.security.allow_list_mode, "debug must not relax security");
+}
+
+#[test]
+fn test_environment_defaults_validate() {
+    for name in ["development", "production"] {
+        let config = defaults::for_environment(&environment(name, false));
+        assert!(config.validate().is_ok(), "{} defaults invalid", name);
+    }
+}
+
+#[test]
+fn test_loader_applies_files_over_environment_defaults() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("default.toml"),
+        "[observability]\nlog_level = \"warn\"\n\n[auth]\njwt_secret = \"test-secret\"\n",
+    )
+    .unwrap();
+    fs::write(dir.path().join("production.toml"), "[server]\nport = 9443\n").unwrap();
+
+    let prod = ConfigLoader::from_env_in(dir.path(), Some("production")).unwrap();
+    // Unset fields keep the production defaults
+    assert_eq!(prod.server.host, "0.0.0.0");
+    assert!(prod.security.allow_list_mode);
+    assert!(prod.persistence.encryption);
+    // Fields the files set override them, without resetting their siblings
+    assert_eq!(prod.observability.log_level, "warn");
+    assert_eq!(prod.server.port, 9443);
+    assert_eq!(prod.server.request_timeout_ms, 30000);
+    assert_eq!(prod.auth.jwt_secret, "test-secret");
+
+    // No development.toml: development defaults plus default.toml
+    let dev = ConfigLoader::from_env_in(dir.path(), Some("development")).unwrap();
+    assert_eq!(dev.server.host, "127.0.0.1");
+    assert!(!dev.security.allow_list_mode);
+    assert_eq!(dev.server.port, 8080);
+    assert_eq!(dev.observability.log_level, "warn");
+}
+
+#[test]
+fn test_loader_interpolates_env_vars_over_environment_defaults() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("default.toml"),
+        "[auth]\njwt_secret = \"${CLARA_CONFIG_TEST_SECRET}\"\n",
+    )
+    .unwrap();
+    std::env::set_var("CLARA_CONFIG_TEST_SECRET", "from-env");
+
+    let prod = ConfigLoader::from_env_in(dir.path(), Some("production")).unwrap();
+    assert_eq!(prod.auth.jwt_secret, "from-env");
+    assert!(prod.security.allow_list_mode);
+}
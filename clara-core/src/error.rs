@@ -30,6 +30,9 @@ pub enum ClaraError {
     #[error("Command not found: {0}")]
     CommandNotFound(String),
 
+    #[error("Fact not found: {0}")]
+    FactNotFound(String),
+
     #[error("Syntax error in scripts-dev: {0}")]
     SyntaxError(String),
 
@@ -73,6 +76,9 @@ pub enum ClaraError {
     #[error("Queue full, please retry later")]
     QueueFull,
 
+    #[error("Service unavailable: {0}")]
+    ServiceUnavailable(String),
+
     // Process/subprocess errors
     #[error("Subprocess error: {0}")]
     SubprocessError(String),
@@ -130,7 +136,8 @@ impl ClaraError {
 
             // 404 Not Found
             ClaraError::SessionNotFound(_)
-            | ClaraError::CommandNotFound(_) => 404,
+            | ClaraError::CommandNotFound(_)
+            | ClaraError::FactNotFound(_) => 404,
 
             // 409 Conflict
             ClaraError::SessionAlreadyExists(_) => 409,
@@ -151,6 +158,9 @@ impl ClaraError {
             | ClaraError::DatabaseError(_) => 500,
 
             // 503 Service Unavailable
+            ClaraError::ServiceUnavailable(_) => 503,
+
+            // 504 Gateway Timeout
             ClaraError::EvalTimeout { .. } => 504,
 
             // Other runtime errors
@@ -176,6 +186,7 @@ impl ClaraError {
             ClaraError::EvalFailed(_) => "EvalFailed",
             ClaraError::EvalTimeout { .. } => "EvalTimeout",
             ClaraError::CommandNotFound(_) => "CommandNotFound",
+            ClaraError::FactNotFound(_) => "FactNotFound",
             ClaraError::SyntaxError(_) => "SyntaxError",
             ClaraError::RuntimeError(_) => "RuntimeError",
             ClaraError::ResourceLimitExceeded { .. } => "ResourceLimitExceeded",
@@ -189,6 +200,7 @@ impl ClaraError {
             ClaraError::MissingField(_) => "MissingField",
             ClaraError::ConcurrencyLimitExceeded => "ConcurrencyLimitExceeded",
             ClaraError::QueueFull => "QueueFull",
+            ClaraError::ServiceUnavailable(_) => "ServiceUnavailable",
             ClaraError::SubprocessError(_) => "SubprocessError",
             ClaraError::ProcessSpawnError(_) => "ProcessSpawnError",
             ClaraError::ProcessCommunicationError(_) => "ProcessCommunicationError",
@@ -250,6 +262,10 @@ mod tests {
             ClaraError::UserSessionLimitExceeded.status_code(),
             429
         );
+        assert_eq!(
+            ClaraError::ServiceUnavailable("no engine".to_string()).status_code(),
+            503
+        );
     }
 
     #[test]
@@ -23,8 +23,10 @@ pub mod error;
 pub mod types;
 pub mod traits;
 pub mod service;
+pub mod util;
 
 // Re-export commonly used items
 pub use error::{ClaraError, ClaraResult, ErrorResponse};
 pub use types::*;
+pub use util::truncate_str;
 pub use traits::{SessionService, EvalService, LoadService, PersistenceService, ReplProtocol, SecurityFilter};
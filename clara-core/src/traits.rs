@@ -42,7 +42,7 @@ pub trait LoadService: Send + Sync + Clone {
     fn load_session(&self, session_id: &str, req: LoadRequest) -> ClaraResult<LoadResponse>;
 }
 
-/// Persistence service trait (future)
+/// Persistence service trait - saves and reloads session state
 pub trait PersistenceService: Send + Sync + Clone {
     /// Save session state
     fn save_session(&self, session_id: &str, req: SaveRequest) -> ClaraResult<SaveResponse>;
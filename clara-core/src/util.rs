@@ -0,0 +1,42 @@
+//! Small helpers shared across crates
+
+/// Truncate `s` to at most `max` characters, appending `…` when shortened.
+///
+/// Cuts on a char boundary, so multibyte input never panics the way a raw
+/// byte slice (`&s[..max]`) can. Intended for logging user-supplied input.
+pub fn truncate_str(s: &str, max: usize) -> String {
+    match s.char_indices().nth(max) {
+        Some((idx, _)) => format!("{}…", &s[..idx]),
+        None => s.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_short_input_unchanged() {
+        assert_eq!(truncate_str("(run)", 10), "(run)");
+        assert_eq!(truncate_str("(run)", 5), "(run)");
+        assert_eq!(truncate_str("", 0), "");
+    }
+
+    #[test]
+    fn test_truncate_ascii() {
+        assert_eq!(truncate_str("(assert (foo))", 7), "(assert…");
+    }
+
+    #[test]
+    fn test_truncate_multibyte_at_every_length() {
+        let input = "héllo wörld 日本語 🦀🦀";
+        let total = input.chars().count();
+        for max in 0..=total + 2 {
+            let out = truncate_str(input, max);
+            let kept: String = out.trim_end_matches('…').to_string();
+            assert!(input.starts_with(&kept), "max {} gave {:?}", max, out);
+            assert_eq!(kept.chars().count(), max.min(total));
+            assert_eq!(out.ends_with('…'), max < total);
+        }
+    }
+}
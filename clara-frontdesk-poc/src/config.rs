@@ -7,6 +7,8 @@ pub struct FrontDeskConfig {
     pub devilish_supervisor: DevilishSupervisorConfig,
     #[serde(default)]
     pub deduction: DeductionConfig,
+    #[serde(default)]
+    pub prompt_guard: PromptGuardConfig,
     pub server: ServerConfig,
     pub paths: PathsConfig,
 }
@@ -39,6 +41,49 @@ pub struct DeductionConfig {
     pub persist: bool,
 }
 
+fn default_guard_enabled() -> bool {
+    true
+}
+
+fn default_injection_phrases() -> Vec<String> {
+    [
+        "ignore previous instructions",
+        "ignore all previous instructions",
+        "disregard previous instructions",
+        "ignore the above",
+        "you are now",
+        "new instructions:",
+        "system prompt",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect()
+}
+
+/// How visitor messages are fenced off in LLM prompts (see `guard.rs`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct PromptGuardConfig {
+    /// Wrap visitor messages in delimited sections.
+    #[serde(default = "default_guard_enabled")]
+    pub enabled: bool,
+    /// Also replace known injection phrases with `[removed]`.
+    #[serde(default)]
+    pub strip_phrases: bool,
+    /// Phrases stripped when `strip_phrases` is on (case-insensitive).
+    #[serde(default = "default_injection_phrases")]
+    pub phrases: Vec<String>,
+}
+
+impl Default for PromptGuardConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_guard_enabled(),
+            strip_phrases: false,
+            phrases: default_injection_phrases(),
+        }
+    }
+}
+
 fn default_model() -> String {
     "qwen-clara:latest".to_string()
 }
@@ -79,12 +124,17 @@ pub struct PathsConfig {
 }
 
 pub fn load_config() -> FrontDeskConfig {
+    try_load_config().unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Like [`load_config`], but reports a missing or malformed file as an
+/// error instead of panicking; used to reload a running server.
+pub fn try_load_config() -> Result<FrontDeskConfig, String> {
     let path = std::env::var("FRONTDESK_CONFIG")
         .unwrap_or_else(|_| "clara-frontdesk-poc/config/city_of_dis.toml".to_string());
 
     let content = fs::read_to_string(&path)
-        .unwrap_or_else(|e| panic!("Cannot read config '{}': {}", path, e));
+        .map_err(|e| format!("Cannot read config '{}': {}", path, e))?;
 
-    toml::from_str(&content)
-        .unwrap_or_else(|e| panic!("Cannot parse config '{}': {}", path, e))
+    toml::from_str(&content).map_err(|e| format!("Cannot parse config '{}': {}", path, e))
 }
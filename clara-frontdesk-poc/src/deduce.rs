@@ -88,14 +88,10 @@ pub fn run_deduce(
 /// Used for goals like `suggestion(visitor, S).` where each solution binds S once.
 #[allow(dead_code)]
 pub fn extract_solutions(result: &Value, var_name: &str) -> Vec<String> {
-    result["result"]["prolog_solutions"]
-        .as_array()
-        .map(|arr| {
-            arr.iter()
-                .filter_map(|sol| sol[var_name].as_str().map(|s| s.to_string()))
-                .collect()
-        })
-        .unwrap_or_default()
+    fiery_pit_client::prolog_solutions(result)
+        .iter()
+        .filter_map(|sol| sol.get(var_name).and_then(|v| v.as_str()).map(|s| s.to_string()))
+        .collect()
 }
 
 /// Extract the first solution as a map of variable name → JSON value.
@@ -103,12 +99,7 @@ pub fn extract_solutions(result: &Value, var_name: &str) -> Vec<String> {
 /// in a single solution.
 pub fn extract_named_solutions(result: &Value) -> HashMap<String, Value> {
     log::debug!("extract_named_solutions({:?})", result);
-    result["result"]["prolog_solutions"]
-        .as_array()
-        .and_then(|arr| arr.first())
-        .and_then(|sol| sol.as_object())
-        .map(|obj| obj.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
-        .unwrap_or_default()
+    fiery_pit_client::prolog_bindings(result)
 }
 
 /// Extract a list-valued variable from a named solution.
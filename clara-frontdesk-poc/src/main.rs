@@ -1,5 +1,6 @@
 mod config;
 mod deduce;
+mod guard;
 mod session;
 mod state;
 mod ws;
@@ -8,7 +9,8 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use actix_files::Files;
-use actix_web::{web, App, HttpServer};
+use actix_web::{web, App, HttpResponse, HttpServer};
+use arc_swap::ArcSwap;
 use fiery_pit_client::FieryPitClient;
 
 use config::load_config;
@@ -56,6 +58,18 @@ fn init_evaluator(fiery_pit: &FieryPitClient) {
     }
 }
 
+/// Re-read the config file; conversations already under way keep the config
+/// they started with.
+async fn reload_config(state: web::Data<AppState>) -> HttpResponse {
+    match state.reload_config() {
+        Ok(()) => HttpResponse::Ok().json(serde_json::json!({"status": "reloaded"})),
+        Err(e) => {
+            log::warn!("Config reload failed, keeping the current config: {}", e);
+            HttpResponse::BadRequest().json(serde_json::json!({"error": e}))
+        }
+    }
+}
+
 fn main() -> std::io::Result<()> {
     env_logger::Builder::from_default_env()
         .format_timestamp_millis()
@@ -91,7 +105,7 @@ fn main() -> std::io::Result<()> {
         clara_api_url: cfg.paths.clara_api_url.clone(),
         clara_pl_path: cfg.paths.clara_pl_path.clone(),
         clara_clp_path: cfg.paths.clara_clp_path.clone(),
-        config: Arc::new(cfg.clone()),
+        config: Arc::new(ArcSwap::from_pointee(cfg.clone())),
     });
 
     let port = cfg.server.port;
@@ -103,6 +117,7 @@ fn main() -> std::io::Result<()> {
             App::new()
                 .app_data(state.clone())
                 .route("/ws", web::get().to(ws_index))
+                .route("/config/reload", web::post().to(reload_config))
                 .service(
                     Files::new("/", &static_path)
                         .index_file("index.html"),
@@ -1,13 +1,41 @@
 use std::sync::Arc;
 
+use arc_swap::ArcSwap;
 use fiery_pit_client::FieryPitClient;
 
-use crate::config::FrontDeskConfig;
+use crate::config::{try_load_config, FrontDeskConfig};
 
 pub struct AppState {
     pub fiery_pit: FieryPitClient,
     pub clara_api_url: String,
     pub clara_pl_path: String,
     pub clara_clp_path: String,
-    pub config: Arc<FrontDeskConfig>,
+    /// Current config; replaced wholesale by [`AppState::reload_config`]
+    pub config: Arc<ArcSwap<FrontDeskConfig>>,
 }
+
+impl AppState {
+    /// Snapshot of the current config.
+    ///
+    /// Take this once when a conversation starts and keep it: a reload then
+    /// only affects conversations started afterwards, so an in-flight one
+    /// never sees its greeting or prompts change halfway through.
+    pub fn config(&self) -> Arc<FrontDeskConfig> {
+        self.config.load_full()
+    }
+
+    /// Replace the config for conversations started from now on.
+    pub fn set_config(&self, config: FrontDeskConfig) {
+        self.config.store(Arc::new(config));
+    }
+
+    /// Re-read the config file (see [`try_load_config`]) and swap it in.
+    /// On error the current config is kept.
+    pub fn reload_config(&self) -> Result<(), String> {
+        let config = try_load_config()?;
+        log::info!("Front desk config reloaded for {}", config.company.name);
+        self.set_config(config);
+        Ok(())
+    }
+}
+
@@ -5,6 +5,7 @@ use reqwest::blocking::Client;
 use serde_json::{json, Value};
 use std::sync::Arc;
 
+use crate::config::FrontDeskConfig;
 use crate::deduce::{extract_list_var, extract_named_solutions, extract_str_var, run_deduce};
 use crate::session::{VisitorSession, VisitorStatus};
 use crate::state::AppState;
@@ -23,27 +24,35 @@ struct TurnResult {
 pub struct FrontDeskActor {
     session: VisitorSession,
     state: Arc<AppState>,
+    /// Config as of when this conversation started; a reload mid-conversation
+    /// doesn't change it
+    config: Arc<FrontDeskConfig>,
 }
 
 impl FrontDeskActor {
     fn new(state: Arc<AppState>) -> Self {
-        let patience = state.config.company.patience;
+        let config = state.config();
         Self {
-            session: VisitorSession::new(patience),
+            session: VisitorSession::new(config.company.patience),
             state,
+            config,
         }
     }
+
+    fn greeting(&self) -> String {
+        format!(
+            "Welcome to the {}. I am {}. State your business.",
+            self.config.company.name,
+            self.config.company.agent_name,
+        )
+    }
 }
 
 impl Actor for FrontDeskActor {
     type Context = ws::WebsocketContext<Self>;
 
     fn started(&mut self, ctx: &mut Self::Context) {
-        let greeting = format!(
-            "Welcome to the {}. I am {}. State your business.",
-            self.state.config.company.name,
-            self.state.config.company.agent_name,
-        );
+        let greeting = self.greeting();
         self.session.push_assistant(&greeting);
         ctx.text(
             json!({"type": "agent", "text": greeting, "status": "active"}).to_string(),
@@ -94,16 +103,17 @@ impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for FrontDeskActor {
                 let clara_api_url = self.state.clara_api_url.clone();
                 let clara_pl_path = self.state.clara_pl_path.clone();
                 let clara_clp_path = self.state.clara_clp_path.clone();
-                let system_prompt    = self.state.config.company.system_prompt.clone();
-                let model           = self.state.config.company.model.clone();
-                let deduction_prompt = self.state.config.deduction_system_prompt().to_string();
-                let deduction_model  = self.state.config.deduction_model().to_string();
-                let persist          = self.state.config.deduction.persist;
+                let system_prompt    = self.config.company.system_prompt.clone();
+                let model           = self.config.company.model.clone();
+                let deduction_prompt = self.config.deduction_system_prompt().to_string();
+                let deduction_model  = self.config.deduction_model().to_string();
+                let persist          = self.config.deduction.persist;
+                let guard            = &self.config.prompt_guard;
                 let fp_client = self.state.fiery_pit.clone();
 
                 let prolog_clauses = self.session.prolog_clauses(&clara_pl_path);
-                let deduce_context = self.session.deduce_context(&deduction_prompt);
-                let evaluate_data  = self.session.evaluate_data(&system_prompt, &model, &deduction_model);
+                let deduce_context = self.session.deduce_context(&deduction_prompt, guard);
+                let evaluate_data  = self.session.evaluate_data(&system_prompt, &model, &deduction_model, guard);
 
                 let addr = ctx.address();
 
@@ -253,34 +263,11 @@ fn run_turn(
         "run_turn: evaluate payload: {}",
         serde_json::to_string_pretty(&eval_payload).unwrap_or_default()
     );
-    // after receiving `tephra` from fp_client.evaluate_tephra(...)
     let assistant_text = match fp_client.evaluate_tephra(eval_payload) {
-        Ok(tephra) => {
-            // Try multiple possible locations for the evaluator text:
-            // 1) response.content (preferred)
-            // 2) response (string field inside object)
-            // 3) response itself is a string value
-            let text_opt = tephra
-                .response() // Option<&serde_json::Value>
-                .and_then(|r| {
-                    // Attempt response.content OR response
-                    r.get("content")
-                        .or_else(|| r.get("response"))
-                        .and_then(|v| v.as_str())
-                        .map(|s| s.to_string())
-                        // If neither key exists but response is a plain string, use that.
-                        .or_else(|| r.as_str().map(|s| s.to_string()))
-                });
-
-            // Fallback and helpful debug logging if nothing found
-            text_opt.unwrap_or_else(|| {
-                log::debug!(
-                "evaluate_tephra returned unexpected shape: {:?}",
-                tephra.response()
-            );
-                "(no response from evaluator)".to_string()
-            })
-        }
+        Ok(tephra) => tephra.text().unwrap_or_else(|| {
+            log::debug!("evaluate_tephra returned unexpected shape: {:?}", tephra.response());
+            "(no response from evaluator)".to_string()
+        }),
         Err(e) => {
             log::error!("FieryPit evaluate error: {}", e);
             "I am unable to process your request at this time. Please try again.".to_string()
@@ -374,3 +361,37 @@ pub async fn ws_index(
 ) -> actix_web::Result<HttpResponse> {
     ws::start(FrontDeskActor::new(state.into_inner()), &req, stream)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arc_swap::ArcSwap;
+    use fiery_pit_client::FieryPitClient;
+
+    fn test_state() -> Arc<AppState> {
+        let config: FrontDeskConfig = toml::from_str(include_str!("../config/city_of_dis.toml")).unwrap();
+        Arc::new(AppState {
+            fiery_pit: FieryPitClient::new("http://localhost:6666"),
+            clara_api_url: config.paths.clara_api_url.clone(),
+            clara_pl_path: config.paths.clara_pl_path.clone(),
+            clara_clp_path: config.paths.clara_clp_path.clone(),
+            config: Arc::new(ArcSwap::from_pointee(config)),
+        })
+    }
+
+    #[test]
+    fn test_reloaded_config_only_reaches_new_conversations() {
+        let state = test_state();
+        let in_flight = FrontDeskActor::new(state.clone());
+        let original_greeting = in_flight.greeting();
+
+        let mut updated = (*state.config()).clone();
+        updated.company.agent_name = "Agent Rhadamanthus".to_string();
+        state.set_config(updated);
+
+        let fresh = FrontDeskActor::new(state.clone());
+        assert!(fresh.greeting().contains("Agent Rhadamanthus"), "{}", fresh.greeting());
+        assert_eq!(in_flight.greeting(), original_greeting);
+        assert!(original_greeting.contains("Agent Minos"));
+    }
+}
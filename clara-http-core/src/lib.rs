@@ -0,0 +1,325 @@
+//! Clara HTTP core - shared configuration for Clara's REST clients
+//!
+//! [`HttpClientConfig`] holds the timeouts, retry policy, default headers and
+//! connection pool settings that `DemonicVoice`, `FieryPitClient` and
+//! `AsyncFieryPitClient` accept in their `with_config` constructors, so they
+//! are all configured (and tested) the same way.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use clara_http_core::{HttpClientConfig, RetryPolicy};
+//! use std::time::Duration;
+//!
+//! let config = HttpClientConfig::default()
+//!     .with_timeout(Some(Duration::from_secs(10)))
+//!     .with_retry(RetryPolicy::new(3, Duration::from_millis(100)))
+//!     .with_header("x-dis-domain", "dis.local");
+//! let client = config.build()?;
+//! let resp = config.retry.send(|| client.get("http://localhost:6666/health"))?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use reqwest::blocking::{Client, RequestBuilder, Response};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::StatusCode;
+use std::time::Duration;
+use thiserror::Error;
+
+/// Whole-request timeout unless overridden; the same as reqwest's blocking
+/// client default.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long an idle pooled connection is kept unless overridden.
+pub const DEFAULT_POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+#[derive(Error, Debug)]
+pub enum HttpConfigError {
+    #[error("invalid header {0}")]
+    InvalidHeader(String),
+    #[error("failed to build HTTP client: {0}")]
+    Build(#[from] reqwest::Error),
+}
+
+/// Check that `base_url` is an absolute `http`/`https` URL with a host and
+/// return it without trailing slashes
+///
+/// Catches typos such as `localhost:8000` (no scheme) when a client is
+/// created rather than on its first request. The error is a description of
+/// the problem.
+pub fn normalize_base_url(base_url: &str) -> Result<String, String> {
+    let url = reqwest::Url::parse(base_url.trim())
+        .map_err(|e| format!("{:?}: {}", base_url, e))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(format!("{:?}: scheme must be http or https", base_url));
+    }
+    if !url.has_host() || url.host_str() == Some("") {
+        return Err(format!("{:?}: missing host", base_url));
+    }
+    Ok(base_url.trim().trim_end_matches('/').to_string())
+}
+
+/// When and how often a failed request is sent again
+///
+/// Connection failures, timeouts and `502`/`503`/`504` responses are
+/// retried; anything else is returned as is. The wait doubles after each
+/// attempt, starting at `initial_backoff` and capped at `max_backoff`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt; 0 sends each request once
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+impl RetryPolicy {
+    /// Retry up to `max_retries` times, waiting `initial_backoff` before the
+    /// first retry; the wait is capped at 10 seconds
+    pub fn new(max_retries: u32, initial_backoff: Duration) -> Self {
+        Self {
+            max_retries,
+            initial_backoff,
+            max_backoff: Duration::from_secs(10),
+        }
+    }
+
+    /// Never retry
+    pub fn none() -> Self {
+        Self::new(0, Duration::ZERO)
+    }
+
+    /// Wait before retry number `retry` (counting from 1)
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        self.initial_backoff.saturating_mul(factor).min(self.max_backoff)
+    }
+
+    /// Send the request built by `make_request`, rebuilding and resending it
+    /// while the failure is retryable and retries remain
+    ///
+    /// Returns the last response or error.
+    pub fn send<F>(&self, make_request: F) -> reqwest::Result<Response>
+    where
+        F: Fn() -> RequestBuilder,
+    {
+        let mut retry = 0;
+        loop {
+            let result = make_request().send();
+            let retryable = match &result {
+                Ok(resp) => is_retryable_status(resp.status()),
+                Err(e) => e.is_connect() || e.is_timeout(),
+            };
+            if !retryable || retry >= self.max_retries {
+                return result;
+            }
+
+            retry += 1;
+            let wait = self.backoff(retry);
+            match &result {
+                Ok(resp) => log::debug!("Retrying after status {} (retry {}, waiting {:?})", resp.status(), retry, wait),
+                Err(e) => log::debug!("Retrying after error: {} (retry {}, waiting {:?})", e, retry, wait),
+            }
+            std::thread::sleep(wait);
+        }
+    }
+
+    /// [`send`](Self::send) for the async client: the backoff waits on the
+    /// Tokio timer instead of blocking the thread
+    pub async fn send_async<F>(&self, make_request: F) -> reqwest::Result<reqwest::Response>
+    where
+        F: Fn() -> reqwest::RequestBuilder,
+    {
+        let mut retry = 0;
+        loop {
+            let result = make_request().send().await;
+            let retryable = match &result {
+                Ok(resp) => is_retryable_status(resp.status()),
+                Err(e) => e.is_connect() || e.is_timeout(),
+            };
+            if !retryable || retry >= self.max_retries {
+                return result;
+            }
+
+            retry += 1;
+            let wait = self.backoff(retry);
+            match &result {
+                Ok(resp) => log::debug!("Retrying after status {} (retry {}, waiting {:?})", resp.status(), retry, wait),
+                Err(e) => log::debug!("Retrying after error: {} (retry {}, waiting {:?})", e, retry, wait),
+            }
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::BAD_GATEWAY | StatusCode::SERVICE_UNAVAILABLE | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// Settings shared by Clara's HTTP clients
+#[derive(Debug, Clone)]
+pub struct HttpClientConfig {
+    /// Limit on a whole request, from connecting to reading the body;
+    /// `None` waits indefinitely
+    pub timeout: Option<Duration>,
+    /// Limit on establishing the connection; `None` leaves only `timeout`
+    pub connect_timeout: Option<Duration>,
+    pub retry: RetryPolicy,
+    /// Sent with every request
+    pub headers: Vec<(String, String)>,
+    /// `User-Agent` header; `None` sends none
+    pub user_agent: Option<String>,
+    /// Idle connections kept open per host
+    pub pool_max_idle_per_host: usize,
+    /// Close pooled connections idle for this long; `None` keeps them open
+    pub pool_idle_timeout: Option<Duration>,
+}
+
+impl Default for HttpClientConfig {
+    fn default() -> Self {
+        Self {
+            timeout: Some(DEFAULT_TIMEOUT),
+            connect_timeout: None,
+            retry: RetryPolicy::none(),
+            headers: Vec::new(),
+            user_agent: None,
+            pool_max_idle_per_host: usize::MAX,
+            pool_idle_timeout: Some(DEFAULT_POOL_IDLE_TIMEOUT),
+        }
+    }
+}
+
+impl HttpClientConfig {
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn with_connect_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Add a header sent with every request
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    pub fn with_pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.pool_max_idle_per_host = max;
+        self
+    }
+
+    pub fn with_pool_idle_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.pool_idle_timeout = timeout;
+        self
+    }
+
+    /// Build a blocking client with these settings
+    ///
+    /// Fails if a header name or value is invalid. Retries aren't part of
+    /// the client; send requests through [`RetryPolicy::send`] to apply them.
+    pub fn build(&self) -> Result<Client, HttpConfigError> {
+        let mut builder = Client::builder()
+            .timeout(self.timeout)
+            .default_headers(self.header_map()?)
+            .pool_max_idle_per_host(self.pool_max_idle_per_host)
+            .pool_idle_timeout(self.pool_idle_timeout);
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(user_agent) = &self.user_agent {
+            builder = builder.user_agent(user_agent);
+        }
+        Ok(builder.build()?)
+    }
+
+    /// Build an async client with these settings, as [`build`](Self::build)
+    /// does for the blocking one; send through [`RetryPolicy::send_async`]
+    pub fn build_async(&self) -> Result<reqwest::Client, HttpConfigError> {
+        let mut builder = reqwest::Client::builder()
+            .default_headers(self.header_map()?)
+            .pool_max_idle_per_host(self.pool_max_idle_per_host)
+            .pool_idle_timeout(self.pool_idle_timeout);
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(user_agent) = &self.user_agent {
+            builder = builder.user_agent(user_agent);
+        }
+        Ok(builder.build()?)
+    }
+
+    fn header_map(&self) -> Result<HeaderMap, HttpConfigError> {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| HttpConfigError::InvalidHeader(format!("name {:?}", name)))?;
+            let value = HeaderValue::from_str(value)
+                .map_err(|_| HttpConfigError::InvalidHeader(format!("value for {}", name)))?;
+            headers.append(name, value);
+        }
+        Ok(headers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_up_to_cap() {
+        let policy = RetryPolicy {
+            max_retries: 10,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(500),
+        };
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(400));
+        assert_eq!(policy.backoff(4), Duration::from_millis(500));
+        assert_eq!(policy.backoff(40), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_normalize_base_url() {
+        assert_eq!(normalize_base_url("http://localhost:8000/").unwrap(), "http://localhost:8000");
+        assert_eq!(normalize_base_url("https://pit.example/api").unwrap(), "https://pit.example/api");
+
+        for bad in ["localhost:8000", "localhost", "", "ftp://host", "http://", "file:///tmp/x"] {
+            assert!(normalize_base_url(bad).is_err(), "{:?} was accepted", bad);
+        }
+    }
+
+    #[test]
+    fn test_invalid_header_rejected() {
+        let config = HttpClientConfig::default().with_header("bad header", "x");
+        assert!(matches!(config.build(), Err(HttpConfigError::InvalidHeader(_))));
+
+        let config = HttpClientConfig::default().with_header("x-ok", "line\nbreak");
+        assert!(matches!(config.build(), Err(HttpConfigError::InvalidHeader(_))));
+        assert!(matches!(config.build_async(), Err(HttpConfigError::InvalidHeader(_))));
+    }
+}
@@ -0,0 +1,128 @@
+//! The same `HttpClientConfig` should make every Clara client behave alike.
+//!
+//! Each test builds one config and exercises it through `DemonicVoice` and
+//! `FieryPitClient`.
+
+use clara_http_core::{HttpClientConfig, RetryPolicy};
+use demonic_voice::{DemonicVoice, DemonicVoiceError};
+use fiery_pit_client::{FieryPitClient, FieryPitError};
+use serde_json::json;
+use std::net::TcpListener;
+use std::time::{Duration, Instant};
+
+/// Run one request through each client, returning the HTTP error if it
+/// failed at the transport level and `None` otherwise
+fn call_each(config: &HttpClientConfig, url: &str) -> Vec<(&'static str, Option<reqwest::Error>)> {
+    let voice = DemonicVoice::with_config(url, config.clone()).unwrap();
+    let voice_err = match voice.evaluate(json!({"q": 1})) {
+        Err(DemonicVoiceError::Http(e)) => Some(e),
+        _ => None,
+    };
+
+    let pit = FieryPitClient::with_config(url, config.clone()).unwrap();
+    let pit_err = match pit.health() {
+        Err(FieryPitError::Http(e)) | Err(FieryPitError::Timeout(e)) => Some(e),
+        _ => None,
+    };
+
+    vec![("DemonicVoice", voice_err), ("FieryPitClient", pit_err)]
+}
+
+#[test]
+fn test_all_clients_honor_timeout() {
+    // Connections queue in the backlog and are never answered
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let config = HttpClientConfig::default().with_timeout(Some(Duration::from_millis(300)));
+
+    let start = Instant::now();
+    for (name, err) in call_each(&config, &url) {
+        let err = err.unwrap_or_else(|| panic!("{} did not fail", name));
+        assert!(err.is_timeout(), "{} failed with {}", name, err);
+    }
+    assert!(start.elapsed() < Duration::from_secs(5));
+    drop(listener);
+}
+
+#[test]
+fn test_all_clients_send_configured_headers() {
+    let mut srv = mockito::Server::new();
+    let evaluate = srv
+        .mock("POST", "/evaluate")
+        .match_header("user-agent", "clara-test/1.0")
+        .match_header("x-dis-domain", "dis.local")
+        .with_status(200)
+        .with_body("{}")
+        .create();
+    let health = srv
+        .mock("GET", "/health")
+        .match_header("user-agent", "clara-test/1.0")
+        .match_header("x-dis-domain", "dis.local")
+        .with_status(200)
+        .with_body(r#"{"status":"ok"}"#)
+        .create();
+
+    let config = HttpClientConfig::default()
+        .with_user_agent("clara-test/1.0")
+        .with_header("x-dis-domain", "dis.local");
+    for (name, err) in call_each(&config, &srv.url()) {
+        assert!(err.is_none(), "{} failed with {:?}", name, err);
+    }
+    evaluate.assert();
+    health.assert();
+}
+
+#[test]
+fn test_all_clients_retry_unavailable() {
+    let mut srv = mockito::Server::new();
+    let evaluate = srv
+        .mock("POST", "/evaluate")
+        .with_status(503)
+        .expect(3)
+        .create();
+    let health = srv
+        .mock("GET", "/health")
+        .with_status(503)
+        .expect(3)
+        .create();
+    // Client errors are not retried
+    let missing = srv.mock("GET", "/missing").with_status(404).expect(1).create();
+
+    let config = HttpClientConfig::default().with_retry(RetryPolicy::new(2, Duration::from_millis(1)));
+    call_each(&config, &srv.url());
+    evaluate.assert();
+    health.assert();
+
+    let client = config.build().unwrap();
+    let resp = config
+        .retry
+        .send(|| client.get(format!("{}/missing", srv.url())))
+        .unwrap();
+    assert_eq!(resp.status(), 404);
+    missing.assert();
+}
+
+#[test]
+fn test_invalid_header_rejected_by_all_clients() {
+    let config = HttpClientConfig::default().with_header("bad header", "x");
+    assert!(DemonicVoice::with_config("http://localhost:8000", config.clone()).is_err());
+    assert!(FieryPitClient::with_config("http://localhost:8000", config).is_err());
+}
+
+#[test]
+fn test_invalid_base_url_rejected_by_all_clients() {
+    for url in ["localhost:8000", "not a url", "ftp://localhost"] {
+        assert!(
+            matches!(DemonicVoice::try_new(url), Err(DemonicVoiceError::InvalidBaseUrl(_))),
+            "DemonicVoice accepted {:?}",
+            url
+        );
+        assert!(
+            matches!(FieryPitClient::try_new(url), Err(FieryPitError::InvalidBaseUrl(_))),
+            "FieryPitClient accepted {:?}",
+            url
+        );
+    }
+    assert!(DemonicVoice::try_new("http://localhost:8000/").is_ok());
+    assert_eq!(FieryPitClient::try_new("http://localhost:8000/").unwrap().base_url(), "http://localhost:8000");
+}
@@ -0,0 +1,62 @@
+//! Query path benchmarks: `query` vs `query_once` vs `check`.
+//!
+//! Run with `cargo bench -p clara-prolog --bench prolog`. Each goal is run
+//! through all three entry points in the same engine and the mean time per
+//! call is printed, so a regression in one path shows up next to the others.
+//!
+//! `check` skips JSON conversion entirely, `query_once` converts one
+//! solution, and `query` opens an iterating query and converts every
+//! solution. For existence checks `check` should be no slower than
+//! `query_once`; if it is, the fast path has regressed.
+
+use clara_prolog::PrologEnvironment;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const WARMUP_ITERATIONS: u32 = 100;
+const ITERATIONS: u32 = 2_000;
+
+/// Representative goals: trivial, arithmetic, a fact lookup and a goal with
+/// a large binding to convert
+const GOALS: &[(&str, &str)] = &[
+    ("true", "true"),
+    ("arithmetic", "X is 6 * 7"),
+    ("fact_lookup", "bench_parent(tom, X)"),
+    ("large_binding", "numlist(1, 500, L)"),
+];
+
+fn time_per_call(mut f: impl FnMut()) -> Duration {
+    for _ in 0..WARMUP_ITERATIONS {
+        f();
+    }
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    start.elapsed() / ITERATIONS
+}
+
+fn main() {
+    let env = PrologEnvironment::new().expect("Failed to create environment");
+    env.consult_string(
+        "bench_parent(tom, bob). bench_parent(tom, liz). bench_parent(bob, ann).",
+    )
+    .expect("Failed to load benchmark facts");
+
+    println!("{:<16} {:>12} {:>12} {:>12}", "goal", "query", "query_once", "check");
+    for (name, goal) in GOALS {
+        let query = time_per_call(|| {
+            black_box(env.query(goal).expect("query failed"));
+        });
+        let query_once = time_per_call(|| {
+            black_box(env.query_once(goal).expect("query_once failed"));
+        });
+        let check = time_per_call(|| {
+            assert!(black_box(env.check(goal).expect("check failed")));
+        });
+        println!(
+            "{:<16} {:>12?} {:>12?} {:>12?}",
+            name, query, query_once, check
+        );
+    }
+}
@@ -42,6 +42,18 @@ pub type fid_t = usize;
 /// Foreign function pointer type
 pub type pl_function_t = *const c_void;
 
+/// Signal handler registration for `PL_sigaction` (pl_sigaction_t)
+#[repr(C)]
+pub struct pl_sigaction_t {
+    /// C handler, called with the signal number
+    pub sa_cfunction: Option<extern "C" fn(c_int)>,
+    /// Prolog predicate handler (mutually exclusive with `sa_cfunction`)
+    pub sa_predicate: predicate_t,
+    /// PLSIG_* flags
+    pub sa_flags: c_int,
+    pub reserved: [*mut c_void; 2],
+}
+
 // =============================================================================
 // Constants
 // =============================================================================
@@ -63,6 +75,18 @@ pub const PL_Q_PASS_EXCEPTION: c_int = 0x0010;
 pub const PL_Q_ALLOW_YIELD: c_int = 0x0020;
 pub const PL_Q_EXT_STATUS: c_int = 0x0040;
 
+// PL_next_solution() results under PL_Q_EXT_STATUS (PL_S_*)
+pub const PL_S_EXCEPTION: c_int = -1;
+pub const PL_S_FALSE: c_int = 0;
+pub const PL_S_TRUE: c_int = 1;
+pub const PL_S_LAST: c_int = 2;
+
+// Signal handler flags (PLSIG_*)
+pub const PLSIG_THROW: c_int = 0x0002;
+pub const PLSIG_SYNC: c_int = 0x0004;
+pub const PLSIG_NOFRAME: c_int = 0x0008;
+pub const PLSIG_IGNORE: c_int = 0x0010;
+
 // Term types (PL_term_type return values)
 pub const PL_VARIABLE: c_int = 1;
 pub const PL_ATOM: c_int = 2;
@@ -447,6 +471,18 @@ extern "C" {
 
     /// Get current thread ID
     pub fn PL_thread_self() -> c_int;
+
+    /// Raise a signal in another Prolog thread; it is handled at the
+    /// thread's next safe point
+    pub fn PL_thread_raise(tid: c_int, sig: c_int) -> c_int;
+
+    // =========================================================================
+    // Signal Handling
+    // =========================================================================
+
+    /// Install a signal handler; `sig` 0 allocates a free virtual signal
+    /// and returns its number
+    pub fn PL_sigaction(sig: c_int, act: *mut pl_sigaction_t, old: *mut pl_sigaction_t) -> c_int;
 }
 
 #[cfg(test)]
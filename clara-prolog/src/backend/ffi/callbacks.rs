@@ -10,6 +10,7 @@ use super::bindings::*;
 use clara_toolbox::ffi::{evaluate_json_string, free_c_string};
 use libc::{c_char, c_int};
 use std::ffi::{CStr, CString};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 /// Foreign predicate implementation for clara_evaluate/2
 ///
@@ -78,15 +79,30 @@ pub extern "C" fn pl_clara_evaluate(t0: term_t, t1: term_t) -> c_int {
 /// Track whether clara_evaluate/2 has been registered
 static CLARA_EVALUATE_REGISTERED: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
 
+/// Number of times clara_evaluate/2 was actually handed to Prolog
+static CLARA_EVALUATE_REGISTRATIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// How many times [`register_clara_evaluate`] has registered the predicate
+/// with Prolog, as opposed to returning the cached result; at most 1
+pub fn clara_evaluate_registrations() -> usize {
+    CLARA_EVALUATE_REGISTRATIONS.load(Ordering::Relaxed)
+}
+
 /// Register the clara_evaluate/2 predicate with Prolog
 ///
-/// This can be called multiple times safely - subsequent calls will return
-/// the cached registration result without re-registering.
+/// Registration happens once per process. Subsequent calls are logged and
+/// return the cached registration result without re-registering.
 ///
 /// # Returns
 /// true if registration succeeded (or was already registered), false otherwise
 pub fn register_clara_evaluate() -> bool {
+    if let Some(registered) = CLARA_EVALUATE_REGISTERED.get() {
+        log::debug!("clara_evaluate/2 already registered (ok: {}); skipping", registered);
+        return *registered;
+    }
+
     *CLARA_EVALUATE_REGISTERED.get_or_init(|| {
+        CLARA_EVALUATE_REGISTRATIONS.fetch_add(1, Ordering::Relaxed);
         unsafe {
             let module = match CString::new("the_rabbit") {
                 Ok(s) => s,
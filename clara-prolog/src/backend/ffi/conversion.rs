@@ -2,8 +2,27 @@
 
 use super::bindings::*;
 use crate::error::{PrologError, PrologResult};
-use libc::c_char;
+use libc::{c_char, c_int};
 use std::ffi::{CStr, CString};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Key of the object an unbound variable becomes when
+/// [`set_mark_unbound_variables`] is on, e.g. `{"var": "_123"}`
+pub const UNBOUND_VARIABLE_KEY: &str = "var";
+
+static MARK_UNBOUND_VARIABLES: AtomicBool = AtomicBool::new(false);
+
+/// Choose how [`term_to_json`] renders unbound variables: as
+/// `{"var": "_123"}` markers when `mark` is true, or as `null` (the default),
+/// which can't be told apart from a genuine null value
+pub fn set_mark_unbound_variables(mark: bool) {
+    MARK_UNBOUND_VARIABLES.store(mark, Ordering::SeqCst);
+}
+
+/// Whether unbound variables are rendered as `{"var": ...}` markers
+pub fn mark_unbound_variables() -> bool {
+    MARK_UNBOUND_VARIABLES.load(Ordering::SeqCst)
+}
 
 /// Convert a Prolog term to a Rust string representation
 ///
@@ -13,8 +32,20 @@ use std::ffi::{CStr, CString};
 /// # Safety
 /// This function is unsafe because it dereferences raw pointers from FFI.
 pub unsafe fn term_to_string(t: term_t) -> PrologResult<String> {
+    get_chars(t, CVT_ALL | CVT_WRITE | BUF_STACK | REP_UTF8)
+}
+
+/// Write a term as `write_canonical/1` would: quoted, ignoring operators,
+/// with variables named `A`, `B`, ... and singletons written `_`
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers from FFI.
+pub unsafe fn term_to_canonical_string(t: term_t) -> PrologResult<String> {
+    get_chars(t, CVT_WRITE_CANONICAL | BUF_STACK | REP_UTF8)
+}
+
+unsafe fn get_chars(t: term_t, flags: c_int) -> PrologResult<String> {
     let mut s: *mut c_char = std::ptr::null_mut();
-    let flags = CVT_ALL | CVT_WRITE | BUF_STACK | REP_UTF8;
 
     if PL_get_chars(t, &mut s, flags) != 0 {
         if s.is_null() {
@@ -32,7 +63,13 @@ pub unsafe fn term_to_string(t: term_t) -> PrologResult<String> {
 
 /// Convert a Prolog term to a JSON-compatible value
 ///
-/// Handles atoms, strings, integers, floats, lists, and compounds.
+/// Handles atoms, strings, integers, floats, lists, and compounds. Unbound
+/// variables become `null`, or markers per [`set_mark_unbound_variables`].
+/// Integers too big for `i64` or `u64` become strings of their decimal digits
+/// rather than losing precision, and rationals such as `1r3` become
+/// `{"num": 1, "den": 3}`. Dicts,
+/// `library(assoc)` trees and `json([Key=Value, ...])` terms from `json_read/2`
+/// become JSON objects, and `@(true)`/`@(false)`/`@(null)` become literals.
 ///
 /// # Safety
 /// This function is unsafe because it calls FFI functions.
@@ -41,8 +78,13 @@ pub unsafe fn term_to_json(t: term_t) -> PrologResult<serde_json::Value> {
 
     match term_type {
         PL_VARIABLE => {
-            // Unbound variable - represent as null or special marker
-            Ok(serde_json::Value::Null)
+            // Unbound variable - represent as null or, if enabled, a marker
+            // naming it so shared variables can be recognized
+            if mark_unbound_variables() {
+                Ok(serde_json::json!({ UNBOUND_VARIABLE_KEY: term_to_string(t)? }))
+            } else {
+                Ok(serde_json::Value::Null)
+            }
         }
         PL_ATOM => {
             let mut a: atom_t = 0;
@@ -69,12 +111,25 @@ pub unsafe fn term_to_json(t: term_t) -> PrologResult<serde_json::Value> {
         PL_INTEGER => {
             let mut i: i64 = 0;
             if PL_get_int64(t, &mut i) != 0 {
-                Ok(serde_json::Value::Number(i.into()))
-            } else {
-                Err(PrologError::ConversionError(
-                    "Failed to get integer".to_string(),
-                ))
+                return Ok(serde_json::Value::Number(i.into()));
+            }
+
+            // Unbounded integer outside i64: keep every digit
+            let digits = get_chars(t, CVT_INTEGER | BUF_STACK | REP_UTF8)?;
+            match digits.parse::<u64>() {
+                Ok(n) => Ok(serde_json::Value::Number(n.into())),
+                Err(_) => Ok(serde_json::Value::String(digits)),
+            }
+        }
+        PL_RATIONAL => {
+            // Non-integer rational, e.g. from `1 rdiv 3`: rational(T, Num, Den)
+            if let Some(args) = call_with_args("rational", 3, t) {
+                return Ok(serde_json::json!({
+                    "num": term_to_json(args + 1)?,
+                    "den": term_to_json(args + 2)?,
+                }));
             }
+            Ok(serde_json::Value::String(term_to_string(t)?))
         }
         PL_FLOAT => {
             let mut f: f64 = 0.0;
@@ -138,19 +193,41 @@ pub unsafe fn term_to_json(t: term_t) -> PrologResult<serde_json::Value> {
                     PL_get_arg(1, t, key_term);
                     PL_get_arg(2, t, val_term);
 
-                    let key = term_to_json(key_term)?;
-                    let val = term_to_json(val_term)?;
-
-                    let key_str = match key {
-                        serde_json::Value::String(s) => s,
-                        _ => term_to_string(key_term)?,
-                    };
-
                     let mut obj = serde_json::Map::new();
-                    obj.insert(key_str, val);
+                    obj.insert(key_to_string(key_term)?, term_to_json(val_term)?);
                     return Ok(serde_json::Value::Object(obj));
                 }
 
+                if name == "json" && arity == 1 {
+                    // Classic json_read/2 object: json([Key=Value, ...])
+                    let pairs = PL_new_term_ref();
+                    PL_get_arg(1, t, pairs);
+                    if let Some(obj) = pairs_to_object(pairs)? {
+                        return Ok(serde_json::Value::Object(obj));
+                    }
+                }
+
+                if name == "@" && arity == 1 {
+                    // Classic json_read/2 literals: @(true), @(false), @(null)
+                    let arg = PL_new_term_ref();
+                    PL_get_arg(1, t, arg);
+                    match term_to_string(arg)?.as_str() {
+                        "true" => return Ok(serde_json::Value::Bool(true)),
+                        "false" => return Ok(serde_json::Value::Bool(false)),
+                        "null" => return Ok(serde_json::Value::Null),
+                        _ => {}
+                    }
+                }
+
+                if name == "t" && arity == 5 {
+                    // library(assoc) AVL tree: t(Key, Value, Balance, Left, Right)
+                    if let Some(pairs) = call_for_result("assoc_to_list", 2, t) {
+                        if let Some(obj) = pairs_to_object(pairs)? {
+                            return Ok(serde_json::Value::Object(obj));
+                        }
+                    }
+                }
+
                 // General compound: functor(args...) -> {"functor": "name", "args": [...]}
                 let mut args = Vec::new();
                 for i in 1..=arity {
@@ -168,6 +245,15 @@ pub unsafe fn term_to_json(t: term_t) -> PrologResult<serde_json::Value> {
                 Ok(serde_json::Value::String(term_to_string(t)?))
             }
         }
+        PL_DICT => {
+            // SWI-Prolog dict: Tag{Key: Value, ...} -> {"Key": Value, ...}
+            if let Some(pairs) = call_for_result("dict_pairs", 3, t) {
+                if let Some(obj) = pairs_to_object(pairs)? {
+                    return Ok(serde_json::Value::Object(obj));
+                }
+            }
+            Ok(serde_json::Value::String(term_to_string(t)?))
+        }
         _ => {
             // Unknown type - use string representation
             Ok(serde_json::Value::String(term_to_string(t)?))
@@ -175,6 +261,90 @@ pub unsafe fn term_to_json(t: term_t) -> PrologResult<serde_json::Value> {
     }
 }
 
+/// Convert an object key term to a string
+///
+/// # Safety
+/// This function is unsafe because it calls FFI functions.
+unsafe fn key_to_string(key_term: term_t) -> PrologResult<String> {
+    match term_to_json(key_term)? {
+        serde_json::Value::String(s) => Ok(s),
+        _ => term_to_string(key_term),
+    }
+}
+
+/// Convert a list of `Key-Value` or `Key=Value` pairs to a JSON object
+///
+/// Returns `None` if the term is not a proper list of such pairs.
+///
+/// # Safety
+/// This function is unsafe because it calls FFI functions.
+unsafe fn pairs_to_object(
+    list: term_t,
+) -> PrologResult<Option<serde_json::Map<String, serde_json::Value>>> {
+    let mut obj = serde_json::Map::new();
+    let head = PL_new_term_ref();
+    let tail = PL_copy_term_ref(list);
+    let key_term = PL_new_term_ref();
+    let val_term = PL_new_term_ref();
+
+    while PL_get_list(tail, head, tail) != 0 {
+        let mut f: functor_t = 0;
+        if PL_get_functor(head, &mut f) == 0 || PL_functor_arity(f) != 2 {
+            return Ok(None);
+        }
+        let name = c_string_to_string(PL_atom_chars(PL_functor_name(f)));
+        if name != "-" && name != "=" {
+            return Ok(None);
+        }
+
+        PL_get_arg(1, head, key_term);
+        PL_get_arg(2, head, val_term);
+        obj.insert(key_to_string(key_term)?, term_to_json(val_term)?);
+    }
+
+    if PL_get_nil(tail) == 0 {
+        return Ok(None);
+    }
+    Ok(Some(obj))
+}
+
+/// Call `name/arity` with `input` as the first argument and return the term
+/// bound to the last argument, or `None` if the call fails or raises.
+///
+/// # Safety
+/// This function is unsafe because it calls FFI functions.
+unsafe fn call_for_result(name: &str, arity: c_int, input: term_t) -> Option<term_t> {
+    call_with_args(name, arity, input).map(|args| args + (arity as term_t) - 1)
+}
+
+/// Call `name/arity` with `input` as the first argument and return the
+/// first of its `arity` consecutive argument terms, or `None` if the call
+/// fails or raises.
+///
+/// # Safety
+/// This function is unsafe because it calls FFI functions.
+unsafe fn call_with_args(name: &str, arity: c_int, input: term_t) -> Option<term_t> {
+    let name_c = string_to_c_string(name).ok()?;
+    let pred = PL_predicate(name_c.as_ptr(), arity, std::ptr::null());
+    if pred.is_null() {
+        return None;
+    }
+
+    let args = PL_new_term_refs(arity);
+    if PL_put_term(args, input) == 0 {
+        return None;
+    }
+
+    let flags = PL_Q_NODEBUG | PL_Q_CATCH_EXCEPTION;
+    if PL_call_predicate(std::ptr::null_mut(), flags, pred, args) == 0 {
+        if PL_exception(std::ptr::null_mut()) != 0 {
+            PL_clear_exception();
+        }
+        return None;
+    }
+    Some(args)
+}
+
 /// Convert a Rust string to a CString for Prolog
 pub fn string_to_c_string(s: &str) -> PrologResult<CString> {
     CString::new(s).map_err(|e| PrologError::ConversionError(format!("CString error: {}", e)))
@@ -191,93 +361,111 @@ pub unsafe fn c_string_to_string(c_str: *const c_char) -> String {
     CStr::from_ptr(c_str).to_string_lossy().into_owned()
 }
 
+/// Deepest JSON nesting [`json_to_term`] converts; deeper values are
+/// rejected instead of risking the Rust stack
+pub const MAX_JSON_TO_TERM_DEPTH: usize = 256;
+
 /// Put a JSON value into a Prolog term
 ///
+/// Objects become lists of `Key-Value` pairs. Every FFI call is checked, so
+/// a failure part way through (e.g. a Prolog stack overflow) returns a
+/// `ConversionError` naming the step rather than leaving a malformed term.
+/// Values nested deeper than [`MAX_JSON_TO_TERM_DEPTH`] are rejected.
+///
 /// # Safety
 /// This function is unsafe because it calls FFI functions.
 pub unsafe fn json_to_term(value: &serde_json::Value, t: term_t) -> PrologResult<()> {
+    json_to_term_at(value, t, 0)
+}
+
+/// Fail with a `ConversionError` for `context` if an FFI call returned 0
+fn check_ffi(rc: c_int, context: &str) -> PrologResult<()> {
+    if rc == 0 {
+        Err(PrologError::ConversionError(format!("Failed to {}", context)))
+    } else {
+        Ok(())
+    }
+}
+
+/// A fresh term reference, or an error if the term stack is exhausted
+unsafe fn new_term_ref() -> PrologResult<term_t> {
+    match PL_new_term_ref() {
+        0 => Err(PrologError::ConversionError("Failed to allocate term reference".to_string())),
+        t => Ok(t),
+    }
+}
+
+unsafe fn json_to_term_at(value: &serde_json::Value, t: term_t, depth: usize) -> PrologResult<()> {
+    if depth > MAX_JSON_TO_TERM_DEPTH {
+        return Err(PrologError::ConversionError(format!(
+            "JSON value nested deeper than {} levels",
+            MAX_JSON_TO_TERM_DEPTH
+        )));
+    }
+
     match value {
         serde_json::Value::Null => {
             // Represent null as the atom 'null'
             let null_atom = string_to_c_string("null")?;
-            if PL_put_atom_chars(t, null_atom.as_ptr()) == 0 {
-                return Err(PrologError::ConversionError(
-                    "Failed to put null atom".to_string(),
-                ));
-            }
+            check_ffi(PL_put_atom_chars(t, null_atom.as_ptr()), "put null atom")?;
         }
         serde_json::Value::Bool(b) => {
             let atom_str = if *b { "true" } else { "false" };
             let c_str = string_to_c_string(atom_str)?;
-            if PL_put_atom_chars(t, c_str.as_ptr()) == 0 {
-                return Err(PrologError::ConversionError(
-                    "Failed to put bool atom".to_string(),
-                ));
-            }
+            check_ffi(PL_put_atom_chars(t, c_str.as_ptr()), "put bool atom")?;
         }
         serde_json::Value::Number(n) => {
             if let Some(i) = n.as_i64() {
-                if PL_put_integer(t, i) == 0 {
-                    return Err(PrologError::ConversionError(
-                        "Failed to put integer".to_string(),
-                    ));
-                }
+                check_ffi(PL_put_integer(t, i), "put integer")?;
             } else if let Some(f) = n.as_f64() {
-                if PL_put_float(t, f) == 0 {
-                    return Err(PrologError::ConversionError(
-                        "Failed to put float".to_string(),
-                    ));
-                }
+                check_ffi(PL_put_float(t, f), "put float")?;
+            } else {
+                return Err(PrologError::ConversionError(format!("Unrepresentable number: {}", n)));
             }
         }
         serde_json::Value::String(s) => {
             let c_str = string_to_c_string(s)?;
-            if PL_put_string_chars(t, c_str.as_ptr()) == 0 {
-                return Err(PrologError::ConversionError(
-                    "Failed to put string".to_string(),
-                ));
-            }
+            check_ffi(PL_put_string_chars(t, c_str.as_ptr()), "put string")?;
         }
         serde_json::Value::Array(arr) => {
             // Build list from end to front
-            PL_put_nil(t);
-            for item in arr.iter().rev() {
-                let head = PL_new_term_ref();
-                json_to_term(item, head)?;
-                if PL_cons_list(t, head, t) == 0 {
-                    return Err(PrologError::ConversionError(
-                        "Failed to build list".to_string(),
-                    ));
-                }
+            check_ffi(PL_put_nil(t), "start list")?;
+            for (index, item) in arr.iter().enumerate().rev() {
+                let head = new_term_ref()?;
+                json_to_term_at(item, head, depth + 1)?;
+                check_ffi(PL_cons_list(t, head, t), &format!("add list element {}", index))?;
             }
         }
         serde_json::Value::Object(obj) => {
             // Convert object to list of Key-Value pairs
             // Could also use dict{} syntax for SWI-Prolog dicts
-            PL_put_nil(t);
+            let dash_name = string_to_c_string("-")?;
+            let dash_atom = PL_new_atom(dash_name.as_ptr());
+            if dash_atom == 0 {
+                return Err(PrologError::ConversionError("Failed to create '-' atom".to_string()));
+            }
+            let dash_functor = PL_new_functor(dash_atom, 2);
+            if dash_functor == 0 {
+                return Err(PrologError::ConversionError("Failed to create '-'/2 functor".to_string()));
+            }
+
+            check_ffi(PL_put_nil(t), "start object list")?;
             for (key, val) in obj.iter().rev() {
-                // Create Key-Value compound
-                let pair = PL_new_term_ref();
-                let key_term = PL_new_term_ref();
-                let val_term = PL_new_term_ref();
+                // Create -(Key, Value) compound
+                let pair = new_term_ref()?;
+                let key_term = new_term_ref()?;
+                let val_term = new_term_ref()?;
 
                 let key_c = string_to_c_string(key)?;
-                PL_put_atom_chars(key_term, key_c.as_ptr());
-                json_to_term(val, val_term)?;
+                check_ffi(PL_put_atom_chars(key_term, key_c.as_ptr()), &format!("put key {:?}", key))?;
+                json_to_term_at(val, val_term, depth + 1)?;
 
-                // Create -(Key, Value) compound
-                let dash_atom = PL_new_atom(string_to_c_string("-")?.as_ptr());
-                let dash_functor = PL_new_functor(dash_atom, 2);
-                PL_put_functor(pair, dash_functor);
-                PL_unify_arg(1, pair, key_term);
-                PL_unify_arg(2, pair, val_term);
+                check_ffi(PL_put_functor(pair, dash_functor), &format!("build pair for key {:?}", key))?;
+                check_ffi(PL_unify_arg(1, pair, key_term), &format!("set key {:?}", key))?;
+                check_ffi(PL_unify_arg(2, pair, val_term), &format!("set value for key {:?}", key))?;
 
                 // Cons onto list
-                if PL_cons_list(t, pair, t) == 0 {
-                    return Err(PrologError::ConversionError(
-                        "Failed to build object list".to_string(),
-                    ));
-                }
+                check_ffi(PL_cons_list(t, pair, t), &format!("add key {:?} to object list", key))?;
             }
         }
     }
@@ -6,114 +6,275 @@
 use super::bindings::*;
 use super::conversion::*;
 use crate::error::{PrologError, PrologResult};
+use crate::profile::BuiltinProfile;
 use std::ffi::CString;
-use std::sync::OnceLock;
+use libc::c_int;
+use std::sync::atomic::{AtomicBool, AtomicI32, AtomicU32, AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
 use uuid::Uuid;
 
 /// Compile-time SWI_HOME_DIR from build.rs
 const SWI_HOME_DIR: &str = env!("SWI_HOME_DIR");
 
+/// Attempts to acquire a busy engine used until [`set_engine_acquire_attempts`]
+/// is called
+pub const DEFAULT_ENGINE_ACQUIRE_ATTEMPTS: u32 = 10;
+
+/// Longest pause between attempts to acquire a busy engine
+const MAX_ENGINE_ACQUIRE_BACKOFF: Duration = Duration::from_millis(50);
+
+static ENGINE_ACQUIRE_ATTEMPTS: AtomicU32 = AtomicU32::new(DEFAULT_ENGINE_ACQUIRE_ATTEMPTS);
+
+/// Set how many times an engine held by another thread is tried before
+/// giving up with [`PrologError::EngineInUse`]. The pause between attempts
+/// starts at 1ms and doubles up to 50ms. Values below 1 are treated as 1.
+pub fn set_engine_acquire_attempts(attempts: u32) {
+    ENGINE_ACQUIRE_ATTEMPTS.store(attempts.max(1), Ordering::SeqCst);
+}
+
+/// The configured number of engine acquire attempts
+pub fn engine_acquire_attempts() -> u32 {
+    ENGINE_ACQUIRE_ATTEMPTS.load(Ordering::SeqCst)
+}
+
+/// Memoized outcome of global Prolog initialization
+///
+/// Behaves like a `OnceLock` for success, but a stored failure can be retried
+/// through [`InitState::retry`]. The init step is passed in as a closure so the
+/// bookkeeping can be tested without SWI-Prolog.
+struct InitState {
+    result: Mutex<Option<Result<(), String>>>,
+}
+
+impl InitState {
+    const fn new() -> Self {
+        Self { result: Mutex::new(None) }
+    }
+
+    /// Run `init` if nothing has been attempted yet; otherwise return the stored result
+    fn get_or_init(&self, init: impl FnOnce() -> Result<(), String>) -> Result<(), String> {
+        self.run(false, init)
+    }
+
+    /// Like `get_or_init`, but also re-runs `init` if the stored result is a failure
+    fn retry(&self, init: impl FnOnce() -> Result<(), String>) -> Result<(), String> {
+        self.run(true, init)
+    }
+
+    fn run(&self, retry_failed: bool, init: impl FnOnce() -> Result<(), String>) -> Result<(), String> {
+        // Holding the lock across `init` serializes concurrent first callers,
+        // as `OnceLock::get_or_init` did
+        let mut result = self.result.lock().unwrap_or_else(|e| e.into_inner());
+        match &*result {
+            Some(Ok(())) => return Ok(()),
+            Some(Err(e)) if !retry_failed => return Err(e.clone()),
+            _ => {}
+        }
+        let outcome = init();
+        *result = Some(outcome.clone());
+        outcome
+    }
+
+    fn is_ok(&self) -> bool {
+        matches!(
+            *self.result.lock().unwrap_or_else(|e| e.into_inner()),
+            Some(Ok(()))
+        )
+    }
+}
+
 /// Initialization result: Ok(()) for success, Err(message) for failure
-static INIT_RESULT: OnceLock<Result<(), String>> = OnceLock::new();
+static INIT_STATE: InitState = InitState::new();
 
 /// Ensure the global Prolog system is initialized
 ///
 /// This is called automatically when creating environments.
-/// It only runs once per process.
+/// It only runs once per process; a failure is remembered until
+/// [`reinitialize`] is called.
 pub fn ensure_prolog_initialized() -> PrologResult<()> {
-    let result = INIT_RESULT.get_or_init(|| {
-        // Set SWI_HOME_DIR environment variable if not already set
-        // This tells SWI-Prolog where to find its library/boot files
-        if std::env::var("SWI_HOME_DIR").is_err() {
-            std::env::set_var("SWI_HOME_DIR", SWI_HOME_DIR);
-            log::debug!("Set SWI_HOME_DIR to {}", SWI_HOME_DIR);
-        }
-
-        // Build argv for PL_initialise
-        // --quiet: suppress banner
-        // --nosignals: don't install signal handlers (Rust handles those)
-        let argv_strings: Vec<CString> = vec![
-            CString::new("clara-prolog").unwrap(),
-            CString::new("--quiet").unwrap(),
-            CString::new("--nosignals").unwrap(),
-        ];
-
-        let mut argv_ptrs: Vec<*mut i8> = argv_strings
-            .iter()
-            .map(|s| s.as_ptr() as *mut i8)
-            .collect();
-
-        let argc = argv_ptrs.len() as i32;
-
-        log::debug!("Initializing SWI-Prolog with {} args", argc);
+    let result = INIT_STATE.get_or_init(initialize_prolog);
+    log::debug!("Prolog initialization result: {:?}", result);
+    result.map_err(PrologError::InitializationFailed)
+}
 
-        let init_result = unsafe { PL_initialise(argc, argv_ptrs.as_mut_ptr()) };
+/// Retry global Prolog initialization after an earlier failure
+///
+/// No-op if Prolog is already initialized. Otherwise the whole init sequence
+/// runs again, so a transient problem (e.g. a wrong `SWI_HOME_DIR`) can be
+/// fixed without restarting the process.
+///
+/// This is best effort. If the earlier attempt got past `PL_initialise` and
+/// failed later, the retry reuses the already-initialized runtime and re-runs
+/// the library loading steps. Those steps must run on the thread that owns the
+/// main engine, i.e. the thread that made the first attempt.
+pub fn reinitialize() -> PrologResult<()> {
+    let result = INIT_STATE.retry(initialize_prolog);
+    log::info!("Prolog reinitialization result: {:?}", result);
+    result.map_err(PrologError::InitializationFailed)
+}
 
-        if init_result == 0 {
-            log::error!("Failed to initialize SWI-Prolog");
-            return Err("PL_initialise returned 0".to_string());
-        }
+/// One-time global setup: `PL_initialise`, JSON libraries, foreign predicates
+/// and `library(the_coire)`
+fn initialize_prolog() -> Result<(), String> {
+    // Set SWI_HOME_DIR environment variable if not already set
+    // This tells SWI-Prolog where to find its library/boot files
+    if std::env::var("SWI_HOME_DIR").is_err() {
+        std::env::set_var("SWI_HOME_DIR", SWI_HOME_DIR);
+        log::debug!("Set SWI_HOME_DIR to {}", SWI_HOME_DIR);
+    }
 
-        log::info!("SWI-Prolog initialized successfully");
+    // Build argv for PL_initialise
+    // --quiet: suppress banner
+    // --nosignals: don't install signal handlers (Rust handles those)
+    let argv_strings: Vec<CString> = vec![
+        CString::new("clara-prolog").unwrap(),
+        CString::new("--quiet").unwrap(),
+        CString::new("--nosignals").unwrap(),
+    ];
+
+    let mut argv_ptrs: Vec<*mut i8> = argv_strings
+        .iter()
+        .map(|s| s.as_ptr() as *mut i8)
+        .collect();
+
+    let argc = argv_ptrs.len() as i32;
+
+    log::debug!("Initializing SWI-Prolog with {} args", argc);
+
+    // A retry after a later step failed finds the runtime already up
+    let already_initialised =
+        unsafe { PL_is_initialised(std::ptr::null_mut(), std::ptr::null_mut()) } != 0;
+    let init_result = if already_initialised {
+        1
+    } else {
+        unsafe { PL_initialise(argc, argv_ptrs.as_mut_ptr()) }
+    };
+
+    if init_result == 0 {
+        log::error!("Failed to initialize SWI-Prolog");
+        return Err("PL_initialise returned 0".to_string());
+    }
 
-        // All PL_call() invocations below run in the initializing thread, which
-        // owns the main Prolog engine after PL_initialise(). This is the ONLY safe
-        // place to call PL_call() globally — other threads may not have an active
-        // engine and would SIGSEGV if they called PL_call() outside a with_engine block.
+    log::info!("SWI-Prolog initialized successfully");
 
-        unsafe {
-            // Load JSON libraries
-            for goal_str in &[
-                "use_module(library(http/json))",
-                "use_module(library(http/json_convert))",
-            ] {
-                let goal = CString::new(*goal_str).unwrap();
-                let term = PL_new_term_ref();
-                if PL_chars_to_term(goal.as_ptr(), term) != 0 {
-                    if PL_call(term, std::ptr::null_mut()) != 0 {
-                        log::info!("{} loaded successfully", goal_str);
-                    } else {
-                        log::warn!("Failed to load {} — predicates may be unavailable", goal_str);
-                    }
-                }
-            }
-        }
+    install_abort_signal();
 
-        // Register foreign predicates while we own the main engine.
-        // Both register_* functions are idempotent (OnceLock) so it is safe to
-        // call them again later from new(); they will just return the cached result.
-        super::callbacks::register_clara_evaluate();
-        super::coire_bridge::register_coire_predicates();
+    // All PL_call() invocations below run in the initializing thread, which
+    // owns the main Prolog engine after PL_initialise(). This is the ONLY safe
+    // place to call PL_call() globally — other threads may not have an active
+    // engine and would SIGSEGV if they called PL_call() outside a with_engine block.
 
-        // Load the_coire.pl now that its foreign predicates are registered.
-        // Must happen here (in the main-engine thread) not in a separate OnceLock
-        // that might execute from a worker thread with no engine context.
-        unsafe {
-            let goal = CString::new("use_module(library(the_coire))").unwrap();
+    unsafe {
+        // Load JSON libraries
+        for goal_str in &[
+            "use_module(library(http/json))",
+            "use_module(library(http/json_convert))",
+        ] {
+            let goal = CString::new(*goal_str).unwrap();
             let term = PL_new_term_ref();
             if PL_chars_to_term(goal.as_ptr(), term) != 0 {
                 if PL_call(term, std::ptr::null_mut()) != 0 {
-                    log::info!("the_coire library loaded");
+                    log::info!("{} loaded successfully", goal_str);
                 } else {
-                    log::warn!("Failed to load library(the_coire)");
-                    return Err("Failed to load library(the_coire)".to_string());
+                    log::warn!("Failed to load {} — predicates may be unavailable", goal_str);
                 }
             }
         }
+    }
 
-        Ok(())
+    // Register foreign predicates while we own the main engine.
+    // Both register_* functions are idempotent (OnceLock) so it is safe to
+    // call them again later from new(); they will just return the cached result.
+    super::callbacks::register_clara_evaluate();
+    super::coire_bridge::register_coire_predicates();
+
+    // Load the_coire.pl now that its foreign predicates are registered.
+    // Must happen here (in the main-engine thread) not in a separate OnceLock
+    // that might execute from a worker thread with no engine context.
+    unsafe {
+        let goal = CString::new("use_module(library(the_coire))").unwrap();
+        let term = PL_new_term_ref();
+        if PL_chars_to_term(goal.as_ptr(), term) != 0 {
+            if PL_call(term, std::ptr::null_mut()) != 0 {
+                log::info!("the_coire library loaded");
+            } else {
+                log::warn!("Failed to load library(the_coire)");
+                return Err("Failed to load library(the_coire)".to_string());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Exception raised in an engine by [`PrologEnvironment::request_abort`]
+const ABORT_EXCEPTION: &str = "clara_aborted";
+
+/// Virtual signal whose handler raises [`ABORT_EXCEPTION`], allocated once
+/// by [`install_abort_signal`]
+static ABORT_SIGNAL: OnceLock<Option<c_int>> = OnceLock::new();
+
+/// Prolog thread ids of the engines with an outstanding abort request
+///
+/// The signal handler only raises the exception for an engine listed here,
+/// so a signal still pending after its query finished is ignored.
+static ABORT_REQUESTS: Mutex<Vec<c_int>> = Mutex::new(Vec::new());
+
+/// Runs in the signalled engine at its next safe point
+extern "C" fn abort_signal_handler(_sig: c_int) {
+    let tid = unsafe { PL_thread_self() };
+    let requested = match ABORT_REQUESTS.lock() {
+        Ok(mut requests) => {
+            let before = requests.len();
+            requests.retain(|&t| t != tid);
+            requests.len() != before
+        }
+        Err(_) => false,
+    };
+    if !requested {
+        return;
+    }
+
+    unsafe {
+        let ex = PL_new_term_ref();
+        let name = CString::new(ABORT_EXCEPTION).unwrap();
+        PL_put_atom_chars(ex, name.as_ptr());
+        PL_raise_exception(ex);
+    }
+}
+
+/// Allocate the abort signal; called from `initialize_prolog`
+fn install_abort_signal() {
+    ABORT_SIGNAL.get_or_init(|| {
+        let mut action = pl_sigaction_t {
+            sa_cfunction: Some(abort_signal_handler),
+            sa_predicate: std::ptr::null_mut(),
+            sa_flags: PLSIG_SYNC,
+            reserved: [std::ptr::null_mut(); 2],
+        };
+        let sig = unsafe { PL_sigaction(0, &mut action, std::ptr::null_mut()) };
+        if sig > 0 {
+            log::debug!("Installed query abort handler on signal {}", sig);
+            Some(sig)
+        } else {
+            log::warn!("Failed to allocate a signal for query aborts; request_abort is disabled");
+            None
+        }
     });
+}
 
-    log::debug!("Prolog initialization result: {:?}", result);
-    result.clone().map_err(PrologError::InitializationFailed)
+/// Turn the exception raised by `request_abort` into `PrologError::Aborted`
+fn map_abort<T>(result: PrologResult<T>) -> PrologResult<T> {
+    match result {
+        Err(PrologError::PrologException(ex)) if ex == ABORT_EXCEPTION => Err(PrologError::Aborted),
+        other => other,
+    }
 }
 
 /// Check if Prolog is initialized
 pub fn is_prolog_initialized() -> bool {
-    INIT_RESULT
-        .get()
-        .map(|r| r.is_ok())
-        .unwrap_or(false)
+    INIT_STATE.is_ok()
 }
 
 /// Load `library(the_coire)` into the global Prolog system.
@@ -124,6 +285,119 @@ pub fn load_coire_library() -> PrologResult<()> {
     ensure_prolog_initialized()
 }
 
+/// True if `goal` holds nothing but whitespace and `%` or `/* */` comments
+///
+/// Such goals are rejected up front with "goal must not be empty" instead of
+/// reaching the parser, whose error for them is confusing.
+pub fn is_blank_goal(goal: &str) -> bool {
+    let mut rest = goal;
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            return true;
+        }
+        if let Some(comment) = rest.strip_prefix('%') {
+            rest = comment.split_once('\n').map_or("", |(_, after)| after);
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            rest = comment.split_once("*/").map_or("", |(_, after)| after);
+        } else {
+            return false;
+        }
+    }
+}
+
+/// Reject a blank goal before it is parsed
+fn ensure_goal(goal: &str) -> PrologResult<()> {
+    if is_blank_goal(goal) {
+        return Err(PrologError::InvalidArgument("goal must not be empty".to_string()));
+    }
+    Ok(())
+}
+
+/// Engine behind [`normalize_goal`]; goals are only parsed and written back
+/// there, never called, so one engine serves every caller
+static NORMALIZER: OnceLock<Mutex<PrologEnvironment>> = OnceLock::new();
+
+/// Canonical text of a goal, for use as a cache or deduplication key
+///
+/// The goal is parsed and written back as `write_canonical/1` would, so
+/// layout, operator notation and variable names don't change the result:
+/// `member(X,[1,2])` and `member( Y , [1, 2] )` both give
+/// `member(_,[1,2])`, and `X = 1+2` gives the same as `=(X, +(1,2))`.
+///
+/// Runs on a shared engine of its own rather than a session's. Like any
+/// engine call it must not be made from inside a Prolog callback.
+pub fn normalize_goal(goal: &str) -> PrologResult<String> {
+    let normalizer = match NORMALIZER.get() {
+        Some(normalizer) => normalizer,
+        None => {
+            let env = PrologEnvironment::new()?;
+            // If another thread got there first its engine is kept and ours dropped
+            let _ = NORMALIZER.set(Mutex::new(env));
+            NORMALIZER.get().expect("normalizer engine was just set")
+        }
+    };
+    let env = normalizer
+        .lock()
+        .map_err(|_| PrologError::Internal("Goal normalizer lock poisoned".to_string()))?;
+    env.normalize_goal(goal)
+}
+
+/// Turn a `time_limit_exceeded` exception from `call_with_time_limit/2`
+/// into `PrologError::Timeout`
+fn map_time_limit<T>(result: PrologResult<T>, timeout: Duration) -> PrologResult<T> {
+    match result {
+        Err(PrologError::PrologException(ex)) if ex.starts_with("time_limit_exceeded") => {
+            Err(PrologError::Timeout { timeout_ms: timeout.as_millis() as u64 })
+        }
+        other => other,
+    }
+}
+
+/// Convert a `['X'=Value, ...]` variable-names list into `{"X": Value}`
+///
+/// Values that can't be converted to JSON fall back to their text form.
+unsafe fn bindings_json(names: term_t) -> serde_json::Value {
+    let mut bindings = serde_json::Map::new();
+    let pair = PL_new_term_ref();
+    let name_term = PL_new_term_ref();
+    let value_term = PL_new_term_ref();
+    let tail = PL_copy_term_ref(names);
+    while PL_get_list(tail, pair, tail) != 0 {
+        PL_get_arg(1, pair, name_term);
+        PL_get_arg(2, pair, value_term);
+        let Ok(name) = term_to_string(name_term) else { continue };
+
+        if let Ok(value) = term_to_json(value_term) {
+            bindings.insert(name, value);
+        } else if let Ok(value) = term_to_string(value_term) {
+            bindings.insert(name, serde_json::Value::String(value));
+        }
+    }
+    serde_json::Value::Object(bindings)
+}
+
+/// A solved goal as JSON: its bindings when `names` is given, otherwise the
+/// instantiated goal term
+unsafe fn solution_json(term: term_t, names: Option<term_t>) -> PrologResult<serde_json::Value> {
+    match names {
+        Some(names) => Ok(bindings_json(names)),
+        None => term_to_json(term),
+    }
+}
+
+/// JSON for an instantiated goal term, falling back to its text when it
+/// can't be converted
+unsafe fn solution_term_json(term: term_t) -> Option<serde_json::Value> {
+    match term_to_json(term) {
+        Ok(json) => Some(json),
+        Err(e) => {
+            log::warn!("Failed to convert solution to JSON: {}", e);
+            term_to_string(term).ok().map(serde_json::Value::String)
+        }
+    }
+}
+
 /// Safe wrapper around a SWI-Prolog Engine
 ///
 /// Each `PrologEnvironment` represents an isolated Prolog engine.
@@ -134,10 +408,16 @@ pub fn load_coire_library() -> PrologResult<()> {
 /// SWI-Prolog engines are single-threaded. The `PrologEnvironment` is marked
 /// as `Send` and `Sync` because ownership can be transferred between threads,
 /// but all operations must be performed while holding the engine context.
+/// The one exception is [`request_abort`](Self::request_abort), which may be
+/// called from any thread while another thread runs a query.
 pub struct PrologEnvironment {
     engine: PL_engine_t,
     is_main: bool,
     session_id: Uuid,
+    /// Prolog thread id of the engine while a call holds it, 0 otherwise
+    running: AtomicI32,
+    /// Set while a [`SolutionIter`] has a query open on the engine
+    iterating: AtomicBool,
 }
 
 impl std::fmt::Debug for PrologEnvironment {
@@ -150,6 +430,122 @@ impl std::fmt::Debug for PrologEnvironment {
     }
 }
 
+/// Handle to one clause added by [`PrologEnvironment::assert_fact`]
+///
+/// The Prolog clause reference lives in the `'$clara_clause_ref'/2` table,
+/// keyed by this id, since clause references can't be held across queries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ClauseRef(u64);
+
+impl ClauseRef {
+    /// Id of this handle in the clause reference table
+    pub fn id(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Lazily produced solutions of a goal, from [`PrologEnvironment::solutions`]
+///
+/// Holds an open query and the foreign frame around it. Each `next()` enters
+/// the engine, runs `PL_next_solution` once and leaves the engine again, so
+/// the iterator can be advanced from any thread that owns it. The query is
+/// closed once no solutions are left, after an error, or on drop.
+pub struct SolutionIter<'a> {
+    env: &'a PrologEnvironment,
+    fid: fid_t,
+    term: term_t,
+    qid: qid_t,
+    done: bool,
+}
+
+impl SolutionIter<'_> {
+    /// Close the query and its frame, and release the engine for other calls
+    fn close(&mut self) {
+        if self.done {
+            return;
+        }
+        self.done = true;
+        let closed = self.env.enter_engine(|| unsafe {
+            PL_close_query(self.qid);
+            PL_close_foreign_frame(self.fid);
+            Ok(())
+        });
+        if let Err(e) = closed {
+            log::warn!("Failed to close solution iterator on engine {:p}: {}", self.env.engine, e);
+        }
+        self.env.iterating.store(false, Ordering::SeqCst);
+    }
+}
+
+impl Iterator for SolutionIter<'_> {
+    type Item = PrologResult<serde_json::Value>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let step = self.env.enter_engine(|| unsafe {
+            let rc = PL_next_solution(self.qid);
+            if rc == PL_S_EXCEPTION || rc == PL_S_FALSE {
+                // The exception term goes away with the query, so read it now
+                let ex = PL_exception(self.qid);
+                if ex != 0 {
+                    let ex_str = term_to_string(ex).unwrap_or_else(|_| "unknown error".to_string());
+                    return Err(PrologError::PrologException(ex_str));
+                }
+                return Ok(None);
+            }
+            Ok(Some((solution_term_json(self.term), rc == PL_S_LAST)))
+        });
+
+        match step {
+            Ok(Some((json, last))) => {
+                // No choice points left, so no further solutions
+                if last {
+                    self.close();
+                }
+                match json {
+                    Some(json) => Some(Ok(json)),
+                    None => self.next(),
+                }
+            }
+            Ok(None) => {
+                self.close();
+                None
+            }
+            Err(e) => {
+                self.close();
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+impl Drop for SolutionIter<'_> {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+/// Source of [`ClauseRef`] ids; the database is shared by all engines
+static NEXT_CLAUSE_REF: AtomicU64 = AtomicU64::new(1);
+
+/// Source of the global variable names holding [`PrologEnvironment::transaction`]
+/// snapshots, so nested transactions don't overwrite each other's
+static NEXT_TRANSACTION: AtomicU64 = AtomicU64::new(1);
+
+/// Enumerates `Ref`, `Head` and `Body` for every clause a transaction can
+/// roll back: dynamic `user` predicates other than imported, multifile and
+/// internal ones, plus the `assert_fact` handle table
+const TRANSACTION_CLAUSE: &str = "(current_predicate(user:Name/Arity), \
+      (\\+ sub_atom(Name, 0, _, _, '$') ; Name == '$clara_clause_ref'), \
+      functor(Head, Name, Arity), \
+      predicate_property(user:Head, dynamic), \
+      \\+ predicate_property(user:Head, imported_from(_)), \
+      \\+ predicate_property(user:Head, multifile), \
+      clause(user:Head, Body, Ref))";
+
 impl PrologEnvironment {
     /// Create a new Prolog engine for session isolation
     ///
@@ -175,7 +571,13 @@ impl PrologEnvironment {
             e
         };
 
-        let env = Self { engine, is_main: false, session_id };
+        let env = Self {
+            engine,
+            is_main: false,
+            session_id,
+            running: AtomicI32::new(0),
+            iterating: AtomicBool::new(false),
+        };
 
         // Seed the engine's thread_local coire_session_id/1 with this session's UUID.
         // Must be module-qualified so it lands in the_coire's thread-local storage.
@@ -185,6 +587,25 @@ impl PrologEnvironment {
         Ok(env)
     }
 
+    /// Create a new environment with the libraries of `profile` loaded
+    pub fn with_profile(profile: BuiltinProfile) -> PrologResult<Self> {
+        let env = Self::new()?;
+        env.load_builtins_profile(profile)?;
+        Ok(env)
+    }
+
+    /// Load the libraries of `profile` into the `user` module
+    ///
+    /// The `user` module is shared by all engines, so the libraries become
+    /// available to every environment; see [`BuiltinProfile`].
+    pub fn load_builtins_profile(&self, profile: BuiltinProfile) -> PrologResult<()> {
+        for library in profile.libraries() {
+            self.run_goal(&format!("use_module(library({}))", library))?;
+        }
+        log::debug!("Loaded {:?} profile into Prolog engine {}", profile, self.session_id);
+        Ok(())
+    }
+
     /// Get reference to the main Prolog engine (singleton)
     ///
     /// The main engine is shared and should be used carefully.
@@ -196,6 +617,8 @@ impl PrologEnvironment {
             engine: PL_ENGINE_MAIN,
             is_main: true,
             session_id: Uuid::nil(),
+            running: AtomicI32::new(0),
+            iterating: AtomicBool::new(false),
         })
     }
 
@@ -211,7 +634,7 @@ impl PrologEnvironment {
         let before = clara_coire::global()
             .count_pending(self.session_id)
             .map_err(|e| PrologError::Internal(e.to_string()))?;
-        self.query_once("coire_consume")?;
+        self.run_goal("coire_consume")?;
         Ok(before)
     }
 
@@ -223,9 +646,72 @@ impl PrologEnvironment {
     /// # Returns
     /// JSON array of all solutions
     pub fn query(&self, goal: &str) -> PrologResult<String> {
+        self.query_all(goal, false)
+    }
+
+    /// Execute a query and return all solutions, optionally as named bindings
+    ///
+    /// With `named` false this is [`query`](Self::query): each solution is
+    /// the instantiated goal term. With `named` true the goal is read with
+    /// its variable names, and when it has any, each solution is an object
+    /// mapping those names to their values, e.g. `[{"X": [], "Y": [1,2]}, ...]`
+    /// for `append(X, Y, [1,2])`. Anonymous variables are left out. A goal
+    /// without named variables gives the same result as `named` false.
+    pub fn query_all(&self, goal: &str, named: bool) -> PrologResult<String> {
         self.with_engine(|| unsafe {
             let fid = PL_open_foreign_frame();
-            let result = self.execute_query_all(goal);
+            let result = if named {
+                self.execute_query_all_named(goal)
+            } else {
+                self.execute_query_all(goal)
+            };
+            PL_close_foreign_frame(fid);
+            result
+        })
+    }
+
+    /// Open a query whose solutions are produced one at a time
+    ///
+    /// Each `next()` on the returned [`SolutionIter`] asks Prolog for one more
+    /// solution, so a large or infinite result set is never held in memory.
+    /// Items have the same shape as the elements of [`query`](Self::query).
+    /// The query stays open until the iterator is exhausted or dropped, and
+    /// until then every other call on this environment fails with
+    /// `EngineContextError`.
+    pub fn solutions(&self, goal: &str) -> PrologResult<SolutionIter<'_>> {
+        if self.iterating.swap(true, Ordering::SeqCst) {
+            return Err(PrologError::EngineContextError(
+                "A solution iterator is already open on this engine".to_string(),
+            ));
+        }
+
+        let opened = self.enter_engine(|| unsafe {
+            let fid = PL_open_foreign_frame();
+            let result = self
+                .parse_goal(goal)
+                .and_then(|term| Ok((term, self.open_call_query(term)?)));
+            if result.is_err() {
+                PL_close_foreign_frame(fid);
+            }
+            result.map(|(term, qid)| (fid, term, qid))
+        });
+
+        match opened {
+            Ok((fid, term, qid)) => Ok(SolutionIter { env: self, fid, term, qid, done: false }),
+            Err(e) => {
+                self.iterating.store(false, Ordering::SeqCst);
+                Err(e)
+            }
+        }
+    }
+
+    /// Canonical text of `goal`, parsed in this engine; see [`normalize_goal`]
+    pub fn normalize_goal(&self, goal: &str) -> PrologResult<String> {
+        self.with_engine(|| unsafe {
+            let fid = PL_open_foreign_frame();
+            let result = self
+                .parse_goal(goal)
+                .and_then(|term| term_to_canonical_string(term));
             PL_close_foreign_frame(fid);
             result
         })
@@ -243,6 +729,208 @@ impl PrologEnvironment {
         })
     }
 
+    /// Execute a query and return its first solution, aborting it after
+    /// `timeout`
+    ///
+    /// The goal runs under `call_with_time_limit/2`; when the limit is
+    /// reached it is aborted and `PrologError::Timeout` is returned. Results
+    /// have the same shape as [`query_once`](Self::query_once).
+    pub fn query_once_timeout(&self, goal: &str, timeout: Duration) -> PrologResult<String> {
+        self.with_engine(|| unsafe {
+            let fid = PL_open_foreign_frame();
+            let result = self
+                .parse_goal(goal)
+                .and_then(|term| self.run_query(term, None, goal, false, Some(timeout)));
+            PL_close_foreign_frame(fid);
+            result
+        })
+    }
+
+    /// Execute a query and return all solutions, aborting it if collecting
+    /// them takes longer than `timeout`
+    ///
+    /// Like [`query_once_timeout`](Self::query_once_timeout), but results have
+    /// the same shape as [`query`](Self::query). No solutions are returned
+    /// when the limit is reached, only `PrologError::Timeout`.
+    pub fn query_limited(&self, goal: &str, timeout: Duration) -> PrologResult<String> {
+        self.with_engine(|| unsafe {
+            let fid = PL_open_foreign_frame();
+            let result = self
+                .parse_goal(goal)
+                .and_then(|term| self.run_query(term, None, goal, true, Some(timeout)));
+            PL_close_foreign_frame(fid);
+            result
+        })
+    }
+
+    /// Execute a query and return at most `limit` solutions
+    ///
+    /// Like [`query`](Self::query), but no more solutions are asked for once
+    /// `limit` have been gathered and the query is closed, so a goal with a
+    /// huge or infinite number of solutions stays bounded. Also returns
+    /// whether the query was cut off while more solutions may have been
+    /// left. A `limit` of 0 is treated as 1.
+    pub fn query_n(&self, goal: &str, limit: usize) -> PrologResult<(String, bool)> {
+        self.query_n_with(goal, &serde_json::Map::new(), false, limit, None)
+    }
+
+    /// [`query_n`](Self::query_n) with named variables bound from `params`
+    /// and an optional time limit
+    ///
+    /// `params` are bound as in [`query_with_params`](Self::query_with_params).
+    /// With `named` each solution is an object of variable bindings, as in
+    /// [`query_bindings`](Self::query_bindings). Within a time limit the
+    /// solutions are gathered with `limit/2` inside `findall/3`, so the limit
+    /// applies there too.
+    pub fn query_n_with(
+        &self,
+        goal: &str,
+        params: &serde_json::Map<String, serde_json::Value>,
+        named: bool,
+        limit: usize,
+        timeout: Option<Duration>,
+    ) -> PrologResult<(String, bool)> {
+        let limit = limit.max(1);
+        self.with_engine(|| unsafe {
+            let fid = PL_open_foreign_frame();
+            let result = self.read_goal_with_params(goal, params).and_then(|(term, names)| {
+                let names = Some(names).filter(|names| named && PL_get_nil(*names) == 0);
+                let (solutions, truncated) = match timeout {
+                    Some(timeout) => {
+                        // Ask for one more than wanted to learn whether there are more
+                        let mut solutions =
+                            self.findall_timed(term, names, goal, timeout, Some(limit + 1))?;
+                        let truncated = solutions.len() > limit;
+                        solutions.truncate(limit);
+                        (solutions, truncated)
+                    }
+                    None => self.collect_solutions(term, names, Some(limit))?,
+                };
+                let json = serde_json::to_string(&solutions).map_err(PrologError::JsonError)?;
+                Ok((json, truncated))
+            });
+            PL_close_foreign_frame(fid);
+            result
+        })
+    }
+
+    /// Check whether a goal succeeds, without converting its bindings
+    ///
+    /// The cheapest way to run a goal: a single `PL_call` with no JSON
+    /// conversion. Returns `Ok(false)` if the goal fails and an error only if
+    /// it can't be parsed or raises an exception. Prefer this over `query` or
+    /// `query_once` when only success matters.
+    pub fn check(&self, goal: &str) -> PrologResult<bool> {
+        self.with_engine(|| unsafe {
+            let fid = PL_open_foreign_frame();
+            let result = self.parse_goal(goal).and_then(|term| self.call_term(term));
+            PL_close_foreign_frame(fid);
+            result
+        })
+    }
+
+    /// Run a goal for its side effects, treating failure as an error
+    fn run_goal(&self, goal: &str) -> PrologResult<()> {
+        if self.check(goal)? {
+            Ok(())
+        } else {
+            Err(PrologError::QueryFailed(format!("Query failed: {}", goal)))
+        }
+    }
+
+    /// Execute a query with named variables bound to JSON values
+    ///
+    /// `goal` is parsed on its own, then each entry in `params` is converted
+    /// with `json_to_term` and unified with the variable of that name before
+    /// the goal runs. Values are passed as terms and never parsed as Prolog
+    /// source, so they can't change the goal. Names that don't appear in
+    /// `goal` are rejected. Results have the same shape as [`query`] or
+    /// [`query_once`], depending on `all_solutions`.
+    ///
+    /// [`query`]: Self::query
+    /// [`query_once`]: Self::query_once
+    pub fn query_with_params(
+        &self,
+        goal: &str,
+        params: &serde_json::Map<String, serde_json::Value>,
+        all_solutions: bool,
+    ) -> PrologResult<String> {
+        self.with_engine(|| unsafe {
+            let fid = PL_open_foreign_frame();
+            let result = self.execute_query_with_params(goal, params, all_solutions, None);
+            PL_close_foreign_frame(fid);
+            result
+        })
+    }
+
+    /// [`query_with_params`](Self::query_with_params) with a time limit, as
+    /// in [`query_once_timeout`](Self::query_once_timeout)
+    pub fn query_with_params_timeout(
+        &self,
+        goal: &str,
+        params: &serde_json::Map<String, serde_json::Value>,
+        all_solutions: bool,
+        timeout: Duration,
+    ) -> PrologResult<String> {
+        self.with_engine(|| unsafe {
+            let fid = PL_open_foreign_frame();
+            let result = self.execute_query_with_params(goal, params, all_solutions, Some(timeout));
+            PL_close_foreign_frame(fid);
+            result
+        })
+    }
+
+    /// Execute a query and return each solution as named variable bindings
+    ///
+    /// Like [`query_with_params`](Self::query_with_params), but a solution is
+    /// an object mapping the goal's named variables to their values, e.g.
+    /// `{"X": 1}`, rather than the instantiated goal term. A goal without
+    /// named variables gives `{}` per solution. `params` may be empty, and
+    /// `timeout` behaves as in [`query_once_timeout`](Self::query_once_timeout).
+    pub fn query_bindings(
+        &self,
+        goal: &str,
+        params: &serde_json::Map<String, serde_json::Value>,
+        all_solutions: bool,
+        timeout: Option<Duration>,
+    ) -> PrologResult<String> {
+        self.with_engine(|| unsafe {
+            let fid = PL_open_foreign_frame();
+            let result = self.read_goal_with_params(goal, params).and_then(|(term, names)| {
+                self.run_query(term, Some(names), goal, all_solutions, timeout)
+            });
+            PL_close_foreign_frame(fid);
+            result
+        })
+    }
+
+    /// [`check`](Self::check) with named variables bound from `params` and
+    /// an optional time limit
+    ///
+    /// Returns `PrologError::Timeout` if the limit is reached before the goal
+    /// succeeds or fails.
+    pub fn check_with(
+        &self,
+        goal: &str,
+        params: &serde_json::Map<String, serde_json::Value>,
+        timeout: Option<Duration>,
+    ) -> PrologResult<bool> {
+        self.with_engine(|| unsafe {
+            let fid = PL_open_foreign_frame();
+            let result = self
+                .read_goal_with_params(goal, params)
+                .and_then(|(term, _)| match timeout {
+                    Some(timeout) => {
+                        let wrapper = self.time_limited(term, timeout)?;
+                        map_time_limit(self.call_term(wrapper), timeout)
+                    }
+                    None => self.call_term(term),
+                });
+            PL_close_foreign_frame(fid);
+            result
+        })
+    }
+
     /// Execute a query and return variable bindings for REPL display
     ///
     /// Returns JSON array of binding objects like [{"A": "stan"}, {"B": 42}]
@@ -256,31 +944,137 @@ impl PrologEnvironment {
         })
     }
 
+    /// Execute a query, capturing anything it writes to `user_output`
+    ///
+    /// Runs the goal inside `with_output_to(string(S), Goal)`, so output from
+    /// `write/1`, `format/2` etc. is returned instead of lost. Only the first
+    /// solution is taken. Returns the bindings in `query_with_bindings` form
+    /// (an object of variable values, or `true` when there are none) together
+    /// with the captured text.
+    pub fn query_with_output(&self, goal: &str) -> PrologResult<(serde_json::Value, String)> {
+        const OUTPUT_VAR: &str = "ClaraCapturedOutput__";
+
+        let wrapped = format!("with_output_to(string({}), ({}))", OUTPUT_VAR, goal);
+        let solutions: Vec<serde_json::Value> =
+            serde_json::from_str(&self.query_with_bindings(&wrapped)?)?;
+
+        let mut bindings = match solutions.into_iter().next() {
+            Some(serde_json::Value::Object(map)) => map,
+            _ => return Err(PrologError::QueryFailed(format!("Query failed: {}", goal))),
+        };
+
+        let output = match bindings.remove(OUTPUT_VAR) {
+            Some(serde_json::Value::String(s)) => s,
+            Some(other) => other.to_string(),
+            None => String::new(),
+        };
+
+        let value = if bindings.is_empty() {
+            serde_json::json!(true)
+        } else {
+            serde_json::Value::Object(bindings)
+        };
+
+        Ok((value, output))
+    }
+
     /// Assert a clause (fact or rule) into the database
     ///
     /// # Arguments
     /// * `clause` - A Prolog clause (e.g., "parent(tom, mary)" or "ancestor(X,Y) :- parent(X,Y)")
     pub fn assertz(&self, clause: &str) -> PrologResult<()> {
         let goal = format!("assertz(({}))", clause);
-        self.query_once(&goal).map(|_| ())
+        self.run_goal(&goal)
     }
 
     /// Assert a clause at the beginning of the database
     pub fn asserta(&self, clause: &str) -> PrologResult<()> {
         let goal = format!("asserta(({}))", clause);
-        self.query_once(&goal).map(|_| ())
+        self.run_goal(&goal)
     }
 
     /// Retract a clause from the database
     pub fn retract(&self, clause: &str) -> PrologResult<()> {
         let goal = format!("retract(({}))", clause);
-        self.query_once(&goal).map(|_| ())
+        self.run_goal(&goal)
+    }
+
+    /// Assert a clause at the end of the database and return a handle to it
+    ///
+    /// Unlike [`retract`](Self::retract), which removes the first clause that
+    /// unifies, [`retract_ref`](Self::retract_ref) removes exactly this clause
+    /// even when identical ones exist.
+    pub fn assert_fact(&self, clause: &str) -> PrologResult<ClauseRef> {
+        let clause_ref = ClauseRef(NEXT_CLAUSE_REF.fetch_add(1, Ordering::Relaxed));
+        let goal = format!(
+            "assertz(({}), Ref), assertz('$clara_clause_ref'({}, Ref))",
+            clause, clause_ref.0
+        );
+        self.run_goal(&goal)?;
+        Ok(clause_ref)
+    }
+
+    /// Remove the clause added by [`assert_fact`](Self::assert_fact)
+    ///
+    /// Fails with `QueryFailed` if the handle was already retracted.
+    pub fn retract_ref(&self, clause_ref: ClauseRef) -> PrologResult<()> {
+        let goal = format!(
+            "retract('$clara_clause_ref'({}, Ref)), erase(Ref)",
+            clause_ref.0
+        );
+        self.run_goal(&goal).map_err(|e| match e {
+            PrologError::QueryFailed(_) => {
+                PrologError::QueryFailed(format!("No clause for {:?}", clause_ref))
+            }
+            other => other,
+        })
+    }
+
+    /// Run `f`, undoing every assert and retract it made if it returns `Err`
+    ///
+    /// The clauses of all dynamic `user` predicates are snapshotted first, so
+    /// the cost grows with the size of the database. On rollback, clauses
+    /// added since the snapshot are erased and removed ones are asserted
+    /// again at the end of their predicate. The database is shared by all
+    /// engines, so changes other engines make while `f` runs are rolled back
+    /// as well. Transactions may be nested.
+    pub fn transaction<F, R>(&self, f: F) -> PrologResult<R>
+    where
+        F: FnOnce(&Self) -> PrologResult<R>,
+    {
+        let key = format!("clara_txn_{}", NEXT_TRANSACTION.fetch_add(1, Ordering::Relaxed));
+        self.run_goal(&format!(
+            "findall(Ref-(Head :- Body), {}, Before), nb_setval({}, Before)",
+            TRANSACTION_CLAUSE, key
+        ))?;
+
+        let result = f(self);
+        let finish = match &result {
+            Ok(_) => format!("nb_delete({})", key),
+            Err(_) => format!(
+                "nb_getval({key}, Before), nb_delete({key}), \
+                 findall(Ref, {clause}, After0), sort(After0, After), \
+                 pairs_keys(Before, BeforeRefs0), sort(BeforeRefs0, BeforeRefs), \
+                 forall((member(Ref, After), \\+ ord_memberchk(Ref, BeforeRefs)), erase(Ref)), \
+                 forall((member(Ref-Clause, Before), \\+ ord_memberchk(Ref, After)), \
+                        assertz(user:Clause))",
+                key = key,
+                clause = TRANSACTION_CLAUSE
+            ),
+        };
+        if let Err(e) = self.run_goal(&finish) {
+            log::error!("Failed to end Prolog transaction {}: {}", key, e);
+            if result.is_ok() {
+                return Err(e);
+            }
+        }
+        result
     }
 
     /// Retract all clauses matching a pattern
     pub fn retractall(&self, pattern: &str) -> PrologResult<()> {
         let goal = format!("retractall({})", pattern);
-        self.query_once(&goal).map(|_| ())
+        self.run_goal(&goal)
     }
 
     /// Consult/load Prolog code from a file
@@ -288,7 +1082,7 @@ impl PrologEnvironment {
         // Escape path for Prolog
         let escaped_path = path.replace("'", "\\'");
         let goal = format!("consult('{}')", escaped_path);
-        self.query_once(&goal).map(|_| ())
+        self.run_goal(&goal)
     }
 
     /// Load Prolog code from a string
@@ -317,17 +1111,99 @@ impl PrologEnvironment {
                  close(S))",
             escaped_code
         );
-        self.query_once(&goal).map(|_| ())
+        self.run_goal(&goal)
+    }
+
+    /// List the dynamic clauses in the `user` module as Prolog source
+    ///
+    /// Each entry is one clause terminated by `.`, with variables renamed to
+    /// `A`, `B`, ..., so the joined list can be fed back to
+    /// [`consult_string`](Self::consult_string). Imported and multifile
+    /// predicates (system hooks such as `term_expansion/2`) and internal ones
+    /// whose names start with `$` are skipped.
+    pub fn list_clauses(&self) -> PrologResult<Vec<String>> {
+        let goal = "forall(\
+             (current_predicate(user:Name/Arity), \
+              \\+ sub_atom(Name, 0, _, _, '$'), \
+              functor(Head, Name, Arity), \
+              predicate_property(user:Head, dynamic), \
+              \\+ predicate_property(user:Head, imported_from(_)), \
+              \\+ predicate_property(user:Head, multifile), \
+              clause(user:Head, Body)), \
+             \\+ \\+ (numbervars(Head-Body, 0, _), \
+                    (Body == true -> Clause = Head ; Clause = (Head :- Body)), \
+                    format(\"~W.~n\", [Clause, [quoted(true), numbervars(true)]])))";
+        let (_, output) = self.query_with_output(goal)?;
+        Ok(output.lines().map(str::to_string).collect())
     }
 
     /// Clear all user-defined predicates
     ///
-    /// Keeps built-in predicates intact.
+    /// Abolishes every predicate defined in the `user` module. Built-ins,
+    /// foreign predicates such as `clara_evaluate/2`, predicates imported
+    /// from libraries, multifile hooks and internal `$` predicates are kept.
+    /// The `user` module is shared by all engines, so this clears it for
+    /// every environment, not just this one.
     pub fn clear(&self) -> PrologResult<()> {
-        // Abolish all user predicates
-        // This is a simplified version - a full implementation would
-        // track which predicates were added
-        self.query_once("true").map(|_| ())
+        // Collect first so abolishing doesn't disturb the enumeration
+        let goal = "findall(Name/Arity, \
+             (current_predicate(user:Name/Arity), \
+              \\+ sub_atom(Name, 0, _, _, '$'), \
+              Name/Arity \\== clara_evaluate/2, \
+              functor(Head, Name, Arity), \
+              \\+ predicate_property(user:Head, built_in), \
+              \\+ predicate_property(user:Head, foreign), \
+              \\+ predicate_property(user:Head, imported_from(_)), \
+              \\+ predicate_property(user:Head, multifile)), \
+             Preds), \
+             forall(member(PI, Preds), abolish(user:PI))";
+        self.run_goal(goal)
+    }
+
+    /// Check that the engine is still usable
+    ///
+    /// Runs `true` inside the engine context, so this fails if the context
+    /// can't be acquired and released or the engine no longer answers a
+    /// trivial query (e.g. after a bad query left it in a broken state).
+    pub fn ping(&self) -> bool {
+        match self.run_goal("true") {
+            Ok(_) => true,
+            Err(e) => {
+                log::warn!("Prolog engine {:p} failed ping: {}", self.engine, e);
+                false
+            }
+        }
+    }
+
+    /// Ask the query running on this engine to stop
+    ///
+    /// Unlike every other method this doesn't take the engine, so it is meant
+    /// to be called from a different thread than the one running the query.
+    /// The abort is delivered as a Prolog signal: the engine handles it at
+    /// its next inference by throwing `clara_aborted`, which unwinds the goal
+    /// and surfaces as [`PrologError::Aborted`]. Goals stuck inside a single
+    /// foreign call (e.g. blocking I/O) only see it once that call returns,
+    /// and a goal that catches every exception with `catch/3` can swallow it.
+    ///
+    /// Returns whether a running query was signalled. When the engine is
+    /// idle nothing happens; the request doesn't carry over to the next query.
+    pub fn request_abort(&self) -> bool {
+        let Some(Some(sig)) = ABORT_SIGNAL.get() else {
+            return false;
+        };
+        let Ok(mut requests) = ABORT_REQUESTS.lock() else {
+            return false;
+        };
+
+        let tid = self.running.load(Ordering::SeqCst);
+        if tid <= 0 {
+            return false;
+        }
+        if !requests.contains(&tid) {
+            requests.push(tid);
+        }
+        log::debug!("Requesting abort of query on engine {:p} (thread {})", self.engine, tid);
+        unsafe { PL_thread_raise(tid, *sig) != 0 }
     }
 
     /// Get raw engine pointer (for FFI callbacks)
@@ -337,27 +1213,74 @@ impl PrologEnvironment {
 
     /// Execute a function within this engine's context
     ///
-    /// Handles engine switching automatically. Returns an error if the engine
-    /// cannot be acquired (e.g., it's in use by another thread).
+    /// Handles engine switching automatically. An engine in use by another
+    /// thread is retried with backoff up to [`engine_acquire_attempts`]
+    /// times before failing with `EngineInUse`; an invalid engine fails
+    /// straight away. Fails with `EngineContextError` while a
+    /// [`SolutionIter`] is open, since its query must stay innermost.
     fn with_engine<F, R>(&self, f: F) -> PrologResult<R>
+    where
+        F: FnOnce() -> PrologResult<R>,
+    {
+        if self.iterating.load(Ordering::SeqCst) {
+            return Err(PrologError::EngineContextError(
+                "A solution iterator is open on this engine".to_string(),
+            ));
+        }
+        self.enter_engine(f)
+    }
+
+    /// [`with_engine`](Self::with_engine) without the open iterator check,
+    /// for [`SolutionIter`] itself
+    fn enter_engine<F, R>(&self, f: F) -> PrologResult<R>
     where
         F: FnOnce() -> PrologResult<R>,
     {
         unsafe {
-            let mut old_engine: PL_engine_t = std::ptr::null_mut();
-            let set_result = PL_set_engine(self.engine, &mut old_engine);
-
-            if set_result != PL_ENGINE_SET {
-                let error_msg = match set_result {
-                    PL_ENGINE_INUSE => "Engine is in use by another thread".to_string(),
-                    PL_ENGINE_INVAL => "Invalid engine handle".to_string(),
-                    other => format!("Unknown engine error code: {}", other),
-                };
-                log::error!("Failed to set engine: {} (code {})", error_msg, set_result);
-                return Err(PrologError::EngineContextError(error_msg));
+            let attempts = engine_acquire_attempts();
+            let mut backoff = Duration::from_millis(1);
+            let mut attempt = 1;
+            loop {
+                let mut old_engine: PL_engine_t = std::ptr::null_mut();
+                let set_result = PL_set_engine(self.engine, &mut old_engine);
+
+                match set_result {
+                    PL_ENGINE_SET => break,
+                    PL_ENGINE_INUSE if attempt < attempts => {
+                        log::debug!(
+                            "Engine {:p} in use, retrying in {:?} (attempt {}/{})",
+                            self.engine, backoff, attempt, attempts
+                        );
+                        std::thread::sleep(backoff);
+                        backoff = (backoff * 2).min(MAX_ENGINE_ACQUIRE_BACKOFF);
+                        attempt += 1;
+                    }
+                    PL_ENGINE_INUSE => {
+                        log::error!("Failed to set engine: in use after {} attempts", attempts);
+                        return Err(PrologError::EngineInUse { attempts });
+                    }
+                    other => {
+                        let error_msg = match other {
+                            PL_ENGINE_INVAL => "Invalid engine handle".to_string(),
+                            other => format!("Unknown engine error code: {}", other),
+                        };
+                        log::error!("Failed to set engine: {} (code {})", error_msg, other);
+                        return Err(PrologError::EngineContextError(error_msg));
+                    }
+                }
             }
 
-            let result = f();
+            self.running.store(PL_thread_self(), Ordering::SeqCst);
+            let result = map_abort(f());
+
+            // Drop any abort request that arrived too late to take effect,
+            // under the lock so request_abort can't add one in between
+            if let Ok(mut requests) = ABORT_REQUESTS.lock() {
+                let tid = self.running.swap(0, Ordering::SeqCst);
+                requests.retain(|&t| t != tid);
+            } else {
+                self.running.store(0, Ordering::SeqCst);
+            }
 
             // Detach from this engine so other threads can use it.
             // In a multi-threaded server, different worker threads may handle
@@ -373,6 +1296,8 @@ impl PrologEnvironment {
     ///
     /// Uses a wrapper query to extract variable names and their bindings.
     unsafe fn execute_query_with_bindings(&self, goal: &str) -> PrologResult<String> {
+        ensure_goal(goal)?;
+
         // Escape the goal for embedding in an atom
         let escaped_goal = goal
             .replace("\\", "\\\\")
@@ -494,43 +1419,279 @@ impl PrologEnvironment {
         serde_json::to_string(&solutions).map_err(|e| PrologError::JsonError(e))
     }
 
-    /// Execute query and collect all solutions
-    unsafe fn execute_query_all(&self, goal: &str) -> PrologResult<String> {
-        let goal_c = string_to_c_string(goal)?;
-        let term = PL_new_term_ref();
+    /// Parse `goal`, ground the variables named in `params`, then run it
+    unsafe fn execute_query_with_params(
+        &self,
+        goal: &str,
+        params: &serde_json::Map<String, serde_json::Value>,
+        all_solutions: bool,
+        timeout: Option<Duration>,
+    ) -> PrologResult<String> {
+        let (term, _) = self.read_goal_with_params(goal, params)?;
+        self.run_query(term, None, goal, all_solutions, timeout)
+    }
 
-        if PL_chars_to_term(goal_c.as_ptr(), term) == 0 {
-            return Err(PrologError::ParseError(format!(
-                "Failed to parse goal: {}",
-                goal
+    /// Parse `goal` with its variable names and ground the variables named
+    /// in `params`; names that don't appear in `goal` are rejected
+    unsafe fn read_goal_with_params(
+        &self,
+        goal: &str,
+        params: &serde_json::Map<String, serde_json::Value>,
+    ) -> PrologResult<(term_t, term_t)> {
+        let (term, names) = self.read_goal_with_names(goal)?;
+
+        let mut found = Vec::new();
+        let pair = PL_new_term_ref();
+        let name_term = PL_new_term_ref();
+        let var = PL_new_term_ref();
+        let value = PL_new_term_ref();
+        let tail = PL_copy_term_ref(names);
+        while PL_get_list(tail, pair, tail) != 0 {
+            PL_get_arg(1, pair, name_term);
+            PL_get_arg(2, pair, var);
+            let name = term_to_string(name_term)?;
+
+            if let Some(json) = params.get(&name) {
+                json_to_term(json, value)?;
+                if PL_unify(var, value) == 0 {
+                    return Err(PrologError::ConversionError(format!(
+                        "Failed to bind variable {}",
+                        name
+                    )));
+                }
+            }
+            found.push(name);
+        }
+
+        let unknown: Vec<&str> = params
+            .keys()
+            .filter(|k| !found.contains(k))
+            .map(String::as_str)
+            .collect();
+        if !unknown.is_empty() {
+            return Err(PrologError::InvalidArgument(format!(
+                "No variable named {} in goal",
+                unknown.join(", ")
             )));
         }
 
-        // Get the 'call' predicate
-        let call_name = CString::new("call").unwrap();
-        let pred = PL_predicate(call_name.as_ptr(), 1, std::ptr::null());
+        Ok((term, names))
+    }
 
-        if pred.is_null() {
-            return Err(PrologError::Internal("Failed to get call/1 predicate".to_string()));
+    /// Parse `goal` with `read_term/3`, returning the goal term and its
+    /// `variable_names` list (`['X'=_, ...]`)
+    unsafe fn read_goal_with_names(&self, goal: &str) -> PrologResult<(term_t, term_t)> {
+        ensure_goal(goal)?;
+
+        // The goal text is bound to the first argument as an atom, so it is
+        // never spliced into the reader's input.
+        let reader_c = string_to_c_string("read_term_from_atom(_, _, [variable_names(_)])")?;
+        let reader = PL_new_term_ref();
+        if PL_chars_to_term(reader_c.as_ptr(), reader) == 0 {
+            return Err(PrologError::Internal("Failed to build goal reader".to_string()));
         }
 
-        let qid = PL_open_query(
-            std::ptr::null_mut(),
-            PL_Q_NORMAL | PL_Q_CATCH_EXCEPTION,
-            pred,
-            term,
-        );
+        let goal_atom = PL_new_term_ref();
+        let term = PL_new_term_ref();
+        let options = PL_new_term_ref();
+        PL_get_arg(1, reader, goal_atom);
+        PL_get_arg(2, reader, term);
+        PL_get_arg(3, reader, options);
 
-        if qid.is_null() {
-            return Err(PrologError::QueryFailed("Failed to open query".to_string()));
+        let goal_c = string_to_c_string(goal)?;
+        if PL_unify_atom_chars(goal_atom, goal_c.as_ptr()) == 0 {
+            return Err(PrologError::ConversionError("Failed to bind goal text".to_string()));
         }
 
+        if PL_call(reader, std::ptr::null_mut()) == 0 {
+            let ex = PL_exception(std::ptr::null_mut());
+            let msg = if ex != 0 {
+                let ex_str = term_to_string(ex).unwrap_or_else(|_| "unknown error".to_string());
+                PL_clear_exception();
+                ex_str
+            } else {
+                goal.to_string()
+            };
+            return Err(PrologError::ParseError(format!("Failed to parse goal: {}", msg)));
+        }
+
+        // options = [variable_names(Names)]
+        let names = PL_new_term_ref();
+        let option = PL_new_term_ref();
+        let rest = PL_new_term_ref();
+        if PL_get_list(options, option, rest) == 0 || PL_get_arg(1, option, names) == 0 {
+            return Err(PrologError::Internal("Failed to read goal variable names".to_string()));
+        }
+
+        Ok((term, names))
+    }
+
+    /// Run an already-built goal term, for its first or all solutions and
+    /// within `timeout` if one is given
+    ///
+    /// With `names` (a `variable_names` list from
+    /// [`read_goal_with_names`](Self::read_goal_with_names)) each solution is
+    /// reported as a bindings object; otherwise as the instantiated goal.
+    unsafe fn run_query(
+        &self,
+        term: term_t,
+        names: Option<term_t>,
+        goal: &str,
+        all_solutions: bool,
+        timeout: Option<Duration>,
+    ) -> PrologResult<String> {
+        match timeout {
+            Some(timeout) => self.run_query_timed(term, names, goal, all_solutions, timeout),
+            None if all_solutions => self.run_query_all(term, names),
+            None => self.run_query_once(term, names, goal),
+        }
+    }
+
+    /// Wrap a goal term in `call_with_time_limit/2`
+    unsafe fn time_limited(&self, term: term_t, timeout: Duration) -> PrologResult<term_t> {
+        let wrapper = self.parse_goal("call_with_time_limit(_, _)")?;
+
+        let limit = PL_new_term_ref();
+        let goal_slot = PL_new_term_ref();
+        PL_get_arg(1, wrapper, limit);
+        PL_get_arg(2, wrapper, goal_slot);
+        if PL_unify_float(limit, timeout.as_secs_f64()) == 0 {
+            return Err(PrologError::Internal("Failed to set time limit".to_string()));
+        }
+        if PL_unify(goal_slot, term) == 0 {
+            return Err(PrologError::Internal("Failed to bind goal".to_string()));
+        }
+        Ok(wrapper)
+    }
+
+    /// Run a goal term under `call_with_time_limit/2`
+    ///
+    /// All solutions are gathered with `findall/3` inside the limit, since
+    /// `call_with_time_limit/2` only takes the first solution of its goal.
+    /// The `findall/3` template is `names` when given, so each collected copy
+    /// still carries the variable names.
+    unsafe fn run_query_timed(
+        &self,
+        term: term_t,
+        names: Option<term_t>,
+        goal: &str,
+        all_solutions: bool,
+        timeout: Duration,
+    ) -> PrologResult<String> {
+        if !all_solutions {
+            let wrapper = self.time_limited(term, timeout)?;
+            return match map_time_limit(self.call_term(wrapper), timeout)? {
+                true => {
+                    let json = solution_json(term, names)?;
+                    serde_json::to_string(&json).map_err(PrologError::JsonError)
+                }
+                false => Err(PrologError::QueryFailed(format!("Query failed: {}", goal))),
+            };
+        }
+
+        let results = self.findall_timed(term, names, goal, timeout, None)?;
+        serde_json::to_string(&results).map_err(PrologError::JsonError)
+    }
+
+    /// Gather the solutions of a goal term with `findall/3` under
+    /// `call_with_time_limit/2`, at most `max` of them when given
+    unsafe fn findall_timed(
+        &self,
+        term: term_t,
+        names: Option<term_t>,
+        goal: &str,
+        timeout: Duration,
+        max: Option<usize>,
+    ) -> PrologResult<Vec<serde_json::Value>> {
+        let collector = self.parse_goal("findall(_, _, _)")?;
+        let template = PL_new_term_ref();
+        let goal_slot = PL_new_term_ref();
+        let solutions = PL_new_term_ref();
+        PL_get_arg(1, collector, template);
+        PL_get_arg(2, collector, goal_slot);
+        PL_get_arg(3, collector, solutions);
+
+        let generator = match max {
+            Some(max) => {
+                let limited = self.parse_goal("limit(_, _)")?;
+                let count = PL_new_term_ref();
+                let inner = PL_new_term_ref();
+                PL_get_arg(1, limited, count);
+                PL_get_arg(2, limited, inner);
+                if PL_unify_integer(count, max as i64) == 0 || PL_unify(inner, term) == 0 {
+                    return Err(PrologError::Internal("Failed to bind solution limit".to_string()));
+                }
+                limited
+            }
+            None => term,
+        };
+        if PL_unify(template, names.unwrap_or(term)) == 0 || PL_unify(goal_slot, generator) == 0 {
+            return Err(PrologError::Internal("Failed to bind goal".to_string()));
+        }
+
+        let wrapper = self.time_limited(collector, timeout)?;
+        if !map_time_limit(self.call_term(wrapper), timeout)? {
+            return Err(PrologError::QueryFailed(format!("Query failed: {}", goal)));
+        }
+
+        let mut results = Vec::new();
+        let head = PL_new_term_ref();
+        let tail = PL_copy_term_ref(solutions);
+        while PL_get_list(tail, head, tail) != 0 {
+            if names.is_some() {
+                results.push(bindings_json(head));
+                continue;
+            }
+            match term_to_json(head) {
+                Ok(json) => results.push(json),
+                Err(e) => {
+                    log::warn!("Failed to convert solution to JSON: {}", e);
+                    if let Ok(s) = term_to_string(head) {
+                        results.push(serde_json::Value::String(s));
+                    }
+                }
+            }
+        }
+        Ok(results)
+    }
+
+    /// Execute query and collect all solutions
+    unsafe fn execute_query_all(&self, goal: &str) -> PrologResult<String> {
+        let term = self.parse_goal(goal)?;
+        self.run_query_all(term, None)
+    }
+
+    /// Collect all solutions of an already-built goal term, as bindings
+    /// objects when `names` is given
+    unsafe fn run_query_all(&self, term: term_t, names: Option<term_t>) -> PrologResult<String> {
+        let (solutions, _) = self.collect_solutions(term, names, None)?;
+        serde_json::to_string(&solutions).map_err(|e| PrologError::JsonError(e))
+    }
+
+    /// Step through the solutions of a goal term with `PL_next_solution`,
+    /// stopping after `limit` of them when given
+    ///
+    /// Also returns whether the query was stopped at the limit with choice
+    /// points left, i.e. more solutions may exist.
+    unsafe fn collect_solutions(
+        &self,
+        term: term_t,
+        names: Option<term_t>,
+        limit: Option<usize>,
+    ) -> PrologResult<(Vec<serde_json::Value>, bool)> {
+        let qid = self.open_call_query(term)?;
         let mut solutions = Vec::new();
+        let mut truncated = false;
 
         loop {
+            if limit.is_some_and(|limit| solutions.len() >= limit) {
+                truncated = true;
+                break;
+            }
+
             let rc = PL_next_solution(qid);
 
-            if rc == 0 {
+            if rc == PL_S_EXCEPTION || rc == PL_S_FALSE {
                 // Check for exception
                 let ex = PL_exception(qid);
                 if ex != 0 {
@@ -543,25 +1704,79 @@ impl PrologEnvironment {
             }
 
             // Extract solution
-            match term_to_json(term) {
-                Ok(json) => solutions.push(json),
-                Err(e) => {
-                    log::warn!("Failed to convert solution to JSON: {}", e);
-                    // Try string representation as fallback
-                    if let Ok(s) = term_to_string(term) {
-                        solutions.push(serde_json::Value::String(s));
-                    }
-                }
+            if let Some(names) = names {
+                solutions.push(bindings_json(names));
+            } else {
+                solutions.extend(solution_term_json(term));
+            }
+
+            // No choice points left, so no further solutions
+            if rc == PL_S_LAST {
+                break;
             }
         }
 
+        // Closing discards the remaining choice points of a cut-off query
         PL_close_query(qid);
 
-        serde_json::to_string(&solutions).map_err(|e| PrologError::JsonError(e))
+        Ok((solutions, truncated))
+    }
+
+    /// Open a `call/1` query on a goal term, to be stepped through with
+    /// `PL_next_solution`
+    ///
+    /// It reports exceptions and `PL_S_LAST` through the extended status
+    /// codes, and must be closed with `PL_close_query`.
+    unsafe fn open_call_query(&self, term: term_t) -> PrologResult<qid_t> {
+        let call_name = CString::new("call").unwrap();
+        let pred = PL_predicate(call_name.as_ptr(), 1, std::ptr::null());
+
+        if pred.is_null() {
+            return Err(PrologError::Internal("Failed to get call/1 predicate".to_string()));
+        }
+
+        let qid = PL_open_query(
+            std::ptr::null_mut(),
+            PL_Q_NORMAL | PL_Q_CATCH_EXCEPTION | PL_Q_EXT_STATUS,
+            pred,
+            term,
+        );
+
+        if qid.is_null() {
+            return Err(PrologError::QueryFailed("Failed to open query".to_string()));
+        }
+        Ok(qid)
+    }
+
+    /// Execute query and collect all solutions as `{Name: Value}` objects
+    unsafe fn execute_query_all_named(&self, goal: &str) -> PrologResult<String> {
+        let (term, names) = self.read_goal_with_names(goal)?;
+        if PL_get_nil(names) != 0 {
+            return self.run_query_all(term, None);
+        }
+        self.run_query_all(term, Some(names))
     }
 
     /// Execute query and return first solution only
     unsafe fn execute_query_once(&self, goal: &str) -> PrologResult<String> {
+        let term = self.parse_goal(goal)?;
+        self.run_query_once(term, None, goal)
+    }
+
+    /// Call an already-built goal term and return its first solution
+    unsafe fn run_query_once(&self, term: term_t, names: Option<term_t>, goal: &str) -> PrologResult<String> {
+        if self.call_term(term)? {
+            // Success - convert result to JSON
+            let json = solution_json(term, names)?;
+            serde_json::to_string(&json).map_err(|e| PrologError::JsonError(e))
+        } else {
+            Err(PrologError::QueryFailed(format!("Query failed: {}", goal)))
+        }
+    }
+
+    /// Parse a goal string into a fresh term
+    unsafe fn parse_goal(&self, goal: &str) -> PrologResult<term_t> {
+        ensure_goal(goal)?;
         let goal_c = string_to_c_string(goal)?;
         let term = PL_new_term_ref();
 
@@ -571,23 +1786,25 @@ impl PrologEnvironment {
                 goal
             )));
         }
+        Ok(term)
+    }
 
-        let result = PL_call(term, std::ptr::null_mut());
+    /// Call a goal term once with `PL_call`
+    ///
+    /// Returns whether it succeeded; an exception is cleared and returned as
+    /// `PrologException`.
+    unsafe fn call_term(&self, term: term_t) -> PrologResult<bool> {
+        if PL_call(term, std::ptr::null_mut()) != 0 {
+            return Ok(true);
+        }
 
-        if result != 0 {
-            // Success - convert result to JSON
-            let json = term_to_json(term)?;
-            serde_json::to_string(&json).map_err(|e| PrologError::JsonError(e))
+        let ex = PL_exception(std::ptr::null_mut());
+        if ex != 0 {
+            let ex_str = term_to_string(ex).unwrap_or_else(|_| "unknown error".to_string());
+            PL_clear_exception();
+            Err(PrologError::PrologException(ex_str))
         } else {
-            // Check for exception
-            let ex = PL_exception(std::ptr::null_mut());
-            if ex != 0 {
-                let ex_str = term_to_string(ex).unwrap_or_else(|_| "unknown error".to_string());
-                PL_clear_exception();
-                Err(PrologError::PrologException(ex_str))
-            } else {
-                Err(PrologError::QueryFailed(format!("Query failed: {}", goal)))
-            }
+            Ok(false)
         }
     }
 }
@@ -624,4 +1841,40 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_blank_goals_detected() {
+        for goal in ["", "   ", "\t\n", "% just a comment", "/* block */", "  /* a */ % b\n  "] {
+            assert!(is_blank_goal(goal), "{:?} should be blank", goal);
+        }
+        for goal in ["true", "  member(X, [1])", "% comment\nfoo", "/* c */ bar", "'%'"] {
+            assert!(!is_blank_goal(goal), "{:?} should not be blank", goal);
+        }
+    }
+
+    #[test]
+    fn test_init_state_recovers_after_failure() {
+        let state = InitState::new();
+
+        let first = state.get_or_init(|| Err("SWI_HOME_DIR not found".to_string()));
+        assert_eq!(first, Err("SWI_HOME_DIR not found".to_string()));
+        assert!(!state.is_ok());
+
+        // The failure is memoized: plain init does not try again
+        let mut attempts = 0;
+        let cached = state.get_or_init(|| {
+            attempts += 1;
+            Ok(())
+        });
+        assert!(cached.is_err());
+        assert_eq!(attempts, 0);
+
+        // An explicit retry runs init again and records the success
+        assert_eq!(state.retry(|| Ok(())), Ok(()));
+        assert!(state.is_ok());
+
+        // Once initialized, neither path re-runs init
+        assert_eq!(state.retry(|| panic!("init should not run again")), Ok(()));
+        assert_eq!(state.get_or_init(|| panic!("init should not run again")), Ok(()));
+    }
 }
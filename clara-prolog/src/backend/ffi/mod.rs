@@ -13,10 +13,10 @@ pub mod conversion;
 pub mod environment;
 
 pub use bindings::*;
-pub use callbacks::register_clara_evaluate;
+pub use callbacks::{clara_evaluate_registrations, register_clara_evaluate};
 pub use coire_bridge::register_coire_predicates;
 pub use conversion::*;
-pub use environment::PrologEnvironment;
+pub use environment::{ClauseRef, PrologEnvironment, SolutionIter};
 
 // Re-export FFI functions from clara-toolbox for convenience
 pub use clara_toolbox::ffi::{evaluate_json_string, free_c_string};
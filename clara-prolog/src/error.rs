@@ -20,10 +20,14 @@ pub enum PrologError {
     #[error("Failed to set engine context: code {0}")]
     EngineSetFailed(i32),
 
-    /// Engine context error (e.g., engine in use by another thread)
+    /// Engine context error (e.g., invalid engine handle)
     #[error("Engine context error: {0}")]
     EngineContextError(String),
 
+    /// The engine stayed in use by another thread through every acquire attempt
+    #[error("Engine is in use by another thread (gave up after {attempts} attempts)")]
+    EngineInUse { attempts: u32 },
+
     /// Failed to parse a Prolog term/goal
     #[error("Failed to parse Prolog term: {0}")]
     ParseError(String),
@@ -36,6 +40,18 @@ pub enum PrologError {
     #[error("Prolog exception: {0}")]
     PrologException(String),
 
+    /// The query ran past its time limit and was aborted
+    #[error("Query timed out after {timeout_ms}ms")]
+    Timeout { timeout_ms: u64 },
+
+    /// The query was stopped by `PrologEnvironment::request_abort`
+    #[error("Query was aborted")]
+    Aborted,
+
+    /// A caller-supplied argument doesn't fit the goal
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+
     /// Failed to convert between Rust and Prolog types
     #[error("Type conversion error: {0}")]
     ConversionError(String),
@@ -24,13 +24,21 @@
 
 pub mod backend;
 pub mod error;
+pub mod profile;
 
 // Re-export main types for convenience
-pub use backend::ffi::PrologEnvironment;
-pub use backend::ffi::register_clara_evaluate;
+pub use backend::ffi::{ClauseRef, PrologEnvironment, SolutionIter};
+pub use backend::ffi::{clara_evaluate_registrations, register_clara_evaluate};
 pub use backend::ffi::register_coire_predicates;
-pub use backend::ffi::environment::load_coire_library;
+pub use backend::ffi::conversion::{
+    mark_unbound_variables, set_mark_unbound_variables, UNBOUND_VARIABLE_KEY,
+};
+pub use backend::ffi::environment::{
+    engine_acquire_attempts, is_blank_goal, load_coire_library, normalize_goal, reinitialize,
+    set_engine_acquire_attempts, DEFAULT_ENGINE_ACQUIRE_ATTEMPTS,
+};
 pub use error::{PrologError, PrologResult};
+pub use profile::BuiltinProfile;
 
 // Re-export FFI functions from clara-toolbox
 pub use clara_toolbox::ffi::{evaluate_json_string, free_c_string};
@@ -0,0 +1,64 @@
+//! Library sets a Prolog session can ask for when it is created
+
+use serde::{Deserialize, Serialize};
+
+/// Libraries loaded into the `user` module for a session
+///
+/// A profile only adds libraries; it is not a sandbox. SWI-Prolog modules
+/// are shared by every engine in the process, so a profile can't hide the
+/// libraries loaded at startup (such as `library(http/json)`) or for another
+/// session, and `Minimal` sessions can call everything the others can.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BuiltinProfile {
+    /// Nothing beyond what is loaded at startup; restricts nothing
+    #[default]
+    Minimal,
+    /// List and higher-order helpers
+    Standard,
+    /// `Standard` plus JSON, pairs, aggregation and lambdas
+    DataProcessing,
+}
+
+impl BuiltinProfile {
+    /// Library names, as given to `use_module(library(Name))`
+    pub fn libraries(self) -> &'static [&'static str] {
+        match self {
+            Self::Minimal => &[],
+            Self::Standard => &["lists", "apply"],
+            Self::DataProcessing => &[
+                "lists",
+                "apply",
+                "http/json",
+                "http/json_convert",
+                "pairs",
+                "aggregate",
+                "yall",
+            ],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_libraries() {
+        assert!(BuiltinProfile::Minimal.libraries().is_empty());
+        assert!(!BuiltinProfile::Standard.libraries().contains(&"http/json"));
+        assert!(BuiltinProfile::DataProcessing.libraries().contains(&"http/json"));
+        for library in BuiltinProfile::Standard.libraries() {
+            assert!(BuiltinProfile::DataProcessing.libraries().contains(library));
+        }
+    }
+
+    #[test]
+    fn test_profile_names() {
+        assert_eq!(serde_json::to_string(&BuiltinProfile::DataProcessing).unwrap(), "\"data_processing\"");
+        assert_eq!(
+            serde_json::from_str::<BuiltinProfile>("\"minimal\"").unwrap(),
+            BuiltinProfile::Minimal
+        );
+    }
+}
@@ -0,0 +1,31 @@
+//! `PrologEnvironment::clear`
+//!
+//! `clear` wipes the `user` module shared by every engine, so this test lives
+//! in its own test binary rather than alongside the other integration tests.
+
+use clara_prolog::PrologEnvironment;
+
+/// Test that `clear` removes asserted and consulted predicates but keeps
+/// built-ins, libraries and `clara_evaluate/2`
+#[test]
+fn test_clear_removes_user_predicates() {
+    let env = PrologEnvironment::new().expect("Failed to create environment");
+    env.assertz("pet(cat)").expect("Failed to assert");
+    env.assertz("pet(dog)").expect("Failed to assert");
+    env.assertz("owner(ann, cat)").expect("Failed to assert");
+    env.assertz("has_pet(P) :- owner(P, _)").expect("Failed to assert");
+    assert!(env.check("pet(dog)").unwrap());
+
+    env.clear().expect("clear failed");
+
+    assert!(env.query_once("pet(_)").is_err(), "pet/1 should be gone");
+    assert!(env.query_once("has_pet(_)").is_err(), "has_pet/1 should be gone");
+    assert_eq!(env.list_clauses().unwrap(), Vec::<String>::new());
+    assert!(env.check("current_predicate(clara_evaluate/2)").unwrap());
+    assert!(env.check("append([a], [b], [a, b])").unwrap());
+    assert!(env.check("atom_json_term('{}', _, [])").unwrap());
+
+    // The database is usable again afterwards
+    env.assertz("pet(fish)").expect("Failed to assert after clear");
+    assert!(env.check("pet(fish)").unwrap());
+}
@@ -3,7 +3,8 @@
 //! These tests verify the full Prolog integration works correctly,
 //! including FFI bindings, query execution, and knowledge base management.
 
-use clara_prolog::PrologEnvironment;
+use clara_prolog::{PrologEnvironment, PrologError};
+use std::time::Duration;
 use clara_prolog::register_clara_evaluate;
 use std::sync::Mutex;
 
@@ -18,6 +19,27 @@ fn test_create_environment() {
     assert!(env.is_ok(), "Should be able to create a Prolog environment");
 }
 
+/// Test that a freshly created environment answers a ping, before and after use
+#[test]
+fn test_ping_healthy_environment() {
+    let env = PrologEnvironment::new().expect("Failed to create environment");
+    assert!(env.ping());
+
+    // A failing query must not leave the engine unusable
+    let _ = env.query_once("fail");
+    assert!(env.ping());
+}
+
+/// Test that check reports success and failure, and errors only on exceptions
+#[test]
+fn test_check_goal() {
+    let env = PrologEnvironment::new().expect("Failed to create environment");
+    assert!(env.check("member(2, [1,2,3])").unwrap());
+    assert!(!env.check("member(4, [1,2,3])").unwrap());
+    assert!(env.check("atom_length(X, _)").is_err());
+    assert!(env.check("bad syntax (").is_err());
+}
+
 /// Test basic arithmetic query
 #[test]
 fn test_arithmetic_query() {
@@ -74,6 +96,73 @@ fn test_query_all_solutions() {
     // Should contain multiple colors or indicate multiple solutions
 }
 
+/// Test all-solutions queries with named variable bindings
+#[test]
+fn test_query_all_named_bindings() {
+    let env = PrologEnvironment::new().expect("Failed to create environment");
+
+    let output = env.query_all("append(X, Y, [1,2])", true).expect("Query should succeed");
+    let solutions: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(
+        solutions,
+        serde_json::json!([
+            {"X": [], "Y": [1, 2]},
+            {"X": [1], "Y": [2]},
+            {"X": [1, 2], "Y": []}
+        ])
+    );
+
+    // Anonymous variables are not reported
+    let output = env.query_all("member(X-_, [a-1, b-2])", true).unwrap();
+    let solutions: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(solutions, serde_json::json!([{"X": "a"}, {"X": "b"}]));
+
+    // Without named variables the result matches the unnamed form
+    assert_eq!(
+        env.query_all("append([1], [2], [1,2])", true).unwrap(),
+        env.query("append([1], [2], [1,2])").unwrap()
+    );
+}
+
+/// Test named-binding results with params and time limits
+#[test]
+fn test_query_bindings() {
+    let env = PrologEnvironment::new().expect("Failed to create environment");
+    let no_params = serde_json::Map::new();
+    let mut params = serde_json::Map::new();
+    params.insert("High".to_string(), serde_json::json!(3));
+
+    for timeout in [None, Some(Duration::from_secs(5))] {
+        let all = env.query_bindings("between(1, High, X)", &params, true, timeout).unwrap();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&all).unwrap(),
+            serde_json::json!([{"High": 3, "X": 1}, {"High": 3, "X": 2}, {"High": 3, "X": 3}])
+        );
+
+        let first = env.query_bindings("append(X, [c], [a, b, c])", &no_params, false, timeout).unwrap();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&first).unwrap(),
+            serde_json::json!({"X": ["a", "b"]})
+        );
+
+        // No named variables: one empty object per solution
+        let none = env.query_bindings("member(_, [a, b])", &no_params, true, timeout).unwrap();
+        assert_eq!(none, "[{},{}]");
+
+        assert!(env.check_with("between(1, High, 2)", &params, timeout).unwrap());
+        assert!(!env.check_with("between(1, High, 4)", &params, timeout).unwrap());
+    }
+
+    assert!(matches!(
+        env.query_bindings("repeat, fail", &no_params, true, Some(Duration::from_millis(100))),
+        Err(PrologError::Timeout { .. })
+    ));
+    assert!(matches!(
+        env.check_with("repeat, fail", &no_params, Some(Duration::from_millis(100))),
+        Err(PrologError::Timeout { .. })
+    ));
+}
+
 /// Test defining and using rules
 #[test]
 fn test_rules() {
@@ -170,6 +259,36 @@ fn test_recursive_predicates() {
     println!("Ancestor result: {}", output);
 }
 
+/// Test that threads sharing one engine take turns instead of failing
+/// with an engine-in-use error
+#[test]
+fn test_shared_engine_access_serializes() {
+    let env = std::sync::Arc::new(PrologEnvironment::new().expect("Failed to create environment"));
+    env.assertz("shared_count(0)").expect("Failed to assert");
+
+    // Each query holds the engine for a few milliseconds, so the threads
+    // collide; allow enough attempts to ride out the longest wait
+    clara_prolog::set_engine_acquire_attempts(1000);
+
+    let handles: Vec<_> = (0..8)
+        .map(|_| {
+            let env = std::sync::Arc::clone(&env);
+            std::thread::spawn(move || {
+                for _ in 0..5 {
+                    env.query_once("retract(shared_count(N)), N1 is N + 1, assertz(shared_count(N1)), sleep(0.002)")
+                        .expect("Shared engine access should not fail");
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().expect("Worker thread panicked");
+    }
+
+    assert!(env.check("shared_count(40)").unwrap(), "Every update should have run exactly once");
+    clara_prolog::set_engine_acquire_attempts(clara_prolog::DEFAULT_ENGINE_ACQUIRE_ATTEMPTS);
+}
+
 /// Test multiple environments (engine isolation)
 #[test]
 fn test_multiple_environments() {
@@ -304,6 +423,22 @@ fn test_clara_evaluate_predicate_registration() {
     println!("\n=== clara_evaluate/2 Foreign Predicate Test PASSED ===");
 }
 
+/// Test that repeated registration calls register clara_evaluate/2 only once
+#[test]
+fn test_register_clara_evaluate_idempotent() {
+    // Initialization registers the predicate itself
+    let env = PrologEnvironment::new().expect("Failed to create environment");
+    assert_eq!(clara_prolog::clara_evaluate_registrations(), 1);
+
+    for _ in 0..5 {
+        assert!(register_clara_evaluate());
+    }
+    let _other = PrologEnvironment::new().expect("Failed to create environment");
+    assert_eq!(clara_prolog::clara_evaluate_registrations(), 1);
+
+    assert!(env.check("current_predicate(the_rabbit:clara_evaluate/2)").unwrap());
+}
+
 /// Test that consulted Prolog code can call clara_evaluate/2
 ///
 /// This verifies that user-defined predicates which wrap clara_evaluate/2
@@ -612,3 +747,378 @@ fn test_reasoned_response_with_context() {
 
     println!("=== reasoned_response_with_context/3 Test PASSED ===");
 }
+
+/// Test that output written by a goal is captured rather than lost
+#[test]
+fn test_query_with_output_captures_write() {
+    let env = PrologEnvironment::new().expect("Failed to create environment");
+
+    let (bindings, output) = env
+        .query_with_output("write(hello), nl")
+        .expect("write/1 goal should succeed");
+    assert_eq!(output, "hello\n");
+    assert_eq!(bindings, serde_json::json!(true));
+
+    let (bindings, output) = env
+        .query_with_output("X = 42, format(\"x=~w~n\", [X])")
+        .expect("format/2 goal should succeed");
+    assert_eq!(output, "x=42\n");
+    assert_eq!(bindings["X"], serde_json::json!(42));
+}
+
+/// Test that dicts, assocs and classic json/1 terms come back as JSON objects
+#[test]
+fn test_dicts_and_assocs_convert_to_json_objects() {
+    println!("=== Testing dict/assoc conversion to JSON objects ===");
+
+    let env = PrologEnvironment::new().expect("Failed to create environment");
+
+    println!("\n[1] Nested dict from atom_json_dict/3...");
+    let result = env.query_with_bindings(
+        r#"atom_json_dict('{"a":{"b":[1,2],"c":"x"}}', D, [])"#
+    );
+    match &result {
+        Ok(r) => {
+            println!("    Result: {}", r);
+            assert!(r.contains(r#"{"D":{"a":{"b":[1,2],"c":"x"}}}"#),
+                "Dict should convert to a nested JSON object: {}", r);
+        }
+        Err(e) => panic!("atom_json_dict query failed: {}", e),
+    }
+
+    println!("\n[2] Assoc from list_to_assoc/2...");
+    let result = env.query_with_bindings("list_to_assoc([k1-1, k2-two], A)");
+    match &result {
+        Ok(r) => {
+            println!("    Result: {}", r);
+            assert!(r.contains(r#"{"A":{"k1":1,"k2":"two"}}"#),
+                "Assoc should convert to a JSON object: {}", r);
+        }
+        Err(e) => panic!("list_to_assoc query failed: {}", e),
+    }
+
+    println!("\n[3] Classic json/1 term from atom_json_term/3...");
+    let result = env.query_with_bindings(
+        r#"atom_json_term('{"none":null,"ok":true}', T, [])"#
+    );
+    match &result {
+        Ok(r) => {
+            println!("    Result: {}", r);
+            assert!(r.contains(r#"{"T":{"none":null,"ok":true}}"#),
+                "json/1 term should convert to a JSON object: {}", r);
+        }
+        Err(e) => panic!("atom_json_term query failed: {}", e),
+    }
+
+    println!("\n[4] Dict literal bound directly...");
+    let result = env.query_with_bindings("D = _{a:1, b:2}");
+    match &result {
+        Ok(r) => {
+            println!("    Result: {}", r);
+            assert!(r.contains(r#"{"D":{"a":1,"b":2}}"#),
+                "Dict literal should convert to a JSON object: {}", r);
+        }
+        Err(e) => panic!("dict literal query failed: {}", e),
+    }
+
+    println!("\n=== Dict/assoc conversion test PASSED ===");
+}
+
+/// Test that integers beyond i64 and rationals convert without losing precision
+#[test]
+fn test_big_integers_and_rationals_convert_exactly() {
+    let env = PrologEnvironment::new().expect("Failed to create environment");
+
+    let result = env.query_with_bindings("X is 2^100").expect("2^100 query failed");
+    assert!(result.contains(r#"{"X":"1267650600228229401496703205376"}"#),
+        "Big integer should keep every digit: {}", result);
+
+    // Between i64::MAX and u64::MAX still fits a JSON number
+    let result = env.query_with_bindings("X is 2^63").expect("2^63 query failed");
+    assert!(result.contains(r#"{"X":9223372036854775808}"#), "{}", result);
+
+    let result = env.query_with_bindings("X is 1 rdiv 3").expect("rdiv query failed");
+    assert!(result.contains(r#"{"X":{"den":3,"num":1}}"#),
+        "Rational should convert to num/den: {}", result);
+
+    let result = env.query_with_bindings("X is -(2^70) rdiv 3").expect("rdiv query failed");
+    assert!(result.contains(r#""num":"-1180591620717411303424""#), "{}", result);
+}
+
+/// Test that retract_ref removes exactly the clause assert_fact added
+#[test]
+fn test_retract_ref_removes_one_of_identical_facts() {
+    let env = PrologEnvironment::new().expect("Failed to create environment");
+
+    let first = env.assert_fact("clause_ref_color(red)").expect("first assert failed");
+    let second = env.assert_fact("clause_ref_color(red)").expect("second assert failed");
+    assert_ne!(first, second);
+    assert!(env.query_once("aggregate_all(count, clause_ref_color(red), 2)").is_ok());
+
+    env.retract_ref(first).expect("retract_ref failed");
+    assert!(
+        env.query_once("aggregate_all(count, clause_ref_color(red), 1)").is_ok(),
+        "One identical fact should remain"
+    );
+
+    // A handle can only be retracted once
+    assert!(env.retract_ref(first).is_err());
+
+    env.retract_ref(second).expect("retract_ref failed");
+    assert!(env.query_once("clause_ref_color(red)").is_err());
+}
+
+/// Test that a failed transaction undoes its asserts and retracts
+#[test]
+fn test_transaction_rolls_back_on_error() {
+    let env = PrologEnvironment::new().expect("Failed to create environment");
+    env.assertz("txn_color(blue)").expect("assert failed");
+
+    let result: Result<(), PrologError> = env.transaction(|env| {
+        env.assertz("txn_color(red)")?;
+        env.assertz("txn_shape(square)")?;
+        env.retract("txn_color(blue)")?;
+        Err(PrologError::QueryFailed("abort".to_string()))
+    });
+    assert!(matches!(result, Err(PrologError::QueryFailed(ref m)) if m == "abort"));
+
+    assert!(env.query_once("txn_color(red)").is_err(), "red should be rolled back");
+    assert!(env.query_once("txn_shape(square)").is_err(), "square should be rolled back");
+    assert!(env.query_once("txn_color(blue)").is_ok(), "blue should be restored");
+
+    // A successful transaction keeps its changes
+    let count = env
+        .transaction(|env| {
+            env.assertz("txn_color(green)")?;
+            env.query_once("aggregate_all(count, txn_color(_), N)")
+        })
+        .expect("transaction failed");
+    assert!(count.contains("2"), "Expected two colors: {}", count);
+    assert!(env.query_once("txn_color(green)").is_ok());
+}
+
+/// Test that builtin profiles load their libraries
+#[test]
+fn test_builtin_profiles() {
+    use clara_prolog::BuiltinProfile;
+
+    // Profiles only add libraries: the shared `user` module still has the
+    // JSON libraries loaded at startup, so Minimal doesn't hide them
+    let minimal = PrologEnvironment::with_profile(BuiltinProfile::Minimal).expect("Failed to create environment");
+    assert!(minimal.check("X = 1").unwrap());
+    assert!(minimal.check("atom_json_term('{\"a\": 1}', json([a=1]), [])").unwrap());
+
+    let data = PrologEnvironment::with_profile(BuiltinProfile::DataProcessing).expect("Failed to create environment");
+    assert!(data.check("atom_json_term('{\"a\": 1}', json([a=1]), [])").unwrap());
+    assert!(data.check("pairs_keys_values(P, [a, b], [1, 2]), P == [a-1, b-2]").unwrap());
+    assert!(data.check("aggregate_all(count, member(_, [x, y]), 2)").unwrap());
+    assert!(data.check("maplist([X, Y]>>(Y is X * 2), [1, 2], [2, 4])").unwrap());
+
+    // Loading a profile again is harmless
+    data.load_builtins_profile(BuiltinProfile::Standard).unwrap();
+    data.load_builtins_profile(BuiltinProfile::DataProcessing).unwrap();
+}
+
+/// Test that query_n stops an infinite generator at the limit
+#[test]
+fn test_query_n_limits_solutions() {
+    let env = PrologEnvironment::new().expect("Failed to create environment");
+
+    let (solutions, truncated) = env.query_n("between(1, inf, X)", 5).unwrap();
+    let solutions: serde_json::Value = serde_json::from_str(&solutions).unwrap();
+    assert_eq!(solutions.as_array().unwrap().len(), 5);
+    assert_eq!(solutions[4]["args"][2], 5);
+    assert!(truncated);
+
+    // A goal with fewer solutions than the limit isn't truncated
+    let (solutions, truncated) = env.query_n("member(X, [a, b])", 5).unwrap();
+    assert_eq!(solutions, env.query("member(X, [a, b])").unwrap());
+    assert!(!truncated);
+
+    let no_params = serde_json::Map::new();
+    let (solutions, truncated) = env
+        .query_n_with("between(1, inf, X)", &no_params, true, 3, Some(Duration::from_secs(5)))
+        .unwrap();
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&solutions).unwrap(),
+        serde_json::json!([{"X": 1}, {"X": 2}, {"X": 3}])
+    );
+    assert!(truncated);
+    assert!(env.ping());
+}
+
+/// Test that solutions() yields one item per solution, lazily
+#[test]
+fn test_solution_iterator() {
+    let env = PrologEnvironment::new().expect("Failed to create environment");
+
+    let solutions: Vec<_> = env
+        .solutions("member(X, [a, b, c])")
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(solutions.len(), 3);
+    assert_eq!(
+        serde_json::Value::Array(solutions),
+        serde_json::from_str::<serde_json::Value>(&env.query("member(X, [a, b, c])").unwrap()).unwrap()
+    );
+
+    // An infinite generator is only run as far as it's drained
+    let mut iter = env.solutions("between(1, inf, X)").unwrap();
+    assert_eq!(iter.nth(99).unwrap().unwrap()["args"][2], 100);
+    assert!(matches!(env.query_once("true"), Err(PrologError::EngineContextError(_))));
+    assert!(matches!(env.solutions("true"), Err(PrologError::EngineContextError(_))));
+    drop(iter);
+    assert!(env.ping());
+
+    let mut iter = env.solutions("throw(oops)").unwrap();
+    assert!(matches!(iter.next(), Some(Err(PrologError::PrologException(_)))));
+    assert!(iter.next().is_none());
+    assert_eq!(env.solutions("fail").unwrap().count(), 0);
+}
+
+/// Test that timed queries return the usual results and abort slow goals
+#[test]
+fn test_query_timeout() {
+    let env = PrologEnvironment::new().expect("Failed to create environment");
+    let limit = Duration::from_millis(200);
+
+    assert_eq!(
+        env.query_once_timeout("X = 1", limit).unwrap(),
+        env.query_once("X = 1").unwrap()
+    );
+    assert_eq!(
+        env.query_limited("member(X, [a, b])", limit).unwrap(),
+        env.query("member(X, [a, b])").unwrap()
+    );
+
+    for result in [
+        env.query_once_timeout("repeat, fail", limit),
+        env.query_limited("repeat, fail", limit),
+    ] {
+        assert!(
+            matches!(result, Err(PrologError::Timeout { timeout_ms: 200 })),
+            "Expected a timeout, got {:?}",
+            result
+        );
+    }
+    assert!(env.ping(), "Engine should be usable after a timeout");
+
+    // A goal blocked in sleep/1 is interrupted too, not just a busy loop
+    let start = std::time::Instant::now();
+    let result = env.query_once_timeout("sleep(5)", limit);
+    assert!(matches!(result, Err(PrologError::Timeout { .. })), "Expected a timeout, got {:?}", result);
+    assert!(start.elapsed() < Duration::from_secs(2), "sleep(5) ran for {:?}", start.elapsed());
+    assert!(env.ping(), "Engine should be usable after a timeout");
+}
+
+/// `leaf` wrapped in `levels` arrays and objects chosen by a small LCG, with
+/// scalar siblings sprinkled in along the way
+fn fuzz_nested_json(levels: usize, seed: u64) -> serde_json::Value {
+    let mut state = seed;
+    let mut next = move || {
+        state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        state >> 33
+    };
+
+    let mut value = serde_json::json!("leaf");
+    for level in 0..levels {
+        let sibling = match next() % 4 {
+            0 => serde_json::Value::Null,
+            1 => serde_json::json!(level as i64 - 7),
+            2 => serde_json::json!(level as f64 / 3.0),
+            _ => serde_json::json!(format!("s{}", level)),
+        };
+        value = if next() % 2 == 0 {
+            serde_json::json!([sibling, value])
+        } else {
+            serde_json::json!({ "sibling": sibling, "child": value })
+        };
+    }
+    value
+}
+
+/// Test that deeply nested parameters either convert completely or fail
+/// with a clear conversion error, never a partial term
+#[test]
+fn test_nested_params_convert_or_fail_cleanly() {
+    use clara_prolog::backend::ffi::MAX_JSON_TO_TERM_DEPTH;
+
+    let env = PrologEnvironment::new().expect("Failed to create environment");
+
+    for seed in 0..8u64 {
+        for levels in [1, 17, 64, MAX_JSON_TO_TERM_DEPTH, MAX_JSON_TO_TERM_DEPTH + 1, 1000] {
+            let mut params = serde_json::Map::new();
+            params.insert("V".to_string(), fuzz_nested_json(levels, seed));
+
+            // The leaf must be reachable through every level of the term
+            let result = env.query_with_params(r#"sub_term(S, V), S == "leaf""#, &params, false);
+            if levels <= MAX_JSON_TO_TERM_DEPTH {
+                assert!(result.is_ok(), "seed {} depth {} failed: {:?}", seed, levels, result);
+            } else {
+                match result {
+                    Err(PrologError::ConversionError(msg)) => {
+                        assert!(msg.contains("nested deeper"), "Unclear error: {}", msg)
+                    }
+                    other => panic!("seed {} depth {}: expected ConversionError, got {:?}", seed, levels, other),
+                }
+            }
+        }
+    }
+    assert!(env.ping(), "Engine should be usable after a rejected conversion");
+}
+
+/// Test that layout, variable names and operator notation don't change a
+/// goal's normalized form
+#[test]
+fn test_normalize_goal_variants_match() {
+    use clara_prolog::normalize_goal;
+
+    let variants = [
+        ["member(X,[1,2])", "member( X , [1,2] )", "member(Y, [1, 2])"],
+        ["X = 1+2*3", "=(X, +(1, *(2, 3)))", "X=1 + 2 * 3"],
+        ["append(X, Y, [a|T]), T = X", "append(A,B,'[|]'(a,C)) , C=A", "(append(P, _Q, [a|R]), R = P)"],
+    ];
+    for group in variants {
+        let first = normalize_goal(group[0]).unwrap();
+        for goal in &group[1..] {
+            assert_eq!(normalize_goal(goal).unwrap(), first, "{:?} vs {:?}", goal, group[0]);
+        }
+    }
+
+    // Goals that differ stay distinct
+    assert_ne!(normalize_goal("X = 1+2").unwrap(), normalize_goal("X = 2+1").unwrap());
+    assert_ne!(normalize_goal("f(X, X)").unwrap(), normalize_goal("f(X, Y)").unwrap());
+    assert_ne!(normalize_goal("a").unwrap(), normalize_goal("'A'").unwrap());
+
+    // A session engine gives the same key as the shared one
+    let env = PrologEnvironment::new().expect("Failed to create environment");
+    assert_eq!(env.normalize_goal("member(X,[1,2])").unwrap(), normalize_goal("member(Z, [1,2])").unwrap());
+
+    assert!(normalize_goal("bad syntax (").is_err());
+    assert!(matches!(normalize_goal("  % nothing\n"), Err(PrologError::InvalidArgument(_))));
+}
+
+/// Test that a query looping forever can be aborted from another thread
+#[test]
+fn test_request_abort_stops_running_query() {
+    let env = std::sync::Arc::new(PrologEnvironment::new().expect("Failed to create environment"));
+    assert!(!env.request_abort(), "An idle engine has nothing to abort");
+
+    let runner = std::sync::Arc::clone(&env);
+    let handle = std::thread::spawn(move || runner.query_once("repeat, fail"));
+
+    // Keep asking until the query has taken the engine
+    let deadline = std::time::Instant::now() + Duration::from_secs(5);
+    while !env.request_abort() {
+        assert!(std::time::Instant::now() < deadline, "Query never started");
+        std::thread::sleep(Duration::from_millis(10));
+    }
+
+    let result = handle.join().expect("Query thread panicked");
+    assert!(matches!(result, Err(PrologError::Aborted)), "Expected an abort, got {:?}", result);
+
+    // The abort doesn't leak into later queries
+    assert!(env.check("true").unwrap());
+    assert!(env.ping());
+}
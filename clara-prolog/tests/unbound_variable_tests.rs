@@ -0,0 +1,34 @@
+//! Rendering of unbound variables by `term_to_json`
+//!
+//! `set_mark_unbound_variables` is process-wide, so these tests live in their
+//! own test binary rather than alongside the other integration tests.
+
+use clara_prolog::{set_mark_unbound_variables, PrologEnvironment, UNBOUND_VARIABLE_KEY};
+use serde_json::Value;
+
+/// Test that `X = Y` renders both sides as the same variable marker, and as
+/// nulls with marking off
+#[test]
+fn test_unbound_variables_marked() {
+    let env = PrologEnvironment::new().expect("Failed to create environment");
+
+    set_mark_unbound_variables(true);
+    let marked: Value = serde_json::from_str(&env.query_once("X = Y").unwrap()).unwrap();
+    let bindings: Value = serde_json::from_str(&env.query_with_bindings("X = Y").unwrap()).unwrap();
+    set_mark_unbound_variables(false);
+    let plain: Value = serde_json::from_str(&env.query_once("X = Y").unwrap()).unwrap();
+
+    let args = marked["args"].as_array().unwrap();
+    assert_eq!(args.len(), 2);
+    for arg in args {
+        let name = arg[UNBOUND_VARIABLE_KEY].as_str().unwrap_or_else(|| panic!("not a marker: {}", arg));
+        assert!(name.starts_with('_'), "{}", name);
+    }
+    // Unified variables share a name
+    assert_eq!(args[0], args[1]);
+
+    assert!(bindings[0]["X"][UNBOUND_VARIABLE_KEY].is_string(), "{}", bindings);
+    assert_eq!(bindings[0]["X"], bindings[0]["Y"]);
+
+    assert_eq!(plain["args"], serde_json::json!([null, null]));
+}
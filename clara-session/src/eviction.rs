@@ -1 +1,99 @@
-//! Session eviction policies (stub for future implementation)
+//! Session eviction policies
+//!
+//! Used by the [`SessionManager`](crate::SessionManager) to reclaim slots when
+//! the global session limit is reached, and to purge the records terminated
+//! sessions leave behind.
+
+use crate::metadata::{Session, SessionId, SessionStatus};
+
+/// Select up to `needed` sessions idle for longer than `idle_ttl_seconds`,
+/// least recently touched first.
+///
+/// Terminated sessions and sessions currently evaluating are never selected.
+pub fn select_idle_sessions(
+    sessions: &[Session],
+    idle_ttl_seconds: u64,
+    now: u64,
+    needed: usize,
+) -> Vec<SessionId> {
+    let mut candidates: Vec<&Session> = sessions
+        .iter()
+        .filter(|s| !matches!(s.status, SessionStatus::Terminated | SessionStatus::Evaluating))
+        .filter(|s| now.saturating_sub(s.touched_at) > idle_ttl_seconds)
+        .collect();
+
+    candidates.sort_by_key(|s| s.touched_at);
+
+    candidates
+        .into_iter()
+        .take(needed)
+        .map(|s| s.session_id.clone())
+        .collect()
+}
+
+/// Select terminated sessions whose termination is more than
+/// `retention_seconds` old.
+///
+/// A terminated session's `touched_at` is the time it was terminated.
+pub fn select_expired_tombstones(
+    sessions: &[Session],
+    retention_seconds: u64,
+    now: u64,
+) -> Vec<SessionId> {
+    sessions
+        .iter()
+        .filter(|s| s.status == SessionStatus::Terminated)
+        .filter(|s| now.saturating_sub(s.touched_at) > retention_seconds)
+        .map(|s| s.session_id.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session_touched_at(touched_at: u64) -> Session {
+        let mut session = Session::new("user-1".to_string(), None);
+        session.activate();
+        session.touched_at = touched_at;
+        session
+    }
+
+    #[test]
+    fn test_select_idle_sessions_oldest_first() {
+        let fresh = session_touched_at(990);
+        let old = session_touched_at(100);
+        let older = session_touched_at(50);
+        let sessions = vec![fresh, old.clone(), older.clone()];
+
+        let selected = select_idle_sessions(&sessions, 60, 1000, 5);
+        assert_eq!(selected, vec![older.session_id, old.session_id]);
+    }
+
+    #[test]
+    fn test_select_idle_sessions_skips_terminated_and_limits_count() {
+        let mut terminated = session_touched_at(10);
+        terminated.terminate();
+        terminated.touched_at = 10;
+        let idle_a = session_touched_at(20);
+        let idle_b = session_touched_at(30);
+        let sessions = vec![terminated, idle_a.clone(), idle_b];
+
+        let selected = select_idle_sessions(&sessions, 60, 1000, 1);
+        assert_eq!(selected, vec![idle_a.session_id]);
+    }
+
+    #[test]
+    fn test_select_expired_tombstones_only_past_retention() {
+        let mut expired = session_touched_at(100);
+        expired.terminate();
+        expired.touched_at = 100;
+        let mut recent = session_touched_at(100);
+        recent.terminate();
+        recent.touched_at = 980;
+        let idle = session_touched_at(10);
+        let sessions = vec![expired.clone(), recent, idle];
+
+        assert_eq!(select_expired_tombstones(&sessions, 60, 1000), vec![expired.session_id]);
+    }
+}
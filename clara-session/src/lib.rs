@@ -7,6 +7,9 @@
 //! - Resource tracking and limits
 //! - Session metadata and status
 //! - In-memory session storage
+//! - A bounded evaluation queue for backpressure
+//! - Snapshot and restore for backup
+//! - Saving sessions to disk and restoring them after a restart
 //!
 //! # Example
 //!
@@ -29,12 +32,18 @@
 pub mod metadata;
 pub mod store;
 pub mod manager;
+pub mod eviction;
+pub mod snapshot;
+pub mod persistence;
+
+pub mod queue;
 
 // Stub modules for future implementation
 pub mod lifecycle;
-pub mod queue;
-pub mod eviction;
 
 pub use metadata::{Session, SessionId, SessionStatus, SessionStats, SessionType, ResourceUsage, ResourceLimits};
 pub use store::{SessionStore, StoreError};
-pub use manager::{SessionManager, ManagerConfig, ManagerError};
+pub use manager::{SessionManager, ManagerConfig, ManagerConfigBuilder, ManagerError, ConfigError};
+pub use snapshot::Snapshot;
+pub use persistence::{DiskPersistence, SessionFile};
+pub use queue::{EvalPermit, EvalQueue, QueueError};
@@ -1,9 +1,20 @@
-use crate::metadata::{ResourceLimits, Session, SessionId, SessionStatus, SessionType};
+use crate::eviction;
+use crate::metadata::{current_timestamp, ResourceLimits, Session, SessionId, SessionStatus, SessionType};
+use crate::persistence::{self, SessionFile};
+use crate::queue::{EvalQueue, QueueError};
+use crate::snapshot::Snapshot;
 use crate::store::{SessionStore, StoreError};
-use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
+use clara_clips::clips_conversion::{classify_clips_error, ClipsErrorKind};
+use std::collections::{HashMap, HashSet};
+use std::mem::ManuallyDrop;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
 use thiserror::Error;
 
+/// `(user_id, name, type)` of a session from `get_or_create_named_session`
+type NamedSessionKey = (String, String, SessionType);
+
 #[derive(Error, Debug)]
 pub enum ManagerError {
     #[error("Store error: {0}")]
@@ -21,21 +32,61 @@ pub enum ManagerError {
     #[error("Session not found")]
     SessionNotFound,
 
+    #[error("Invalid session status transition: {from} -> {to}")]
+    InvalidTransition { from: SessionStatus, to: SessionStatus },
+
     #[error("Wrong session type: expected {expected}, got {actual}")]
     WrongSessionType { expected: String, actual: String },
 
     #[error("Environment execution error: {0}")]
     EnvironmentError(String),
 
+    /// CLIPS rejected the input itself, e.g. a rule that doesn't parse
+    #[error("CLIPS syntax error: {0}")]
+    ClipsSyntaxError(String),
+
     #[error("Prolog error: {0}")]
     PrologError(#[from] clara_prolog::PrologError),
+
+    /// A session file couldn't be written or read
+    #[error("Persistence error: {0}")]
+    Persistence(String),
+
+    /// The evaluation queue has no room; retry later
+    #[error("Evaluation queue full")]
+    QueueFull,
+
+    /// A session file names a session type this build doesn't support
+    #[error("Unsupported session type: {0}")]
+    UnsupportedSessionType(String),
+
+    /// A session id that can't name a session file, e.g. one holding a path
+    /// separator
+    #[error("Invalid session id: {0:?}")]
+    InvalidSessionId(String),
 }
 
 /// Session manager configuration
 #[derive(Debug, Clone)]
 pub struct ManagerConfig {
+    /// Global cap on live sessions across all users and session types
     pub max_concurrent_sessions: usize,
     pub max_sessions_per_user: usize,
+    /// When set, sessions idle for longer than this many seconds are
+    /// terminated (least recently used first) to make room once the global
+    /// cap is reached. `None` disables eviction.
+    pub idle_eviction_ttl_seconds: Option<u64>,
+    /// When set, [`SessionManager::evict_idle`] terminates sessions idle for
+    /// longer than this many seconds, whether or not any cap is reached.
+    /// `None` makes `evict_idle` a no-op.
+    pub idle_timeout_seconds: Option<u64>,
+    /// Engine used when a session is created without an explicit type
+    pub default_session_type: SessionType,
+    /// Evaluations [`SessionManager::run_queued`] lets run at once
+    pub max_in_flight_evals: usize,
+    /// Evaluations that may wait for a slot before more are turned away
+    /// with `QueueFull`; 0 turns them away as soon as every slot is busy
+    pub max_queued_evals: usize,
 }
 
 impl Default for ManagerConfig {
@@ -43,10 +94,133 @@ impl Default for ManagerConfig {
         Self {
             max_concurrent_sessions: 100,
             max_sessions_per_user: 10,
+            idle_eviction_ttl_seconds: None,
+            idle_timeout_seconds: None,
+            default_session_type: SessionType::default(),
+            max_in_flight_evals: 8,
+            max_queued_evals: 10,
         }
     }
 }
 
+impl ManagerConfig {
+    /// Start a [`ManagerConfigBuilder`] from the defaults
+    pub fn builder() -> ManagerConfigBuilder {
+        ManagerConfigBuilder { config: Self::default() }
+    }
+
+    /// Check that the limits can be satisfied
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.max_concurrent_sessions == 0 {
+            return Err(ConfigError::ZeroLimit("max_concurrent_sessions"));
+        }
+        if self.max_sessions_per_user == 0 {
+            return Err(ConfigError::ZeroLimit("max_sessions_per_user"));
+        }
+        if self.max_sessions_per_user > self.max_concurrent_sessions {
+            return Err(ConfigError::PerUserAboveGlobal {
+                per_user: self.max_sessions_per_user,
+                global: self.max_concurrent_sessions,
+            });
+        }
+        if self.idle_eviction_ttl_seconds == Some(0) {
+            return Err(ConfigError::ZeroIdleTtl);
+        }
+        if self.idle_timeout_seconds == Some(0) {
+            return Err(ConfigError::ZeroLimit("idle_timeout_seconds"));
+        }
+        if self.max_in_flight_evals == 0 {
+            return Err(ConfigError::ZeroLimit("max_in_flight_evals"));
+        }
+        Ok(())
+    }
+}
+
+/// Invalid [`ManagerConfig`] rejected by [`ManagerConfigBuilder::build`]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    #[error("{0} must be greater than 0")]
+    ZeroLimit(&'static str),
+
+    #[error("max_sessions_per_user ({per_user}) exceeds max_concurrent_sessions ({global})")]
+    PerUserAboveGlobal { per_user: usize, global: usize },
+
+    #[error("idle_eviction_ttl_seconds must be greater than 0; use no TTL to disable eviction")]
+    ZeroIdleTtl,
+}
+
+/// Fluent builder for [`ManagerConfig`]; unset fields keep their defaults
+///
+/// ```
+/// use clara_session::{ManagerConfig, SessionType};
+///
+/// let config = ManagerConfig::builder()
+///     .max_concurrent_sessions(50)
+///     .max_sessions_per_user(5)
+///     .idle_eviction_ttl_seconds(600)
+///     .default_session_type(SessionType::Prolog)
+///     .build()?;
+/// assert_eq!(config.idle_eviction_ttl_seconds, Some(600));
+/// # Ok::<(), clara_session::ConfigError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct ManagerConfigBuilder {
+    config: ManagerConfig,
+}
+
+impl ManagerConfigBuilder {
+    pub fn max_concurrent_sessions(mut self, max: usize) -> Self {
+        self.config.max_concurrent_sessions = max;
+        self
+    }
+
+    pub fn max_sessions_per_user(mut self, max: usize) -> Self {
+        self.config.max_sessions_per_user = max;
+        self
+    }
+
+    /// Evict sessions idle for longer than `ttl_seconds` once the global cap
+    /// is reached
+    pub fn idle_eviction_ttl_seconds(mut self, ttl_seconds: u64) -> Self {
+        self.config.idle_eviction_ttl_seconds = Some(ttl_seconds);
+        self
+    }
+
+    /// Never evict idle sessions (the default)
+    pub fn no_idle_eviction(mut self) -> Self {
+        self.config.idle_eviction_ttl_seconds = None;
+        self
+    }
+
+    /// Let [`SessionManager::evict_idle`] terminate sessions idle for longer
+    /// than `timeout_seconds`
+    pub fn idle_timeout_seconds(mut self, timeout_seconds: u64) -> Self {
+        self.config.idle_timeout_seconds = Some(timeout_seconds);
+        self
+    }
+
+    pub fn default_session_type(mut self, session_type: SessionType) -> Self {
+        self.config.default_session_type = session_type;
+        self
+    }
+
+    pub fn max_in_flight_evals(mut self, max: usize) -> Self {
+        self.config.max_in_flight_evals = max;
+        self
+    }
+
+    pub fn max_queued_evals(mut self, max: usize) -> Self {
+        self.config.max_queued_evals = max;
+        self
+    }
+
+    /// Validate and return the config
+    pub fn build(self) -> Result<ManagerConfig, ConfigError> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+}
+
 /// High-level session manager
 pub struct SessionManager {
     store: SessionStore,
@@ -55,17 +229,157 @@ pub struct SessionManager {
     clips_envs: Arc<RwLock<HashMap<SessionId, clara_clips::ClipsEnvironment>>>,
     /// Separate storage for Prolog environments (LilDevils)
     prolog_envs: Arc<RwLock<HashMap<SessionId, clara_prolog::PrologEnvironment>>>,
+    /// Session ID of each `(user_id, name, type)` handed out by
+    /// `get_or_create_named_session`; held for the whole lookup-or-create
+    named_sessions: Arc<Mutex<HashMap<NamedSessionKey, SessionId>>>,
+    /// Held while a new session is checked against the caps and reserved in
+    /// the store, so concurrent creations can't all pass the same check
+    creation_lock: Arc<Mutex<()>>,
+    /// Held while a session is marked terminated and its engine dropped, so
+    /// concurrent terminations of one session can't interleave
+    termination_lock: Arc<Mutex<()>>,
+    /// Engines created ahead of time by [`SessionManager::prewarm`], handed
+    /// to the next sessions of their type
+    idle_clips_envs: Arc<Mutex<Vec<clara_clips::ClipsEnvironment>>>,
+    idle_prolog_envs: Arc<Mutex<Vec<clara_prolog::PrologEnvironment>>>,
+    /// Idle Prolog engines that failed a ping, most likely because another
+    /// thread still has them attached; destroyed once a ping succeeds and
+    /// leaked if the manager goes away first
+    quarantined_prolog_envs: Arc<Mutex<Vec<ManuallyDrop<clara_prolog::PrologEnvironment>>>>,
+    /// Bounds the evaluations run through [`SessionManager::run_queued`]
+    eval_queue: EvalQueue,
 }
 
 impl SessionManager {
     /// Create a new session manager
     pub fn new(config: ManagerConfig) -> Self {
+        let eval_queue = EvalQueue::new(config.max_in_flight_evals, config.max_queued_evals);
         Self {
             store: SessionStore::new(),
             config,
             clips_envs: Arc::new(RwLock::new(HashMap::new())),
             prolog_envs: Arc::new(RwLock::new(HashMap::new())),
+            named_sessions: Arc::new(Mutex::new(HashMap::new())),
+            creation_lock: Arc::new(Mutex::new(())),
+            termination_lock: Arc::new(Mutex::new(())),
+            idle_clips_envs: Arc::new(Mutex::new(Vec::new())),
+            idle_prolog_envs: Arc::new(Mutex::new(Vec::new())),
+            quarantined_prolog_envs: Arc::new(Mutex::new(Vec::new())),
+            eval_queue,
+        }
+    }
+
+    /// Create up to `prolog` Prolog and `clips` CLIPS engines ahead of time
+    ///
+    /// The engines wait in idle pools and are handed to the next sessions of
+    /// their type, so those skip engine startup. Idle engines count against
+    /// `max_concurrent_sessions` together with the active sessions; requests
+    /// beyond it are cut back, Prolog first, with a warning. Returns the
+    /// number of `(prolog, clips)` engines created.
+    pub fn prewarm(&self, prolog: usize, clips: usize) -> Result<(usize, usize), ManagerError> {
+        let mut idle_prolog = self.idle_prolog_envs.lock()
+            .map_err(|_| ManagerError::Store(StoreError::LockPoisoned))?;
+        let mut idle_clips = self.idle_clips_envs.lock()
+            .map_err(|_| ManagerError::Store(StoreError::LockPoisoned))?;
+
+        let in_use = self.store.count_active()? + idle_prolog.len() + idle_clips.len();
+        let room = self.config.max_concurrent_sessions.saturating_sub(in_use);
+        let prolog_count = prolog.min(room);
+        let clips_count = clips.min(room - prolog_count);
+        if prolog_count < prolog || clips_count < clips {
+            log::warn!(
+                "Prewarm of {} Prolog / {} CLIPS engines capped to {} / {} by max_concurrent_sessions ({})",
+                prolog, clips, prolog_count, clips_count, self.config.max_concurrent_sessions
+            );
+        }
+
+        for _ in 0..prolog_count {
+            idle_prolog.push(clara_prolog::PrologEnvironment::new()?);
+        }
+        for _ in 0..clips_count {
+            idle_clips.push(clara_clips::ClipsEnvironment::new().map_err(ManagerError::EnvironmentError)?);
+        }
+
+        log::info!("Prewarmed {} Prolog and {} CLIPS engines", prolog_count, clips_count);
+        Ok((prolog_count, clips_count))
+    }
+
+    /// Ping every idle Prolog engine, discard those that fail and top the
+    /// pool back up to `min_idle`
+    ///
+    /// A query aborted mid-run can leave an engine unusable; checking the
+    /// pool keeps such engines from being handed to new sessions. A failed
+    /// engine may still be attached to another thread, so it can't be
+    /// destroyed yet: it is quarantined, and destroyed by a later check once
+    /// it answers a ping again. Replacements are created as in
+    /// [`prewarm`](Self::prewarm), within `max_concurrent_sessions`. Returns
+    /// the number of `(discarded, created)` engines.
+    pub fn check_idle_prolog_engines(&self, min_idle: usize) -> Result<(usize, usize), ManagerError> {
+        self.destroy_released_prolog_engines()?;
+
+        let broken = self.take_broken_idle_prolog_engines()?;
+        let discarded = broken.len();
+        self.quarantined_prolog_envs.lock()
+            .map_err(|_| ManagerError::Store(StoreError::LockPoisoned))?
+            .extend(broken.into_iter().map(ManuallyDrop::new));
+
+        let missing = min_idle.saturating_sub(self.idle_prolog_count());
+        let created = if missing > 0 { self.prewarm(missing, 0)?.0 } else { 0 };
+
+        if discarded > 0 {
+            log::info!("Replaced {} broken idle Prolog engine(s) with {} new one(s)", discarded, created);
+        }
+        Ok((discarded, created))
+    }
+
+    /// Remove the idle Prolog engines that fail `ping` from the pool
+    fn take_broken_idle_prolog_engines(&self) -> Result<Vec<clara_prolog::PrologEnvironment>, ManagerError> {
+        // Ping outside the lock so session creation isn't held up meanwhile
+        let engines = std::mem::take(
+            &mut *self.idle_prolog_envs.lock()
+                .map_err(|_| ManagerError::Store(StoreError::LockPoisoned))?,
+        );
+        let (healthy, broken): (Vec<_>, Vec<_>) = engines.into_iter().partition(|env| env.ping());
+
+        self.idle_prolog_envs.lock()
+            .map_err(|_| ManagerError::Store(StoreError::LockPoisoned))?
+            .extend(healthy);
+        for env in &broken {
+            log::warn!("Discarding idle Prolog engine {} after a failed ping", env.session_id());
         }
+        Ok(broken)
+    }
+
+    /// Destroy the quarantined Prolog engines that answer a ping again,
+    /// i.e. that no other thread holds any more
+    fn destroy_released_prolog_engines(&self) -> Result<(), ManagerError> {
+        let mut quarantined = self.quarantined_prolog_envs.lock()
+            .map_err(|_| ManagerError::Store(StoreError::LockPoisoned))?;
+        let (released, held): (Vec<_>, Vec<_>) =
+            std::mem::take(&mut *quarantined).into_iter().partition(|env| env.ping());
+        *quarantined = held;
+        drop(quarantined);
+
+        if !released.is_empty() {
+            log::info!("Destroying {} released quarantined Prolog engine(s)", released.len());
+        }
+        released.into_iter().for_each(|env| drop(ManuallyDrop::into_inner(env)));
+        Ok(())
+    }
+
+    /// Number of broken Prolog engines waiting to be destroyed
+    pub fn quarantined_prolog_count(&self) -> usize {
+        self.quarantined_prolog_envs.lock().map(|q| q.len()).unwrap_or(0)
+    }
+
+    /// Number of prewarmed Prolog engines waiting for a session
+    pub fn idle_prolog_count(&self) -> usize {
+        self.idle_prolog_envs.lock().map(|idle| idle.len()).unwrap_or(0)
+    }
+
+    /// Number of prewarmed CLIPS engines waiting for a session
+    pub fn idle_clips_count(&self) -> usize {
+        self.idle_clips_envs.lock().map(|idle| idle.len()).unwrap_or(0)
     }
 
     /// Create a new session for a user
@@ -84,46 +398,277 @@ impl SessionManager {
         name: Option<String>,
         limits: Option<ResourceLimits>,
     ) -> Result<Session, ManagerError> {
-        // Check global session limit
-        let active_count = self.store.count_active()?;
-        if active_count >= self.config.max_concurrent_sessions {
-            return Err(ManagerError::GlobalSessionLimitExceeded);
+        self.insert_new_session(Session::new_with_name(user_id, name, limits))
+    }
+
+    /// Create a session of `session_type`, or of the configured
+    /// `default_session_type` when none is given
+    pub fn create_typed_session(
+        &self,
+        user_id: String,
+        session_type: Option<SessionType>,
+        name: Option<String>,
+        limits: Option<ResourceLimits>,
+    ) -> Result<Session, ManagerError> {
+        let session_type = session_type.unwrap_or(self.config.default_session_type);
+        self.insert_new_session(Session::new_typed_with_name(user_id, session_type, name, limits))
+    }
+
+    /// Return the live session `name` of `session_type` for `user_id`,
+    /// creating it if it doesn't exist or was terminated
+    ///
+    /// Named sessions get ordinary random IDs, remembered per user, name
+    /// and type; concurrent callers are serialized, so only the first call
+    /// creates a session. A remembered session is only returned if it still
+    /// belongs to that user, name and type.
+    pub fn get_or_create_named_session(
+        &self,
+        user_id: String,
+        name: String,
+        session_type: SessionType,
+        limits: Option<ResourceLimits>,
+    ) -> Result<Session, ManagerError> {
+        let mut named = self.named_sessions.lock()
+            .map_err(|_| ManagerError::Store(StoreError::LockPoisoned))?;
+
+        let key = (user_id.clone(), name.clone(), session_type);
+        if let Some(session_id) = named.get(&key) {
+            match self.store.get(session_id) {
+                Ok(session)
+                    if session.status != SessionStatus::Terminated
+                        && session.user_id == user_id
+                        && session.name.as_deref() == Some(name.as_str())
+                        && session.session_type == session_type =>
+                {
+                    return Ok(session);
+                }
+                // Terminated, evicted or not this user's: start a new one
+                Ok(_) | Err(StoreError::NotFound(_)) => {}
+                Err(e) => return Err(e.into()),
+            }
         }
 
+        let session = self.insert_new_session(
+            Session::new_typed_with_name(user_id, session_type, Some(name), limits),
+        )?;
+        named.insert(key, session.session_id.clone());
+        Ok(session)
+    }
+
+    /// Check limits and reserve `session` in the store, create the engine
+    /// environment for its type, then activate it
+    ///
+    /// The session is stored (still `Initializing`) before its engine is
+    /// built, so it counts against the caps from the moment it passes the
+    /// check; if the engine can't be created the reservation is dropped.
+    fn insert_new_session(&self, mut session: Session) -> Result<Session, ManagerError> {
+        {
+            let _guard = self.creation_lock.lock()
+                .map_err(|_| ManagerError::Store(StoreError::LockPoisoned))?;
+            self.check_session_limits(&session.user_id)?;
+            self.store.insert(session.clone())?;
+        }
+
+        let session_id = session.session_id.clone();
+        if let Err(e) = self.attach_environment(&session) {
+            self.store.remove(&session_id)?;
+            return Err(e);
+        }
+
+        // Activate and update
+        session.activate();
+        self.store.update(session.clone())?;
+
+        log::info!("Created {} session: {}", session.session_type, session_id);
+
+        Ok(session)
+    }
+    
+    /// Create the engine environment for a reserved session, taking a
+    /// prewarmed one when available
+    fn attach_environment(&self, session: &Session) -> Result<(), ManagerError> {
+        let session_id = session.session_id.clone();
+        match session.session_type {
+            SessionType::Clips => {
+                // Take a prewarmed CLIPS FFI environment or create one
+                let pooled = self.idle_clips_envs.lock().ok().and_then(|mut idle| idle.pop());
+                let clips_env = pooled.map_or_else(clara_clips::ClipsEnvironment::new, Ok)
+                    .map_err(|e| {
+                        log::error!("Failed to create CLIPS environment: {}", e);
+                        ManagerError::Store(StoreError::InvalidState)
+                    })?;
+
+                // Store CLIPS environment separately
+                let mut envs = self.clips_envs.write()
+                    .map_err(|_| ManagerError::Store(StoreError::LockPoisoned))?;
+                envs.insert(session_id, clips_env);
+            }
+            SessionType::Prolog => {
+                // Take a prewarmed Prolog FFI environment or create one
+                let pooled = self.idle_prolog_envs.lock().ok().and_then(|mut idle| idle.pop());
+                let prolog_env = pooled.map_or_else(clara_prolog::PrologEnvironment::new, Ok)
+                    .map_err(|e| {
+                        log::error!("Failed to create Prolog environment: {}", e);
+                        ManagerError::Store(StoreError::InvalidState)
+                    })?;
+
+                // Store Prolog environment separately
+                let mut envs = self.prolog_envs.write()
+                    .map_err(|_| ManagerError::Store(StoreError::LockPoisoned))?;
+                envs.insert(session_id, prolog_env);
+            }
+        }
+        Ok(())
+    }
+
+    /// Enforce the per-user and global session caps before creating a session
+    ///
+    /// CLIPS and Prolog sessions count against the same caps. If the global
+    /// cap is reached and idle eviction is configured, idle sessions are
+    /// terminated to make room before giving up.
+    fn check_session_limits(&self, user_id: &str) -> Result<(), ManagerError> {
         // Check per-user session limit
-        let user_count = self.store.count_user_sessions(&user_id)?;
+        let user_count = self.session_count_by_user(user_id)?;
         if user_count >= self.config.max_sessions_per_user {
             return Err(ManagerError::UserSessionLimitExceeded);
         }
 
-        let mut session = Session::new_with_name(user_id, name, limits);
+        // Check global session limit
+        let mut active_count = self.store.count_active()?;
+        if active_count >= self.config.max_concurrent_sessions {
+            if let Some(ttl) = self.config.idle_eviction_ttl_seconds {
+                let needed = active_count + 1 - self.config.max_concurrent_sessions;
+                let sessions = self.store.list_all()?;
+                for session_id in eviction::select_idle_sessions(&sessions, ttl, current_timestamp(), needed) {
+                    log::info!("Evicting idle session {} to make room", session_id);
+                    self.evict_session(&session_id)?;
+                }
+                active_count = self.store.count_active()?;
+            }
+        }
+        if active_count >= self.config.max_concurrent_sessions {
+            return Err(ManagerError::GlobalSessionLimitExceeded);
+        }
 
-        // Create CLIPS FFI environment
-        let clips_env = clara_clips::ClipsEnvironment::new()
-            .map_err(|e| {
-                log::error!("Failed to create CLIPS environment: {}", e);
-                ManagerError::Store(StoreError::InvalidState)
-            })?;
+        Ok(())
+    }
 
-        // Insert session
-        let session_id = session.session_id.clone();
-        self.store.insert(session.clone())?;
+    /// Terminate every session idle for longer than the configured
+    /// `idle_timeout_seconds`, dropping its CLIPS or Prolog engine
+    ///
+    /// Meant to be run on a timer. Sessions mid-evaluation are left alone,
+    /// and one that can't be terminated (e.g. it was terminated concurrently)
+    /// is logged and skipped. Returns the ids of the sessions terminated;
+    /// without a timeout configured nothing is.
+    pub fn evict_idle(&self) -> Result<Vec<SessionId>, ManagerError> {
+        let Some(timeout) = self.config.idle_timeout_seconds else {
+            return Ok(Vec::new());
+        };
 
-        // Store CLIPS environment separately
+        let sessions = self.store.list_all()?;
+        let mut evicted = Vec::new();
+        for session_id in eviction::select_idle_sessions(&sessions, timeout, current_timestamp(), usize::MAX) {
+            match self.evict_session(&session_id) {
+                Ok(_) => {
+                    log::info!("Evicted session {} after {}s idle", session_id, timeout);
+                    evicted.push(session_id);
+                }
+                Err(e) => log::warn!("Could not evict idle session {}: {}", session_id, e),
+            }
+        }
+        Ok(evicted)
+    }
+
+    /// Remove the records of sessions terminated more than `older_than` ago
+    ///
+    /// Terminated sessions stay in the store as tombstones, so a client that
+    /// asks about one just after terminating it is told it was terminated
+    /// rather than not found. Meant to be run on a timer with a short
+    /// retention window; purged sessions are reported as not found and
+    /// dropped from the named-session index. Returns the ids of the sessions
+    /// removed.
+    pub fn cleanup_terminated(&self, older_than: Duration) -> Result<Vec<SessionId>, ManagerError> {
+        let mut removed = Vec::new();
         {
-            let mut envs = self.clips_envs.write()
+            // Under the termination lock so a session can't be purged between
+            // terminate_with reading and updating it
+            let _guard = self.termination_lock.lock()
                 .map_err(|_| ManagerError::Store(StoreError::LockPoisoned))?;
-            envs.insert(session_id.clone(), clips_env);
+
+            let sessions = self.store.list_all()?;
+            for session_id in eviction::select_expired_tombstones(&sessions, older_than.as_secs(), current_timestamp()) {
+                self.store.remove(&session_id)?;
+                removed.push(session_id);
+            }
+        }
+        if removed.is_empty() {
+            return Ok(removed);
         }
 
-        // Activate and update
-        session.activate();
+        // Taken after the termination lock is released: session creation
+        // holds this lock while it may evict, i.e. take the termination lock
+        let purged: HashSet<&SessionId> = removed.iter().collect();
+        self.named_sessions.lock()
+            .map_err(|_| ManagerError::Store(StoreError::LockPoisoned))?
+            .retain(|_, session_id| !purged.contains(session_id));
+
+        log::debug!("Purged {} terminated session(s)", removed.len());
+        Ok(removed)
+    }
+
+    /// Terminate a session of either type and drop its engine environment
+    fn evict_session(&self, session_id: &SessionId) -> Result<Session, ManagerError> {
+        self.terminate_with(session_id, None).map(|(session, _)| session)
+    }
+
+    /// Mark a session terminated and drop its engine as one step
+    ///
+    /// Both happen under `termination_lock`, so of several concurrent calls
+    /// for one session exactly one terminates it. A session that is already
+    /// terminated is returned unchanged. With `expected` set, a session of
+    /// another type is rejected with `WrongSessionType`. Returns the session
+    /// and whether this call dropped its engine.
+    fn terminate_with(
+        &self,
+        session_id: &SessionId,
+        expected: Option<SessionType>,
+    ) -> Result<(Session, bool), ManagerError> {
+        let _guard = self.termination_lock.lock()
+            .map_err(|_| ManagerError::Store(StoreError::LockPoisoned))?;
+
+        let mut session = self.store.get(session_id)?;
+        if let Some(expected) = expected {
+            if session.session_type != expected {
+                return Err(ManagerError::WrongSessionType {
+                    expected: expected.to_string(),
+                    actual: session.session_type.to_string(),
+                });
+            }
+        }
+        if session.status == SessionStatus::Terminated {
+            log::debug!("Session {} is already terminated", session_id);
+            return Ok((session, false));
+        }
+
+        check_transition(&session, SessionStatus::Terminated)?;
+        session.terminate();
         self.store.update(session.clone())?;
 
-        // Return the session
-        Ok(session)
+        let dropped = match session.session_type {
+            SessionType::Clips => self.clips_envs.write()
+                .map_err(|_| ManagerError::Store(StoreError::LockPoisoned))?
+                .remove(session_id)
+                .is_some(),
+            SessionType::Prolog => self.prolog_envs.write()
+                .map_err(|_| ManagerError::Store(StoreError::LockPoisoned))?
+                .remove(session_id)
+                .is_some(),
+        };
+
+        log::info!("Terminated {} session: {}", session.session_type, session_id);
+        Ok((session, dropped))
     }
-    
+
     /// Save a session's facts and rules
     pub fn save_session(&self, session_id: &SessionId) -> Result<(), ManagerError> {
         
@@ -152,44 +697,97 @@ impl SessionManager {
     }
 
     /// Update a session's metadata
+    ///
+    /// Fails with `InvalidTransition` if the stored session can't move to
+    /// the new status (see [`SessionStatus::can_transition_to`]).
     pub fn update_session(&self, session: Session) -> Result<(), ManagerError> {
         if session.status == SessionStatus::Terminated {
             return Err(ManagerError::SessionTerminated);
         }
 
+        let current = self.store.get(&session.session_id)?;
+        check_transition(&current, session.status)?;
+
         self.store.update(session)?;
         Ok(())
     }
 
-    /// Terminate a session
-    pub fn terminate_session(&self, session_id: &SessionId) -> Result<Session, ManagerError> {
+    /// Move a session to `status`, e.g. to pause or resume it
+    ///
+    /// Terminating through this drops the session's engine environment, as
+    /// [`terminate_session`](Self::terminate_session) does. Fails with
+    /// `InvalidTransition` if the move isn't allowed.
+    pub fn set_session_status(
+        &self,
+        session_id: &SessionId,
+        status: SessionStatus,
+    ) -> Result<Session, ManagerError> {
+        if status == SessionStatus::Terminated {
+            return self.evict_session(session_id);
+        }
+
         let mut session = self.store.get(session_id)?;
-        session.terminate();
+        check_transition(&session, status)?;
+        session.status = status;
+        session.touch();
         self.store.update(session.clone())?;
 
-        // Remove CLIPS environment
-        {
-            let mut envs = self.clips_envs.write()
-                .map_err(|_| ManagerError::Store(StoreError::LockPoisoned))?;
-            envs.remove(session_id);
-        }
-
+        log::info!("Session {} is now {}", session_id, status);
         Ok(session)
     }
 
+    /// Terminate a session of either type and drop its engine
+    ///
+    /// Terminating a session that is already terminated succeeds and
+    /// returns it as it is, so concurrent or repeated calls are safe.
+    pub fn terminate_session(&self, session_id: &SessionId) -> Result<Session, ManagerError> {
+        self.evict_session(session_id)
+    }
+
     /// Execute an operation on a session's CLIPS environment
     /// Returns an error if the session or environment doesn't exist
+    ///
+    /// Errors from `f` are classified with `classify_clips_error`: input CLIPS
+    /// rejected becomes `ClipsSyntaxError`, anything else `EnvironmentError`.
     pub fn with_clips_env<F, R>(&self, session_id: &SessionId, f: F) -> Result<R, ManagerError>
     where
         F: FnOnce(&mut clara_clips::ClipsEnvironment) -> Result<R, String>,
     {
+        let _tools = self.install_tool_allowlist(session_id)?;
         let mut envs = self.clips_envs.write()
             .map_err(|_| ManagerError::Store(StoreError::LockPoisoned))?;
 
         let env = envs.get_mut(session_id)
             .ok_or_else(|| ManagerError::SessionNotFound)?;
 
-        f(env).map_err(|e| ManagerError::EnvironmentError(e))
+        f(env).map_err(|e| match classify_clips_error(&e) {
+            ClipsErrorKind::Syntax => ManagerError::ClipsSyntaxError(e),
+            ClipsErrorKind::Runtime => ManagerError::EnvironmentError(e),
+        })
+    }
+
+    /// Run an evaluation once the evaluation queue has a slot for it
+    ///
+    /// At most `max_in_flight_evals` run at once across all sessions and up
+    /// to `max_queued_evals` more wait; beyond that `f` isn't run and
+    /// `QueueFull` is returned.
+    pub fn run_queued<F, R>(&self, f: F) -> Result<R, ManagerError>
+    where
+        F: FnOnce() -> Result<R, ManagerError>,
+    {
+        match self.eval_queue.run(f) {
+            Ok(result) => result,
+            Err(QueueError::Full { in_flight, queued }) => {
+                log::warn!("Rejecting evaluation: {} running, {} queued", in_flight, queued);
+                Err(ManagerError::QueueFull)
+            }
+            Err(QueueError::LockPoisoned) => Err(ManagerError::Store(StoreError::LockPoisoned)),
+        }
+    }
+
+    /// The queue bounding [`run_queued`](Self::run_queued) evaluations
+    pub fn eval_queue(&self) -> &EvalQueue {
+        &self.eval_queue
     }
 
     // =========================================================================
@@ -212,72 +810,14 @@ impl SessionManager {
         name: Option<String>,
         limits: Option<ResourceLimits>,
     ) -> Result<Session, ManagerError> {
-        // Check global session limit
-        let active_count = self.store.count_active()?;
-        if active_count >= self.config.max_concurrent_sessions {
-            return Err(ManagerError::GlobalSessionLimitExceeded);
-        }
-
-        // Check per-user session limit
-        let user_count = self.store.count_user_sessions(&user_id)?;
-        if user_count >= self.config.max_sessions_per_user {
-            return Err(ManagerError::UserSessionLimitExceeded);
-        }
-
-        let mut session = Session::new_typed_with_name(user_id, SessionType::Prolog, name, limits);
-
-        // Create Prolog FFI environment
-        let prolog_env = clara_prolog::PrologEnvironment::new()
-            .map_err(|e| {
-                log::error!("Failed to create Prolog environment: {}", e);
-                ManagerError::Store(StoreError::InvalidState)
-            })?;
-
-        // Insert session
-        let session_id = session.session_id.clone();
-        self.store.insert(session.clone())?;
-
-        // Store Prolog environment separately
-        {
-            let mut envs = self.prolog_envs.write()
-                .map_err(|_| ManagerError::Store(StoreError::LockPoisoned))?;
-            envs.insert(session_id.clone(), prolog_env);
-        }
-
-        // Activate and update
-        session.activate();
-        self.store.update(session.clone())?;
-
-        log::info!("Created Prolog session: {}", session_id);
-
-        Ok(session)
+        self.insert_new_session(Session::new_typed_with_name(user_id, SessionType::Prolog, name, limits))
     }
 
-    /// Terminate a Prolog session
+    /// Terminate a Prolog session; idempotent like
+    /// [`terminate_session`](Self::terminate_session)
     pub fn terminate_prolog_session(&self, session_id: &SessionId) -> Result<Session, ManagerError> {
-        let mut session = self.store.get(session_id)?;
-
-        // Verify it's a Prolog session
-        if session.session_type != SessionType::Prolog {
-            return Err(ManagerError::WrongSessionType {
-                expected: "prolog".to_string(),
-                actual: session.session_type.to_string(),
-            });
-        }
-
-        session.terminate();
-        self.store.update(session.clone())?;
-
-        // Remove Prolog environment
-        {
-            let mut envs = self.prolog_envs.write()
-                .map_err(|_| ManagerError::Store(StoreError::LockPoisoned))?;
-            envs.remove(session_id);
-        }
-
-        log::info!("Terminated Prolog session: {}", session_id);
-
-        Ok(session)
+        self.terminate_with(session_id, Some(SessionType::Prolog))
+            .map(|(session, _)| session)
     }
 
     /// Execute an operation on a session's Prolog environment
@@ -286,13 +826,68 @@ impl SessionManager {
     where
         F: FnOnce(&mut clara_prolog::PrologEnvironment) -> Result<R, clara_prolog::PrologError>,
     {
+        self.with_prolog_env_typed(session_id, f)?
+            .map_err(ManagerError::PrologError)
+    }
+
+    /// Execute an operation on a session's Prolog environment, preserving the
+    /// closure's own error type
+    ///
+    /// The outer `Result` reports session lookup failures; the inner one is
+    /// exactly what the closure returned, so callers can match on specific
+    /// `PrologError` variants (or their own error type) without it being
+    /// folded into `ManagerError`.
+    pub fn with_prolog_env_typed<F, R, E>(
+        &self,
+        session_id: &SessionId,
+        f: F,
+    ) -> Result<Result<R, E>, ManagerError>
+    where
+        F: FnOnce(&mut clara_prolog::PrologEnvironment) -> Result<R, E>,
+    {
+        let _tools = self.install_tool_allowlist(session_id)?;
         let mut envs = self.prolog_envs.write()
             .map_err(|_| ManagerError::Store(StoreError::LockPoisoned))?;
 
         let env = envs.get_mut(session_id)
             .ok_or_else(|| ManagerError::SessionNotFound)?;
 
-        f(env).map_err(ManagerError::PrologError)
+        Ok(f(env))
+    }
+
+    /// Limit the tools `clara_evaluate` may call from a session
+    ///
+    /// The limit applies to everything later run through
+    /// [`with_clips_env`](Self::with_clips_env) and
+    /// [`with_prolog_env`](Self::with_prolog_env); `None` allows every
+    /// registered tool again.
+    pub fn set_allowed_tools(
+        &self,
+        session_id: &SessionId,
+        tools: Option<Vec<String>>,
+    ) -> Result<Session, ManagerError> {
+        let mut session = self.store.get(session_id)?;
+        if session.status == SessionStatus::Terminated {
+            return Err(ManagerError::SessionTerminated);
+        }
+        session.allowed_tools = tools;
+        self.store.update(session.clone())?;
+        Ok(session)
+    }
+
+    /// Install a session's tool allowlist on this thread, where its engine
+    /// runs, until the returned guard is dropped
+    fn install_tool_allowlist(
+        &self,
+        session_id: &SessionId,
+    ) -> Result<Option<clara_toolbox::ToolAllowlistGuard>, ManagerError> {
+        let allowed_tools = match self.store.get(session_id) {
+            Ok(session) => session.allowed_tools,
+            // The environment lookup reports the missing session
+            Err(StoreError::NotFound(_)) => None,
+            Err(e) => return Err(e.into()),
+        };
+        Ok(allowed_tools.map(clara_toolbox::tool_allowlist))
     }
 
     /// Get all sessions for a user
@@ -308,22 +903,21 @@ impl SessionManager {
         Ok(sessions)
     }
 
-    /// Get count of active sessions
+    /// Get count of live sessions across all users and session types
     pub fn count_active_sessions(&self) -> Result<usize, ManagerError> {
         Ok(self.store.count_active()?)
     }
 
+    /// Get count of non-terminated sessions (of any type) owned by a user
+    ///
+    /// This is the count checked against `max_sessions_per_user` on creation.
+    pub fn session_count_by_user(&self, user_id: &str) -> Result<usize, ManagerError> {
+        Ok(self.store.count_user_sessions(user_id)?)
+    }
+
     /// Get count of active sessions for a user
     pub fn count_user_active_sessions(&self, user_id: &str) -> Result<usize, ManagerError> {
-        let session_ids = self.store.get_user_sessions(user_id)?;
-
-        let count = session_ids
-            .iter()
-            .filter_map(|id| self.store.get(id).ok())
-            .filter(|s| s.status != SessionStatus::Terminated)
-            .count();
-
-        Ok(count)
+        self.session_count_by_user(user_id)
     }
 
     /// List all sessions (across all users)
@@ -344,6 +938,250 @@ impl SessionManager {
         self.store.update(session)?;
         Ok(())
     }
+
+    // =========================================================================
+    // Backup
+    // =========================================================================
+
+    /// Capture the metadata of every live session
+    ///
+    /// Engines aren't serialized. With `include_prolog_clauses`, the dynamic
+    /// clauses of the Prolog database are listed too, so [`restore`] can
+    /// replay them; they are only listed if a Prolog session is live.
+    ///
+    /// [`restore`]: Self::restore
+    pub fn snapshot(&self, include_prolog_clauses: bool) -> Result<Snapshot, ManagerError> {
+        let sessions: Vec<Session> = self
+            .store
+            .list_all()?
+            .into_iter()
+            .filter(|s| s.status != SessionStatus::Terminated)
+            .collect();
+
+        let prolog_clauses = if include_prolog_clauses {
+            let envs = self.prolog_envs.read()
+                .map_err(|_| ManagerError::Store(StoreError::LockPoisoned))?;
+            match envs.values().next() {
+                Some(env) => Some(env.list_clauses()?),
+                None => Some(Vec::new()),
+            }
+        } else {
+            None
+        };
+
+        log::info!("Snapshot taken of {} sessions", sessions.len());
+
+        Ok(Snapshot {
+            created_at: current_timestamp(),
+            sessions,
+            prolog_clauses,
+        })
+    }
+
+    /// Recreate the sessions in `snapshot` and replay its Prolog clauses
+    ///
+    /// Sessions keep their ids and metadata but get fresh engines. Sessions
+    /// whose id already exists are left alone. Session limits apply as for
+    /// any new session. Returns the restored sessions.
+    pub fn restore(&self, snapshot: &Snapshot) -> Result<Vec<Session>, ManagerError> {
+        let mut restored = Vec::new();
+        for session in &snapshot.sessions {
+            if self.store.exists(&session.session_id)? {
+                log::warn!("Not restoring session {}: it already exists", session.session_id);
+                continue;
+            }
+            let session = self.insert_new_session(session.clone())?;
+            self.remember_named_session(&session)?;
+            restored.push(session);
+        }
+
+        if let Some(clauses) = snapshot.prolog_clauses.as_ref().filter(|c| !c.is_empty()) {
+            self.replay_prolog_clauses(clauses)?;
+        }
+
+        log::info!("Restored {} sessions from snapshot", restored.len());
+
+        Ok(restored)
+    }
+
+    /// Add `clauses` to the Prolog database, skipping any it already holds
+    fn replay_prolog_clauses(&self, clauses: &[String]) -> Result<(), ManagerError> {
+        let envs = self.prolog_envs.read()
+            .map_err(|_| ManagerError::Store(StoreError::LockPoisoned))?;
+        // The database outlives engines, so a short-lived one will do
+        let fresh;
+        let env = match envs.values().next() {
+            Some(env) => env,
+            None => {
+                fresh = clara_prolog::PrologEnvironment::new()?;
+                &fresh
+            }
+        };
+
+        let existing: HashSet<String> = env.list_clauses()?.into_iter().collect();
+        let missing: Vec<&str> = clauses
+            .iter()
+            .filter(|c| !existing.contains(*c))
+            .map(String::as_str)
+            .collect();
+        if !missing.is_empty() {
+            env.consult_string(&missing.join("\n"))?;
+        }
+        log::info!("Replayed {} Prolog clauses", missing.len());
+        Ok(())
+    }
+
+    // =========================================================================
+    // Persistence
+    // =========================================================================
+
+    /// Write a live session's metadata and knowledge base to
+    /// `<dir>/<session_id>.json`, creating `dir` if needed
+    ///
+    /// CLIPS sessions save their templates, functions, rules and facts;
+    /// Prolog sessions the dynamic clauses of the shared Prolog database.
+    /// Returns the path written.
+    pub fn save_to_disk(&self, session_id: &SessionId, dir: &Path, label: &str) -> Result<PathBuf, ManagerError> {
+        let session = self.store.get(session_id)?;
+        if session.status == SessionStatus::Terminated {
+            return Err(ManagerError::SessionTerminated);
+        }
+
+        let mut file = SessionFile::new(session, label);
+        match file.session.session_type {
+            SessionType::Clips => {
+                let (constructs, facts) =
+                    self.with_clips_env(session_id, |env| Ok((env.constructs()?, env.facts()?)))?;
+                file.clips_constructs = constructs;
+                file.clips_facts = facts;
+            }
+            SessionType::Prolog => {
+                file.prolog_clauses = self.with_prolog_env(session_id, |env| env.list_clauses())?;
+            }
+        }
+
+        let path = persistence::write_session_file(dir, &file)?;
+        log::info!("Saved session {} to {}", session_id, path.display());
+        Ok(path)
+    }
+
+    /// Rebuild every session saved under `dir` by [`save_to_disk`]
+    ///
+    /// Sessions keep their ids and metadata but get fresh engines with the
+    /// saved knowledge base replayed into them. Sessions whose id already
+    /// exists are left alone, and session limits apply as for any new
+    /// session. A file that can't be restored, including one naming a
+    /// session type this build doesn't support, is logged and skipped; a
+    /// missing `dir` restores nothing. Returns the restored sessions.
+    ///
+    /// [`save_to_disk`]: Self::save_to_disk
+    pub fn restore_from_disk(&self, dir: &Path) -> Result<Vec<Session>, ManagerError> {
+        let mut restored = Vec::new();
+        let mut prolog_clauses = Vec::new();
+        let mut seen = HashSet::new();
+        for path in persistence::session_files(dir)? {
+            let file = match persistence::read_session_file(&path) {
+                Ok(file) => file,
+                Err(ManagerError::UnsupportedSessionType(session_type)) => {
+                    log::warn!("Not restoring {}: unsupported session type {}", path.display(), session_type);
+                    continue;
+                }
+                Err(e) => {
+                    log::warn!("Not restoring {}: {}", path.display(), e);
+                    continue;
+                }
+            };
+            if self.store.exists(&file.session.session_id)? {
+                log::warn!("Not restoring session {}: it already exists", file.session.session_id);
+                continue;
+            }
+
+            match self.restore_session(&file) {
+                Ok(session) => {
+                    self.remember_named_session(&session)?;
+                    restored.push(session);
+                }
+                Err(e) => {
+                    log::warn!("Failed to restore session {}: {}", file.session.session_id, e);
+                    continue;
+                }
+            }
+            // Every Prolog file holds the whole shared database; replay it once
+            for clause in file.prolog_clauses {
+                if seen.insert(clause.clone()) {
+                    prolog_clauses.push(clause);
+                }
+            }
+        }
+
+        if !prolog_clauses.is_empty() {
+            self.replay_prolog_clauses(&prolog_clauses)?;
+        }
+
+        log::info!("Restored {} sessions from {}", restored.len(), dir.display());
+
+        Ok(restored)
+    }
+
+    /// Recreate the session in `file`, including its Prolog clauses
+    ///
+    /// Fails with `AlreadyExists` if the session is live.
+    pub fn restore_session_file(&self, file: &SessionFile) -> Result<Session, ManagerError> {
+        let session = self.restore_session(file)?;
+        self.remember_named_session(&session)?;
+        if !file.prolog_clauses.is_empty() {
+            self.replay_prolog_clauses(&file.prolog_clauses)?;
+        }
+        Ok(session)
+    }
+
+    /// Let [`get_or_create_named_session`](Self::get_or_create_named_session)
+    /// find a restored named session again, unless the name already maps to
+    /// a session
+    fn remember_named_session(&self, session: &Session) -> Result<(), ManagerError> {
+        let Some(name) = &session.name else { return Ok(()) };
+        self.named_sessions.lock()
+            .map_err(|_| ManagerError::Store(StoreError::LockPoisoned))?
+            .entry((session.user_id.clone(), name.clone(), session.session_type))
+            .or_insert_with(|| session.session_id.clone());
+        Ok(())
+    }
+
+    /// Create `file`'s session and rebuild its CLIPS knowledge base; the
+    /// session is dropped again if that fails
+    fn restore_session(&self, file: &SessionFile) -> Result<Session, ManagerError> {
+        let session = self.insert_new_session(file.session.clone())?;
+        if file.clips_constructs.is_empty() && file.clips_facts.is_empty() {
+            return Ok(session);
+        }
+
+        let replayed = self.with_clips_env(&session.session_id, |env| {
+            // The Coire library constructs are already in a fresh environment
+            let library = env.constructs()?;
+            for construct in file.clips_constructs.iter().filter(|c| !library.contains(c)) {
+                env.build(construct)?;
+            }
+            for fact in &file.clips_facts {
+                env.eval(&format!("(assert {})", fact))?;
+            }
+            Ok(())
+        });
+        if let Err(e) = replayed {
+            self.evict_session(&session.session_id)?;
+            self.store.remove(&session.session_id)?;
+            return Err(e);
+        }
+        Ok(session)
+    }
+}
+
+/// Reject moving `session` to `status` if the status state machine forbids it
+fn check_transition(session: &Session, status: SessionStatus) -> Result<(), ManagerError> {
+    if session.status.can_transition_to(status) {
+        Ok(())
+    } else {
+        Err(ManagerError::InvalidTransition { from: session.status, to: status })
+    }
 }
 
 impl Clone for SessionManager {
@@ -353,6 +1191,13 @@ impl Clone for SessionManager {
             config: self.config.clone(),
             clips_envs: Arc::clone(&self.clips_envs),
             prolog_envs: Arc::clone(&self.prolog_envs),
+            named_sessions: Arc::clone(&self.named_sessions),
+            creation_lock: Arc::clone(&self.creation_lock),
+            termination_lock: Arc::clone(&self.termination_lock),
+            idle_clips_envs: Arc::clone(&self.idle_clips_envs),
+            idle_prolog_envs: Arc::clone(&self.idle_prolog_envs),
+            quarantined_prolog_envs: Arc::clone(&self.quarantined_prolog_envs),
+            eval_queue: self.eval_queue.clone(),
         }
     }
 }
@@ -379,11 +1224,187 @@ mod tests {
         assert_eq!(retrieved.session_id, created.session_id);
     }
 
+    #[test]
+    fn test_status_transitions_enforced() {
+        let manager = SessionManager::new(ManagerConfig::default());
+        let session = manager.create_session("user-1".to_string(), None).unwrap();
+        let id = session.session_id.clone();
+
+        // active -> paused -> active
+        let paused = manager.set_session_status(&id, SessionStatus::Paused).unwrap();
+        assert_eq!(paused.status, SessionStatus::Paused);
+
+        // A paused session must be resumed before it can evaluate
+        let result = manager.set_session_status(&id, SessionStatus::Evaluating);
+        assert!(matches!(
+            result,
+            Err(ManagerError::InvalidTransition { from: SessionStatus::Paused, to: SessionStatus::Evaluating })
+        ));
+        let mut evaluating = paused.clone();
+        evaluating.start_evaluating();
+        assert!(matches!(manager.update_session(evaluating), Err(ManagerError::InvalidTransition { .. })));
+
+        let resumed = manager.set_session_status(&id, SessionStatus::Active).unwrap();
+        assert_eq!(resumed.status, SessionStatus::Active);
+
+        // Terminated is final
+        let terminated = manager.set_session_status(&id, SessionStatus::Terminated).unwrap();
+        assert_eq!(terminated.status, SessionStatus::Terminated);
+        assert!(matches!(
+            manager.with_clips_env(&id, |_| Ok(())),
+            Err(ManagerError::SessionNotFound)
+        ));
+        for status in [SessionStatus::Paused, SessionStatus::Active] {
+            assert!(matches!(
+                manager.set_session_status(&id, status),
+                Err(ManagerError::InvalidTransition { from: SessionStatus::Terminated, .. })
+            ));
+        }
+        let mut revived = terminated;
+        revived.activate();
+        assert!(matches!(manager.update_session(revived), Err(ManagerError::InvalidTransition { .. })));
+
+        // Terminating again is a no-op
+        manager.terminate_session(&id).unwrap();
+    }
+
+    #[test]
+    fn test_config_builder() {
+        let config = ManagerConfig::builder()
+            .max_concurrent_sessions(20)
+            .max_sessions_per_user(4)
+            .idle_eviction_ttl_seconds(300)
+            .default_session_type(SessionType::Prolog)
+            .build()
+            .unwrap();
+        assert_eq!(config.max_concurrent_sessions, 20);
+        assert_eq!(config.max_sessions_per_user, 4);
+        assert_eq!(config.idle_eviction_ttl_seconds, Some(300));
+        assert_eq!(config.default_session_type, SessionType::Prolog);
+
+        let defaults = ManagerConfig::builder().build().unwrap();
+        assert_eq!(defaults.max_concurrent_sessions, ManagerConfig::default().max_concurrent_sessions);
+        assert_eq!(defaults.idle_eviction_ttl_seconds, None);
+
+        let config = ManagerConfig::builder()
+            .idle_eviction_ttl_seconds(300)
+            .no_idle_eviction()
+            .build()
+            .unwrap();
+        assert_eq!(config.idle_eviction_ttl_seconds, None);
+    }
+
+    #[test]
+    fn test_config_builder_rejects_invalid_limits() {
+        assert_eq!(
+            ManagerConfig::builder().max_concurrent_sessions(0).build().unwrap_err(),
+            ConfigError::ZeroLimit("max_concurrent_sessions")
+        );
+        assert_eq!(
+            ManagerConfig::builder().max_sessions_per_user(0).build().unwrap_err(),
+            ConfigError::ZeroLimit("max_sessions_per_user")
+        );
+        assert_eq!(
+            ManagerConfig::builder()
+                .max_concurrent_sessions(5)
+                .max_sessions_per_user(10)
+                .build()
+                .unwrap_err(),
+            ConfigError::PerUserAboveGlobal { per_user: 10, global: 5 }
+        );
+        assert_eq!(
+            ManagerConfig::builder().idle_eviction_ttl_seconds(0).build().unwrap_err(),
+            ConfigError::ZeroIdleTtl
+        );
+        assert_eq!(
+            ManagerConfig::builder().idle_timeout_seconds(0).build().unwrap_err(),
+            ConfigError::ZeroLimit("idle_timeout_seconds")
+        );
+    }
+
+    #[test]
+    fn test_prewarm_fills_idle_pools() {
+        let manager = SessionManager::new(ManagerConfig::default());
+        assert_eq!(manager.prewarm(2, 3).unwrap(), (2, 3));
+        assert_eq!(manager.idle_prolog_count(), 2);
+        assert_eq!(manager.idle_clips_count(), 3);
+
+        // New sessions take their engine from the pool
+        manager.create_prolog_session("user-1".to_string(), None).unwrap();
+        let session = manager.create_session("user-1".to_string(), None).unwrap();
+        assert_eq!(manager.idle_prolog_count(), 1);
+        assert_eq!(manager.idle_clips_count(), 2);
+        assert!(manager.with_clips_env(&session.session_id, |env| env.eval("(+ 1 2)")).unwrap().contains('3'));
+    }
+
+    #[test]
+    fn test_prewarm_respects_global_limit() {
+        let config = ManagerConfig {
+            max_concurrent_sessions: 4,
+            max_sessions_per_user: 4,
+            ..ManagerConfig::default()
+        };
+        let manager = SessionManager::new(config);
+        manager.create_session("user-1".to_string(), None).unwrap();
+
+        assert_eq!(manager.prewarm(2, 5).unwrap(), (2, 1));
+        assert_eq!(manager.idle_prolog_count(), 2);
+        assert_eq!(manager.idle_clips_count(), 1);
+        assert_eq!(manager.prewarm(1, 1).unwrap(), (0, 0));
+    }
+
+    #[test]
+    fn test_broken_idle_prolog_engines_replaced() {
+        let manager = SessionManager::new(ManagerConfig::default());
+        manager.prewarm(2, 0).unwrap();
+        assert_eq!(manager.check_idle_prolog_engines(2).unwrap(), (0, 0));
+
+        // Leave one pooled engine attached to another thread, as a query
+        // aborted before releasing its engine would
+        let (engine, broken_id) = {
+            let idle = manager.idle_prolog_envs.lock().unwrap();
+            (idle[0].as_ptr() as usize, idle[0].session_id())
+        };
+        let (attached_tx, attached_rx) = std::sync::mpsc::channel();
+        let (release_tx, release_rx) = std::sync::mpsc::channel::<()>();
+        let holder = std::thread::spawn(move || unsafe {
+            use clara_prolog::backend::ffi::{PL_engine_t, PL_set_engine};
+            PL_set_engine(engine as PL_engine_t, std::ptr::null_mut());
+            attached_tx.send(()).unwrap();
+            release_rx.recv().ok();
+            PL_set_engine(std::ptr::null_mut(), std::ptr::null_mut());
+        });
+        attached_rx.recv().unwrap();
+
+        // The broken engine is quarantined, not destroyed, and replaced
+        assert_eq!(manager.check_idle_prolog_engines(2).unwrap(), (1, 1));
+        assert_eq!(manager.idle_prolog_count(), 2);
+        assert_eq!(manager.quarantined_prolog_count(), 1);
+        {
+            let quarantined = manager.quarantined_prolog_envs.lock().unwrap();
+            assert_eq!(quarantined[0].session_id(), broken_id);
+            let idle = manager.idle_prolog_envs.lock().unwrap();
+            assert!(idle.iter().all(|env| env.session_id() != broken_id && env.ping()));
+        }
+
+        // Kept while its holder is still attached
+        assert_eq!(manager.check_idle_prolog_engines(2).unwrap(), (0, 0));
+        assert_eq!(manager.quarantined_prolog_count(), 1);
+
+        // Destroyed once its holder has let go
+        release_tx.send(()).unwrap();
+        holder.join().unwrap();
+        assert_eq!(manager.check_idle_prolog_engines(2).unwrap(), (0, 0));
+        assert_eq!(manager.quarantined_prolog_count(), 0);
+        assert_eq!(manager.idle_prolog_count(), 2);
+    }
+
     #[test]
     fn test_user_session_limit() {
         let config = ManagerConfig {
             max_concurrent_sessions: 100,
             max_sessions_per_user: 2,
+            ..ManagerConfig::default()
         };
         let manager = SessionManager::new(config);
 
@@ -394,6 +1415,245 @@ mod tests {
         assert!(matches!(result, Err(ManagerError::UserSessionLimitExceeded)));
     }
 
+    #[test]
+    fn test_user_session_limit_spans_session_types() {
+        let config = ManagerConfig {
+            max_concurrent_sessions: 100,
+            max_sessions_per_user: 2,
+            ..ManagerConfig::default()
+        };
+        let manager = SessionManager::new(config);
+
+        manager.create_session("user-1".to_string(), None).unwrap();
+        manager.create_prolog_session("user-1".to_string(), None).unwrap();
+        assert_eq!(manager.session_count_by_user("user-1").unwrap(), 2);
+
+        let result = manager.create_prolog_session("user-1".to_string(), None);
+        assert!(matches!(result, Err(ManagerError::UserSessionLimitExceeded)));
+
+        // Other users are unaffected
+        manager.create_session("user-2".to_string(), None).unwrap();
+    }
+
+    #[test]
+    fn test_concurrent_creation_respects_limits() {
+        let config = ManagerConfig {
+            max_concurrent_sessions: 5,
+            max_sessions_per_user: 3,
+            ..ManagerConfig::default()
+        };
+        let manager = SessionManager::new(config);
+
+        // Eight threads per user race for three slots each; only five fit overall
+        let handles: Vec<_> = (0..16)
+            .map(|i| {
+                let manager = manager.clone();
+                std::thread::spawn(move || manager.create_session(format!("user-{}", i % 2), None))
+            })
+            .collect();
+        let results: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+
+        assert_eq!(results.iter().filter(|r| r.is_ok()).count(), 5);
+        assert!(results.iter().all(|r| matches!(
+            r,
+            Ok(_) | Err(ManagerError::UserSessionLimitExceeded) | Err(ManagerError::GlobalSessionLimitExceeded)
+        )));
+        assert_eq!(manager.count_active_sessions().unwrap(), 5);
+        assert!(manager.session_count_by_user("user-0").unwrap() <= 3);
+        assert!(manager.session_count_by_user("user-1").unwrap() <= 3);
+    }
+
+    #[test]
+    fn test_eval_queue_rejects_overflow_without_deadlock() {
+        let config = ManagerConfig {
+            max_in_flight_evals: 2,
+            max_queued_evals: 2,
+            ..ManagerConfig::default()
+        };
+        let manager = SessionManager::new(config);
+        let session = manager.create_session("user-1".to_string(), None).unwrap();
+
+        // Twelve evaluations arrive together; two run, two wait, the rest bounce
+        let barrier = Arc::new(std::sync::Barrier::new(12));
+        let handles: Vec<_> = (0..12)
+            .map(|_| {
+                let manager = manager.clone();
+                let barrier = Arc::clone(&barrier);
+                let session_id = session.session_id.clone();
+                std::thread::spawn(move || {
+                    barrier.wait();
+                    manager.run_queued(|| {
+                        std::thread::sleep(std::time::Duration::from_millis(100));
+                        manager.with_clips_env(&session_id, |env| env.eval("(+ 1 2)"))
+                    })
+                })
+            })
+            .collect();
+        let results: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+
+        let ok = results.iter().filter(|r| r.is_ok()).count();
+        let full = results.iter().filter(|r| matches!(r, Err(ManagerError::QueueFull))).count();
+        assert_eq!(ok + full, 12, "{:?}", results);
+        assert!(ok >= 4, "Running and queued evaluations should all complete: {:?}", results);
+        assert!(full > 0, "Evaluations beyond the queue should be rejected");
+        assert_eq!((manager.eval_queue().in_flight(), manager.eval_queue().queued()), (0, 0));
+
+        // Once drained the queue admits work again
+        assert!(manager.run_queued(|| Ok(())).is_ok());
+    }
+
+    #[test]
+    fn test_terminate_frees_user_session_slot() {
+        let config = ManagerConfig {
+            max_concurrent_sessions: 100,
+            max_sessions_per_user: 2,
+            ..ManagerConfig::default()
+        };
+        let manager = SessionManager::new(config);
+
+        let first = manager.create_session("user-1".to_string(), None).unwrap();
+        manager.create_session("user-1".to_string(), None).unwrap();
+        assert!(manager.create_session("user-1".to_string(), None).is_err());
+
+        manager.terminate_session(&first.session_id).unwrap();
+        assert_eq!(manager.session_count_by_user("user-1").unwrap(), 1);
+
+        manager.create_session("user-1".to_string(), None).unwrap();
+        assert_eq!(manager.session_count_by_user("user-1").unwrap(), 2);
+    }
+
+    #[test]
+    fn test_global_session_limit() {
+        let config = ManagerConfig {
+            max_concurrent_sessions: 2,
+            max_sessions_per_user: 10,
+            ..ManagerConfig::default()
+        };
+        let manager = SessionManager::new(config);
+
+        let clips_session = manager.create_session("user-1".to_string(), None).unwrap();
+        manager.create_prolog_session("user-2".to_string(), None).unwrap();
+        assert_eq!(manager.count_active_sessions().unwrap(), 2);
+
+        let result = manager.create_session("user-3".to_string(), None);
+        assert!(matches!(result, Err(ManagerError::GlobalSessionLimitExceeded)));
+        let result = manager.create_prolog_session("user-3".to_string(), None);
+        assert!(matches!(result, Err(ManagerError::GlobalSessionLimitExceeded)));
+
+        manager.terminate_session(&clips_session.session_id).unwrap();
+        manager.create_prolog_session("user-3".to_string(), None).unwrap();
+    }
+
+    #[test]
+    fn test_global_session_limit_evicts_idle_sessions() {
+        let config = ManagerConfig {
+            max_concurrent_sessions: 2,
+            max_sessions_per_user: 10,
+            idle_eviction_ttl_seconds: Some(60),
+            ..ManagerConfig::default()
+        };
+        let manager = SessionManager::new(config);
+
+        let mut idle = manager.create_session("user-1".to_string(), None).unwrap();
+        let busy = manager.create_prolog_session("user-2".to_string(), None).unwrap();
+
+        // Nothing is idle yet, so the limit holds
+        let result = manager.create_session("user-3".to_string(), None);
+        assert!(matches!(result, Err(ManagerError::GlobalSessionLimitExceeded)));
+
+        idle.touched_at -= 120;
+        manager.update_session(idle.clone()).unwrap();
+
+        manager.create_session("user-3".to_string(), None).unwrap();
+        assert!(matches!(
+            manager.get_session(&idle.session_id),
+            Err(ManagerError::SessionTerminated)
+        ));
+        assert!(manager.get_session(&busy.session_id).is_ok());
+    }
+
+    #[test]
+    fn test_evict_idle_reaps_sessions_past_timeout() {
+        let config = ManagerConfig::builder().idle_timeout_seconds(60).build().unwrap();
+        let manager = SessionManager::new(config);
+
+        let mut idle_clips = manager.create_session("user-1".to_string(), None).unwrap();
+        let mut idle_prolog = manager.create_prolog_session("user-1".to_string(), None).unwrap();
+        let fresh = manager.create_session("user-2".to_string(), None).unwrap();
+        assert!(manager.evict_idle().unwrap().is_empty());
+
+        idle_clips.touched_at -= 120;
+        manager.update_session(idle_clips.clone()).unwrap();
+        idle_prolog.touched_at -= 120;
+        manager.update_session(idle_prolog.clone()).unwrap();
+
+        let mut evicted = manager.evict_idle().unwrap();
+        evicted.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut expected = vec![idle_clips.session_id.clone(), idle_prolog.session_id.clone()];
+        expected.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(evicted, expected);
+
+        // Their engines are gone along with the sessions
+        assert!(matches!(
+            manager.get_session(&idle_clips.session_id),
+            Err(ManagerError::SessionTerminated)
+        ));
+        assert!(matches!(
+            manager.with_clips_env(&idle_clips.session_id, |_| Ok(())),
+            Err(ManagerError::SessionNotFound)
+        ));
+        assert!(manager.with_prolog_env(&idle_prolog.session_id, |_| Ok(())).is_err());
+        assert!(manager.with_clips_env(&fresh.session_id, |env| env.eval("(+ 1 2)")).is_ok());
+
+        // Already terminated sessions aren't reaped twice
+        assert!(manager.evict_idle().unwrap().is_empty());
+
+        // Without a timeout nothing is evicted
+        let manager = SessionManager::new(ManagerConfig::default());
+        let mut stale = manager.create_session("user-1".to_string(), None).unwrap();
+        stale.touched_at -= 1_000_000;
+        manager.update_session(stale).unwrap();
+        assert!(manager.evict_idle().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_cleanup_terminated_keeps_recent_tombstones() {
+        let manager = SessionManager::new(ManagerConfig::default());
+        let live = manager.create_session("user-1".to_string(), None).unwrap();
+        let old = manager
+            .get_or_create_named_session("user-1".to_string(), "old".to_string(), SessionType::Prolog, None)
+            .unwrap();
+        let recent = manager
+            .get_or_create_named_session("user-1".to_string(), "recent".to_string(), SessionType::Clips, None)
+            .unwrap();
+        manager.terminate_prolog_session(&old.session_id).unwrap();
+        manager.terminate_session(&recent.session_id).unwrap();
+        let named_ids = |manager: &SessionManager| -> Vec<SessionId> {
+            manager.named_sessions.lock().unwrap().values().cloned().collect()
+        };
+
+        // Within the retention window both tombstones answer "terminated"
+        let retention = Duration::from_secs(60);
+        assert!(manager.cleanup_terminated(retention).unwrap().is_empty());
+        assert!(matches!(manager.get_session(&old.session_id), Err(ManagerError::SessionTerminated)));
+
+        let mut tombstone = manager.store.get(&old.session_id).unwrap();
+        tombstone.touched_at -= 120;
+        manager.store.update(tombstone).unwrap();
+
+        assert_eq!(named_ids(&manager).len(), 2);
+        assert_eq!(manager.cleanup_terminated(retention).unwrap(), vec![old.session_id.clone()]);
+        assert!(matches!(
+            manager.get_session(&old.session_id),
+            Err(ManagerError::Store(StoreError::NotFound(_)))
+        ));
+        assert!(matches!(manager.get_session(&recent.session_id), Err(ManagerError::SessionTerminated)));
+        assert!(manager.get_session(&live.session_id).is_ok());
+
+        // The purged session no longer has a name index entry; the recent one keeps its own
+        assert_eq!(named_ids(&manager), vec![recent.session_id.clone()]);
+    }
+
     #[test]
     fn test_terminate_session() {
         let manager = SessionManager::new(ManagerConfig::default());
@@ -445,6 +1705,41 @@ mod tests {
         assert!(matches!(result, Err(ManagerError::SessionTerminated)));
     }
 
+    #[test]
+    fn test_concurrent_terminate_drops_engine_once() {
+        let manager = SessionManager::new(ManagerConfig::default());
+        for session in [
+            manager.create_session("user-1".to_string(), None).unwrap(),
+            manager.create_prolog_session("user-1".to_string(), None).unwrap(),
+        ] {
+            let barrier = Arc::new(std::sync::Barrier::new(2));
+            let handles: Vec<_> = (0..2)
+                .map(|_| {
+                    let manager = manager.clone();
+                    let barrier = Arc::clone(&barrier);
+                    let id = session.session_id.clone();
+                    std::thread::spawn(move || {
+                        barrier.wait();
+                        manager.terminate_with(&id, None)
+                    })
+                })
+                .collect();
+            let results: Vec<_> = handles.into_iter().map(|h| h.join().unwrap().unwrap()).collect();
+
+            let dropped = results.iter().filter(|(_, dropped)| *dropped).count();
+            assert_eq!(dropped, 1, "{} engine dropped {} times", session.session_type, dropped);
+            for (terminated, _) in &results {
+                assert_eq!(terminated.status, SessionStatus::Terminated);
+            }
+        }
+
+        // The public entry points are idempotent too
+        let prolog = manager.create_prolog_session("user-1".to_string(), None).unwrap();
+        manager.terminate_prolog_session(&prolog.session_id).unwrap();
+        let again = manager.terminate_prolog_session(&prolog.session_id).unwrap();
+        assert_eq!(again.status, SessionStatus::Terminated);
+    }
+
     #[test]
     fn test_prolog_session_wrong_type() {
         let manager = SessionManager::new(ManagerConfig::default());
@@ -489,4 +1784,150 @@ mod tests {
         manager.terminate_session(&clips_session.session_id).unwrap();
         manager.terminate_prolog_session(&prolog_session.session_id).unwrap();
     }
+
+    #[test]
+    fn test_get_or_create_named_session() {
+        let manager = SessionManager::new(ManagerConfig::default());
+
+        let first = manager
+            .get_or_create_named_session("user-1".to_string(), "scratch".to_string(), SessionType::Clips, None)
+            .unwrap();
+        let second = manager
+            .get_or_create_named_session("user-1".to_string(), "scratch".to_string(), SessionType::Clips, None)
+            .unwrap();
+
+        assert_eq!(first.session_id, second.session_id);
+        assert_eq!(first.created_at, second.created_at);
+        assert_eq!(second.name.as_deref(), Some("scratch"));
+        assert_eq!(manager.session_count_by_user("user-1").unwrap(), 1);
+
+        // Different user, name or type gets its own session
+        let other = manager
+            .get_or_create_named_session("user-2".to_string(), "scratch".to_string(), SessionType::Clips, None)
+            .unwrap();
+        assert_ne!(other.session_id, first.session_id);
+        let prolog = manager
+            .get_or_create_named_session("user-1".to_string(), "scratch".to_string(), SessionType::Prolog, None)
+            .unwrap();
+        assert_ne!(prolog.session_id, first.session_id);
+        assert_eq!(prolog.session_type, SessionType::Prolog);
+
+        manager.terminate_prolog_session(&prolog.session_id).unwrap();
+    }
+
+    #[test]
+    fn test_get_or_create_named_session_replaces_terminated() {
+        let manager = SessionManager::new(ManagerConfig::default());
+
+        let first = manager
+            .get_or_create_named_session("user-1".to_string(), "scratch".to_string(), SessionType::Clips, None)
+            .unwrap();
+        manager.terminate_session(&first.session_id).unwrap();
+
+        let second = manager
+            .get_or_create_named_session("user-1".to_string(), "scratch".to_string(), SessionType::Clips, None)
+            .unwrap();
+        assert_ne!(second.session_id, first.session_id);
+        assert_eq!(second.status, SessionStatus::Active);
+        assert!(manager.with_clips_env(&second.session_id, |env| env.eval("(+ 1 2)")).is_ok());
+    }
+
+    #[test]
+    fn test_get_or_create_named_session_checks_owner() {
+        let manager = SessionManager::new(ManagerConfig::default());
+
+        let theirs = manager
+            .get_or_create_named_session("user-1".to_string(), "scratch".to_string(), SessionType::Clips, None)
+            .unwrap();
+        // Point another user's name at the first user's session
+        manager.named_sessions.lock().unwrap().insert(
+            ("user-2".to_string(), "scratch".to_string(), SessionType::Clips),
+            theirs.session_id.clone(),
+        );
+
+        let mine = manager
+            .get_or_create_named_session("user-2".to_string(), "scratch".to_string(), SessionType::Clips, None)
+            .unwrap();
+        assert_ne!(mine.session_id, theirs.session_id);
+        assert_eq!(mine.user_id, "user-2");
+        assert_eq!(manager.get_session(&theirs.session_id).unwrap().user_id, "user-1");
+    }
+
+    #[test]
+    fn test_create_typed_session_uses_default_type() {
+        let config = ManagerConfig {
+            default_session_type: SessionType::Prolog,
+            ..ManagerConfig::default()
+        };
+        let manager = SessionManager::new(config);
+
+        let session = manager
+            .create_typed_session("user-1".to_string(), None, None, None)
+            .unwrap();
+        assert_eq!(session.session_type, SessionType::Prolog);
+        assert!(manager.with_prolog_env(&session.session_id, |env| env.query_once("true")).is_ok());
+
+        // An explicit type overrides the default
+        let session = manager
+            .create_typed_session("user-1".to_string(), Some(SessionType::Clips), None, None)
+            .unwrap();
+        assert_eq!(session.session_type, SessionType::Clips);
+    }
+
+    #[test]
+    fn test_default_session_type_is_clips() {
+        let manager = SessionManager::new(ManagerConfig::default());
+        let session = manager
+            .create_typed_session("user-1".to_string(), None, None, None)
+            .unwrap();
+        assert_eq!(session.session_type, SessionType::Clips);
+    }
+
+    #[test]
+    fn test_snapshot_and_restore() {
+        let manager = SessionManager::new(ManagerConfig::default());
+        let clips = manager.create_session_with_name("user-1".to_string(), Some("rules".to_string()), None).unwrap();
+        let prolog = manager.create_prolog_session("user-2".to_string(), None).unwrap();
+        manager.with_prolog_env(&prolog.session_id, |env| {
+            env.assertz("snapshot_color(red)")?;
+            env.assertz("snapshot_color('light blue')")?;
+            env.assertz("snapshot_bright(X) :- snapshot_color(X), X \\== red")
+        }).unwrap();
+
+        let snapshot = manager.snapshot(true).unwrap();
+        assert_eq!(snapshot.sessions.len(), 2);
+        let clauses = snapshot.prolog_clauses.as_ref().unwrap();
+        assert!(clauses.iter().any(|c| c == "snapshot_color('light blue')."), "{:?}", clauses);
+
+        // The document round-trips through JSON
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let snapshot: Snapshot = serde_json::from_str(&json).unwrap();
+
+        // The clause database is shared, so clear it to see the replay
+        manager.with_prolog_env(&prolog.session_id, |env| {
+            env.retractall("snapshot_color(_)")?;
+            env.retractall("snapshot_bright(_)")
+        }).unwrap();
+
+        let fresh = SessionManager::new(ManagerConfig::default());
+        let restored = fresh.restore(&snapshot).unwrap();
+        assert_eq!(restored.len(), 2);
+
+        let session = fresh.get_session(&clips.session_id).unwrap();
+        assert_eq!(session.name.as_deref(), Some("rules"));
+        assert_eq!(session.session_type, SessionType::Clips);
+        assert!(fresh.with_clips_env(&clips.session_id, |env| env.eval("(+ 1 2)")).is_ok());
+
+        let session = fresh.get_session(&prolog.session_id).unwrap();
+        assert_eq!(session.user_id, "user-2");
+        let found = fresh.with_prolog_env(&prolog.session_id, |env| {
+            Ok(env.check("snapshot_color(red)")?
+                && env.check("snapshot_bright('light blue')")?
+                && !env.check("snapshot_bright(red)")?)
+        }).unwrap();
+        assert!(found, "Restored clauses should be queryable");
+
+        // Restoring again leaves existing sessions alone
+        assert!(fresh.restore(&Snapshot { prolog_clauses: None, ..snapshot }).unwrap().is_empty());
+    }
 }
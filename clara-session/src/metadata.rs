@@ -89,7 +89,7 @@ impl Default for ResourceLimits {
 }
 
 /// Type of reasoning engine for the session
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum SessionType {
     /// CLIPS expert system (LilDaemon)
@@ -133,6 +133,29 @@ pub enum SessionStatus {
     Terminated,
 }
 
+impl SessionStatus {
+    /// Whether a session in this status may move to `new`
+    ///
+    /// Staying in the same status is always allowed. `Terminated` is final,
+    /// a session only leaves `Initializing` by becoming `Active` or being
+    /// terminated, and `Paused` or `Suspended` sessions must be resumed
+    /// (made `Active`) before they can evaluate again.
+    pub fn can_transition_to(&self, new: SessionStatus) -> bool {
+        use SessionStatus::*;
+
+        if *self == new {
+            return true;
+        }
+        match self {
+            Initializing => matches!(new, Active | Terminated),
+            Active | Idle => matches!(new, Active | Idle | Evaluating | Paused | Suspended | Terminated),
+            Evaluating => matches!(new, Active | Idle | Terminated),
+            Paused | Suspended => matches!(new, Active | Terminated),
+            Terminated => false,
+        }
+    }
+}
+
 impl std::fmt::Display for SessionStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -207,6 +230,11 @@ pub struct Session {
 
     /// List of preloaded files/rules
     pub loaded_files: Vec<String>,
+
+    /// Tools `clara_evaluate` may call from this session; all registered
+    /// tools when `None`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_tools: Option<Vec<String>>,
 }
 
 impl Session {
@@ -231,6 +259,7 @@ impl Session {
             stats: SessionStats::default(),
             metadata: HashMap::new(),
             loaded_files: Vec::new(),
+            allowed_tools: None,
         }
     }
 
@@ -309,7 +338,7 @@ impl Session {
 }
 
 /// Get current Unix timestamp in seconds
-fn current_timestamp() -> u64 {
+pub(crate) fn current_timestamp() -> u64 {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap_or_default()
@@ -328,6 +357,48 @@ mod tests {
         assert_eq!(session.resources.facts, 0);
     }
 
+    #[test]
+    fn test_session_status_transitions() {
+        use SessionStatus::*;
+
+        let all = [Active, Initializing, Idle, Evaluating, Paused, Suspended, Terminated];
+        let legal = [
+            (Initializing, Active),
+            (Initializing, Terminated),
+            (Active, Idle),
+            (Active, Evaluating),
+            (Active, Paused),
+            (Active, Suspended),
+            (Active, Terminated),
+            (Idle, Active),
+            (Idle, Evaluating),
+            (Idle, Paused),
+            (Idle, Suspended),
+            (Idle, Terminated),
+            (Evaluating, Active),
+            (Evaluating, Idle),
+            (Evaluating, Terminated),
+            (Paused, Active),
+            (Paused, Terminated),
+            (Suspended, Active),
+            (Suspended, Terminated),
+        ];
+
+        for from in all {
+            for to in all {
+                let expected = from == to || legal.contains(&(from, to));
+                assert_eq!(
+                    from.can_transition_to(to),
+                    expected,
+                    "{} -> {} should be {}",
+                    from,
+                    to,
+                    if expected { "legal" } else { "illegal" }
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_session_id_unique() {
         let id1 = SessionId::new();
@@ -0,0 +1,363 @@
+//! Session state saved to disk so it survives a server restart
+//!
+//! Each session is written by [`SessionManager::save_to_disk`] to its own
+//! `<session_id>.json` [`SessionFile`] and read back by
+//! [`SessionManager::restore_from_disk`]. [`DiskPersistence`] exposes the
+//! same operations as a `clara_core::PersistenceService`.
+
+use crate::manager::{ManagerError, SessionManager};
+use crate::metadata::{current_timestamp, Session, SessionId, SessionType};
+use crate::store::StoreError;
+use clara_core::{
+    ClaraError, ClaraResult, PersistenceService, ReloadRequest, ReloadResponse, ResourceInfo, SaveRequest,
+    SaveResponse,
+};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Extension of the files in a persistence directory
+const SESSION_FILE_EXTENSION: &str = "json";
+
+/// One saved session: its metadata and everything needed to rebuild its
+/// knowledge base in a fresh engine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionFile {
+    /// When the file was written (Unix timestamp in seconds)
+    pub saved_at: u64,
+
+    /// Label given when saving; empty when none was
+    #[serde(default)]
+    pub label: String,
+
+    /// Session metadata, restored with the same id
+    pub session: Session,
+
+    /// Templates, functions and rules of a CLIPS session, in build order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub clips_constructs: Vec<String>,
+
+    /// Facts of a CLIPS session, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub clips_facts: Vec<String>,
+
+    /// Dynamic Prolog clauses, one per entry
+    ///
+    /// The clause database is shared by all Prolog engines, so every Prolog
+    /// session file holds all of it; restoring replays each clause once.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prolog_clauses: Vec<String>,
+}
+
+impl SessionFile {
+    /// An empty knowledge base for `session`, stamped now
+    pub fn new(session: Session, label: &str) -> Self {
+        Self {
+            saved_at: current_timestamp(),
+            label: label.to_string(),
+            session,
+            clips_constructs: Vec::new(),
+            clips_facts: Vec::new(),
+            prolog_clauses: Vec::new(),
+        }
+    }
+}
+
+/// Path of the file `session_id` is saved to under `dir`
+///
+/// Fails with `InvalidSessionId` for an id that could point outside `dir`:
+/// an empty one, or one containing a path separator or `..`.
+pub fn session_file_path(dir: &Path, session_id: &SessionId) -> Result<PathBuf, ManagerError> {
+    let id = session_id.as_str();
+    if id.is_empty() || id.contains(['/', '\\', '\0']) || id.contains("..") {
+        return Err(ManagerError::InvalidSessionId(id.to_string()));
+    }
+    Ok(dir.join(format!("{}.{}", id, SESSION_FILE_EXTENSION)))
+}
+
+/// Write `file` under `dir`, creating `dir` if needed
+///
+/// The JSON goes to a temporary file that is then renamed over the old
+/// one, so a crash mid-write never leaves a truncated session file.
+pub(crate) fn write_session_file(dir: &Path, file: &SessionFile) -> Result<PathBuf, ManagerError> {
+    std::fs::create_dir_all(dir).map_err(|e| io_error(dir, e))?;
+
+    let path = session_file_path(dir, &file.session.session_id)?;
+    let tmp = path.with_extension("json.tmp");
+    let json = serde_json::to_vec_pretty(file).map_err(|e| ManagerError::Persistence(e.to_string()))?;
+    std::fs::write(&tmp, json).map_err(|e| io_error(&tmp, e))?;
+    std::fs::rename(&tmp, &path).map_err(|e| io_error(&path, e))?;
+    Ok(path)
+}
+
+/// Read a session file
+///
+/// A file whose session type this build doesn't know fails with
+/// `UnsupportedSessionType` rather than a generic parse error.
+pub(crate) fn read_session_file(path: &Path) -> Result<SessionFile, ManagerError> {
+    let bytes = std::fs::read(path).map_err(|e| io_error(path, e))?;
+    let value: serde_json::Value =
+        serde_json::from_slice(&bytes).map_err(|e| ManagerError::Persistence(format!("{}: {}", path.display(), e)))?;
+
+    if let Some(session_type) = value.pointer("/session/session_type") {
+        if serde_json::from_value::<SessionType>(session_type.clone()).is_err() {
+            return Err(ManagerError::UnsupportedSessionType(session_type.to_string()));
+        }
+    }
+
+    serde_json::from_value(value).map_err(|e| ManagerError::Persistence(format!("{}: {}", path.display(), e)))
+}
+
+/// The session files under `dir`, sorted by name; none if `dir` doesn't exist
+pub(crate) fn session_files(dir: &Path) -> Result<Vec<PathBuf>, ManagerError> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(io_error(dir, e)),
+    };
+
+    let mut files = Vec::new();
+    for entry in entries {
+        let path = entry.map_err(|e| io_error(dir, e))?.path();
+        if path.is_file() && path.extension().is_some_and(|ext| ext == SESSION_FILE_EXTENSION) {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn io_error(path: &Path, e: std::io::Error) -> ManagerError {
+    ManagerError::Persistence(format!("{}: {}", path.display(), e))
+}
+
+/// [`PersistenceService`] that saves sessions as JSON files in one directory
+#[derive(Clone)]
+pub struct DiskPersistence {
+    manager: SessionManager,
+    dir: PathBuf,
+}
+
+impl DiskPersistence {
+    /// Save and reload `manager`'s sessions under `dir`
+    pub fn new(manager: SessionManager, dir: impl Into<PathBuf>) -> Self {
+        Self { manager, dir: dir.into() }
+    }
+
+    /// Directory the session files are kept in
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+impl PersistenceService for DiskPersistence {
+    fn save_session(&self, session_id: &str, req: SaveRequest) -> ClaraResult<SaveResponse> {
+        let session_id = SessionId(session_id.to_string());
+        let path = self
+            .manager
+            .save_to_disk(&session_id, &self.dir, &req.label)
+            .map_err(clara_error)?;
+
+        Ok(SaveResponse {
+            session_id: session_id.0,
+            saved_as: path.display().to_string(),
+            timestamp: current_timestamp().to_string(),
+        })
+    }
+
+    /// Recreate a session that isn't live from its file
+    ///
+    /// The label must match the one it was saved with.
+    fn reload_session(&self, session_id: &str, req: ReloadRequest) -> ClaraResult<ReloadResponse> {
+        let session_id = SessionId(session_id.to_string());
+        let path = session_file_path(&self.dir, &session_id).map_err(clara_error)?;
+        let file = read_session_file(&path).map_err(clara_error)?;
+        if file.label != req.label {
+            return Err(ClaraError::ValidationError(format!(
+                "Session {} was saved as {:?}, not {:?}",
+                session_id, file.label, req.label
+            )));
+        }
+
+        let session = self.manager.restore_session_file(&file).map_err(clara_error)?;
+        Ok(ReloadResponse {
+            session_id: session.session_id.0,
+            status: session.status.to_string(),
+            resources: ResourceInfo {
+                facts: session.resources.facts,
+                rules: session.resources.rules,
+                objects: session.resources.objects,
+                memory_mb: Some((session.resources.memory_bytes / (1024 * 1024)) as u32),
+            },
+        })
+    }
+}
+
+fn clara_error(e: ManagerError) -> ClaraError {
+    match e {
+        ManagerError::Store(StoreError::NotFound(id)) => ClaraError::SessionNotFound(id),
+        ManagerError::Store(StoreError::AlreadyExists(id)) => ClaraError::SessionAlreadyExists(id),
+        ManagerError::SessionNotFound => ClaraError::SessionNotFound("Session not found".to_string()),
+        ManagerError::SessionTerminated => ClaraError::SessionTerminated,
+        ManagerError::UserSessionLimitExceeded => ClaraError::UserSessionLimitExceeded,
+        ManagerError::GlobalSessionLimitExceeded => ClaraError::GlobalSessionLimitExceeded,
+        ManagerError::QueueFull => ClaraError::QueueFull,
+        ManagerError::UnsupportedSessionType(t) => {
+            ClaraError::ValidationError(format!("Unsupported session type: {}", t))
+        }
+        ManagerError::InvalidSessionId(id) => ClaraError::ValidationError(format!("Invalid session id: {:?}", id)),
+        other => ClaraError::Internal(other.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manager::ManagerConfig;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("clara-session-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_prolog_session_survives_restart() {
+        let dir = temp_dir("prolog");
+        let session_id = {
+            let manager = SessionManager::new(ManagerConfig::default());
+            let session = manager.create_prolog_session("user-1".to_string(), None).unwrap();
+            manager
+                .with_prolog_env(&session.session_id, |env| {
+                    env.assertz("persisted_pet(rex)")?;
+                    env.assertz("persisted_loud(X) :- persisted_pet(X)")
+                })
+                .unwrap();
+
+            let persistence = DiskPersistence::new(manager.clone(), &dir);
+            let saved = persistence
+                .save_session(session.session_id.as_str(), SaveRequest { label: "nightly".to_string() })
+                .unwrap();
+            assert!(saved.saved_as.ends_with(".json"));
+
+            // The clause database outlives managers, so drop the clauses too
+            manager
+                .with_prolog_env(&session.session_id, |env| {
+                    env.retractall("persisted_pet(_)")?;
+                    env.retractall("persisted_loud(_)")
+                })
+                .unwrap();
+            session.session_id
+        };
+
+        let manager = SessionManager::new(ManagerConfig::default());
+        let restored = manager.restore_from_disk(&dir).unwrap();
+        assert_eq!(restored.len(), 1);
+        assert_eq!(restored[0].session_id, session_id);
+        assert_eq!(restored[0].user_id, "user-1");
+
+        let found = manager
+            .with_prolog_env(&session_id, |env| env.check("persisted_loud(rex)"))
+            .unwrap();
+        assert!(found, "Restored clauses should be queryable");
+
+        // Restoring again leaves the live session alone
+        assert!(manager.restore_from_disk(&dir).unwrap().is_empty());
+        let count = manager
+            .with_prolog_env(&session_id, |env| env.query_once("aggregate_all(count, persisted_pet(_), N)"))
+            .unwrap();
+        assert!(count.contains('1'), "Clauses should not be replayed twice: {}", count);
+
+        manager
+            .with_prolog_env(&session_id, |env| env.retractall("persisted_pet(_)"))
+            .unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_clips_session_survives_restart() {
+        let dir = temp_dir("clips");
+        let session_id = {
+            let manager = SessionManager::new(ManagerConfig::default());
+            let session = manager.create_session("user-1".to_string(), None).unwrap();
+            manager
+                .with_clips_env(&session.session_id, |env| {
+                    env.build("(deftemplate saved-pet (slot name))")?;
+                    env.eval("(assert (saved-pet (name rex)))").map(|_| ())
+                })
+                .unwrap();
+            manager.save_to_disk(&session.session_id, &dir, "").unwrap();
+            session.session_id
+        };
+
+        let manager = SessionManager::new(ManagerConfig::default());
+        let restored = manager.restore_from_disk(&dir).unwrap();
+        assert_eq!(restored.len(), 1);
+        let facts = manager
+            .with_clips_env(&session_id, |env| env.eval("(facts)"))
+            .unwrap();
+        assert!(facts.contains("rex"), "Restored facts should be present: {}", facts);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_named_session_resolves_after_restart() {
+        let dir = temp_dir("named");
+        let session_id = {
+            let manager = SessionManager::new(ManagerConfig::default());
+            let session = manager
+                .get_or_create_named_session("user-1".to_string(), "scratch".to_string(), SessionType::Clips, None)
+                .unwrap();
+            manager.save_to_disk(&session.session_id, &dir, "").unwrap();
+            session.session_id
+        };
+
+        let manager = SessionManager::new(ManagerConfig::default());
+        manager.restore_from_disk(&dir).unwrap();
+        let found = manager
+            .get_or_create_named_session("user-1".to_string(), "scratch".to_string(), SessionType::Clips, None)
+            .unwrap();
+        assert_eq!(found.session_id, session_id);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_unsupported_session_type_skipped() {
+        let dir = temp_dir("unsupported");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let manager = SessionManager::new(ManagerConfig::default());
+        let session = manager.create_prolog_session("user-1".to_string(), None).unwrap();
+        let path = manager.save_to_disk(&session.session_id, &dir, "").unwrap();
+
+        // Rewrite the file as if a newer build had saved a datalog session
+        let mut value: serde_json::Value = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        value["session"]["session_type"] = serde_json::json!("datalog");
+        value["session"]["session_id"] = serde_json::json!("sess-datalog");
+        std::fs::write(dir.join("sess-datalog.json"), value.to_string()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(matches!(
+            read_session_file(&dir.join("sess-datalog.json")),
+            Err(ManagerError::UnsupportedSessionType(_))
+        ));
+        assert!(manager.restore_from_disk(&dir).unwrap().is_empty());
+
+        // A missing directory restores nothing
+        assert!(manager.restore_from_disk(&dir.join("missing")).unwrap().is_empty());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_reload_rejects_ids_that_leave_the_directory() {
+        let dir = temp_dir("traversal");
+        let persistence = DiskPersistence::new(SessionManager::new(ManagerConfig::default()), &dir);
+
+        for id in ["../../x", "a/b", "a\\b", "..", ""] {
+            let result = persistence.reload_session(id, ReloadRequest { label: String::new() });
+            assert!(matches!(result, Err(ClaraError::ValidationError(_))), "{:?}: {:?}", id, result);
+        }
+        assert!(session_file_path(&dir, &SessionId("sess-1a2b-42".to_string())).is_ok());
+    }
+}
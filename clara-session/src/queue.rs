@@ -1 +1,154 @@
-//! Evaluation queue management (stub for future implementation)
+//! Bounded queue for evaluations
+//!
+//! [`EvalQueue`] lets at most `max_in_flight` evaluations run at once and up
+//! to `max_queued` more wait for a slot. Anything beyond that is turned away
+//! straight away with [`QueueError::Full`], so a burst of requests gets
+//! backpressure instead of piling up behind the engine locks.
+
+use std::sync::{Arc, Condvar, Mutex};
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum QueueError {
+    #[error("Evaluation queue full ({in_flight} running, {queued} waiting)")]
+    Full { in_flight: usize, queued: usize },
+
+    #[error("Lock poisoned")]
+    LockPoisoned,
+}
+
+#[derive(Debug, Default)]
+struct QueueState {
+    in_flight: usize,
+    queued: usize,
+}
+
+/// Admission control for evaluations; clones share the same slots
+#[derive(Debug, Clone)]
+pub struct EvalQueue {
+    state: Arc<(Mutex<QueueState>, Condvar)>,
+    max_in_flight: usize,
+    max_queued: usize,
+}
+
+impl EvalQueue {
+    /// A queue running up to `max_in_flight` evaluations with `max_queued`
+    /// more waiting. A `max_in_flight` of 0 is treated as 1.
+    pub fn new(max_in_flight: usize, max_queued: usize) -> Self {
+        Self {
+            state: Arc::new((Mutex::new(QueueState::default()), Condvar::new())),
+            max_in_flight: max_in_flight.max(1),
+            max_queued,
+        }
+    }
+
+    /// Take a slot, waiting for one if all are busy and the queue has room
+    ///
+    /// The slot is held until the returned permit is dropped.
+    pub fn acquire(&self) -> Result<EvalPermit, QueueError> {
+        let (lock, freed) = &*self.state;
+        let mut state = lock.lock().map_err(|_| QueueError::LockPoisoned)?;
+
+        if state.in_flight >= self.max_in_flight {
+            if state.queued >= self.max_queued {
+                return Err(QueueError::Full { in_flight: state.in_flight, queued: state.queued });
+            }
+            state.queued += 1;
+            while state.in_flight >= self.max_in_flight {
+                state = match freed.wait(state) {
+                    Ok(state) => state,
+                    Err(_) => return Err(QueueError::LockPoisoned),
+                };
+            }
+            state.queued -= 1;
+        }
+
+        state.in_flight += 1;
+        Ok(EvalPermit { queue: self.clone() })
+    }
+
+    /// Run `f` in a slot, see [`acquire`](Self::acquire)
+    pub fn run<F, R>(&self, f: F) -> Result<R, QueueError>
+    where
+        F: FnOnce() -> R,
+    {
+        let _permit = self.acquire()?;
+        Ok(f())
+    }
+
+    /// Evaluations currently holding a slot
+    pub fn in_flight(&self) -> usize {
+        self.state.0.lock().map(|s| s.in_flight).unwrap_or(0)
+    }
+
+    /// Evaluations waiting for a slot
+    pub fn queued(&self) -> usize {
+        self.state.0.lock().map(|s| s.queued).unwrap_or(0)
+    }
+
+    pub fn max_in_flight(&self) -> usize {
+        self.max_in_flight
+    }
+
+    pub fn max_queued(&self) -> usize {
+        self.max_queued
+    }
+}
+
+/// A running slot in an [`EvalQueue`], released on drop
+#[derive(Debug)]
+pub struct EvalPermit {
+    queue: EvalQueue,
+}
+
+impl Drop for EvalPermit {
+    fn drop(&mut self) {
+        let (lock, freed) = &*self.queue.state;
+        // Poisoning can't leave the count inconsistent; release anyway
+        let mut state = lock.lock().unwrap_or_else(|e| e.into_inner());
+        state.in_flight -= 1;
+        freed.notify_one();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    #[test]
+    fn test_full_queue_rejects() {
+        let queue = EvalQueue::new(1, 1);
+        let running = queue.acquire().unwrap();
+        assert_eq!(queue.in_flight(), 1);
+
+        // One caller fits in the queue and waits for the running slot
+        let (started, waiting) = mpsc::channel();
+        let waiter = {
+            let queue = queue.clone();
+            std::thread::spawn(move || {
+                started.send(()).unwrap();
+                queue.run(|| 42)
+            })
+        };
+        waiting.recv().unwrap();
+        while queue.queued() == 0 {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+
+        assert_eq!(queue.acquire().unwrap_err(), QueueError::Full { in_flight: 1, queued: 1 });
+
+        drop(running);
+        assert_eq!(waiter.join().unwrap(), Ok(42));
+        assert_eq!((queue.in_flight(), queue.queued()), (0, 0));
+    }
+
+    #[test]
+    fn test_zero_depth_rejects_when_busy() {
+        let queue = EvalQueue::new(2, 0);
+        let _first = queue.acquire().unwrap();
+        let _second = queue.acquire().unwrap();
+        assert!(matches!(queue.acquire(), Err(QueueError::Full { .. })));
+    }
+}
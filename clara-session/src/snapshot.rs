@@ -0,0 +1,27 @@
+//! Point-in-time backup of the session manager
+//!
+//! A [`Snapshot`] holds the metadata of every live session and, optionally,
+//! the dynamic Prolog clauses. Engines themselves aren't captured: restoring
+//! creates fresh ones and replays the clauses into them.
+
+use crate::metadata::Session;
+use serde::{Deserialize, Serialize};
+
+/// Serializable copy of all live sessions, produced by
+/// [`SessionManager::snapshot`](crate::SessionManager::snapshot)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// When the snapshot was taken (Unix timestamp in seconds)
+    pub created_at: u64,
+
+    /// Metadata of every session that wasn't terminated
+    pub sessions: Vec<Session>,
+
+    /// Dynamic clauses of the Prolog database, one per entry
+    ///
+    /// The clause database is shared by all Prolog engines, so it is listed
+    /// once for the whole snapshot rather than per session. `None` when the
+    /// snapshot was taken without clauses.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prolog_clauses: Option<Vec<String>>,
+}
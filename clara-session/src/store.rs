@@ -1,4 +1,4 @@
-use crate::metadata::{Session, SessionId};
+use crate::metadata::{Session, SessionId, SessionStatus};
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
 use thiserror::Error;
@@ -112,24 +112,30 @@ impl SessionStore {
         Ok(sessions.values().cloned().collect())
     }
 
-    /// Get count of active sessions
+    /// Get count of non-terminated sessions
     pub fn count_active(&self) -> Result<usize, StoreError> {
         let sessions = self
             .sessions
             .read()
             .map_err(|_| StoreError::LockPoisoned)?;
 
-        Ok(sessions.len())
+        Ok(sessions
+            .values()
+            .filter(|s| s.status != SessionStatus::Terminated)
+            .count())
     }
 
-    /// Get count of active sessions for a specific user
+    /// Get count of non-terminated sessions for a specific user
     pub fn count_user_sessions(&self, user_id: &str) -> Result<usize, StoreError> {
         let sessions = self
             .sessions
             .read()
             .map_err(|_| StoreError::LockPoisoned)?;
 
-        Ok(sessions.iter().filter(|(_, s)| s.user_id == user_id).count())
+        Ok(sessions
+            .values()
+            .filter(|s| s.user_id == user_id && s.status != SessionStatus::Terminated)
+            .count())
     }
 
     /// Check if a session exists
@@ -210,4 +216,19 @@ mod tests {
         let user2_sessions = store.get_user_sessions("user-2").unwrap();
         assert_eq!(user2_sessions.len(), 1);
     }
+
+    #[test]
+    fn test_count_user_sessions_skips_terminated() {
+        let store = SessionStore::new();
+        let s1 = Session::new("user-1".to_string(), None);
+        let mut s2 = Session::new("user-1".to_string(), None);
+        s2.terminate();
+
+        store.insert(s1).unwrap();
+        store.insert(s2).unwrap();
+
+        assert_eq!(store.count_user_sessions("user-1").unwrap(), 1);
+        assert_eq!(store.count_user_sessions("user-2").unwrap(), 0);
+        assert_eq!(store.count_active().unwrap(), 1);
+    }
 }
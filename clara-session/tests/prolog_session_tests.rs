@@ -180,6 +180,51 @@ fn test_access_wrong_session_type_env() {
     assert!(result.is_err(), "Should fail to access CLIPS session as Prolog env");
 }
 
+/// Test that the typed variant hands the closure's error back unchanged
+#[test]
+fn test_with_prolog_env_typed_preserves_error() {
+    let manager = create_manager();
+
+    let session = manager
+        .create_prolog_session("user".to_string(), None)
+        .expect("Failed to create session");
+
+    let result = manager
+        .with_prolog_env_typed(&session.session_id, |_env| {
+            Err::<(), _>(clara_prolog::PrologError::PrologException("time_limit_exceeded".to_string()))
+        })
+        .expect("Session lookup should succeed");
+
+    match result {
+        Err(clara_prolog::PrologError::PrologException(msg)) => {
+            assert_eq!(msg, "time_limit_exceeded");
+        }
+        other => panic!("Expected PrologException, got {:?}", other),
+    }
+
+    // A successful closure passes its value through as well
+    let value = manager
+        .with_prolog_env_typed(&session.session_id, |env| env.query_once("X = 1"))
+        .expect("Session lookup should succeed");
+    assert!(value.is_ok());
+}
+
+/// Test that the typed variant still reports unknown sessions as manager errors
+#[test]
+fn test_with_prolog_env_typed_unknown_session() {
+    let manager = create_manager();
+
+    let clips_session = manager
+        .create_session("user".to_string(), None)
+        .expect("Failed to create CLIPS session");
+
+    let result = manager.with_prolog_env_typed(&clips_session.session_id, |_env| {
+        Ok::<_, std::io::Error>(())
+    });
+
+    assert!(matches!(result, Err(clara_session::ManagerError::SessionNotFound)));
+}
+
 /// Test touch updates session timestamp
 #[test]
 fn test_prolog_session_touch() {
@@ -259,3 +304,35 @@ fn test_prolog_env_persistence() {
 
     println!("Counters: {}", result);
 }
+
+/// Test a session's tool allowlist applies to clara_evaluate/2 calls made
+/// through its environment
+#[test]
+fn test_prolog_session_tool_allowlist() {
+    clara_toolbox::ToolboxManager::init_global();
+    let manager = create_manager();
+
+    let session = manager
+        .create_prolog_session("sandboxed".to_string(), None)
+        .expect("Failed to create session");
+    let session = manager
+        .set_allowed_tools(&session.session_id, Some(vec!["echo".to_string()]))
+        .expect("Failed to set allowed tools");
+    assert_eq!(session.allowed_tools, Some(vec!["echo".to_string()]));
+
+    let echo = manager.with_prolog_env(&session.session_id, |env| {
+        env.query_once(r#"the_rabbit:clara_evaluate('{"tool":"echo","arguments":{"message":"allowed"}}', R)"#)
+    }).expect("Echo call failed");
+    assert!(echo.contains("success"), "Echo should run, got: {}", echo);
+
+    let refused = manager.with_prolog_env(&session.session_id, |env| {
+        env.query_once(r#"the_rabbit:clara_evaluate('{"tool":"splinteredmind","arguments":{}}', R)"#)
+    }).expect("Refused call failed");
+    assert!(
+        refused.contains("Tool not allowed: splinteredmind"),
+        "splinteredmind should be refused, got: {}", refused
+    );
+
+    // The limit is the session's, not the thread's
+    assert!(clara_toolbox::is_tool_allowed("splinteredmind"));
+}
@@ -6,8 +6,8 @@
 use crate::{ToolboxManager, ToolRequest, ToolResponse};
 use libc::c_char;
 use serde_json::json;
-use std::cell::Cell;
-use std::collections::HashMap;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
 use std::ffi::CString;
 #[cfg(any(feature = "ffi", test))]
 use std::ffi::CStr;
@@ -33,6 +33,73 @@ pub fn reset_evaluate_call_count() {
     EVALUATE_CALL_COUNT.store(0, Ordering::SeqCst);
 }
 
+// ── Response size limit ───────────────────────────────────────────────────────
+// Responses are unified back into the calling engine as one string, so a tool
+// returning a huge blob could exhaust the engine's memory. Larger responses are
+// replaced by an error carrying a short preview. Adjustable at runtime from
+// application config (`security.max_tool_response_bytes`).
+
+/// Default for [`max_evaluate_response_bytes`]: 1 MiB.
+pub const DEFAULT_MAX_EVALUATE_RESPONSE_BYTES: usize = 1024 * 1024;
+
+/// Bytes of an oversized response kept in the error's `preview`.
+const RESPONSE_PREVIEW_BYTES: usize = 256;
+
+static MAX_EVALUATE_RESPONSE_BYTES: AtomicUsize =
+    AtomicUsize::new(DEFAULT_MAX_EVALUATE_RESPONSE_BYTES);
+
+/// Set the largest serialized tool response `clara_evaluate` returns.
+pub fn set_max_evaluate_response_bytes(limit: usize) {
+    MAX_EVALUATE_RESPONSE_BYTES.store(limit, Ordering::SeqCst);
+}
+
+/// The largest serialized tool response `clara_evaluate` returns.
+pub fn max_evaluate_response_bytes() -> usize {
+    MAX_EVALUATE_RESPONSE_BYTES.load(Ordering::SeqCst)
+}
+
+/// Replace a serialized response over the size limit with an error that
+/// reports its size and keeps as much of the first few hundred bytes as a
+/// preview as fits within the limit; the preview is left out if none does.
+fn limit_response_size(response_str: String) -> String {
+    let limit = max_evaluate_response_bytes();
+    if response_str.len() <= limit {
+        return response_str;
+    }
+
+    log::warn!(
+        "evaluate_json_string: response of {} bytes exceeds the {} byte limit",
+        response_str.len(),
+        limit
+    );
+    let mut error = ToolResponse::error(format!(
+        "Tool response of {} bytes exceeds the {} byte limit",
+        response_str.len(),
+        limit
+    ));
+    error.result["truncated"] = json!(true);
+    error.result["size_bytes"] = json!(response_str.len());
+
+    // Escaping can make the preview longer than the bytes it holds, so
+    // shrink it by the overflow until the whole error fits
+    let mut end = RESPONSE_PREVIEW_BYTES.min(response_str.len());
+    while end > 0 {
+        while !response_str.is_char_boundary(end) {
+            end -= 1;
+        }
+        error.result["preview"] = json!(&response_str[..end]);
+        let serialized = serde_json::to_string(&error).unwrap();
+        if serialized.len() <= limit {
+            return serialized;
+        }
+        end = end.saturating_sub(serialized.len() - limit);
+    }
+    if let Some(result) = error.result.as_object_mut() {
+        result.remove("preview");
+    }
+    serde_json::to_string(&error).unwrap()
+}
+
 // ── Dis domain identity ───────────────────────────────────────────────────────
 // Set once at startup from application config; stamped onto every new cache
 // entry as `domain_id` for future cross-domain gossip via the Coire relay.
@@ -102,6 +169,73 @@ pub fn deduction_context(id: Uuid) -> DeductionContextGuard {
     DeductionContextGuard
 }
 
+// ── Tool allowlist ────────────────────────────────────────────────────────────
+// Restricts which tools `clara_evaluate` may dispatch to from the calling
+// thread, i.e. from the engine currently running on it. `None` means every
+// registered tool is allowed. Checked before the cache so a refused tool can't
+// be served a result cached for a less restricted caller. `SessionManager`
+// installs a session's `allowed_tools` here around each use of its engine.
+thread_local! {
+    static TOOL_ALLOWLIST: RefCell<Option<HashSet<String>>> = const { RefCell::new(None) };
+}
+
+/// Limit `clara_evaluate` calls on this thread to the tools in `tools`, or
+/// lift the limit with `None`.
+///
+/// Prefer [`tool_allowlist`] so the previous limit is restored on drop.
+pub fn set_tool_allowlist(tools: Option<HashSet<String>>) {
+    TOOL_ALLOWLIST.with(|a| *a.borrow_mut() = tools);
+}
+
+/// Whether `clara_evaluate` may run `tool` on this thread.
+pub fn is_tool_allowed(tool: &str) -> bool {
+    TOOL_ALLOWLIST.with(|a| a.borrow().as_ref().is_none_or(|allowed| allowed.contains(tool)))
+}
+
+/// RAII guard that restores the previous tool allowlist on drop.
+/// Obtain via [`tool_allowlist`].
+pub struct ToolAllowlistGuard {
+    previous: Option<HashSet<String>>,
+}
+
+impl Drop for ToolAllowlistGuard {
+    fn drop(&mut self) {
+        set_tool_allowlist(self.previous.take());
+    }
+}
+
+/// Allow only `tools` for `clara_evaluate` calls made on this thread until
+/// the returned guard is dropped.
+///
+/// ```rust,ignore
+/// // A sandboxed session may echo but not reach FieryPit
+/// let _tools = clara_toolbox::ffi::tool_allowlist(["echo"]);
+/// env.query_once("clara_evaluate('{\"tool\":\"echo\"}', R)")?;
+/// ```
+pub fn tool_allowlist<I, S>(tools: I) -> ToolAllowlistGuard
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    let allowed = tools.into_iter().map(Into::into).collect();
+    let previous = TOOL_ALLOWLIST.with(|a| a.borrow_mut().replace(allowed));
+    ToolAllowlistGuard { previous }
+}
+
+/// Name of the tool a request will be dispatched to: its `tool` field, or the
+/// default evaluator when there is none.
+fn requested_tool(json_value: &serde_json::Value) -> String {
+    match json_value.get("tool") {
+        Some(serde_json::Value::String(name)) => name.clone(),
+        Some(other) => other.to_string(),
+        None => ToolboxManager::global()
+            .lock()
+            .unwrap()
+            .get_default_evaluator()
+            .to_string(),
+    }
+}
+
 // ── Result cache ──────────────────────────────────────────────────────────────
 
 /// Metadata-bearing wrapper for a cached evaluation result.
@@ -258,6 +392,20 @@ pub extern "C" fn rust_clara_evaluate(input_json: *const c_char) -> *mut c_char
 /// This is the core evaluation logic, separated out so it can be used
 /// by both the C FFI function and Rust callers.
 pub fn evaluate_json_string(input_str: &str) -> *mut c_char {
+    // 0. Refuse tools outside this thread's allowlist before anything runs.
+    if TOOL_ALLOWLIST.with(|a| a.borrow().is_some()) {
+        if let Ok(json_value) = serde_json::from_str::<serde_json::Value>(input_str) {
+            let tool = requested_tool(&json_value);
+            if !is_tool_allowed(&tool) {
+                log::warn!("evaluate_json_string: tool '{}' is not allowed here", tool);
+                let response = ToolResponse::error(format!("Tool not allowed: {}", tool));
+                return CString::new(serde_json::to_string(&response).unwrap())
+                    .unwrap_or_else(|_| CString::new("{}").unwrap())
+                    .into_raw();
+            }
+        }
+    }
+
     let key = cache_key(input_str);
 
     // 1. Cache hit: return memoised result without executing the tool or
@@ -305,7 +453,7 @@ pub fn evaluate_json_string(input_str: &str) -> *mut c_char {
             match serde_json::from_value::<ToolRequest>(json_value) {
                 Ok(request) => manager.execute_tool(&request).unwrap_or_else(|e| {
                     log::error!("Tool execution error: {}", e);
-                    ToolResponse::error(format!("{}", e))
+                    manager.error_response(&e)
                 }),
                 Err(e) => {
                     log::error!("Failed to parse ToolRequest: {}", e);
@@ -317,7 +465,7 @@ pub fn evaluate_json_string(input_str: &str) -> *mut c_char {
             log::debug!("No tool specified, using default evaluator");
             manager.evaluate(json_value).unwrap_or_else(|e| {
                 log::error!("Default evaluator error: {}", e);
-                ToolResponse::error(format!("{}", e))
+                manager.error_response(&e)
             })
         }
     })
@@ -327,7 +475,7 @@ pub fn evaluate_json_string(input_str: &str) -> *mut c_char {
         ToolResponse::error("Tool execution failed: thread panicked".to_string())
     });
 
-    let response_str = serde_json::to_string(&response).unwrap();
+    let response_str = limit_response_size(serde_json::to_string(&response).unwrap());
 
     // 3. Store result in cache before returning so future identical calls are
     //    served without re-executing the tool.
@@ -448,6 +596,145 @@ mod tests {
         free_c_string(result_ptr);
     }
 
+    // ── Tool allowlist ───────────────────────────────────────────────────────
+
+    /// Second tool for the allowlist test; never expected to run
+    struct ForbiddenTool;
+
+    impl crate::Tool for ForbiddenTool {
+        fn name(&self) -> &str {
+            "forbidden"
+        }
+
+        fn description(&self) -> &str {
+            "Must not run under an allowlist that excludes it"
+        }
+
+        fn execute(&self, _args: serde_json::Value) -> Result<serde_json::Value, crate::ToolError> {
+            Ok(json!({"ran": true}))
+        }
+    }
+
+    #[test]
+    fn tool_allowlist_refuses_other_tools() {
+        let _guard = setup();
+        ToolboxManager::global()
+            .lock()
+            .unwrap()
+            .register_tool(std::sync::Arc::new(ForbiddenTool));
+
+        let call = |input: &str| unsafe {
+            let ptr = evaluate_json_string(input);
+            let out: serde_json::Value =
+                serde_json::from_str(CStr::from_ptr(ptr).to_str().unwrap()).unwrap();
+            free_c_string(ptr);
+            out
+        };
+        let echo = r#"{"tool":"echo","arguments":{"message":"allowed"}}"#;
+        let forbidden = r#"{"tool":"forbidden","arguments":{}}"#;
+
+        {
+            let _tools = tool_allowlist(["echo"]);
+            assert_eq!(call(echo)["status"], "success");
+
+            let refused = call(forbidden);
+            assert_eq!(refused["status"], "error");
+            assert_eq!(refused["message"], "Tool not allowed: forbidden");
+
+            // The default evaluator is checked as well
+            assert_eq!(call(r#"{"question":"anything"}"#)["status"], "error");
+        }
+        assert_eq!(get_evaluate_call_count(), 1, "refused calls must not execute or count");
+
+        // Dropping the guard lifts the limit
+        assert!(is_tool_allowed("forbidden"));
+        assert_eq!(call(forbidden)["status"], "success");
+    }
+
+    // ── Response size limit ──────────────────────────────────────────────────
+
+    /// Returns a string of the requested length
+    struct BlobTool;
+
+    impl crate::Tool for BlobTool {
+        fn name(&self) -> &str {
+            "blob"
+        }
+
+        fn description(&self) -> &str {
+            "Returns `size` bytes of text"
+        }
+
+        fn execute(&self, args: serde_json::Value) -> Result<serde_json::Value, crate::ToolError> {
+            let size = args["size"].as_u64().unwrap_or(0) as usize;
+            Ok(json!({"blob": "x".repeat(size)}))
+        }
+    }
+
+    #[test]
+    fn oversized_response_replaced_by_error() {
+        let _guard = setup();
+        ToolboxManager::global()
+            .lock()
+            .unwrap()
+            .register_tool(std::sync::Arc::new(BlobTool));
+        set_max_evaluate_response_bytes(1024);
+
+        let call = |input: &str| unsafe {
+            let ptr = evaluate_json_string(input);
+            let out = CStr::from_ptr(ptr).to_str().unwrap().to_string();
+            free_c_string(ptr);
+            out
+        };
+
+        let small = call(r#"{"tool":"blob","arguments":{"size":100}}"#);
+        let large = call(r#"{"tool":"blob","arguments":{"size":100000}}"#);
+        // Later calls are unaffected
+        let echo = call(r#"{"tool":"echo","arguments":{"message":"still here"}}"#);
+        set_max_evaluate_response_bytes(DEFAULT_MAX_EVALUATE_RESPONSE_BYTES);
+
+        assert!(small.contains(&"x".repeat(100)), "{}", small);
+
+        assert!(large.len() <= 1024, "error response is {} bytes", large.len());
+        let large: serde_json::Value = serde_json::from_str(&large).unwrap();
+        assert_eq!(large["status"], "error");
+        assert_eq!(large["truncated"], true);
+        assert!(large["size_bytes"].as_u64().unwrap() > 100_000);
+        assert!(large["preview"].as_str().unwrap().starts_with(r#"{"status":"success""#));
+
+        let echo: serde_json::Value = serde_json::from_str(&echo).unwrap();
+        assert_eq!(echo["status"], "success");
+    }
+
+    #[test]
+    fn oversized_response_error_fits_small_limit() {
+        let _guard = setup();
+        ToolboxManager::global()
+            .lock()
+            .unwrap()
+            .register_tool(std::sync::Arc::new(BlobTool));
+
+        let call = |input: &str| unsafe {
+            let ptr = evaluate_json_string(input);
+            let out = CStr::from_ptr(ptr).to_str().unwrap().to_string();
+            free_c_string(ptr);
+            out
+        };
+
+        // Below the preview size the preview is cut so the error still fits
+        set_max_evaluate_response_bytes(200);
+        let large = call(r#"{"tool":"blob","arguments":{"size":1000}}"#);
+        set_max_evaluate_response_bytes(DEFAULT_MAX_EVALUATE_RESPONSE_BYTES);
+
+        assert!(large.len() <= 200, "error response is {} bytes: {}", large.len(), large);
+        let large: serde_json::Value = serde_json::from_str(&large).unwrap();
+        assert_eq!(large["status"], "error");
+        assert_eq!(large["truncated"], true);
+        let preview = large["preview"].as_str().unwrap();
+        assert!(preview.len() < RESPONSE_PREVIEW_BYTES);
+        assert!(preview.starts_with(r#"{"status":"success""#), "{}", preview);
+    }
+
     // ── Per-deduction cache scoping ──────────────────────────────────────────
 
     /// Identical requests memoize within one deduction context but never
@@ -18,5 +18,8 @@ pub use ffi::{
     evict_cache_older_than, evict_cache_by_deduction,
     set_current_deduction_id, current_deduction_id, deduction_context, DeductionContextGuard,
     set_domain_id, domain_id,
+    set_tool_allowlist, is_tool_allowed, tool_allowlist, ToolAllowlistGuard,
+    set_max_evaluate_response_bytes, max_evaluate_response_bytes,
+    DEFAULT_MAX_EVALUATE_RESPONSE_BYTES,
     CacheEntry, ToolboxCacheEviction,
 };
@@ -63,6 +63,35 @@ impl ToolboxManager {
         }
     }
 
+    /// Execute a tool by name, forwarding intermediate progress to `on_progress`
+    ///
+    /// Every progress update the tool emits is delivered before this returns
+    /// the final success/error response. Tools that don't report progress
+    /// behave exactly like [`execute_tool`](Self::execute_tool).
+    pub fn execute_tool_with_progress(
+        &self,
+        request: &ToolRequest,
+        on_progress: &mut dyn FnMut(ToolResponse),
+    ) -> Result<ToolResponse, ToolError> {
+        log::debug!("Executing tool with progress: {} with args: {}", request.tool, request.arguments);
+
+        let tool = self
+            .tools
+            .get(&request.tool)
+            .ok_or_else(|| ToolError::NotFound(request.tool.clone()))?;
+
+        match tool.execute_with_progress(request.arguments.clone(), on_progress) {
+            Ok(result) => {
+                log::debug!("Tool {} succeeded", request.tool);
+                Ok(ToolResponse::success(result))
+            }
+            Err(e) => {
+                log::error!("Tool {} failed: {}", request.tool, e);
+                Ok(ToolResponse::error(format!("{}", e)))
+            }
+        }
+    }
+
     /// Execute using the default evaluator with the given arguments
     ///
     /// This is a convenience method for calling the default evaluator without
@@ -80,6 +109,21 @@ impl ToolboxManager {
         self.tools.keys().cloned().collect()
     }
 
+    /// Turn an error from [`execute_tool`](Self::execute_tool) into the
+    /// response sent back to the calling engine
+    ///
+    /// `NotFound` lists the registered tools, sorted by name.
+    pub fn error_response(&self, error: &ToolError) -> ToolResponse {
+        match error {
+            ToolError::NotFound(name) => {
+                let mut available = self.list_tools();
+                available.sort();
+                ToolResponse::not_found(name.clone(), available)
+            }
+            other => ToolResponse::error(format!("{}", other)),
+        }
+    }
+
     /// Get access to the global ToolboxManager instance
     pub fn global() -> &'static Mutex<ToolboxManager> {
         &GLOBAL_TOOLBOX
@@ -184,6 +228,98 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_tool_not_found_response_lists_alternatives() {
+        let mut mgr = ToolboxManager::new();
+        mgr.register_tool(Arc::new(SteppingTool));
+        mgr.register_tool(Arc::new(EchoTool));
+
+        let request = ToolRequest {
+            tool: "ecko".to_string(),
+            arguments: json!({}),
+        };
+        let error = mgr.execute_tool(&request).unwrap_err();
+        let response = serde_json::to_value(mgr.error_response(&error)).unwrap();
+
+        assert_eq!(response["status"], "error");
+        assert_eq!(response["message"], "Tool not found: ecko");
+        assert_eq!(response["tool"], "ecko");
+        assert_eq!(response["available_tools"], json!(["echo", "stepping"]));
+    }
+
+    /// Mock long-running tool that reports each step before finishing
+    struct SteppingTool;
+
+    impl Tool for SteppingTool {
+        fn name(&self) -> &str {
+            "stepping"
+        }
+
+        fn description(&self) -> &str {
+            "Reports progress for each step"
+        }
+
+        fn execute(&self, args: serde_json::Value) -> Result<serde_json::Value, ToolError> {
+            self.execute_with_progress(args, &mut |_| {})
+        }
+
+        fn execute_with_progress(
+            &self,
+            args: serde_json::Value,
+            progress: &mut dyn FnMut(ToolResponse),
+        ) -> Result<serde_json::Value, ToolError> {
+            let steps = args["steps"].as_u64().unwrap_or(3);
+            for step in 1..=steps {
+                progress(ToolResponse::progress(
+                    format!("step {}", step),
+                    Some(step as f64 / steps as f64),
+                ));
+            }
+            Ok(json!({"completed": steps}))
+        }
+    }
+
+    #[test]
+    fn test_toolbox_manager_execute_with_progress() {
+        let mut mgr = ToolboxManager::new();
+        mgr.register_tool(Arc::new(SteppingTool));
+
+        let request = ToolRequest {
+            tool: "stepping".to_string(),
+            arguments: json!({"steps": 3}),
+        };
+
+        let mut events = Vec::new();
+        let response = mgr
+            .execute_tool_with_progress(&request, &mut |update| events.push(update))
+            .unwrap();
+        events.push(response);
+
+        let statuses: Vec<&str> = events.iter().map(|e| e.status.as_str()).collect();
+        assert_eq!(statuses, vec!["progress", "progress", "progress", "success"]);
+        assert_eq!(events[0].result["message"], "step 1");
+        assert_eq!(events[2].result["progress"], 1.0);
+        assert_eq!(events[3].result["completed"], 3);
+    }
+
+    #[test]
+    fn test_toolbox_manager_execute_with_progress_non_streaming_tool() {
+        let mut mgr = ToolboxManager::new();
+        mgr.register_tool(Arc::new(EchoTool));
+
+        let request = ToolRequest {
+            tool: "echo".to_string(),
+            arguments: json!({"message": "test"}),
+        };
+
+        let mut updates = 0;
+        let response = mgr
+            .execute_tool_with_progress(&request, &mut |_| updates += 1)
+            .unwrap();
+        assert_eq!(updates, 0);
+        assert_eq!(response.status, "success");
+    }
+
     #[test]
     fn test_global_toolbox() {
         // Access the global instance
@@ -33,6 +33,21 @@ pub trait Tool: Send + Sync {
 
     /// Execute the tool with the given arguments
     fn execute(&self, args: Value) -> Result<Value, ToolError>;
+
+    /// Execute the tool, reporting intermediate progress through `progress`
+    ///
+    /// Long-running tools (HTTP fetches, multi-step evaluation) override this
+    /// to emit [`ToolResponse::progress`] updates before returning the final
+    /// result. The default implementation emits nothing and delegates to
+    /// [`Tool::execute`].
+    fn execute_with_progress(
+        &self,
+        args: Value,
+        progress: &mut dyn FnMut(ToolResponse),
+    ) -> Result<Value, ToolError> {
+        let _ = progress;
+        self.execute(args)
+    }
 }
 
 /// Tool request structure (what CLIPS sends)
@@ -69,6 +84,45 @@ impl ToolResponse {
             }),
         }
     }
+
+    /// Create an error response for a request naming an unregistered tool
+    ///
+    /// Besides the usual `message`, carries the requested `tool` and the
+    /// registered alternatives in `available_tools`, so a caller in Prolog or
+    /// CLIPS can see what it could have called.
+    pub fn not_found(tool: impl Into<String>, available_tools: Vec<String>) -> Self {
+        let tool = tool.into();
+        Self {
+            status: "error".to_string(),
+            result: serde_json::json!({
+                "message": format!("Tool not found: {}", tool),
+                "tool": tool,
+                "available_tools": available_tools,
+            }),
+        }
+    }
+
+    /// Create an intermediate progress response
+    ///
+    /// `fraction` is the completed share of the work in `0.0..=1.0` when the
+    /// tool can estimate it.
+    pub fn progress(message: impl Into<String>, fraction: Option<f64>) -> Self {
+        let mut result = serde_json::json!({
+            "message": message.into()
+        });
+        if let Some(fraction) = fraction {
+            result["progress"] = serde_json::json!(fraction.clamp(0.0, 1.0));
+        }
+        Self {
+            status: "progress".to_string(),
+            result,
+        }
+    }
+
+    /// Check whether this is an intermediate progress response
+    pub fn is_progress(&self) -> bool {
+        self.status == "progress"
+    }
 }
 
 #[cfg(test)]
@@ -106,6 +160,18 @@ mod tests {
         assert_eq!(resp.result["message"], "Something went wrong");
     }
 
+    #[test]
+    fn test_tool_response_progress() {
+        let resp = ToolResponse::progress("halfway", Some(0.5));
+        assert!(resp.is_progress());
+        assert_eq!(resp.result["message"], "halfway");
+        assert_eq!(resp.result["progress"], 0.5);
+
+        let resp = ToolResponse::progress("working", None);
+        assert!(resp.result.get("progress").is_none());
+        assert!(!ToolResponse::success(json!({})).is_progress());
+    }
+
     #[test]
     fn test_tool_response_serialize() {
         let resp = ToolResponse::success(json!({"value": 42}));
@@ -8,7 +8,8 @@ use crate::tool::{Tool, ToolError};
 use fiery_pit_client::{CreateSessionRequest, FieryPitClient, SessionConfig};
 use serde::Deserialize;
 use serde_json::Value;
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
 /// Operations supported by the SplinteredMind tool
 #[derive(Debug, Clone, Deserialize)]
@@ -91,46 +92,68 @@ pub struct SplinteredMindArgs {
     #[serde(default)]
     pub data: Option<Value>,
 
-    // Evaluator management
+    // Evaluator management; also overrides the evaluator for a single evaluate
     #[serde(default)]
     pub evaluator: Option<String>,
+
+    // Send this request to another FieryPit instance instead of the default
+    #[serde(default)]
+    pub base_url: Option<String>,
 }
 
 /// ClaraSplinteredMindTool - Bridge to FieryPit API
+///
+/// Requests carrying a `base_url` go to that FieryPit instance through a
+/// client derived from the default one and cached per URL.
 pub struct ClaraSplinteredMindTool {
     client: Arc<FieryPitClient>,
+    clients_by_url: Mutex<HashMap<String, Arc<FieryPitClient>>>,
 }
 
 impl ClaraSplinteredMindTool {
     /// Create a new ClaraSplinteredMindTool with the given FieryPitClient
     pub fn new(client: Arc<FieryPitClient>) -> Self {
-        Self { client }
+        Self {
+            client,
+            clients_by_url: Mutex::new(HashMap::new()),
+        }
     }
 
     /// Create with a base URL
     pub fn with_url(base_url: impl Into<String>) -> Self {
-        Self {
-            client: Arc::new(FieryPitClient::new(base_url)),
-        }
+        Self::new(Arc::new(FieryPitClient::new(base_url)))
+    }
+
+    /// The client for `base_url`, or the default client if none is given
+    fn client_for(&self, base_url: Option<&str>) -> Arc<FieryPitClient> {
+        let Some(base_url) = base_url else {
+            return self.client.clone();
+        };
+        let mut clients = self.clients_by_url.lock().unwrap();
+        clients
+            .entry(base_url.to_string())
+            .or_insert_with(|| {
+                log::debug!("SplinteredMindTool: new FieryPit client for {}", base_url);
+                Arc::new(self.client.with_base_url(base_url))
+            })
+            .clone()
     }
 
     fn execute_operation(&self, args: SplinteredMindArgs) -> Result<Value, ToolError> {
+        let client = self.client_for(args.base_url.as_deref());
         match args.operation {
             // =================================================================
             // General FieryPit operations
             // =================================================================
-            Operation::Health => self
-                .client
+            Operation::Health => client
                 .health()
                 .map_err(|e| ToolError::ExecutionFailed(e.to_string())),
 
-            Operation::Status => self
-                .client
+            Operation::Status => client
                 .status()
                 .map_err(|e| ToolError::ExecutionFailed(e.to_string())),
 
-            Operation::Info => self
-                .client
+            Operation::Info => client
                 .info()
                 .map_err(|e| ToolError::ExecutionFailed(e.to_string())),
 
@@ -138,13 +161,14 @@ impl ClaraSplinteredMindTool {
                 let data = args
                     .data
                     .ok_or_else(|| ToolError::InvalidArgs("'data' required for evaluate".into()))?;
-                self.client
-                    .evaluate(data)
-                    .map_err(|e| ToolError::ExecutionFailed(e.to_string()))
+                match args.evaluator {
+                    Some(evaluator) => client.evaluate_with(data, &evaluator),
+                    None => client.evaluate(data),
+                }
+                .map_err(|e| ToolError::ExecutionFailed(e.to_string()))
             }
 
-            Operation::ListEvaluators => self
-                .client
+            Operation::ListEvaluators => client
                 .list_evaluators()
                 .map_err(|e| ToolError::ExecutionFailed(e.to_string())),
 
@@ -152,7 +176,7 @@ impl ClaraSplinteredMindTool {
                 let evaluator = args
                     .evaluator
                     .ok_or_else(|| ToolError::InvalidArgs("'evaluator' required".into()))?;
-                self.client
+                client
                     .get_evaluator(&evaluator)
                     .map_err(|e| ToolError::ExecutionFailed(e.to_string()))
             }
@@ -161,13 +185,12 @@ impl ClaraSplinteredMindTool {
                 let evaluator = args
                     .evaluator
                     .ok_or_else(|| ToolError::InvalidArgs("'evaluator' required".into()))?;
-                self.client
+                client
                     .set_evaluator(&evaluator)
                     .map_err(|e| ToolError::ExecutionFailed(e.to_string()))
             }
 
-            Operation::ResetEvaluator => self
-                .client
+            Operation::ResetEvaluator => client
                 .reset_evaluator()
                 .map_err(|e| ToolError::ExecutionFailed(e.to_string())),
 
@@ -193,13 +216,12 @@ impl ClaraSplinteredMindTool {
                     name: args.name,
                     config,
                 };
-                self.client
+                client
                     .clips_create_session(req)
                     .map_err(|e| ToolError::ExecutionFailed(e.to_string()))
             }
 
-            Operation::ClipsListSessions => self
-                .client
+            Operation::ClipsListSessions => client
                 .clips_list_sessions()
                 .map_err(|e| ToolError::ExecutionFailed(e.to_string())),
 
@@ -207,7 +229,7 @@ impl ClaraSplinteredMindTool {
                 let session_id = args
                     .session_id
                     .ok_or_else(|| ToolError::InvalidArgs("'session_id' required".into()))?;
-                self.client
+                client
                     .clips_get_session(&session_id)
                     .map_err(|e| ToolError::ExecutionFailed(e.to_string()))
             }
@@ -216,7 +238,7 @@ impl ClaraSplinteredMindTool {
                 let session_id = args
                     .session_id
                     .ok_or_else(|| ToolError::InvalidArgs("'session_id' required".into()))?;
-                self.client
+                client
                     .clips_terminate_session(&session_id)
                     .map_err(|e| ToolError::ExecutionFailed(e.to_string()))
             }
@@ -228,7 +250,7 @@ impl ClaraSplinteredMindTool {
                 let script = args
                     .script
                     .ok_or_else(|| ToolError::InvalidArgs("'script' required".into()))?;
-                self.client
+                client
                     .clips_evaluate(&session_id, &script, args.timeout_ms)
                     .map_err(|e| ToolError::ExecutionFailed(e.to_string()))
             }
@@ -240,9 +262,10 @@ impl ClaraSplinteredMindTool {
                 let rules = args
                     .rules
                     .ok_or_else(|| ToolError::InvalidArgs("'rules' required".into()))?;
-                self.client
+                let result = client
                     .clips_load_rules(&session_id, rules)
-                    .map_err(|e| ToolError::ExecutionFailed(e.to_string()))
+                    .map_err(|e| ToolError::ExecutionFailed(e.to_string()))?;
+                serde_json::to_value(result).map_err(|e| ToolError::ExecutionFailed(e.to_string()))
             }
 
             Operation::ClipsLoadFacts => {
@@ -252,7 +275,7 @@ impl ClaraSplinteredMindTool {
                 let facts = args
                     .facts
                     .ok_or_else(|| ToolError::InvalidArgs("'facts' required".into()))?;
-                self.client
+                client
                     .clips_load_facts(&session_id, facts)
                     .map_err(|e| ToolError::ExecutionFailed(e.to_string()))
             }
@@ -261,7 +284,7 @@ impl ClaraSplinteredMindTool {
                 let session_id = args
                     .session_id
                     .ok_or_else(|| ToolError::InvalidArgs("'session_id' required".into()))?;
-                self.client
+                client
                     .clips_query_facts(&session_id, args.pattern.as_deref())
                     .map_err(|e| ToolError::ExecutionFailed(e.to_string()))
             }
@@ -270,7 +293,7 @@ impl ClaraSplinteredMindTool {
                 let session_id = args
                     .session_id
                     .ok_or_else(|| ToolError::InvalidArgs("'session_id' required".into()))?;
-                self.client
+                client
                     .clips_run(&session_id, args.max_iterations)
                     .map_err(|e| ToolError::ExecutionFailed(e.to_string()))
             }
@@ -297,13 +320,12 @@ impl ClaraSplinteredMindTool {
                     name: args.name,
                     config,
                 };
-                self.client
+                client
                     .prolog_create_session(req)
                     .map_err(|e| ToolError::ExecutionFailed(e.to_string()))
             }
 
-            Operation::PrologListSessions => self
-                .client
+            Operation::PrologListSessions => client
                 .prolog_list_sessions()
                 .map_err(|e| ToolError::ExecutionFailed(e.to_string())),
 
@@ -311,7 +333,7 @@ impl ClaraSplinteredMindTool {
                 let session_id = args
                     .session_id
                     .ok_or_else(|| ToolError::InvalidArgs("'session_id' required".into()))?;
-                self.client
+                client
                     .prolog_get_session(&session_id)
                     .map_err(|e| ToolError::ExecutionFailed(e.to_string()))
             }
@@ -320,7 +342,7 @@ impl ClaraSplinteredMindTool {
                 let session_id = args
                     .session_id
                     .ok_or_else(|| ToolError::InvalidArgs("'session_id' required".into()))?;
-                self.client
+                client
                     .prolog_terminate_session(&session_id)
                     .map_err(|e| ToolError::ExecutionFailed(e.to_string()))
             }
@@ -332,7 +354,7 @@ impl ClaraSplinteredMindTool {
                 let goal = args
                     .goal
                     .ok_or_else(|| ToolError::InvalidArgs("'goal' required".into()))?;
-                self.client
+                client
                     .prolog_query(&session_id, &goal, args.all_solutions.unwrap_or(false))
                     .map_err(|e| ToolError::ExecutionFailed(e.to_string()))
             }
@@ -344,7 +366,7 @@ impl ClaraSplinteredMindTool {
                 let clauses = args
                     .clauses
                     .ok_or_else(|| ToolError::InvalidArgs("'clauses' required".into()))?;
-                self.client
+                client
                     .prolog_consult(&session_id, clauses)
                     .map_err(|e| ToolError::ExecutionFailed(e.to_string()))
             }
@@ -374,6 +396,7 @@ impl Tool for ClaraSplinteredMindTool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serde_json::json;
 
     #[test]
     fn test_tool_name() {
@@ -427,6 +450,39 @@ mod tests {
         assert_eq!(args.all_solutions, Some(true));
     }
 
+    #[test]
+    fn test_base_url_override_caches_client_per_url() {
+        let mut srv_a = mockito::Server::new();
+        let mut srv_b = mockito::Server::new();
+        let health_a = srv_a
+            .mock("GET", "/health")
+            .with_body(r#"{"status":"a"}"#)
+            .expect(2)
+            .create();
+        let health_b = srv_b
+            .mock("GET", "/health")
+            .with_body(r#"{"status":"b"}"#)
+            .expect(1)
+            .create();
+
+        let tool = ClaraSplinteredMindTool::with_url("http://localhost:1");
+        let health = |url: &str| tool.execute(json!({"operation": "health", "base_url": url})).unwrap();
+
+        assert_eq!(health(&srv_a.url())["status"], "a");
+        assert_eq!(health(&srv_b.url())["status"], "b");
+        assert_eq!(health(&srv_a.url())["status"], "a");
+        health_a.assert();
+        health_b.assert();
+
+        // One client per URL, reused on repeat; the default is untouched
+        assert_eq!(tool.clients_by_url.lock().unwrap().len(), 2);
+        let first = tool.client_for(Some(&srv_a.url()));
+        assert!(Arc::ptr_eq(&first, &tool.client_for(Some(&srv_a.url()))));
+        assert_eq!(first.base_url(), srv_a.url());
+        assert!(Arc::ptr_eq(&tool.client, &tool.client_for(None)));
+        assert_eq!(tool.clients_by_url.lock().unwrap().len(), 2);
+    }
+
     #[test]
     fn test_missing_operation_fails() {
         let json = r#"{"session_id": "abc"}"#;
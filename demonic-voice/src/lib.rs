@@ -4,11 +4,15 @@
 //! Lil-daemons are REST services offering arbitrary JSON evaluation via LLMs,
 //! rule engines, or other evaluators.
 
-use reqwest::blocking::Client;
+use reqwest::blocking::{Client, Response};
+use reqwest::header::{ACCEPT, CONTENT_TYPE};
 use serde_json::Value;
 use thiserror::Error;
+use std::io::{BufRead, BufReader};
 use std::sync::Arc;
 
+pub use clara_http_core::{HttpClientConfig, HttpConfigError, RetryPolicy};
+
 #[derive(Error, Debug)]
 pub enum DemonicVoiceError {
     #[error("http error: {0}")]
@@ -17,25 +21,50 @@ pub enum DemonicVoiceError {
     Status(reqwest::StatusCode, Value),
     #[error("invalid base url: {0}")]
     InvalidBaseUrl(String),
+    #[error("client configuration: {0}")]
+    Config(#[from] HttpConfigError),
+    #[error("stream read error: {0}")]
+    Stream(#[from] std::io::Error),
 }
 
 #[derive(Clone)]
 pub struct DemonicVoice {
     base_url: Arc<String>,
     client: Client,
+    config: HttpClientConfig,
 }
 
 impl DemonicVoice {
     /// Create a new client for a lil-daemon instance
     ///
+    /// Panics if `base_url` is invalid; see [`DemonicVoice::try_new`].
+    ///
     /// # Arguments
     /// * `base_url` - Base URL of the lil-daemon, e.g. "http://localhost:8000"
     pub fn new(base_url: impl Into<String>) -> Self {
+        Self::try_new(base_url).unwrap_or_else(|e| panic!("failed to create DemonicVoice: {}", e))
+    }
+
+    /// Create a new client, rejecting a base URL without an `http`/`https`
+    /// scheme or host with `InvalidBaseUrl`
+    pub fn try_new(base_url: impl Into<String>) -> Result<Self, DemonicVoiceError> {
+        let base = clara_http_core::normalize_base_url(&base_url.into())
+            .map_err(DemonicVoiceError::InvalidBaseUrl)?;
+        Ok(Self::with_config(base, HttpClientConfig::default())?)
+    }
+
+    /// Create a client with shared HTTP settings (timeouts, retries, default
+    /// headers, connection pool). Fails if a configured header is invalid.
+    pub fn with_config(
+        base_url: impl Into<String>,
+        config: HttpClientConfig,
+    ) -> Result<Self, HttpConfigError> {
         let base = base_url.into();
-        DemonicVoice {
+        Ok(DemonicVoice {
             base_url: Arc::new(base),
-            client: Client::new(),
-        }
+            client: config.build()?,
+            config,
+        })
     }
 
     /// Evaluate a JSON payload via the lil-daemon's /evaluate endpoint.
@@ -43,15 +72,158 @@ impl DemonicVoice {
     pub fn evaluate(&self, payload: Value) -> Result<Value, DemonicVoiceError> {
         let url = format!("{}/evaluate", self.base_url.as_ref().trim_end_matches('/'));
         log::debug!("DemonicVoice::evaluate -> POST {} with payload: {}", url, payload);
-        let resp = self.client.post(&url).json(&payload).send()?;
+        let resp = self.config.retry.send(|| self.client.post(&url).json(&payload))?;
         let status = resp.status();
         // Read response body as text first so we can return it in the Status error if needed.
         let text = resp.text()?;
-        let json: Value = serde_json::from_str(&text).unwrap_or(Value::String(text.clone()));
+        let json = parse_body(text);
         if status.is_success() {
             Ok(json)
         } else {
             Err(DemonicVoiceError::Status(status, json))
         }
     }
+
+    /// Evaluate a JSON payload, reading the result as it arrives.
+    ///
+    /// A `text/event-stream` response yields each event's `data:` payload
+    /// as soon as the event is complete; any other successful response
+    /// yields its whole body as a single item. Payloads that aren't JSON
+    /// come back as `Value::String`, as with [`DemonicVoice::evaluate`].
+    pub fn evaluate_stream(&self, payload: Value) -> Result<EvaluateStream, DemonicVoiceError> {
+        let url = format!("{}/evaluate", self.base_url.as_ref().trim_end_matches('/'));
+        log::debug!("DemonicVoice::evaluate_stream -> POST {} with payload: {}", url, payload);
+        let resp = self.config.retry.send(|| {
+            self.client
+                .post(&url)
+                .header(ACCEPT, "text/event-stream, application/json")
+                .json(&payload)
+        })?;
+        let status = resp.status();
+        if !status.is_success() {
+            return Err(DemonicVoiceError::Status(status, parse_body(resp.text()?)));
+        }
+
+        let is_event_stream = resp
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.trim_start().starts_with("text/event-stream"));
+        if is_event_stream {
+            Ok(EvaluateStream::Events(BufReader::new(resp)))
+        } else {
+            Ok(EvaluateStream::Single(Some(parse_body(resp.text()?))))
+        }
+    }
+}
+
+/// Results of [`DemonicVoice::evaluate_stream`], one per SSE event
+pub enum EvaluateStream {
+    /// A `text/event-stream` body, read one event at a time
+    Events(BufReader<Response>),
+    /// A non-streaming body, yielded once
+    Single(Option<Value>),
+}
+
+impl Iterator for EvaluateStream {
+    type Item = Result<Value, DemonicVoiceError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            EvaluateStream::Single(body) => body.take().map(Ok),
+            EvaluateStream::Events(reader) => read_event(reader).transpose(),
+        }
+    }
+}
+
+/// Read up to the end of the next event carrying data and decode it
+///
+/// Comments, `event:`/`id:`/`retry:` fields and events without `data:`
+/// lines are skipped. Multiple `data:` lines in one event are joined with
+/// newlines. An event cut off by the end of the stream is still returned.
+fn read_event(reader: &mut impl BufRead) -> Result<Option<Value>, DemonicVoiceError> {
+    let mut data: Option<String> = None;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(data.map(parse_body));
+        }
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line.is_empty() {
+            if let Some(data) = data.take() {
+                return Ok(Some(parse_body(data)));
+            }
+            continue;
+        }
+        if let Some(value) = line.strip_prefix("data:") {
+            let value = value.strip_prefix(' ').unwrap_or(value);
+            match &mut data {
+                Some(data) => {
+                    data.push('\n');
+                    data.push_str(value);
+                }
+                None => data = Some(value.to_string()),
+            }
+        }
+    }
+}
+
+/// Parse a body or event payload as JSON, keeping it as a string otherwise
+fn parse_body(text: String) -> Value {
+    serde_json::from_str(&text).unwrap_or(Value::String(text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_evaluate_stream_yields_each_event() {
+        let mut srv = mockito::Server::new();
+        let mock = srv
+            .mock("POST", "/evaluate")
+            .match_header("accept", mockito::Matcher::Regex("text/event-stream".to_string()))
+            .with_status(200)
+            .with_header("content-type", "text/event-stream")
+            .with_body(": keep-alive\n\ndata: {\"token\": \"Hel\"}\n\nevent: token\ndata: lo\n\n")
+            .create();
+
+        let voice = DemonicVoice::new(srv.url());
+        let items: Vec<Value> = voice
+            .evaluate_stream(json!({"prompt": "hi"}))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(items, vec![json!({"token": "Hel"}), json!("lo")]);
+        mock.assert();
+    }
+
+    #[test]
+    fn test_evaluate_stream_single_item_for_plain_json() {
+        let mut srv = mockito::Server::new();
+        srv.mock("POST", "/evaluate")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"answer": 42}"#)
+            .create();
+
+        let voice = DemonicVoice::new(srv.url());
+        let items: Vec<Value> = voice
+            .evaluate_stream(json!({}))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(items, vec![json!({"answer": 42})]);
+    }
+
+    #[test]
+    fn test_read_event_joins_multiline_data() {
+        let mut reader = "data: first\ndata: second\r\n\ndata: {\"tail\": true}".as_bytes();
+        assert_eq!(read_event(&mut reader).unwrap(), Some(json!("first\nsecond")));
+        // The last event has no trailing blank line
+        assert_eq!(read_event(&mut reader).unwrap(), Some(json!({"tail": true})));
+        assert_eq!(read_event(&mut reader).unwrap(), None);
+    }
 }
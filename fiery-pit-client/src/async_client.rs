@@ -0,0 +1,763 @@
+//! AsyncFieryPitClient - non-blocking client for the FieryPit REST API
+//!
+//! Mirrors [`FieryPitClient`](crate::FieryPitClient) method for method on
+//! `reqwest::Client`, so it can be awaited from Tokio/actix code without
+//! `spawn_blocking`. Request, response and Tephra types are shared with the
+//! blocking client.
+
+use reqwest::{Client, RequestBuilder};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::{
+    clips_facts, request_id_from, session_id_from, ClipsEvalRequest, ClipsFact, ClipsLoadFactsRequest, ClipsLoadRulesRequest,
+    ClipsLoadRulesResult, ClipsRunRequest, CreateSessionRequest, EvaluateInput, EvaluateRequest,
+    EvaluationEntry, EvaluationStats, EvaluatorActionResponse, EvaluatorAuth, EvaluatorAuthStatus,
+    FieryPitError, HttpClientConfig, HttpConfigError, HungDetectorConfig, LoadEvaluatorRequest,
+    PrologConsultRequest, PrologQueryRequest, PrologQueryResponse, RitualJoinRequest,
+    ServiceTokenResponse, SetEvaluatorRequest, SetFishRequest, Tephra, TephraError,
+    DEFAULT_POOL_IDLE_TIMEOUT, DEFAULT_POOL_MAX_IDLE_PER_HOST,
+};
+
+/// Non-blocking FieryPit REST API client
+#[derive(Clone)]
+pub struct AsyncFieryPitClient {
+    base_url: Arc<String>,
+    client: Client,
+    service_key: Option<Arc<String>>,
+    config: HttpClientConfig,
+}
+
+impl AsyncFieryPitClient {
+    /// Create a new AsyncFieryPitClient
+    ///
+    /// # Arguments
+    /// * `base_url` - Base URL of the FieryPit API, e.g. "http://localhost:6666"
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self::try_new(base_url)
+            .unwrap_or_else(|e| panic!("failed to create AsyncFieryPitClient: {}", e))
+    }
+
+    /// Create a new client, rejecting an invalid base URL as
+    /// [`FieryPitClient::try_new`](crate::FieryPitClient::try_new) does
+    pub fn try_new(base_url: impl Into<String>) -> Result<Self, FieryPitError> {
+        let base = clara_http_core::normalize_base_url(&base_url.into())
+            .map_err(FieryPitError::InvalidBaseUrl)?;
+        let config = HttpClientConfig::default()
+            .with_pool_max_idle_per_host(DEFAULT_POOL_MAX_IDLE_PER_HOST)
+            .with_pool_idle_timeout(Some(DEFAULT_POOL_IDLE_TIMEOUT));
+        Ok(Self::with_config(base, config)?)
+    }
+
+    /// Create a client with shared HTTP settings, as
+    /// [`FieryPitClient::with_config`](crate::FieryPitClient::with_config)
+    pub fn with_config(
+        base_url: impl Into<String>,
+        config: HttpClientConfig,
+    ) -> Result<Self, HttpConfigError> {
+        let base = base_url.into();
+        Ok(AsyncFieryPitClient {
+            base_url: Arc::new(base.trim_end_matches('/').to_string()),
+            client: config.build_async()?,
+            service_key: None,
+            config,
+        })
+    }
+
+    /// Limit how many idle connections are kept open per host.
+    pub fn with_pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.config.pool_max_idle_per_host = max;
+        self.rebuild_http_client();
+        self
+    }
+
+    /// Close pooled connections that have been idle for `timeout`; `None`
+    /// keeps them open indefinitely.
+    pub fn with_pool_idle_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.config.pool_idle_timeout = timeout;
+        self.rebuild_http_client();
+        self
+    }
+
+    fn rebuild_http_client(&mut self) {
+        self.client = self.config.build_async().expect("failed to build FieryPit HTTP client");
+    }
+
+    /// Attach a Bearer service key for lildaemon's JWT auth.
+    pub fn with_service_key(mut self, key: impl Into<String>) -> Self {
+        self.service_key = Some(Arc::new(key.into()));
+        self
+    }
+
+    /// A client for another FieryPit instance with this client's HTTP
+    /// settings and service key; the connection pool is shared.
+    pub fn with_base_url(&self, base_url: impl Into<String>) -> Self {
+        let base = base_url.into();
+        Self {
+            base_url: Arc::new(base.trim_end_matches('/').to_string()),
+            ..self.clone()
+        }
+    }
+
+    /// The FieryPit base URL requests are sent to
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Create an AsyncFieryPitClient from the same environment variables as
+    /// [`FieryPitClient::from_env`](crate::FieryPitClient::from_env)
+    pub fn from_env() -> Self {
+        let url = std::env::var("FIERY_PIT_URL")
+            .unwrap_or_else(|_| "http://localhost:6666".into());
+        let mut client = Self::new(url);
+        if let Ok(key) = std::env::var("FIERYPIT_SERVICE_KEY") {
+            if !key.is_empty() {
+                client = client.with_service_key(key);
+            }
+        }
+        client
+    }
+
+    // =========================================================================
+    // Internal helpers
+    // =========================================================================
+
+    async fn get(&self, path: &str) -> Result<Value, FieryPitError> {
+        let url = format!("{}{}", self.base_url, path);
+        log::debug!("AsyncFieryPitClient GET {}", url);
+        let resp = self.send(|| self.client.get(&url)).await?;
+        Self::handle_response(resp).await
+    }
+
+    async fn post(&self, path: &str, body: &impl Serialize) -> Result<Value, FieryPitError> {
+        let url = format!("{}{}", self.base_url, path);
+        log::debug!("AsyncFieryPitClient POST {}", url);
+        let resp = self.send(|| self.client.post(&url).json(body)).await?;
+        Self::handle_response(resp).await
+    }
+
+    async fn delete(&self, path: &str) -> Result<Value, FieryPitError> {
+        let url = format!("{}{}", self.base_url, path);
+        log::debug!("AsyncFieryPitClient DELETE {}", url);
+        let resp = self.send(|| self.client.delete(&url)).await?;
+        Self::handle_response(resp).await
+    }
+
+    /// Send a request with the service key attached, retrying per the
+    /// configured [`RetryPolicy`](crate::RetryPolicy)
+    async fn send<F>(&self, make_request: F) -> Result<reqwest::Response, FieryPitError>
+    where
+        F: Fn() -> RequestBuilder,
+    {
+        let resp = self
+            .config
+            .retry
+            .send_async(|| {
+                let req = make_request();
+                match &self.service_key {
+                    Some(key) => req.bearer_auth(key.as_str()),
+                    None => req,
+                }
+            })
+            .await?;
+        Ok(resp)
+    }
+
+    async fn handle_response(resp: reqwest::Response) -> Result<Value, FieryPitError> {
+        let status = resp.status();
+        let headers = resp.headers().clone();
+        let text = resp.text().await?;
+        let json: Value = serde_json::from_str(&text).unwrap_or(Value::String(text.clone()));
+        if status.is_success() {
+            Ok(json)
+        } else {
+            let id = request_id_from(&headers, &json);
+            Err(FieryPitError::Status(status, json, id))
+        }
+    }
+
+    // =========================================================================
+    // Health & Status
+    // =========================================================================
+
+    /// Health check — GET /health
+    pub async fn health(&self) -> Result<Value, FieryPitError> {
+        self.get("/health").await
+    }
+
+    /// Current status including active evaluator — GET /status
+    pub async fn status(&self) -> Result<Value, FieryPitError> {
+        self.get("/status").await
+    }
+
+    /// API metadata — GET /
+    pub async fn info(&self) -> Result<Value, FieryPitError> {
+        self.get("/").await
+    }
+
+    // =========================================================================
+    // Evaluation
+    // =========================================================================
+
+    /// Evaluate using the current active evaluator — POST /evaluate
+    pub async fn evaluate(&self, data: Value) -> Result<Value, FieryPitError> {
+        log::debug!("AsyncFieryPitClient evaluate with data: {}", data);
+        self.post("/evaluate", &EvaluateRequest { data, evaluator: None }).await
+    }
+
+    /// Evaluate using `evaluator` for this call only — POST /evaluate
+    ///
+    /// The name is checked via GET /evaluators/{name} first, as in
+    /// [`FieryPitClient::evaluate_with`](crate::FieryPitClient::evaluate_with).
+    pub async fn evaluate_with(&self, data: Value, evaluator: &str) -> Result<Value, FieryPitError> {
+        log::debug!("AsyncFieryPitClient evaluate with evaluator {} and data: {}", evaluator, data);
+        self.get_evaluator(evaluator).await?;
+        self.post(
+            "/evaluate",
+            &EvaluateRequest {
+                data,
+                evaluator: Some(evaluator.to_string()),
+            },
+        )
+        .await
+    }
+
+    /// Evaluate a prompt or chat messages — POST /evaluate
+    pub async fn evaluate_input(&self, input: EvaluateInput) -> Result<Value, FieryPitError> {
+        self.evaluate(input.into_data()).await
+    }
+
+    /// Evaluate and return a typed Tephra envelope
+    pub async fn evaluate_tephra(&self, data: Value) -> Result<Tephra, FieryPitError> {
+        let value = self.evaluate(data).await?;
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Evaluate each item in turn, keeping every item's outcome; see
+    /// [`FieryPitClient::try_batch`](crate::FieryPitClient::try_batch)
+    pub async fn try_batch(&self, items: Vec<Value>) -> Vec<Result<Value, TephraError>> {
+        let mut results = Vec::with_capacity(items.len());
+        for data in items {
+            results.push(match self.evaluate_tephra(data).await {
+                Ok(tephra) => tephra.into_result(),
+                Err(e) => Err(e.into()),
+            });
+        }
+        results
+    }
+
+    // =========================================================================
+    // Evaluation Monitoring — /evaluations/*
+    // =========================================================================
+
+    /// List all currently running evaluations — GET /evaluations/active
+    pub async fn evaluations_active(&self) -> Result<Vec<EvaluationEntry>, FieryPitError> {
+        let v = self.get("/evaluations/active").await?;
+        Ok(serde_json::from_value(v["active_evaluations"].clone()).unwrap_or_default())
+    }
+
+    /// Evaluation statistics — GET /evaluations/stats
+    pub async fn evaluations_stats(&self) -> Result<EvaluationStats, FieryPitError> {
+        let v = self.get("/evaluations/stats").await?;
+        Ok(serde_json::from_value(v)?)
+    }
+
+    /// Recent evaluation history — GET /evaluations/history?limit=N
+    pub async fn evaluations_history(&self, limit: Option<u32>) -> Result<Vec<EvaluationEntry>, FieryPitError> {
+        let path = match limit {
+            Some(n) => format!("/evaluations/history?limit={}", n),
+            None => "/evaluations/history".to_string(),
+        };
+        let v = self.get(&path).await?;
+        Ok(serde_json::from_value(v["evaluations"].clone()).unwrap_or_default())
+    }
+
+    /// Evaluations exceeding the hung threshold — GET /evaluations/hung
+    pub async fn evaluations_hung(&self) -> Result<Vec<EvaluationEntry>, FieryPitError> {
+        let v = self.get("/evaluations/hung").await?;
+        Ok(serde_json::from_value(v["hung_evaluations"].clone()).unwrap_or_default())
+    }
+
+    /// Evaluations running longer than a threshold — GET /evaluations/long-running
+    pub async fn evaluations_long_running(
+        &self,
+        threshold_seconds: Option<f64>,
+    ) -> Result<Vec<EvaluationEntry>, FieryPitError> {
+        let path = match threshold_seconds {
+            Some(t) => format!("/evaluations/long-running?threshold={}", t),
+            None => "/evaluations/long-running".to_string(),
+        };
+        let v = self.get(&path).await?;
+        Ok(serde_json::from_value(v["long_running_evaluations"].clone()).unwrap_or_default())
+    }
+
+    /// Details of a specific evaluation — GET /evaluations/{task_id}
+    pub async fn evaluation_get(&self, task_id: &str) -> Result<EvaluationEntry, FieryPitError> {
+        let v = self.get(&format!("/evaluations/{}", task_id)).await?;
+        Ok(serde_json::from_value(v)?)
+    }
+
+    /// Cancel a specific active evaluation — DELETE /evaluations/{task_id}
+    pub async fn evaluation_cancel(&self, task_id: &str) -> Result<Value, FieryPitError> {
+        self.delete(&format!("/evaluations/{}", task_id)).await
+    }
+
+    /// Cancel all hung evaluations — POST /evaluations/cancel-hung
+    pub async fn evaluations_cancel_hung(&self) -> Result<Value, FieryPitError> {
+        self.post("/evaluations/cancel-hung", &json!({})).await
+    }
+
+    // =========================================================================
+    // Hung Detector — /hung-detector/*
+    // =========================================================================
+
+    /// Hung detector status and configuration — GET /hung-detector/status
+    pub async fn hung_detector_status(&self) -> Result<Value, FieryPitError> {
+        self.get("/hung-detector/status").await
+    }
+
+    /// Update hung detector configuration — POST /hung-detector/configure
+    pub async fn hung_detector_configure(&self, config: HungDetectorConfig) -> Result<Value, FieryPitError> {
+        self.post("/hung-detector/configure", &config).await
+    }
+
+    // =========================================================================
+    // Evaluator Management — /evaluators/*
+    // =========================================================================
+
+    /// List all available evaluators — GET /evaluators
+    pub async fn list_evaluators(&self) -> Result<Value, FieryPitError> {
+        self.get("/evaluators").await
+    }
+
+    /// Details for a specific evaluator — GET /evaluators/{name}
+    pub async fn get_evaluator(&self, name: &str) -> Result<Value, FieryPitError> {
+        self.get(&format!("/evaluators/{}", name)).await
+    }
+
+    /// Authentication configuration status for an evaluator — GET /evaluators/{name}/auth-status
+    pub async fn get_evaluator_auth_status(&self, name: &str) -> Result<EvaluatorAuthStatus, FieryPitError> {
+        let v = self.get(&format!("/evaluators/{}/auth-status", name)).await?;
+        Ok(serde_json::from_value(v)?)
+    }
+
+    /// Set the current active evaluator — POST /evaluators/set
+    pub async fn set_evaluator(&self, evaluator: &str) -> Result<Value, FieryPitError> {
+        self.set_evaluator_with_config(evaluator, None, None).await
+    }
+
+    /// Set the current active evaluator with optional params and auth config.
+    pub async fn set_evaluator_with_config(
+        &self,
+        evaluator: &str,
+        params: Option<Value>,
+        auth: Option<EvaluatorAuth>,
+    ) -> Result<Value, FieryPitError> {
+        log::debug!("AsyncFieryPitClient set_evaluator to {}", evaluator);
+        self.post(
+            "/evaluators/set",
+            &SetEvaluatorRequest {
+                evaluator: evaluator.to_string(),
+                params,
+                auth,
+            },
+        )
+        .await
+    }
+
+    /// Set the current active evaluator and return a typed response
+    pub async fn set_evaluator_typed(&self, evaluator: &str) -> Result<EvaluatorActionResponse, FieryPitError> {
+        let value = self.set_evaluator(evaluator).await?;
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Load/verify an evaluator with optional parameter overrides — POST /evaluators/{name}/load
+    pub async fn load_evaluator(
+        &self,
+        name: &str,
+        req: LoadEvaluatorRequest,
+    ) -> Result<EvaluatorActionResponse, FieryPitError> {
+        let v = self.post(&format!("/evaluators/{}/load", name), &req).await?;
+        Ok(serde_json::from_value(v)?)
+    }
+
+    /// Load an evaluator with no overrides — convenience wrapper
+    pub async fn load_evaluator_simple(&self, name: &str) -> Result<EvaluatorActionResponse, FieryPitError> {
+        self.load_evaluator(name, LoadEvaluatorRequest::default()).await
+    }
+
+    /// Reset to the default echo evaluator — POST /evaluators/reset
+    pub async fn reset_evaluator(&self) -> Result<Value, FieryPitError> {
+        self.post("/evaluators/reset", &json!({})).await
+    }
+
+    /// Unload/unregister an evaluator — DELETE /evaluators/{name}
+    pub async fn delete_evaluator(&self, name: &str) -> Result<Value, FieryPitError> {
+        self.delete(&format!("/evaluators/{}", name)).await
+    }
+
+    // =========================================================================
+    // Fish (Input Translators) — /fish, /evaluators/{name}/fish
+    // =========================================================================
+
+    /// List all available fish (input translators) — GET /fish
+    pub async fn list_fish(&self) -> Result<Value, FieryPitError> {
+        self.get("/fish").await
+    }
+
+    /// Set the fish (input translator) for a specific evaluator — POST /evaluators/{name}/fish
+    pub async fn set_evaluator_fish(&self, evaluator_name: &str, fish: &str) -> Result<Value, FieryPitError> {
+        self.post(
+            &format!("/evaluators/{}/fish", evaluator_name),
+            &SetFishRequest { fish: fish.to_string() },
+        )
+        .await
+    }
+
+    // =========================================================================
+    // CLIPS Sessions — /clips/sessions/*
+    // =========================================================================
+
+    /// Create CLIPS session — POST /clips/sessions
+    pub async fn clips_create_session(&self, req: CreateSessionRequest) -> Result<Value, FieryPitError> {
+        self.post("/clips/sessions", &req).await
+    }
+
+    /// List CLIPS sessions — GET /clips/sessions
+    pub async fn clips_list_sessions(&self) -> Result<Value, FieryPitError> {
+        self.get("/clips/sessions").await
+    }
+
+    /// Get CLIPS session — GET /clips/sessions/{id}
+    pub async fn clips_get_session(&self, session_id: &str) -> Result<Value, FieryPitError> {
+        self.get(&format!("/clips/sessions/{}", session_id)).await
+    }
+
+    /// Terminate CLIPS session — DELETE /clips/sessions/{id}
+    pub async fn clips_terminate_session(&self, session_id: &str) -> Result<Value, FieryPitError> {
+        self.delete(&format!("/clips/sessions/{}", session_id)).await
+    }
+
+    /// Execute raw CLIPS code — POST /clips/sessions/{id}/evaluate
+    pub async fn clips_evaluate(
+        &self,
+        session_id: &str,
+        script: &str,
+        timeout_ms: Option<i32>,
+    ) -> Result<Value, FieryPitError> {
+        self.post(
+            &format!("/clips/sessions/{}/evaluate", session_id),
+            &ClipsEvalRequest {
+                script: script.to_string(),
+                timeout_ms,
+            },
+        )
+        .await
+    }
+
+    /// Load CLIPS rules — POST /clips/sessions/{id}/rules
+    ///
+    /// Check `failed` in the result: rules that don't compile are reported
+    /// there rather than failing the whole request. Only a batch in which no
+    /// rule compiles is rejected, as a 400 [`FieryPitError::Status`].
+    pub async fn clips_load_rules(
+        &self,
+        session_id: &str,
+        rules: Vec<String>,
+    ) -> Result<ClipsLoadRulesResult, FieryPitError> {
+        let value = self
+            .post(
+                &format!("/clips/sessions/{}/rules", session_id),
+                &ClipsLoadRulesRequest { rules },
+            )
+            .await?;
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Assert CLIPS facts — POST /clips/sessions/{id}/facts
+    pub async fn clips_load_facts(
+        &self,
+        session_id: &str,
+        facts: Vec<String>,
+    ) -> Result<Value, FieryPitError> {
+        self.post(
+            &format!("/clips/sessions/{}/facts", session_id),
+            &ClipsLoadFactsRequest { facts },
+        )
+        .await
+    }
+
+    /// Query CLIPS facts — GET /clips/sessions/{id}/facts
+    pub async fn clips_query_facts(
+        &self,
+        session_id: &str,
+        pattern: Option<&str>,
+    ) -> Result<Value, FieryPitError> {
+        let path = match pattern {
+            Some(p) => format!(
+                "/clips/sessions/{}/facts?pattern={}",
+                session_id,
+                urlencoding::encode(p)
+            ),
+            None => format!("/clips/sessions/{}/facts", session_id),
+        };
+        self.get(&path).await
+    }
+
+    /// [`clips_query_facts`](Self::clips_query_facts), parsed with
+    /// [`clips_facts`](crate::clips_facts)
+    pub async fn clips_query_facts_typed(
+        &self,
+        session_id: &str,
+        pattern: Option<&str>,
+    ) -> Result<Vec<ClipsFact>, FieryPitError> {
+        clips_facts(self.clips_query_facts(session_id, pattern).await?)
+    }
+
+    /// Run the CLIPS rule engine — POST /clips/sessions/{id}/run
+    pub async fn clips_run(
+        &self,
+        session_id: &str,
+        max_iterations: Option<i32>,
+    ) -> Result<Value, FieryPitError> {
+        self.post(
+            &format!("/clips/sessions/{}/run", session_id),
+            &ClipsRunRequest {
+                max_iterations: max_iterations.unwrap_or(-1),
+            },
+        )
+        .await
+    }
+
+    // =========================================================================
+    // Prolog Sessions — /prolog/sessions/*
+    // =========================================================================
+
+    /// Create Prolog session — POST /prolog/sessions
+    pub async fn prolog_create_session(&self, req: CreateSessionRequest) -> Result<Value, FieryPitError> {
+        self.post("/prolog/sessions", &req).await
+    }
+
+    /// Create a Prolog session and return just the session_id
+    pub async fn prolog_create_session_id(&self, req: CreateSessionRequest) -> Result<String, FieryPitError> {
+        session_id_from(self.prolog_create_session(req).await?)
+    }
+
+    /// List Prolog sessions — GET /prolog/sessions
+    pub async fn prolog_list_sessions(&self) -> Result<Value, FieryPitError> {
+        self.get("/prolog/sessions").await
+    }
+
+    /// Get Prolog session — GET /prolog/sessions/{id}
+    pub async fn prolog_get_session(&self, session_id: &str) -> Result<Value, FieryPitError> {
+        self.get(&format!("/prolog/sessions/{}", session_id)).await
+    }
+
+    /// Terminate Prolog session — DELETE /prolog/sessions/{id}
+    pub async fn prolog_terminate_session(&self, session_id: &str) -> Result<Value, FieryPitError> {
+        self.delete(&format!("/prolog/sessions/{}", session_id)).await
+    }
+
+    /// Execute a Prolog goal — POST /prolog/sessions/{id}/query
+    pub async fn prolog_query(
+        &self,
+        session_id: &str,
+        goal: &str,
+        all_solutions: bool,
+    ) -> Result<Value, FieryPitError> {
+        self.post(
+            &format!("/prolog/sessions/{}/query", session_id),
+            &PrologQueryRequest {
+                goal: goal.to_string(),
+                all_solutions,
+                bindings: None,
+            },
+        )
+        .await
+    }
+
+    /// Execute a Prolog goal with named variables bound to JSON values —
+    /// POST /prolog/sessions/{id}/query
+    pub async fn prolog_query_with_bindings(
+        &self,
+        session_id: &str,
+        goal: &str,
+        bindings: serde_json::Map<String, Value>,
+        all_solutions: bool,
+    ) -> Result<Value, FieryPitError> {
+        self.post(
+            &format!("/prolog/sessions/{}/query", session_id),
+            &PrologQueryRequest {
+                goal: goal.to_string(),
+                all_solutions,
+                bindings: Some(bindings),
+            },
+        )
+        .await
+    }
+
+    /// Execute a Prolog goal and return a typed response — POST /prolog/sessions/{id}/query
+    pub async fn prolog_query_typed(
+        &self,
+        session_id: &str,
+        goal: &str,
+        all_solutions: bool,
+    ) -> Result<PrologQueryResponse, FieryPitError> {
+        let value = self.prolog_query(session_id, goal, all_solutions).await?;
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Load Prolog clauses into a session — POST /prolog/sessions/{id}/consult
+    pub async fn prolog_consult(
+        &self,
+        session_id: &str,
+        clauses: Vec<String>,
+    ) -> Result<Value, FieryPitError> {
+        self.post(
+            &format!("/prolog/sessions/{}/consult", session_id),
+            &PrologConsultRequest { clauses },
+        )
+        .await
+    }
+
+    // =========================================================================
+    // Auth — /auth/*
+    // =========================================================================
+
+    /// Acquire a long-lived service JWT — POST /auth/service-token
+    pub async fn auth_service_token(
+        &self,
+        service_name: &str,
+        secret: &str,
+    ) -> Result<ServiceTokenResponse, FieryPitError> {
+        let v = self
+            .post(
+                "/auth/service-token",
+                &json!({
+                    "service_name":   service_name,
+                    "service_secret": secret,
+                }),
+            )
+            .await?;
+        Ok(serde_json::from_value(v)?)
+    }
+
+    // =========================================================================
+    // Ritual coordination — /ritual/*
+    // =========================================================================
+
+    /// Register this Dis instance as a participant in `ritual_id`; see
+    /// [`FieryPitClient::ritual_join`](crate::FieryPitClient::ritual_join)
+    #[allow(clippy::too_many_arguments)]
+    pub async fn ritual_join(
+        &self,
+        ritual_id:        uuid::Uuid,
+        topic:            &str,
+        bootstrap:        &str,
+        dis_domain:       &str,
+        evaluator:        Option<&str>,
+        session_stateful: bool,
+        eval_timeout_s:   f64,
+    ) -> Result<Value, FieryPitError> {
+        self.post(
+            "/ritual/join",
+            &RitualJoinRequest {
+                ritual_id:         ritual_id.to_string(),
+                topic:             topic.to_string(),
+                bootstrap_servers: bootstrap.to_string(),
+                dis_domain:        dis_domain.to_string(),
+                evaluator:         evaluator.map(|s| s.to_string()),
+                session_stateful,
+                eval_timeout_s,
+            },
+        )
+        .await
+    }
+
+    /// Deregister lildaemon from `ritual_id`: stops its consumer and discards state.
+    pub async fn ritual_leave(&self, ritual_id: uuid::Uuid) -> Result<Value, FieryPitError> {
+        self.delete(&format!("/ritual/{}", ritual_id)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_async_client_creation_trims_slash() {
+        let client = AsyncFieryPitClient::new("http://localhost:8000/");
+        assert_eq!(client.base_url(), "http://localhost:8000");
+        assert!(client.service_key.is_none());
+    }
+
+    #[test]
+    fn test_async_client_rejects_invalid_base_url() {
+        let result = AsyncFieryPitClient::try_new("localhost:6666");
+        assert!(matches!(result, Err(FieryPitError::InvalidBaseUrl(_))));
+        assert!(AsyncFieryPitClient::try_new("https://pit.example").is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_async_evaluate_tephra() {
+        let mut srv = mockito::Server::new_async().await;
+        let evaluate = srv
+            .mock("POST", "/evaluate")
+            .match_header("authorization", "Bearer pit-key")
+            .match_body(mockito::Matcher::PartialJson(json!({"data": {"prompt": "hi"}})))
+            .with_status(200)
+            .with_body(r#"{"hohi":{"response":{"result":{"prolog_solutions":[{"X":"hello"}]}}},"task_id":"t-1"}"#)
+            .create_async()
+            .await;
+
+        let client = AsyncFieryPitClient::new(srv.url()).with_service_key("pit-key");
+        let tephra = client.evaluate_tephra(json!({"prompt": "hi"})).await.unwrap();
+
+        assert!(tephra.is_success());
+        assert_eq!(tephra.task_id.as_deref(), Some("t-1"));
+        assert_eq!(tephra.prolog_bindings()["X"], "hello");
+        evaluate.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_async_status_error_and_tabu() {
+        let mut srv = mockito::Server::new_async().await;
+        let _missing = srv
+            .mock("GET", "/clips/sessions/nope")
+            .with_status(404)
+            .with_body(r#"{"detail":"Session not found"}"#)
+            .create_async()
+            .await;
+        let _tabu = srv
+            .mock("POST", "/evaluate")
+            .with_status(200)
+            .with_body(r#"{"tabu":{"message":"boom","code":409}}"#)
+            .create_async()
+            .await;
+
+        let client = AsyncFieryPitClient::new(srv.url());
+        let result = client.clips_get_session("nope").await;
+        assert!(matches!(result, Err(FieryPitError::Status(status, _, _)) if status == 404));
+
+        let results = client.try_batch(vec![json!({"q": 1})]).await;
+        assert_eq!(results[0].as_ref().unwrap_err().code, Some(409));
+    }
+
+    #[tokio::test]
+    async fn test_status_error_keeps_request_id() {
+        let mut srv = mockito::Server::new_async().await;
+        let _failing = srv
+            .mock("GET", "/status")
+            .with_status(502)
+            .with_header("x-request-id", "req-9")
+            .with_body(r#"{"detail":"upstream"}"#)
+            .create_async()
+            .await;
+
+        let client = AsyncFieryPitClient::new(srv.url());
+        let err = client.status().await.unwrap_err();
+        assert_eq!(err.request_id(), Some("req-9"));
+    }
+}
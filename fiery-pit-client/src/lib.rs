@@ -3,22 +3,72 @@
 //! Provides a blocking client to interact with the FieryPit REST API in lildaemon.
 //! Supports health checks, evaluator management, evaluation monitoring,
 //! hung-detector control, fish (input translator) management, CLIPS sessions,
-//! and Prolog sessions.
+//! and Prolog sessions. [`AsyncFieryPitClient`] offers the same methods for
+//! async code.
 
-use reqwest::blocking::Client;
+use reqwest::blocking::{Client, RequestBuilder};
 use serde::{Deserialize, Serialize};
-use serde_json::{json, Value};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 use thiserror::Error;
 
+pub use clara_http_core::{HttpClientConfig, HttpConfigError, RetryPolicy};
+
+mod async_client;
+pub use async_client::AsyncFieryPitClient;
+
 #[derive(Error, Debug)]
 pub enum FieryPitError {
     #[error("HTTP error: {0}")]
-    Http(#[from] reqwest::Error),
-    #[error("Non-success status {0}: {1}")]
-    Status(reqwest::StatusCode, Value),
+    Http(reqwest::Error),
+    /// The request or connection timed out; safe to retry
+    #[error("Request timed out: {0}")]
+    Timeout(reqwest::Error),
+    /// A non-2xx response: status, body, and the id to find the request by
+    /// in FieryPit's logs, if it sent one
+    #[error("Non-success status {0}: {1}{}", .2.as_ref().map(|id| format!(" (request id {})", id)).unwrap_or_default())]
+    Status(reqwest::StatusCode, Value, Option<String>),
     #[error("JSON parse error: {0}")]
     Json(#[from] serde_json::Error),
+    #[error("Invalid base URL: {0}")]
+    InvalidBaseUrl(String),
+    #[error("Client configuration error: {0}")]
+    Config(#[from] HttpConfigError),
+}
+
+impl FieryPitError {
+    /// Correlation id of the failed request, for `Status` errors that have one
+    pub fn request_id(&self) -> Option<&str> {
+        match self {
+            FieryPitError::Status(_, _, id) => id.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// Response header FieryPit uses to tag a request in its logs
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// The id to correlate a response with server logs: the `X-Request-Id`
+/// header, or failing that the `task_id` of the body
+pub(crate) fn request_id_from(headers: &reqwest::header::HeaderMap, body: &Value) -> Option<String> {
+    headers
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+        .or_else(|| body.get("task_id").and_then(Value::as_str).map(str::to_string))
+}
+
+impl From<reqwest::Error> for FieryPitError {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_timeout() {
+            FieryPitError::Timeout(err)
+        } else {
+            FieryPitError::Http(err)
+        }
+    }
 }
 
 // =========================================================================
@@ -60,12 +110,90 @@ pub struct ClipsLoadRulesRequest {
     pub rules: Vec<String>,
 }
 
+/// A rule the server failed to compile
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleLoadFailure {
+    /// Position of the rule in the request
+    pub index: usize,
+    pub error: String,
+}
+
+/// Result of POST /clips/sessions/{id}/rules
+///
+/// Rules are compiled one by one; failures don't stop the rest from loading.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClipsLoadRulesResult {
+    pub loaded: usize,
+    #[serde(default)]
+    pub failed: Vec<RuleLoadFailure>,
+}
+
 /// CLIPS load facts request
 #[derive(Debug, Clone, Serialize)]
 pub struct ClipsLoadFactsRequest {
     pub facts: Vec<String>,
 }
 
+/// A CLIPS fact from GET /clips/sessions/{id}/facts
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClipsFact {
+    /// Fact index (`f-<index>`), when the server reports it
+    #[serde(default)]
+    pub index: Option<u64>,
+    /// Printed fact, e.g. `(person (name "Al"))`
+    pub text: String,
+}
+
+impl ClipsFact {
+    /// Read a printed fact, with or without a leading `f-<index>`
+    fn parse(line: &str) -> Self {
+        let line = line.trim();
+        let indexed = line
+            .strip_prefix("f-")
+            .and_then(|rest| rest.split_once(char::is_whitespace))
+            .and_then(|(index, text)| Some((index.parse().ok()?, text.trim())));
+        match indexed {
+            Some((index, text)) => Self { index: Some(index), text: text.to_string() },
+            None => Self { index: None, text: line.to_string() },
+        }
+    }
+}
+
+/// One entry of a facts listing: printed text or an `{index, text}` object
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum FactEntry {
+    Text(String),
+    Fact(ClipsFact),
+}
+
+/// The facts listing, wrapped as `{"matches": [...], "count": n}` or bare
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum FactsShape {
+    Wrapped { matches: Vec<FactEntry> },
+    Bare(Vec<FactEntry>),
+}
+
+/// Parse a GET /clips/sessions/{id}/facts response into facts
+///
+/// Accepts `{"matches": [...], "count": n}` and a bare array. Entries may be
+/// printed facts, optionally prefixed with `f-<index>`, or `{index, text}`
+/// objects. Any other shape is a `FieryPitError::Json`.
+pub fn clips_facts(value: Value) -> Result<Vec<ClipsFact>, FieryPitError> {
+    let entries = match serde_json::from_value(value)? {
+        FactsShape::Wrapped { matches } => matches,
+        FactsShape::Bare(entries) => entries,
+    };
+    Ok(entries
+        .into_iter()
+        .map(|entry| match entry {
+            FactEntry::Text(line) => ClipsFact::parse(&line),
+            FactEntry::Fact(fact) => fact,
+        })
+        .collect())
+}
+
 /// CLIPS run request
 #[derive(Debug, Clone, Serialize)]
 pub struct ClipsRunRequest {
@@ -78,6 +206,9 @@ pub struct PrologQueryRequest {
     pub goal: String,
     #[serde(default)]
     pub all_solutions: bool,
+    /// Values for named variables in `goal`, bound server-side as terms
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bindings: Option<serde_json::Map<String, Value>>,
 }
 
 /// Prolog consult request
@@ -104,6 +235,66 @@ pub struct EvaluatorAuth {
     pub auth_token_env: Option<String>,
 }
 
+/// POST /evaluate
+#[derive(Debug, Clone, Serialize)]
+pub struct EvaluateRequest {
+    pub data: Value,
+    /// Evaluator to use for this call only; the current evaluator otherwise
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub evaluator: Option<String>,
+}
+
+/// One chat-style message, e.g. `{"role": "user", "content": "..."}`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: String,
+    pub content: String,
+}
+
+/// Evaluate input in either the legacy bare-string form or the chat form
+/// `{"messages": [...]}`
+///
+/// Both are normalized by [`EvaluateInput::into_data`] into the
+/// `{prompt, system, context}` shape the evaluators expect, so callers no
+/// longer depend on a bare JSON string being read as a prompt.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum EvaluateInput {
+    Prompt(String),
+    Messages { messages: Vec<ChatMessage> },
+}
+
+impl EvaluateInput {
+    /// Normalize into evaluate `data`
+    ///
+    /// System messages are joined into `system`, the final non-system
+    /// message becomes `prompt`, and the rest are passed as `context`.
+    pub fn into_data(self) -> Value {
+        match self {
+            EvaluateInput::Prompt(prompt) => json!({
+                "prompt":  prompt,
+                "system":  "",
+                "context": [],
+            }),
+            EvaluateInput::Messages { messages } => {
+                let (system, mut context): (Vec<_>, Vec<_>) =
+                    messages.into_iter().partition(|m| m.role == "system");
+                let prompt = context.pop().map(|m| m.content).unwrap_or_default();
+                let system = system
+                    .into_iter()
+                    .map(|m| m.content)
+                    .collect::<Vec<_>>()
+                    .join("\n\n");
+                json!({
+                    "prompt":  prompt,
+                    "system":  system,
+                    "context": context,
+                })
+            }
+        }
+    }
+}
+
 /// POST /evaluators/set
 #[derive(Debug, Clone, Serialize)]
 pub struct SetEvaluatorRequest {
@@ -257,6 +448,51 @@ pub struct Tabu {
     pub details: Option<Value>,
 }
 
+/// A failed evaluation: the `tabu` of a Tephra response, or the request
+/// failure that kept a response from arriving
+#[derive(Error, Debug, Clone, PartialEq)]
+#[error("{message}")]
+pub struct TephraError {
+    pub message: String,
+    /// Tephra error code, or the HTTP status of a failed request
+    pub code: Option<i32>,
+    pub details: Option<Value>,
+}
+
+impl From<Tabu> for TephraError {
+    fn from(tabu: Tabu) -> Self {
+        Self { message: tabu.message, code: tabu.code, details: tabu.details }
+    }
+}
+
+impl From<FieryPitError> for TephraError {
+    fn from(err: FieryPitError) -> Self {
+        match err {
+            FieryPitError::Status(status, body, _) => Self {
+                message: body
+                    .get("message")
+                    .or_else(|| body.get("detail"))
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+                    .unwrap_or_else(|| format!("HTTP {}", status)),
+                code: Some(status.as_u16() as i32),
+                details: Some(body),
+            },
+            FieryPitError::Timeout(e) => Self { message: e.to_string(), code: None, details: None },
+            FieryPitError::Http(e) => Self {
+                message: e.to_string(),
+                code: e.status().map(|s| s.as_u16() as i32),
+                details: None,
+            },
+            other @ (FieryPitError::Json(_)
+            | FieryPitError::InvalidBaseUrl(_)
+            | FieryPitError::Config(_)) => {
+                Self { message: other.to_string(), code: None, details: None }
+            }
+        }
+    }
+}
+
 /// Tephra response envelope from POST /evaluate
 #[derive(Debug, Clone, Deserialize)]
 pub struct Tephra {
@@ -286,6 +522,37 @@ impl Tephra {
         self.tabu.as_ref().map(|t| t.message.as_str())
     }
 
+    /// Variable bindings of the first Prolog solution in the response; see
+    /// [`prolog_bindings`]
+    pub fn prolog_bindings(&self) -> PrologBindings {
+        self.response().map(prolog_bindings).unwrap_or_default()
+    }
+
+    /// Variable bindings of every Prolog solution in the response; see
+    /// [`prolog_solutions`]
+    pub fn prolog_solutions(&self) -> Vec<PrologBindings> {
+        self.response().map(prolog_solutions).unwrap_or_default()
+    }
+
+    /// Evaluator text in the response; see [`extract_text`]
+    pub fn text(&self) -> Option<String> {
+        self.response().and_then(extract_text)
+    }
+
+    /// Consume self and return the inner response, or the `tabu` with its
+    /// message, code and details kept as typed fields
+    pub fn into_result(self) -> Result<Value, TephraError> {
+        match (self.hohi, self.tabu) {
+            (Some(hohi), _) => Ok(hohi.response),
+            (None, Some(tabu)) => Err(tabu.into()),
+            (None, None) => Err(TephraError {
+                message: "Empty Tephra response".to_string(),
+                code: None,
+                details: None,
+            }),
+        }
+    }
+
     /// Consume self and return the inner response or an error
     pub fn into_response(self) -> Result<Value, FieryPitError> {
         if let Some(hohi) = self.hohi {
@@ -294,41 +561,264 @@ impl Tephra {
             Err(FieryPitError::Status(
                 reqwest::StatusCode::from_u16(tabu.code.unwrap_or(400) as u16)
                     .unwrap_or(reqwest::StatusCode::BAD_REQUEST),
-                serde_json::json!({ "message": tabu.message, "details": tabu.details }),
+                json!({ "message": tabu.message, "details": tabu.details }),
+                self.task_id,
             ))
         } else {
             Err(FieryPitError::Status(
                 reqwest::StatusCode::INTERNAL_SERVER_ERROR,
-                serde_json::json!({ "message": "Empty Tephra response" }),
+                json!({ "message": "Empty Tephra response" }),
+                self.task_id,
             ))
         }
     }
 }
 
+// =========================================================================
+// Evaluator text
+// =========================================================================
+
+/// Keys under which evaluators put their text, most specific first
+const TEXT_KEYS: [&str; 4] = ["content", "response", "result", "hohi"];
+
+/// Extract the text an LLM evaluator produced
+///
+/// Evaluators wrap their output in several shapes:
+///
+/// - a bare string
+/// - `{"content": "..."}`, `{"response": "..."}` or `{"result": "..."}`
+/// - any of those nested, e.g. `{"response": {"content": "..."}}`
+/// - a whole Tephra envelope, `{"hohi": {"response": ...}}`
+///
+/// Returns `None` when no string is found in any of them.
+pub fn extract_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Object(obj) => TEXT_KEYS.iter().find_map(|key| obj.get(*key).and_then(extract_text)),
+        _ => None,
+    }
+}
+
+// =========================================================================
+// Prolog bindings
+// =========================================================================
+
+/// Variable name → bound value for one Prolog solution
+pub type PrologBindings = HashMap<String, Value>;
+
+/// Extract every Prolog solution from a query result
+///
+/// Results reach clients in several shapes depending on the endpoint:
+///
+/// - `{"solutions": [{...}, ...]}` from `POST /prolog/sessions/{id}/query`
+/// - `{"result": {"prolog_solutions": [...]}}` from deduction polls
+/// - `{"prolog_solutions": [...]}`
+/// - `{"result": [...]}` or `{"result": {...}}`
+/// - `{"bindings": {...}}` holding a single solution
+/// - a bare array of solutions
+///
+/// Non-object entries in a solution list are skipped. Returns an empty list
+/// when no solutions are found.
+pub fn prolog_solutions(value: &Value) -> Vec<PrologBindings> {
+    solutions_in(value).unwrap_or_default()
+}
+
+/// Extract the first Prolog solution from a query result, or empty bindings
+/// if there is none; accepts the same shapes as [`prolog_solutions`]
+pub fn prolog_bindings(value: &Value) -> PrologBindings {
+    prolog_solutions(value).into_iter().next().unwrap_or_default()
+}
+
+fn solutions_in(value: &Value) -> Option<Vec<PrologBindings>> {
+    match value {
+        Value::Array(items) => Some(items.iter().filter_map(Value::as_object).map(to_bindings).collect()),
+        Value::Object(obj) => {
+            if let Some(found) = ["solutions", "prolog_solutions"]
+                .iter()
+                .find_map(|key| obj.get(*key).and_then(solutions_in))
+            {
+                return Some(found);
+            }
+            if let Some(result) = obj.get("result") {
+                // A plain object under `result` is the solution itself
+                return solutions_in(result)
+                    .or_else(|| result.as_object().map(|o| vec![to_bindings(o)]));
+            }
+            obj.get("bindings")
+                .and_then(Value::as_object)
+                .map(|b| vec![to_bindings(b)])
+        }
+        _ => None,
+    }
+}
+
+fn to_bindings(obj: &Map<String, Value>) -> PrologBindings {
+    obj.iter().map(|(k, v)| (k.clone(), v.clone())).collect()
+}
+
 // =========================================================================
 // Client
 // =========================================================================
 
+/// Idle connections kept open per host unless overridden with
+/// [`FieryPitClient::with_pool_max_idle_per_host`]. reqwest's own default is
+/// unbounded, which lets many Prolog engines each hold sockets open to FieryPit.
+pub const DEFAULT_POOL_MAX_IDLE_PER_HOST: usize = 8;
+
+/// How long an idle pooled connection is kept before it is closed, unless
+/// overridden with [`FieryPitClient::with_pool_idle_timeout`].
+pub const DEFAULT_POOL_IDLE_TIMEOUT: Duration = clara_http_core::DEFAULT_POOL_IDLE_TIMEOUT;
+
 /// FieryPit REST API Client
 #[derive(Clone)]
 pub struct FieryPitClient {
     base_url: Arc<String>,
     client: Client,
     service_key: Option<Arc<String>>,
+    config: HttpClientConfig,
+}
+
+/// Builder for [`FieryPitClient`], created with [`FieryPitClient::builder`]
+///
+/// Starts from the same defaults as [`FieryPitClient::new`]: a 30 second
+/// request timeout and [`DEFAULT_POOL_MAX_IDLE_PER_HOST`] idle connections.
+///
+/// ```no_run
+/// # use fiery_pit_client::FieryPitClient;
+/// # use std::time::Duration;
+/// let client = FieryPitClient::builder()
+///     .base_url("http://localhost:6666")
+///     .timeout(Duration::from_secs(10))
+///     .connect_timeout(Duration::from_secs(2))
+///     .build()?;
+/// # Ok::<(), fiery_pit_client::HttpConfigError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct FieryPitClientBuilder {
+    base_url: String,
+    service_key: Option<String>,
+    config: HttpClientConfig,
+}
+
+impl Default for FieryPitClientBuilder {
+    fn default() -> Self {
+        Self {
+            base_url: "http://localhost:6666".to_string(),
+            service_key: None,
+            config: HttpClientConfig::default()
+                .with_pool_max_idle_per_host(DEFAULT_POOL_MAX_IDLE_PER_HOST)
+                .with_pool_idle_timeout(Some(DEFAULT_POOL_IDLE_TIMEOUT)),
+        }
+    }
+}
+
+impl FieryPitClientBuilder {
+    /// Base URL of the FieryPit API. Default: `http://localhost:6666`
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Limit on a whole request, from connecting to reading the body
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.config.timeout = Some(timeout);
+        self
+    }
+
+    /// Limit on establishing the connection
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.config.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Idle connections kept open per host
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.config.pool_max_idle_per_host = max;
+        self
+    }
+
+    /// Bearer service key for lildaemon's JWT auth
+    pub fn service_key(mut self, key: impl Into<String>) -> Self {
+        self.service_key = Some(key.into());
+        self
+    }
+
+    /// Build the client; fails only if the HTTP client can't be created
+    pub fn build(self) -> Result<FieryPitClient, HttpConfigError> {
+        let client = FieryPitClient::with_config(self.base_url, self.config)?;
+        Ok(match self.service_key {
+            Some(key) => client.with_service_key(key),
+            None => client,
+        })
+    }
 }
 
 impl FieryPitClient {
-    /// Create a new FieryPitClient
+    /// Create a new FieryPitClient with default HTTP settings
+    ///
+    /// Panics if `base_url` is invalid; see [`FieryPitClient::try_new`].
     ///
     /// # Arguments
     /// * `base_url` - Base URL of the FieryPit API, e.g. "http://localhost:6666"
     pub fn new(base_url: impl Into<String>) -> Self {
+        Self::try_new(base_url).unwrap_or_else(|e| panic!("failed to create FieryPitClient: {}", e))
+    }
+
+    /// Create a new FieryPitClient, rejecting a base URL without an
+    /// `http`/`https` scheme or host with `InvalidBaseUrl`
+    pub fn try_new(base_url: impl Into<String>) -> Result<Self, FieryPitError> {
+        let base = clara_http_core::normalize_base_url(&base_url.into())
+            .map_err(FieryPitError::InvalidBaseUrl)?;
+        Ok(Self::builder().base_url(base).build()?)
+    }
+
+    /// Start configuring a client's timeouts and connection pool
+    pub fn builder() -> FieryPitClientBuilder {
+        FieryPitClientBuilder::default()
+    }
+
+    /// Create a client with shared HTTP settings (timeouts, retries, default
+    /// headers, connection pool).
+    ///
+    /// Unlike [`FieryPitClient::new`], the pool settings come from `config`
+    /// as given. Fails if a configured header is invalid.
+    pub fn with_config(
+        base_url: impl Into<String>,
+        config: HttpClientConfig,
+    ) -> Result<Self, HttpConfigError> {
         let base = base_url.into();
-        FieryPitClient {
+        Ok(FieryPitClient {
             base_url: Arc::new(base.trim_end_matches('/').to_string()),
-            client: Client::new(),
+            client: config.build()?,
             service_key: None,
-        }
+            config,
+        })
+    }
+
+    /// Limit how many idle connections are kept open per host.
+    ///
+    /// Default: [`DEFAULT_POOL_MAX_IDLE_PER_HOST`]. Busy connections are not
+    /// limited; extra ones are closed once the request finishes.
+    pub fn with_pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.config.pool_max_idle_per_host = max;
+        self.rebuild_http_client();
+        self
+    }
+
+    /// Close pooled connections that have been idle for `timeout`; `None`
+    /// keeps them open indefinitely.
+    ///
+    /// Default: [`DEFAULT_POOL_IDLE_TIMEOUT`].
+    pub fn with_pool_idle_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.config.pool_idle_timeout = timeout;
+        self.rebuild_http_client();
+        self
+    }
+
+    /// Rebuild the inner HTTP client after a pool setting changed; the
+    /// headers were validated when the client was first built
+    fn rebuild_http_client(&mut self) {
+        self.client = self.config.build().expect("failed to build FieryPit HTTP client");
     }
 
     /// Attach a Bearer service key for lildaemon's JWT auth.
@@ -344,6 +834,21 @@ impl FieryPitClient {
         self
     }
 
+    /// A client for another FieryPit instance with this client's HTTP
+    /// settings and service key; the connection pool is shared.
+    pub fn with_base_url(&self, base_url: impl Into<String>) -> Self {
+        let base = base_url.into();
+        Self {
+            base_url: Arc::new(base.trim_end_matches('/').to_string()),
+            ..self.clone()
+        }
+    }
+
+    /// The FieryPit base URL requests are sent to
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
     /// Create a FieryPitClient from environment variables.
     ///
     /// - `FIERY_PIT_URL` — base URL (default: `http://localhost:6666`)
@@ -367,44 +872,50 @@ impl FieryPitClient {
     fn get(&self, path: &str) -> Result<Value, FieryPitError> {
         let url = format!("{}{}", self.base_url, path);
         log::debug!("FieryPitClient GET {}", url);
-        let mut req = self.client.get(&url);
-        if let Some(key) = &self.service_key {
-            req = req.bearer_auth(key.as_str());
-        }
-        let resp = req.send()?;
+        let resp = self.send(|| self.client.get(&url))?;
         self.handle_response(resp)
     }
 
     fn post(&self, path: &str, body: &impl Serialize) -> Result<Value, FieryPitError> {
         let url = format!("{}{}", self.base_url, path);
         log::debug!("FieryPitClient POST {}", url);
-        let mut req = self.client.post(&url).json(body);
-        if let Some(key) = &self.service_key {
-            req = req.bearer_auth(key.as_str());
-        }
-        let resp = req.send()?;
+        let resp = self.send(|| self.client.post(&url).json(body))?;
         self.handle_response(resp)
     }
 
     fn delete(&self, path: &str) -> Result<Value, FieryPitError> {
         let url = format!("{}{}", self.base_url, path);
         log::debug!("FieryPitClient DELETE {}", url);
-        let mut req = self.client.delete(&url);
-        if let Some(key) = &self.service_key {
-            req = req.bearer_auth(key.as_str());
-        }
-        let resp = req.send()?;
+        let resp = self.send(|| self.client.delete(&url))?;
         self.handle_response(resp)
     }
 
+    /// Send a request with the service key attached, retrying per the
+    /// configured [`RetryPolicy`]
+    fn send<F>(&self, make_request: F) -> Result<reqwest::blocking::Response, FieryPitError>
+    where
+        F: Fn() -> RequestBuilder,
+    {
+        let resp = self.config.retry.send(|| {
+            let req = make_request();
+            match &self.service_key {
+                Some(key) => req.bearer_auth(key.as_str()),
+                None => req,
+            }
+        })?;
+        Ok(resp)
+    }
+
     fn handle_response(&self, resp: reqwest::blocking::Response) -> Result<Value, FieryPitError> {
         let status = resp.status();
+        let headers = resp.headers().clone();
         let text = resp.text()?;
         let json: Value = serde_json::from_str(&text).unwrap_or(Value::String(text.clone()));
         if status.is_success() {
             Ok(json)
         } else {
-            Err(FieryPitError::Status(status, json))
+            let id = request_id_from(&headers, &json);
+            Err(FieryPitError::Status(status, json, id))
         }
     }
 
@@ -434,7 +945,31 @@ impl FieryPitClient {
     /// Evaluate using the current active evaluator — POST /evaluate
     pub fn evaluate(&self, data: Value) -> Result<Value, FieryPitError> {
         log::debug!("FieryPitClient evaluate with data: {}", data);
-        self.post("/evaluate", &json!({ "data": data }))
+        self.post("/evaluate", &EvaluateRequest { data, evaluator: None })
+    }
+
+    /// Evaluate using `evaluator` for this call only — POST /evaluate
+    ///
+    /// The current active evaluator is left unchanged. The name is checked
+    /// via GET /evaluators/{name} first, so an unknown evaluator fails with
+    /// that endpoint's error status before anything is evaluated.
+    pub fn evaluate_with(&self, data: Value, evaluator: &str) -> Result<Value, FieryPitError> {
+        log::debug!("FieryPitClient evaluate with evaluator {} and data: {}", evaluator, data);
+        self.get_evaluator(evaluator)?;
+        self.post(
+            "/evaluate",
+            &EvaluateRequest {
+                data,
+                evaluator: Some(evaluator.to_string()),
+            },
+        )
+    }
+
+    /// Evaluate a prompt or chat messages — POST /evaluate
+    ///
+    /// See [`EvaluateInput`] for how the two forms are normalized.
+    pub fn evaluate_input(&self, input: EvaluateInput) -> Result<Value, FieryPitError> {
+        self.evaluate(input.into_data())
     }
 
     /// Evaluate and return a typed Tephra envelope
@@ -443,6 +978,18 @@ impl FieryPitClient {
         Ok(serde_json::from_value(value)?)
     }
 
+    /// Evaluate each item in turn, keeping every item's outcome
+    ///
+    /// A failed item doesn't stop the rest; its `tabu` (or request failure)
+    /// is returned as a [`TephraError`] in its slot so the caller can retry
+    /// just that item.
+    pub fn try_batch(&self, items: Vec<Value>) -> Vec<Result<Value, TephraError>> {
+        items
+            .into_iter()
+            .map(|data| self.evaluate_tephra(data)?.into_result())
+            .collect()
+    }
+
     // =========================================================================
     // Evaluation Monitoring — /evaluations/*
     // =========================================================================
@@ -665,15 +1212,20 @@ impl FieryPitClient {
     }
 
     /// Load CLIPS rules — POST /clips/sessions/{id}/rules
+    ///
+    /// Check `failed` in the result: rules that don't compile are reported
+    /// there rather than failing the whole request. Only a batch in which no
+    /// rule compiles is rejected, as a 400 [`FieryPitError::Status`].
     pub fn clips_load_rules(
         &self,
         session_id: &str,
         rules: Vec<String>,
-    ) -> Result<Value, FieryPitError> {
-        self.post(
+    ) -> Result<ClipsLoadRulesResult, FieryPitError> {
+        let value = self.post(
             &format!("/clips/sessions/{}/rules", session_id),
             &ClipsLoadRulesRequest { rules },
-        )
+        )?;
+        Ok(serde_json::from_value(value)?)
     }
 
     /// Assert CLIPS facts — POST /clips/sessions/{id}/facts
@@ -705,6 +1257,16 @@ impl FieryPitClient {
         self.get(&path)
     }
 
+    /// [`clips_query_facts`](Self::clips_query_facts), parsed with
+    /// [`clips_facts`]
+    pub fn clips_query_facts_typed(
+        &self,
+        session_id: &str,
+        pattern: Option<&str>,
+    ) -> Result<Vec<ClipsFact>, FieryPitError> {
+        clips_facts(self.clips_query_facts(session_id, pattern)?)
+    }
+
     /// Run the CLIPS rule engine — POST /clips/sessions/{id}/run
     pub fn clips_run(
         &self,
@@ -730,22 +1292,7 @@ impl FieryPitClient {
 
     /// Create a Prolog session and return just the session_id
     pub fn prolog_create_session_id(&self, req: CreateSessionRequest) -> Result<String, FieryPitError> {
-        let value = self.prolog_create_session(req)?;
-        if let Some(s) = value.as_str() {
-            return Ok(s.to_string());
-        }
-        if let Some(obj) = value.as_object() {
-            if let Some(id) = obj.get("session_id").and_then(|v| v.as_str()) {
-                return Ok(id.to_string());
-            }
-            if let Some(id) = obj.get("id").and_then(|v| v.as_str()) {
-                return Ok(id.to_string());
-            }
-        }
-        Err(FieryPitError::Status(
-            reqwest::StatusCode::INTERNAL_SERVER_ERROR,
-            json!({ "message": format!("No session_id in response: {}", value) }),
-        ))
+        session_id_from(self.prolog_create_session(req)?)
     }
 
     /// List Prolog sessions — GET /prolog/sessions
@@ -775,6 +1322,29 @@ impl FieryPitClient {
             &PrologQueryRequest {
                 goal: goal.to_string(),
                 all_solutions,
+                bindings: None,
+            },
+        )
+    }
+
+    /// Execute a Prolog goal with named variables bound to JSON values —
+    /// POST /prolog/sessions/{id}/query
+    ///
+    /// Prefer this over formatting values into `goal`: the server binds them
+    /// as terms, so they can't alter the query.
+    pub fn prolog_query_with_bindings(
+        &self,
+        session_id: &str,
+        goal: &str,
+        bindings: serde_json::Map<String, Value>,
+        all_solutions: bool,
+    ) -> Result<Value, FieryPitError> {
+        self.post(
+            &format!("/prolog/sessions/{}/query", session_id),
+            &PrologQueryRequest {
+                goal: goal.to_string(),
+                all_solutions,
+                bindings: Some(bindings),
             },
         )
     }
@@ -840,6 +1410,7 @@ impl FieryPitClient {
     ///
     /// Returns `409 Conflict` (mapped to `FieryPitError::Status`) if the
     /// lildaemon is already joined to this ritual.
+    #[allow(clippy::too_many_arguments)]
     pub fn ritual_join(
         &self,
         ritual_id:        uuid::Uuid,
@@ -873,6 +1444,27 @@ impl FieryPitClient {
     }
 }
 
+/// The session id in a session-creation response: a bare string or the
+/// `session_id` / `id` field of an object
+fn session_id_from(value: Value) -> Result<String, FieryPitError> {
+    if let Some(s) = value.as_str() {
+        return Ok(s.to_string());
+    }
+    if let Some(obj) = value.as_object() {
+        if let Some(id) = obj.get("session_id").and_then(|v| v.as_str()) {
+            return Ok(id.to_string());
+        }
+        if let Some(id) = obj.get("id").and_then(|v| v.as_str()) {
+            return Ok(id.to_string());
+        }
+    }
+    Err(FieryPitError::Status(
+        reqwest::StatusCode::INTERNAL_SERVER_ERROR,
+        json!({ "message": format!("No session_id in response: {}", value) }),
+        None,
+    ))
+}
+
 // =========================================================================
 // Tests
 // =========================================================================
@@ -888,6 +1480,18 @@ mod tests {
         assert!(client.service_key.is_none());
     }
 
+    #[test]
+    fn test_try_new_rejects_invalid_base_url() {
+        for url in ["localhost:8000", "", "http://"] {
+            assert!(
+                matches!(FieryPitClient::try_new(url), Err(FieryPitError::InvalidBaseUrl(_))),
+                "{:?} was accepted",
+                url
+            );
+        }
+        assert_eq!(FieryPitClient::try_new("http://localhost:8000/").unwrap().base_url(), "http://localhost:8000");
+    }
+
     #[test]
     fn test_client_creation_trims_slash() {
         let client = FieryPitClient::new("http://localhost:8000/");
@@ -973,4 +1577,428 @@ mod tests {
         assert_eq!(resp.access_token, "tok.123");
         assert_eq!(resp.expires_in, 2_592_000);
     }
+
+    #[test]
+    fn test_evaluate_request_omits_evaluator_by_default() {
+        let req = EvaluateRequest { data: json!({"q": 1}), evaluator: None };
+        let json = serde_json::to_string(&req).unwrap();
+        assert!(!json.contains("evaluator"));
+    }
+
+    #[test]
+    fn test_evaluate_with_leaves_current_evaluator_unchanged() {
+        let mut srv = mockito::Server::new();
+        let _known = srv
+            .mock("GET", "/evaluators/kindling")
+            .with_status(200)
+            .with_body(r#"{"name":"kindling"}"#)
+            .create();
+        let evaluate = srv
+            .mock("POST", "/evaluate")
+            .match_body(mockito::Matcher::PartialJson(json!({"evaluator": "kindling"})))
+            .with_status(200)
+            .with_body(r#"{"response":"ok"}"#)
+            .create();
+        let set = srv.mock("POST", "/evaluators/set").expect(0).create();
+
+        let client = FieryPitClient::new(srv.url());
+        let result = client.evaluate_with(json!({"q": 1}), "kindling").unwrap();
+        assert_eq!(result["response"], "ok");
+
+        evaluate.assert();
+        set.assert();
+    }
+
+    #[test]
+    fn test_evaluate_with_unknown_evaluator() {
+        let mut srv = mockito::Server::new();
+        let _missing = srv
+            .mock("GET", "/evaluators/nope")
+            .with_status(404)
+            .with_body(r#"{"detail":"Evaluator not found"}"#)
+            .create();
+        let evaluate = srv.mock("POST", "/evaluate").expect(0).create();
+
+        let client = FieryPitClient::new(srv.url());
+        let result = client.evaluate_with(json!({"q": 1}), "nope");
+        assert!(matches!(result, Err(FieryPitError::Status(status, _, _)) if status == 404));
+
+        evaluate.assert();
+    }
+
+    #[test]
+    fn test_status_error_keeps_request_id() {
+        let mut srv = mockito::Server::new();
+        srv.mock("GET", "/status")
+            .with_status(503)
+            .with_header("x-request-id", "req-42")
+            .with_body(r#"{"detail":"busy","task_id":"task-7"}"#)
+            .create();
+        srv.mock("GET", "/evaluations/stuck")
+            .with_status(500)
+            .with_body(r#"{"detail":"boom","task_id":"task-7"}"#)
+            .create();
+        srv.mock("GET", "/health").with_status(500).with_body("plain failure").create();
+
+        let client = FieryPitClient::new(srv.url());
+
+        // The header wins over the body
+        let err = client.status().unwrap_err();
+        assert_eq!(err.request_id(), Some("req-42"));
+        assert!(err.to_string().contains("request id req-42"), "{}", err);
+
+        let err = client.evaluation_get("stuck").unwrap_err();
+        assert!(matches!(&err, FieryPitError::Status(status, _, _) if *status == 500));
+        assert_eq!(err.request_id(), Some("task-7"));
+
+        let err = client.health().unwrap_err();
+        assert_eq!(err.request_id(), None);
+    }
+
+    #[test]
+    fn test_evaluate_input_accepts_string_and_messages() {
+        let legacy: EvaluateInput = serde_json::from_value(json!("Who goes there?")).unwrap();
+        let chat: EvaluateInput = serde_json::from_value(json!({
+            "messages": [{"role": "user", "content": "Who goes there?"}]
+        }))
+        .unwrap();
+        assert_eq!(legacy.clone().into_data(), chat.clone().into_data());
+
+        let mut srv = mockito::Server::new();
+        let evaluate = srv
+            .mock("POST", "/evaluate")
+            .match_body(mockito::Matcher::Json(json!({
+                "data": {"prompt": "Who goes there?", "system": "", "context": []}
+            })))
+            .with_status(200)
+            .with_body(r#"{"response":"ok"}"#)
+            .expect(2)
+            .create();
+
+        let client = FieryPitClient::new(srv.url());
+        assert_eq!(client.evaluate_input(legacy).unwrap()["response"], "ok");
+        assert_eq!(client.evaluate_input(chat).unwrap()["response"], "ok");
+
+        evaluate.assert();
+    }
+
+    #[test]
+    fn test_evaluate_input_messages_split_into_system_context_and_prompt() {
+        let input: EvaluateInput = serde_json::from_value(json!({
+            "messages": [
+                {"role": "system", "content": "Be terse."},
+                {"role": "user", "content": "Hello"},
+                {"role": "assistant", "content": "State your business."},
+                {"role": "user", "content": "I seek an audience."}
+            ]
+        }))
+        .unwrap();
+
+        let data = input.into_data();
+        assert_eq!(data["system"], "Be terse.");
+        assert_eq!(data["prompt"], "I seek an audience.");
+        assert_eq!(
+            data["context"],
+            json!([
+                {"role": "user", "content": "Hello"},
+                {"role": "assistant", "content": "State your business."}
+            ])
+        );
+    }
+
+    #[test]
+    fn test_clips_load_rules_reports_failed_rules() {
+        let mut srv = mockito::Server::new();
+        let load = srv
+            .mock("POST", "/clips/sessions/sess-1/rules")
+            .with_status(200)
+            .with_body(r#"{
+                "status": "rules_loaded",
+                "count": 3,
+                "loaded": 2,
+                "failed": [{"index": 1, "error": "CLIPS Build failed (code 2) for: (defrule broken"}]
+            }"#)
+            .create();
+
+        let client = FieryPitClient::new(srv.url());
+        let result = client
+            .clips_load_rules(
+                "sess-1",
+                vec![
+                    "(defrule a (x) => (assert (y)))".to_string(),
+                    "(defrule broken".to_string(),
+                    "(defrule b (y) => (assert (z)))".to_string(),
+                ],
+            )
+            .unwrap();
+
+        assert_eq!(result.loaded, 2);
+        assert_eq!(result.failed.len(), 1);
+        assert_eq!(result.failed[0].index, 1);
+        assert!(result.failed[0].error.contains("broken"));
+        load.assert();
+    }
+
+    #[test]
+    fn test_prolog_bindings_from_each_response_shape() {
+        let expected: PrologBindings =
+            [("X".to_string(), json!("alice")), ("N".to_string(), json!(3))].into();
+        let shapes = [
+            json!({"session_id": "s", "goal": "g", "solutions": [{"X": "alice", "N": 3}, {"X": "bob", "N": 4}]}),
+            json!({"status": "complete", "result": {"prolog_solutions": [{"X": "alice", "N": 3}, {"X": "bob", "N": 4}]}}),
+            json!({"prolog_solutions": [{"X": "alice", "N": 3}, {"X": "bob", "N": 4}]}),
+            json!({"result": [{"X": "alice", "N": 3}, {"X": "bob", "N": 4}]}),
+            json!({"result": {"X": "alice", "N": 3}}),
+            json!({"bindings": {"X": "alice", "N": 3}}),
+            json!([{"X": "alice", "N": 3}, "ignored", {"X": "bob", "N": 4}]),
+        ];
+
+        for shape in &shapes {
+            assert_eq!(prolog_bindings(shape), expected, "shape {}", shape);
+        }
+        assert_eq!(prolog_solutions(&shapes[0]).len(), 2);
+        assert_eq!(prolog_solutions(&shapes[1])[1]["X"], "bob");
+        assert_eq!(prolog_solutions(&shapes[5]).len(), 1);
+        assert_eq!(prolog_solutions(&shapes[6]).len(), 2);
+    }
+
+    #[test]
+    fn test_prolog_bindings_empty_when_absent() {
+        assert!(prolog_bindings(&json!({"solutions": []})).is_empty());
+        assert!(prolog_bindings(&json!({"status": "complete"})).is_empty());
+        assert!(prolog_bindings(&json!({"result": "true"})).is_empty());
+        assert!(prolog_solutions(&json!(null)).is_empty());
+    }
+
+    #[test]
+    fn test_tephra_prolog_bindings() {
+        let tephra: Tephra = serde_json::from_value(json!({
+            "hohi": {"response": {"result": {"prolog_solutions": [{"Decision": "admit"}]}}}
+        }))
+        .unwrap();
+        assert_eq!(tephra.prolog_bindings()["Decision"], "admit");
+        assert_eq!(tephra.prolog_solutions().len(), 1);
+
+        let failed: Tephra = serde_json::from_value(json!({"tabu": {"message": "boom"}})).unwrap();
+        assert!(failed.prolog_bindings().is_empty());
+    }
+
+    #[test]
+    fn test_extract_text_shapes() {
+        assert_eq!(extract_text(&json!("Halt.")).as_deref(), Some("Halt."));
+        assert_eq!(extract_text(&json!({"result": "Halt."})).as_deref(), Some("Halt."));
+        assert_eq!(extract_text(&json!({"response": "Halt."})).as_deref(), Some("Halt."));
+        assert_eq!(extract_text(&json!({"content": "Halt.", "model": "m"})).as_deref(), Some("Halt."));
+        assert_eq!(
+            extract_text(&json!({"response": {"content": "Halt.", "role": "assistant"}})).as_deref(),
+            Some("Halt.")
+        );
+        assert_eq!(
+            extract_text(&json!({"hohi": {"response": {"content": "Halt."}}})).as_deref(),
+            Some("Halt.")
+        );
+
+        // The most specific key wins
+        assert_eq!(
+            extract_text(&json!({"result": "outer", "content": "inner"})).as_deref(),
+            Some("inner")
+        );
+
+        assert_eq!(extract_text(&json!({"answer": 42})), None);
+        assert_eq!(extract_text(&json!({"result": 42})), None);
+        assert_eq!(extract_text(&json!(null)), None);
+    }
+
+    #[test]
+    fn test_tephra_text() {
+        let tephra: Tephra = serde_json::from_value(json!({
+            "hohi": {"response": {"content": "State your business."}}
+        }))
+        .unwrap();
+        assert_eq!(tephra.text().as_deref(), Some("State your business."));
+
+        let failed: Tephra = serde_json::from_value(json!({"tabu": {"message": "boom"}})).unwrap();
+        assert_eq!(failed.text(), None);
+    }
+
+    #[test]
+    fn test_tephra_into_result() {
+        let ok: Tephra = serde_json::from_value(json!({"hohi": {"response": {"answer": 42}}})).unwrap();
+        assert_eq!(ok.into_result().unwrap(), json!({"answer": 42}));
+
+        let failed: Tephra = serde_json::from_value(json!({
+            "tabu": {"message": "boom", "code": 409, "details": {"evaluator": "kindling"}}
+        }))
+        .unwrap();
+        assert_eq!(
+            failed.into_result().unwrap_err(),
+            TephraError {
+                message: "boom".to_string(),
+                code: Some(409),
+                details: Some(json!({"evaluator": "kindling"})),
+            }
+        );
+
+        let empty: Tephra = serde_json::from_value(json!({})).unwrap();
+        assert_eq!(empty.into_result().unwrap_err().message, "Empty Tephra response");
+    }
+
+    #[test]
+    fn test_try_batch_mixed_outcomes() {
+        let mut srv = mockito::Server::new();
+        let _ok = srv
+            .mock("POST", "/evaluate")
+            .match_body(mockito::Matcher::PartialJson(json!({"data": {"q": "ok"}})))
+            .with_status(200)
+            .with_body(r#"{"hohi":{"response":"fine"}}"#)
+            .create();
+        let _tabu = srv
+            .mock("POST", "/evaluate")
+            .match_body(mockito::Matcher::PartialJson(json!({"data": {"q": "tabu"}})))
+            .with_status(200)
+            .with_body(r#"{"tabu":{"message":"evaluator refused","code":451,"details":{"reason":"policy"}}}"#)
+            .create();
+        let _rejected = srv
+            .mock("POST", "/evaluate")
+            .match_body(mockito::Matcher::PartialJson(json!({"data": {"q": "bad"}})))
+            .with_status(422)
+            .with_body(r#"{"detail":"data must not be empty"}"#)
+            .create();
+
+        let client = FieryPitClient::new(srv.url());
+        let results = client.try_batch(vec![
+            json!({"q": "ok"}),
+            json!({"q": "tabu"}),
+            json!({"q": "bad"}),
+            json!({"q": "ok"}),
+        ]);
+
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[0], Ok(json!("fine")));
+        assert_eq!(
+            results[1],
+            Err(TephraError {
+                message: "evaluator refused".to_string(),
+                code: Some(451),
+                details: Some(json!({"reason": "policy"})),
+            })
+        );
+        let rejected = results[2].as_ref().unwrap_err();
+        assert_eq!(rejected.message, "data must not be empty");
+        assert_eq!(rejected.code, Some(422));
+        assert_eq!(results[3], Ok(json!("fine")));
+    }
+
+    #[test]
+    fn test_pool_settings_applied() {
+        let client = FieryPitClient::new("http://localhost:8000");
+        assert_eq!(client.config.pool_max_idle_per_host, DEFAULT_POOL_MAX_IDLE_PER_HOST);
+        assert_eq!(client.config.pool_idle_timeout, Some(DEFAULT_POOL_IDLE_TIMEOUT));
+
+        let mut srv = mockito::Server::new();
+        let health = srv
+            .mock("GET", "/health")
+            .with_status(200)
+            .with_body(r#"{"status":"ok"}"#)
+            .create();
+
+        let client = FieryPitClient::new(srv.url())
+            .with_service_key("token")
+            .with_pool_max_idle_per_host(2)
+            .with_pool_idle_timeout(Some(Duration::from_secs(5)));
+        assert_eq!(client.config.pool_max_idle_per_host, 2);
+        assert_eq!(client.config.pool_idle_timeout, Some(Duration::from_secs(5)));
+        assert_eq!(client.service_key.as_deref().map(|s| s.as_str()), Some("token"));
+
+        assert_eq!(client.health().unwrap()["status"], "ok");
+        health.assert();
+    }
+
+    #[test]
+    fn test_builder_settings_applied() {
+        let client = FieryPitClient::builder()
+            .base_url("http://localhost:8000/")
+            .timeout(Duration::from_secs(5))
+            .connect_timeout(Duration::from_secs(1))
+            .pool_max_idle_per_host(3)
+            .service_key("token")
+            .build()
+            .unwrap();
+        assert_eq!(client.base_url(), "http://localhost:8000");
+        assert_eq!(client.config.timeout, Some(Duration::from_secs(5)));
+        assert_eq!(client.config.connect_timeout, Some(Duration::from_secs(1)));
+        assert_eq!(client.config.pool_max_idle_per_host, 3);
+        assert_eq!(client.service_key.as_deref().map(|s| s.as_str()), Some("token"));
+
+        // new() uses the builder defaults
+        let client = FieryPitClient::new("http://localhost:8000");
+        assert_eq!(client.config.timeout, Some(clara_http_core::DEFAULT_TIMEOUT));
+    }
+
+    #[test]
+    fn test_timeout_is_distinct_error() {
+        // Connections queue in the backlog and are never answered
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let client = FieryPitClient::builder()
+            .base_url(format!("http://{}", listener.local_addr().unwrap()))
+            .timeout(Duration::from_millis(200))
+            .build()
+            .unwrap();
+
+        let result = client.health();
+        assert!(matches!(result, Err(FieryPitError::Timeout(_))), "{:?}", result);
+        drop(listener);
+    }
+
+    #[test]
+    fn test_clips_facts_from_each_shape() {
+        let expected = vec![
+            ClipsFact { index: None, text: "(person (name \"Al\"))".to_string() },
+            ClipsFact { index: None, text: "(initial-fact)".to_string() },
+        ];
+
+        let wrapped = json!({"matches": ["(person (name \"Al\"))", "(initial-fact)"], "count": 2});
+        assert_eq!(clips_facts(wrapped).unwrap(), expected);
+
+        let bare = json!(["(person (name \"Al\"))", "(initial-fact)"]);
+        assert_eq!(clips_facts(bare).unwrap(), expected);
+
+        // Indexed lines and objects keep their index
+        let indexed = json!(["f-3     (point 1 2)", {"index": 7, "text": "(point 3 4)"}]);
+        assert_eq!(
+            clips_facts(indexed).unwrap(),
+            vec![
+                ClipsFact { index: Some(3), text: "(point 1 2)".to_string() },
+                ClipsFact { index: Some(7), text: "(point 3 4)".to_string() },
+            ]
+        );
+
+        assert!(clips_facts(json!({"matches": [], "count": 0})).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_clips_facts_rejects_other_shapes() {
+        for value in [json!({"facts": []}), json!("(a)"), json!([1, 2]), json!({"matches": "(a)"})] {
+            assert!(
+                matches!(clips_facts(value.clone()), Err(FieryPitError::Json(_))),
+                "{} was accepted",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn test_clips_query_facts_typed() {
+        let mut srv = mockito::Server::new();
+        let facts = srv
+            .mock("GET", "/clips/sessions/s1/facts")
+            .with_status(200)
+            .with_body(r#"{"matches":["(a 1)"],"count":1,"facts":[]}"#)
+            .create();
+
+        let client = FieryPitClient::new(srv.url());
+        let result = client.clips_query_facts_typed("s1", None).unwrap();
+        assert_eq!(result, vec![ClipsFact { index: None, text: "(a 1)".to_string() }]);
+        facts.assert();
+    }
 }